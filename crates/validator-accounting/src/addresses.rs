@@ -3,14 +3,14 @@
 //! This module contains mappings of known Solana addresses to human-readable labels.
 //! These are used to automatically categorize transactions.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::LazyLock;
 
 /// Address category for classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub enum AddressCategory {
     /// Solana Foundation (SFDP reimbursements, delegations)
@@ -291,3 +291,19 @@ pub fn is_jito(pubkey: &Pubkey) -> bool {
 pub fn is_exchange(pubkey: &Pubkey) -> bool {
     matches!(get_category(pubkey), AddressCategory::Exchange)
 }
+
+/// Serialize a `Pubkey` as its base58 string (for readable fixture files)
+pub mod pubkey_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&pubkey.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,405 @@
+//! Fixed asset register and depreciation schedules
+//!
+//! Validator servers and NVMe drives are capital purchases: instead of an
+//! expense in the month they're bought, each asset is depreciated over its
+//! useful life. Depreciation is charged at the end of every month from the
+//! month the asset is placed in service, and the pipeline turns those charges
+//! into expenses so the summary, ledger and journal pick them up.
+
+use anyhow::Result;
+use chrono::{Datelike, Months, NaiveDate};
+use clap::ValueEnum;
+
+use crate::expenses::{Expense, ExpenseCategory};
+use crate::money::Usd;
+use crate::period::FiscalCalendar;
+use crate::provenance::Provenance;
+
+/// `paid_with` of depreciation expenses; the journal credits them to
+/// accumulated depreciation instead of a funding account
+pub const DEPRECIATION_PAID_WITH: &str = "Depreciation";
+
+/// MACRS GDS percentages (200% declining balance, half-year convention) by
+/// recovery period, in hundredths of a percent per tax year (IRS Pub. 946, table A-1)
+const MACRS_HALF_YEAR: [(u32, &[u64]); 3] = [
+    (3, &[3333, 4445, 1481, 741]),
+    (5, &[2000, 3200, 1920, 1152, 1152, 576]),
+    (7, &[1429, 2449, 1749, 1249, 893, 892, 893, 446]),
+];
+
+/// How an asset's cost is spread over its useful life
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DepreciationMethod {
+    /// Equal monthly charges of cost less salvage value
+    #[default]
+    StraightLine,
+    /// Double declining balance, switching to straight-line once that is larger
+    DecliningBalance,
+    /// IRS MACRS table for 3, 5 or 7 year property (half-year convention,
+    /// spread over the months of each fiscal year)
+    Macrs,
+}
+
+impl DepreciationMethod {
+    /// Database and display name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DepreciationMethod::StraightLine => "straight-line",
+            DepreciationMethod::DecliningBalance => "declining-balance",
+            DepreciationMethod::Macrs => "macrs",
+        }
+    }
+}
+
+impl std::fmt::Display for DepreciationMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// A capital purchase in the asset register
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    /// Database ID (None for new assets not yet saved)
+    pub id: Option<i64>,
+    pub description: String,
+    pub vendor: String,
+    /// Category its depreciation is reported under (usually Hardware)
+    pub category: ExpenseCategory,
+    /// Date bought and placed in service (YYYY-MM-DD)
+    pub purchase_date: String,
+    pub cost_usd: Usd,
+    /// Value expected at the end of its life (not used by MACRS)
+    pub salvage_usd: Usd,
+    pub life_years: u32,
+    pub method: DepreciationMethod,
+    /// Payment method of the purchase (e.g. "Credit Card", "USD")
+    pub paid_with: String,
+    /// Vote account this asset belongs to (None = shared across validators)
+    pub validator: Option<String>,
+}
+
+/// One month's depreciation charge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Depreciation {
+    /// Last day of the month
+    pub date: NaiveDate,
+    pub amount_usd: Usd,
+}
+
+impl Asset {
+    /// Check the purchase date, life and amounts fit the method
+    pub fn validate(&self) -> Result<()> {
+        self.purchase_date()?;
+        if self.cost_usd <= Usd::ZERO {
+            anyhow::bail!("Asset cost must be positive");
+        }
+        if self.salvage_usd < Usd::ZERO || self.salvage_usd > self.cost_usd {
+            anyhow::bail!("Salvage value must be between $0 and the cost");
+        }
+        if !(1..=50).contains(&self.life_years) {
+            anyhow::bail!("Useful life must be 1-50 years");
+        }
+        if self.method == DepreciationMethod::Macrs {
+            if macrs_rates(self.life_years).is_none() {
+                let lives: Vec<String> = MACRS_HALF_YEAR.iter().map(|(life, _)| life.to_string()).collect();
+                anyhow::bail!(
+                    "MACRS tables cover {} year property, not {} years",
+                    lives.join(", "),
+                    self.life_years
+                );
+            }
+            if self.salvage_usd != Usd::ZERO {
+                anyhow::bail!("MACRS depreciates the full cost; remove the salvage value");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn purchase_date(&self) -> Result<NaiveDate> {
+        NaiveDate::parse_from_str(&self.purchase_date, "%Y-%m-%d")
+            .map_err(|_| anyhow::anyhow!("Invalid purchase date '{}' (expected YYYY-MM-DD)", self.purchase_date))
+    }
+
+    /// Cost less salvage value: what the schedule charges in total
+    pub fn depreciable_usd(&self) -> Usd {
+        match self.method {
+            DepreciationMethod::Macrs => self.cost_usd,
+            DepreciationMethod::StraightLine | DepreciationMethod::DecliningBalance => self.cost_usd - self.salvage_usd,
+        }
+    }
+
+    /// Monthly depreciation from the month of purchase to the end of the
+    /// asset's life, in whole cents adding up to the depreciable cost. Each
+    /// year's amount (asset years, or fiscal years for MACRS) is spread evenly
+    /// over its months.
+    pub fn schedule(&self, fiscal: &FiscalCalendar) -> Vec<Depreciation> {
+        let Ok(start) = self.purchase_date() else {
+            return Vec::new();
+        };
+        let life = self.life_years as u64;
+
+        // (months, amount) per year of the asset's life
+        let years: Vec<(u32, Usd)> = match self.method {
+            DepreciationMethod::StraightLine => vec![(self.life_years * 12, self.depreciable_usd())],
+            DepreciationMethod::DecliningBalance => {
+                let mut book = self.cost_usd;
+                (0..life)
+                    .map(|year| {
+                        let remaining = book - self.salvage_usd;
+                        let amount = if year + 1 == life {
+                            remaining
+                        } else {
+                            book.mul_div(2, life)
+                                .max(remaining.mul_div(1, life - year))
+                                .min(remaining)
+                        };
+                        book -= amount;
+                        (12, amount)
+                    })
+                    .collect()
+            }
+            DepreciationMethod::Macrs => {
+                let rates = macrs_rates(self.life_years).unwrap_or_default();
+                let (_, year_end) = fiscal.year_range(fiscal.year_of(start));
+                let first_year_months = month_index(year_end) - month_index(start) + 1;
+                let mut remaining = self.cost_usd;
+                rates
+                    .iter()
+                    .enumerate()
+                    .map(|(year, &rate)| {
+                        let amount = if year + 1 == rates.len() {
+                            remaining
+                        } else {
+                            self.cost_usd.mul_div(rate, 10_000)
+                        };
+                        remaining -= amount;
+                        (if year == 0 { first_year_months } else { 12 }, amount)
+                    })
+                    .collect()
+            }
+        };
+
+        let mut schedule = Vec::new();
+        for (months, amount) in years {
+            for share in amount.allocate(&vec![1; months as usize]) {
+                schedule.push(Depreciation {
+                    date: month_end(start + Months::new(schedule.len() as u32)),
+                    amount_usd: share,
+                });
+            }
+        }
+        schedule
+    }
+
+    /// Depreciation charged up to and including a date
+    pub fn accumulated_depreciation(&self, fiscal: &FiscalCalendar, date: NaiveDate) -> Usd {
+        self.schedule(fiscal)
+            .iter()
+            .filter(|charge| charge.date <= date)
+            .fold(Usd::ZERO, |total, charge| total + charge.amount_usd)
+    }
+
+    /// Cost less accumulated depreciation at the end of a date
+    pub fn book_value(&self, fiscal: &FiscalCalendar, date: NaiveDate) -> Usd {
+        self.cost_usd - self.accumulated_depreciation(fiscal, date)
+    }
+}
+
+/// Yearly MACRS rates for a recovery period
+fn macrs_rates(life_years: u32) -> Option<&'static [u64]> {
+    MACRS_HALF_YEAR
+        .iter()
+        .find(|(life, _)| *life == life_years)
+        .map(|(_, rates)| *rates)
+}
+
+/// Last day of a date's month
+pub fn month_end(date: NaiveDate) -> NaiveDate {
+    (date.with_day(1).unwrap() + Months::new(1)).pred_opt().unwrap()
+}
+
+/// Months since year 0, for counting the months between two dates
+fn month_index(date: NaiveDate) -> u32 {
+    date.year() as u32 * 12 + date.month0()
+}
+
+/// Monthly depreciation of every asset through a date, as expenses in the
+/// asset's category
+pub fn depreciation_expenses(assets: &[Asset], fiscal: &FiscalCalendar, through: NaiveDate) -> Vec<Expense> {
+    let mut expenses = Vec::new();
+    for asset in assets {
+        let label = match asset.id {
+            Some(id) => format!("asset #{}", id),
+            None => "asset".to_string(),
+        };
+        for charge in asset.schedule(fiscal) {
+            if charge.date > through || charge.amount_usd == Usd::ZERO {
+                continue;
+            }
+            expenses.push(Expense {
+                id: None,
+                date: charge.date.format("%Y-%m-%d").to_string(),
+                vendor: asset.vendor.clone(),
+                category: asset.category.clone(),
+                description: format!("Depreciation: {} ({}, {})", asset.description, label, asset.method),
+                amount_usd: charge.amount_usd,
+                paid_with: DEPRECIATION_PAID_WITH.to_string(),
+                invoice_id: None,
+                validator: asset.validator.clone(),
+                tx_signature: None,
+                amount_lamports: None,
+                paid_date: None,
+                service_start: None,
+                service_end: None,
+                provenance: Provenance::manual(),
+            });
+        }
+    }
+    expenses
+}
+
+/// Split assets across validators given as (vote account, expense share), the
+/// way `expenses::allocate_expenses` splits expenses: assigned assets go to
+/// their validator in full, shared ones are split by cost and salvage value
+pub fn allocate_assets(assets: &[Asset], validators: &[(String, u32)]) -> Vec<Vec<Asset>> {
+    let weights: Vec<u32> = validators.iter().map(|(_, share)| *share).collect();
+    let total_weight: u32 = weights.iter().sum();
+    let mut allocated = vec![Vec::new(); validators.len()];
+
+    for asset in assets {
+        match &asset.validator {
+            Some(vote_account) => {
+                if let Some(index) = validators.iter().position(|(v, _)| v == vote_account) {
+                    allocated[index].push(asset.clone());
+                }
+            }
+            None if validators.len() == 1 => allocated[0].push(asset.clone()),
+            None => {
+                let costs = asset.cost_usd.allocate(&weights);
+                let salvages = asset.salvage_usd.allocate(&weights);
+                for (index, (cost, salvage)) in costs.into_iter().zip(salvages).enumerate() {
+                    if weights[index] == 0 {
+                        continue;
+                    }
+                    let mut portion = asset.clone();
+                    portion.cost_usd = cost;
+                    portion.salvage_usd = salvage;
+                    portion.description = format!("{} (shared {}/{})", asset.description, weights[index], total_weight);
+                    allocated[index].push(portion);
+                }
+            }
+        }
+    }
+
+    allocated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn server(method: DepreciationMethod, life_years: u32, salvage_usd: Usd) -> Asset {
+        Asset {
+            id: Some(1),
+            description: "EPYC server".to_string(),
+            vendor: "Dell".to_string(),
+            category: ExpenseCategory::HARDWARE,
+            purchase_date: "2025-10-20".to_string(),
+            cost_usd: Usd::from_dollars(10_000),
+            salvage_usd,
+            life_years,
+            method,
+            paid_with: "Credit Card".to_string(),
+            validator: None,
+        }
+    }
+
+    fn total(schedule: &[Depreciation]) -> Usd {
+        schedule
+            .iter()
+            .fold(Usd::ZERO, |total, charge| total + charge.amount_usd)
+    }
+
+    #[test]
+    fn test_straight_line_and_declining_balance() {
+        let fiscal = FiscalCalendar::default();
+        let straight = server(DepreciationMethod::StraightLine, 3, Usd::from_dollars(1_000));
+        let schedule = straight.schedule(&fiscal);
+        assert_eq!(schedule.len(), 36);
+        assert_eq!(schedule[0].date, date("2025-10-31"));
+        assert_eq!(schedule[0].amount_usd, Usd::from_dollars(250));
+        assert_eq!(schedule[35].date, date("2028-09-30"));
+        assert_eq!(
+            straight.book_value(&fiscal, date("2025-12-31")),
+            Usd::from_dollars(9_250)
+        );
+        assert_eq!(
+            straight.book_value(&fiscal, date("2030-01-01")),
+            Usd::from_dollars(1_000)
+        );
+
+        // 5-year double declining balance: 40% of book value a year until
+        // straight-line over the remaining life is larger
+        let declining = server(DepreciationMethod::DecliningBalance, 5, Usd::ZERO);
+        let schedule = declining.schedule(&fiscal);
+        let year = |n: usize| total(&schedule[n * 12..(n + 1) * 12]);
+        assert_eq!(year(0), Usd::from_dollars(4_000));
+        assert_eq!(year(1), Usd::from_dollars(2_400));
+        assert_eq!(year(2), Usd::from_dollars(1_440));
+        assert_eq!(year(3), Usd::from_cents(108_000));
+        assert_eq!(year(4), Usd::from_cents(108_000));
+        assert_eq!(total(&schedule), Usd::from_dollars(10_000));
+    }
+
+    #[test]
+    fn test_macrs_follows_fiscal_years() {
+        let asset = server(DepreciationMethod::Macrs, 5, Usd::ZERO);
+        // Calendar year: 20% over October-December, then 32% over 2026
+        let schedule = asset.schedule(&FiscalCalendar::default());
+        assert_eq!(total(&schedule[..3]), Usd::from_dollars(2_000));
+        assert_eq!(total(&schedule[3..15]), Usd::from_dollars(3_200));
+        assert_eq!(schedule.len(), 3 + 5 * 12);
+        assert_eq!(total(&schedule), Usd::from_dollars(10_000));
+
+        // Fiscal year starting in July: the first year runs through June 2026
+        let schedule = asset.schedule(&FiscalCalendar::new(7).unwrap());
+        assert_eq!(total(&schedule[..9]), Usd::from_dollars(2_000));
+        assert_eq!(schedule[8].date, date("2026-06-30"));
+
+        assert!(server(DepreciationMethod::Macrs, 4, Usd::ZERO).validate().is_err());
+        assert!(
+            server(DepreciationMethod::Macrs, 5, Usd::from_dollars(1))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_shared_assets_depreciate_by_share() {
+        let asset = server(DepreciationMethod::StraightLine, 5, Usd::ZERO);
+        let validators = [("vote1".to_string(), 2), ("vote2".to_string(), 1)];
+        let allocated = allocate_assets(std::slice::from_ref(&asset), &validators);
+        assert_eq!(allocated[0][0].cost_usd, Usd::from_cents(666_667));
+        assert_eq!(allocated[1][0].cost_usd, Usd::from_cents(333_333));
+
+        let fiscal = FiscalCalendar::default();
+        let expenses = depreciation_expenses(&allocated[0], &fiscal, date("2025-12-31"));
+        assert_eq!(expenses.len(), 3);
+        assert_eq!(expenses[0].date, "2025-10-31");
+        assert_eq!(expenses[0].paid_with, DEPRECIATION_PAID_WITH);
+        assert_eq!(
+            expenses[0].description,
+            "Depreciation: EPYC server (shared 2/3) (asset #1, straight-line)"
+        );
+        let charged = expenses.iter().fold(Usd::ZERO, |total, e| total + e.amount_usd);
+        assert_eq!(
+            allocated[0][0].book_value(&fiscal, date("2025-12-31")),
+            allocated[0][0].cost_usd - charged
+        );
+    }
+}
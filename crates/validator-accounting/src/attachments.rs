@@ -0,0 +1,274 @@
+//! Receipt and invoice attachments for expenses
+//!
+//! Attached documents are copied into a content-addressed store under the data
+//! directory (`attachments/<first two hex digits>/<sha256>`), so the original
+//! can be moved or deleted and the same PDF attached twice is stored once. The
+//! database only records each attachment's hash and original filename.
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use csv::Writer;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::constants;
+use crate::expenses::Expense;
+
+/// Document attached to an expense
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub expense_id: i64,
+    /// Hex SHA-256 of the contents (the file's name in the store)
+    pub sha256: String,
+    /// Name of the file when it was attached
+    pub filename: String,
+    pub size_bytes: u64,
+    /// UTC, `YYYY-MM-DD HH:MM:SS`
+    pub attached_at: String,
+}
+
+/// Content-addressed document store
+pub struct AttachmentStore {
+    root: PathBuf,
+}
+
+impl AttachmentStore {
+    /// Store in the data directory
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            root: data_dir.join(constants::ATTACHMENTS_DIRNAME),
+        }
+    }
+
+    /// Where a document with this hash is stored
+    pub fn path(&self, sha256: &str) -> PathBuf {
+        self.root.join(&sha256[..2]).join(sha256)
+    }
+
+    /// Copy a file into the store (unless the same contents already are) and
+    /// return an attachment of it to an expense
+    pub fn add(&self, expense_id: i64, file: &Path) -> Result<Attachment> {
+        let contents = std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let filename = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .with_context(|| format!("{} has no file name", file.display()))?
+            .to_string();
+        let sha256 = format!("{:x}", Sha256::digest(&contents));
+
+        let path = self.path(&sha256);
+        if !path.exists() {
+            let dir = path.parent().expect("store paths have a parent");
+            std::fs::create_dir_all(dir)?;
+            // Write under a temporary name so an interrupted copy never sits at the hash's path
+            let partial = dir.join(format!("{}.partial", sha256));
+            std::fs::write(&partial, &contents)?;
+            std::fs::rename(&partial, &path)?;
+        }
+
+        Ok(Attachment {
+            expense_id,
+            sha256,
+            filename,
+            size_bytes: contents.len() as u64,
+            attached_at: String::new(),
+        })
+    }
+
+    /// Read a stored document, checking it still matches its hash
+    pub fn read(&self, sha256: &str) -> Result<Vec<u8>> {
+        let path = self.path(sha256);
+        let contents =
+            std::fs::read(&path).with_context(|| format!("Stored attachment {} is missing", path.display()))?;
+        if format!("{:x}", Sha256::digest(&contents)) != sha256 {
+            anyhow::bail!("Stored attachment {} is corrupt (hash mismatch)", path.display());
+        }
+        Ok(contents)
+    }
+}
+
+/// What a receipts export contained
+#[derive(Debug)]
+pub struct ReceiptsExport {
+    pub expenses: usize,
+    pub receipts: usize,
+    /// Recorded expenses with no attachment
+    pub missing: Vec<i64>,
+}
+
+/// Write a zip archive for the accountant: the expense ledger (with the
+/// archive path of each row's receipts) plus every attached document.
+/// Expenses without an ID (expanded recurring expenses) can't have receipts.
+pub fn export_receipts(
+    path: &Path,
+    expenses: &[Expense],
+    attachments: &HashMap<i64, Vec<Attachment>>,
+    store: &AttachmentStore,
+) -> Result<ReceiptsExport> {
+    let file = std::fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let mut options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    if let Ok(now) = zip::DateTime::try_from(Local::now().naive_local()) {
+        options = options.last_modified_time(now);
+    }
+
+    let mut ledger = Writer::from_writer(Vec::new());
+    ledger.write_record([
+        "ID",
+        "Date",
+        "Vendor",
+        "Category",
+        "Description",
+        "Amount_USD",
+        "Paid_With",
+        "Invoice_ID",
+        "Validator",
+        "Receipts",
+    ])?;
+
+    let mut export = ReceiptsExport {
+        expenses: expenses.len(),
+        receipts: 0,
+        missing: Vec::new(),
+    };
+    let mut used_names = HashSet::new();
+    for expense in expenses {
+        let files = expense
+            .id
+            .and_then(|id| attachments.get(&id))
+            .map_or(&[][..], Vec::as_slice);
+        if let Some(id) = expense.id
+            && files.is_empty()
+        {
+            export.missing.push(id);
+        }
+
+        let mut names = Vec::new();
+        for attachment in files {
+            let name = archive_name(attachment, &mut used_names);
+            zip.start_file(name.as_str(), options)?;
+            zip.write_all(&store.read(&attachment.sha256)?)?;
+            export.receipts += 1;
+            names.push(name);
+        }
+
+        ledger.write_record([
+            &expense.id.map(|id| id.to_string()).unwrap_or_default(),
+            &expense.date,
+            &expense.vendor,
+            &expense.category.to_string(),
+            &expense.description,
+            &expense.amount_usd.to_string(),
+            &expense.paid_with,
+            expense.invoice_id.as_deref().unwrap_or(""),
+            expense.validator.as_deref().unwrap_or("shared"),
+            &names.join("; "),
+        ])?;
+    }
+
+    zip.start_file(constants::RECEIPTS_LEDGER_FILENAME, options)?;
+    zip.write_all(&ledger.into_inner()?)?;
+    zip.finish()?;
+
+    Ok(export)
+}
+
+/// Archive path of a receipt, `receipts/<expense id>-<filename>`, with the
+/// hash prefix added when two receipts of an expense share a filename
+fn archive_name(attachment: &Attachment, used: &mut HashSet<String>) -> String {
+    let name = format!("receipts/{}-{}", attachment.expense_id, attachment.filename);
+    if used.insert(name.clone()) {
+        return name;
+    }
+    let name = format!(
+        "receipts/{}-{}-{}",
+        attachment.expense_id,
+        &attachment.sha256[..8],
+        attachment.filename
+    );
+    used.insert(name.clone());
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expenses::ExpenseCategory;
+    use crate::money::Usd;
+    use crate::provenance::Provenance;
+    use std::io::Read;
+
+    fn expense(id: Option<i64>, vendor: &str) -> Expense {
+        Expense {
+            id,
+            date: "2026-01-05".to_string(),
+            vendor: vendor.to_string(),
+            category: ExpenseCategory::HOSTING,
+            description: "Server".to_string(),
+            amount_usd: Usd::from_cents(50_000),
+            paid_with: "USD".to_string(),
+            invoice_id: None,
+            validator: None,
+            tx_signature: None,
+            amount_lamports: None,
+            paid_date: None,
+            service_start: None,
+            service_end: None,
+            provenance: Provenance::manual(),
+        }
+    }
+
+    #[test]
+    fn test_attach_and_export_receipts() {
+        let dir = std::env::temp_dir().join(format!("validator-accounting-attachments-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let invoice = dir.join("invoice.pdf");
+        std::fs::write(&invoice, b"%PDF-1.4 latitude").unwrap();
+        let credit_note = dir.join("credit").join("invoice.pdf");
+        std::fs::create_dir_all(credit_note.parent().unwrap()).unwrap();
+        std::fs::write(&credit_note, b"%PDF-1.4 credit").unwrap();
+
+        // The same document attached twice is stored once, under its hash
+        let store = AttachmentStore::new(&dir);
+        let first = store.add(1, &invoice).unwrap();
+        assert_eq!(store.add(2, &invoice).unwrap().sha256, first.sha256);
+        assert_eq!(first.filename, "invoice.pdf");
+        assert_eq!(first.size_bytes, 17);
+        std::fs::remove_file(&invoice).unwrap();
+        assert_eq!(store.read(&first.sha256).unwrap(), b"%PDF-1.4 latitude");
+        let second = store.add(1, &credit_note).unwrap();
+
+        let expenses = vec![
+            expense(Some(1), "Latitude"),
+            expense(Some(3), "Cloudflare"),
+            expense(None, "Recurring"),
+        ];
+        let hash_prefix = second.sha256[..8].to_string();
+        let attachments = HashMap::from([(1, vec![first, second])]);
+        let archive = dir.join("receipts.zip");
+        let export = export_receipts(&archive, &expenses, &attachments, &store).unwrap();
+        assert_eq!(export.expenses, 3);
+        assert_eq!(export.receipts, 2);
+        assert_eq!(export.missing, vec![3]);
+
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(&archive).unwrap()).unwrap();
+        let mut ledger = String::new();
+        zip.by_name(constants::RECEIPTS_LEDGER_FILENAME)
+            .unwrap()
+            .read_to_string(&mut ledger)
+            .unwrap();
+        assert!(ledger.contains(&format!(
+            "receipts/1-invoice.pdf; receipts/1-{}-invoice.pdf",
+            hash_prefix
+        )));
+        assert!(zip.by_name("receipts/1-invoice.pdf").is_ok());
+        assert_eq!(zip.len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
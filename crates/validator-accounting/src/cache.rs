@@ -4,34 +4,207 @@
 //! Current/incomplete epochs are always re-fetched.
 //! Expenses are stored persistently for financial tracking.
 
-use anyhow::{Context, Result};
-use sqlx::{FromRow, SqlitePool};
-use std::path::Path;
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use clap::ValueEnum;
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crate::addresses::AddressCategory;
-use crate::expenses::{Expense, ExpenseCategory, RecurringExpense};
+use crate::assets::{Asset, DepreciationMethod};
+use crate::attachments::Attachment;
+use crate::categories::{Categories, Category};
+use crate::cluster::Cluster;
+use crate::config::Config;
+use crate::constants;
+use crate::epochs::EpochBoundary;
+use crate::expenses::{
+    AmountChange, Cadence, Expense, ExpenseCategory, ExpenseChange, ExpenseTable, OccurrenceOverride, RecurringExpense,
+};
 use crate::jito::MevClaim;
 use crate::leader_fees::EpochLeaderFees;
-use crate::prices::PriceCache;
-use crate::transactions::{EpochReward, SolTransfer};
+use crate::money::{Lamports, Usd};
+use crate::prices::{Price, PriceCache};
+use crate::provenance::{DataSource, Provenance};
+use crate::reconcile::{BalanceSnapshot, SnapshotSource};
+use crate::transactions::{EpochReward, SolTransfer, epoch_to_date};
 use crate::vote_costs::EpochVoteCost;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
+/// Tables holding per-validator data, keyed by vote account
+const VALIDATOR_TABLES: &[&str] = &[
+    "epoch_rewards",
+    "leader_fees",
+    "mev_claims",
+    "vote_costs",
+    "sol_transfers",
+    "account_progress",
+];
+
 /// Cache database wrapper
+///
+/// Per-validator tables are read and written for the validator this handle is
+/// scoped to (see `for_validator`); epoch boundaries for that validator's
+/// cluster; expenses, prices and other shared tables are global.
 pub struct Cache {
     pool: SqlitePool,
+    /// Vote account of the validator this handle is scoped to ("" when unscoped)
+    vote_account: String,
+    /// Cluster of that validator (mainnet when unscoped)
+    cluster: Cluster,
+    /// Its epoch schedule, for mapping epoch ranges to slots
+    epoch_schedule: EpochSchedule,
+}
+
+/// A numbered schema change, applied once and recorded in `schema_migrations`
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
 }
 
+/// Schema migrations in the order they're applied. Append new steps with the
+/// next version (and a matching arm in `Cache::apply_migration`); never edit or
+/// renumber a step that has shipped. Steps 2-4 predate versioning and check the
+/// columns they change, so unversioned databases at any stage upgrade cleanly.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create tables, keying per-validator tables by vote account",
+    },
+    Migration {
+        version: 2,
+        description: "Store SOL as lamports and USD as integer micro-dollars",
+    },
+    Migration {
+        version: 3,
+        description: "Add validator assignment to expenses and recurring expenses",
+    },
+    Migration {
+        version: 4,
+        description: "Add unavailable block count to leader fees",
+    },
+    Migration {
+        version: 5,
+        description: "Record the data source of cached rows and expenses",
+    },
+    Migration {
+        version: 6,
+        description: "Add epoch boundaries from each epoch's first block time",
+    },
+    Migration {
+        version: 7,
+        description: "Key epoch boundaries by cluster and add epoch schedules and validator clusters",
+    },
+    Migration {
+        version: 8,
+        description: "Log every expense and recurring expense change to an append-only audit table",
+    },
+    Migration {
+        version: 9,
+        description: "Add receipt and invoice attachments to expenses",
+    },
+    Migration {
+        version: 10,
+        description: "Link SOL-paid expenses to their payment transfer",
+    },
+    Migration {
+        version: 11,
+        description: "Add cadences, amount changes and skipped or overridden charges to recurring expenses",
+    },
+    Migration {
+        version: 12,
+        description: "Store the expense category hierarchy and tax lines",
+    },
+    Migration {
+        version: 13,
+        description: "Add the fixed asset register for depreciation schedules",
+    },
+    Migration {
+        version: 14,
+        description: "Add service periods to expenses for prepaid amortization",
+    },
+];
+
+/// Tables whose changes are logged to `expense_audit`, with the columns recorded.
+/// A migration that adds a column to one of them must recreate its triggers
+/// (see `create_audit_triggers`) for the column to be logged; earlier
+/// migrations skip the columns that don't exist yet.
+const AUDITED_TABLES: [(&str, &[&str]); 2] = [
+    (
+        "expenses",
+        &[
+            "date",
+            "vendor",
+            "category",
+            "description",
+            "amount_usd_micros",
+            "paid_with",
+            "invoice_id",
+            "validator",
+            "source",
+            "tx_signature",
+            "amount_lamports",
+            "service_start",
+            "service_end",
+        ],
+    ),
+    (
+        "recurring_expenses",
+        &[
+            "vendor",
+            "category",
+            "description",
+            "amount_usd_micros",
+            "paid_with",
+            "start_date",
+            "end_date",
+            "validator",
+            "cadence",
+            "billing_day",
+            "prorate",
+        ],
+    ),
+];
+
+/// Recurring expense schedule tables, whose changes are logged under the
+/// recurring expense they belong to (`recurring_id`) with the columns recorded
+const AUDITED_SCHEDULE_TABLES: [(&str, &[&str]); 2] = [
+    ("recurring_expense_amounts", &["effective_date", "amount_usd_micros"]),
+    ("recurring_expense_overrides", &["date", "amount_usd_micros"]),
+];
+
+/// Fixed asset register table, logged to `expense_audit` like the expense
+/// tables (its triggers are created by the migration that adds it)
+const AUDITED_ASSET_TABLE: (&str, &[&str]) = (
+    "fixed_assets",
+    &[
+        "description",
+        "vendor",
+        "category",
+        "purchase_date",
+        "cost_usd_micros",
+        "salvage_usd_micros",
+        "life_years",
+        "method",
+        "paid_with",
+        "validator",
+    ],
+);
+
 /// Row type for epoch rewards query
 #[derive(FromRow)]
 struct EpochRewardRow {
     epoch: i64,
     amount_lamports: i64,
-    amount_sol: f64,
     commission: i64,
     effective_slot: i64,
     date: Option<String>,
+    source: String,
+    fetched_at: String,
 }
 
 /// Row type for leader fees query
@@ -41,9 +214,11 @@ struct LeaderFeesRow {
     leader_slots: i64,
     blocks_produced: i64,
     skipped_slots: i64,
+    unavailable_blocks: i64,
     total_fees_lamports: i64,
-    total_fees_sol: f64,
     date: Option<String>,
+    source: String,
+    fetched_at: String,
 }
 
 /// Row type for MEV claims query
@@ -52,8 +227,11 @@ struct MevClaimRow {
     epoch: i64,
     total_tips_lamports: i64,
     commission_lamports: i64,
-    amount_sol: f64,
+    total_priority_fees_lamports: i64,
+    priority_fee_commission_lamports: i64,
     date: Option<String>,
+    source: String,
+    fetched_at: String,
 }
 
 /// Row type for vote costs query
@@ -62,9 +240,9 @@ struct VoteCostRow {
     epoch: i64,
     vote_count: i64,
     total_fee_lamports: i64,
-    total_fee_sol: f64,
     source: String,
     date: Option<String>,
+    fetched_at: String,
 }
 
 /// Row type for expenses query
@@ -75,9 +253,16 @@ struct ExpenseRow {
     vendor: String,
     category: String,
     description: String,
-    amount_usd: f64,
+    amount_usd_micros: i64,
     paid_with: String,
     invoice_id: Option<String>,
+    validator: Option<String>,
+    tx_signature: Option<String>,
+    amount_lamports: Option<i64>,
+    service_start: Option<String>,
+    service_end: Option<String>,
+    source: String,
+    created_at: String,
 }
 
 /// Row type for recurring expenses query
@@ -87,10 +272,91 @@ struct RecurringExpenseRow {
     vendor: String,
     category: String,
     description: String,
-    amount_usd: f64,
+    amount_usd_micros: i64,
     paid_with: String,
     start_date: String,
     end_date: Option<String>,
+    validator: Option<String>,
+    cadence: String,
+    billing_day: Option<i64>,
+    prorate: bool,
+}
+
+impl From<ExpenseRow> for Expense {
+    fn from(r: ExpenseRow) -> Self {
+        Expense {
+            id: Some(r.id),
+            date: r.date,
+            vendor: r.vendor,
+            category: ExpenseCategory::new(r.category),
+            description: r.description,
+            amount_usd: Usd::from_micros(r.amount_usd_micros),
+            paid_with: r.paid_with,
+            invoice_id: r.invoice_id,
+            validator: r.validator,
+            tx_signature: r.tx_signature,
+            amount_lamports: r.amount_lamports.map(|l| Lamports(l as u64)),
+            paid_date: None,
+            service_start: r.service_start,
+            service_end: r.service_end,
+            provenance: Provenance::cached(&r.source, r.created_at),
+        }
+    }
+}
+
+impl From<RecurringExpenseRow> for RecurringExpense {
+    fn from(r: RecurringExpenseRow) -> Self {
+        RecurringExpense {
+            id: Some(r.id),
+            vendor: r.vendor,
+            category: ExpenseCategory::new(r.category),
+            description: r.description,
+            amount_usd: Usd::from_micros(r.amount_usd_micros),
+            paid_with: r.paid_with,
+            start_date: r.start_date,
+            end_date: r.end_date,
+            validator: r.validator,
+            cadence: Cadence::from_str(&r.cadence, true).unwrap_or_default(),
+            billing_day: r.billing_day.map(|day| day as u32),
+            prorate: r.prorate,
+            amount_changes: Vec::new(),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+/// Row type for fixed assets query
+#[derive(FromRow)]
+struct AssetRow {
+    id: i64,
+    description: String,
+    vendor: String,
+    category: String,
+    purchase_date: String,
+    cost_usd_micros: i64,
+    salvage_usd_micros: i64,
+    life_years: i64,
+    method: String,
+    paid_with: String,
+    validator: Option<String>,
+}
+
+impl From<AssetRow> for Asset {
+    fn from(r: AssetRow) -> Self {
+        Asset {
+            id: Some(r.id),
+            description: r.description,
+            vendor: r.vendor,
+            category: ExpenseCategory::new(r.category),
+            purchase_date: r.purchase_date,
+            cost_usd: Usd::from_micros(r.cost_usd_micros),
+            salvage_usd: Usd::from_micros(r.salvage_usd_micros),
+            life_years: r.life_years as u32,
+            method: DepreciationMethod::from_str(&r.method, true).unwrap_or_default(),
+            paid_with: r.paid_with,
+            validator: r.validator,
+        }
+    }
 }
 
 /// Row type for sol_transfers query
@@ -103,16 +369,24 @@ struct SolTransferRow {
     from_address: String,
     to_address: String,
     amount_lamports: i64,
-    amount_sol: f64,
     from_label: String,
     to_label: String,
     from_category: String,
     to_category: String,
+    source: String,
+    fetched_at: String,
 }
 
 impl Cache {
-    /// Open or create cache database
+    /// Open or create the cache database, applying any pending schema migrations
     pub async fn open(path: &Path) -> Result<Self> {
+        let cache = Self::open_without_migrating(path).await?;
+        cache.migrate(path).await?;
+        Ok(cache)
+    }
+
+    /// Open or create the cache database as-is, without touching its schema
+    pub async fn open_without_migrating(path: &Path) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -133,80 +407,234 @@ impl Cache {
         sqlx::query("PRAGMA journal_mode=WAL").execute(&pool).await?;
         sqlx::query("PRAGMA busy_timeout=5000").execute(&pool).await?;
 
-        let cache = Self { pool };
-        cache.init_schema().await?;
+        Ok(Self {
+            pool,
+            vote_account: String::new(),
+            cluster: Cluster::MainnetBeta,
+            epoch_schedule: Cluster::MainnetBeta.default_epoch_schedule(),
+        })
+    }
 
-        Ok(cache)
+    /// Handle sharing this database, scoped to one validator's cached data
+    pub fn for_validator(&self, config: &Config) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            vote_account: config.vote_account.to_string(),
+            cluster: config.cluster,
+            epoch_schedule: config.epoch_schedule.clone(),
+        }
+    }
+
+    /// Epoch schedule of the validator's cluster
+    pub fn epoch_schedule(&self) -> &EpochSchedule {
+        &self.epoch_schedule
+    }
+
+    // =========================================================================
+    // Schema Migrations
+    // =========================================================================
+
+    /// Highest applied migration (0 for a new database or one created before
+    /// migrations were versioned)
+    pub async fn schema_version(&self) -> Result<i64> {
+        if !self.table_exists("schema_migrations").await? {
+            return Ok(0);
+        }
+        let row: (Option<i64>,) = sqlx::query_as("SELECT MAX(version) FROM schema_migrations")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.0.unwrap_or(0))
+    }
+
+    /// Migrations not yet applied to this database, in order
+    pub async fn pending_migrations(&self) -> Result<Vec<&'static Migration>> {
+        let version = self.schema_version().await?;
+        Ok(MIGRATIONS.iter().filter(|m| m.version > version).collect())
+    }
+
+    /// Apply pending migrations, each in its own transaction. A database that
+    /// already has tables is backed up next to `path` first, since the expense
+    /// tables hold hand-entered data that can't be re-fetched.
+    async fn migrate(&self, path: &Path) -> Result<()> {
+        let pending = self.pending_migrations().await?;
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        if self.has_tables().await? {
+            let backup = self.backup(path).await?;
+            println!(
+                "Backed up cache database to {} before applying {} migration(s)",
+                backup.display(),
+                pending.len()
+            );
+        }
+
+        sqlx::query(
+            "
+            -- Applied schema migrations
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+            ",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        for migration in pending {
+            let mut tx = self.pool.begin().await?;
+            Self::apply_migration(&mut tx, migration.version)
+                .await
+                .with_context(|| format!("Migration {} ({}) failed", migration.version, migration.description))?;
+            sqlx::query("INSERT INTO schema_migrations (version, description) VALUES (?, ?)")
+                .bind(migration.version)
+                .bind(migration.description)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Run one migration's statements
+    async fn apply_migration(conn: &mut SqliteConnection, version: i64) -> Result<()> {
+        match version {
+            1 => Self::create_tables(conn).await,
+            2 => Self::upgrade_money_columns(conn).await,
+            3 => Self::upgrade_expense_validator_columns(conn).await,
+            4 => Self::upgrade_leader_fee_unavailable_column(conn).await,
+            5 => Self::add_source_columns(conn).await,
+            6 => Self::create_epoch_boundaries(conn).await,
+            7 => Self::add_cluster_tables(conn).await,
+            8 => Self::create_expense_audit(conn).await,
+            9 => Self::create_expense_attachments(conn).await,
+            10 => Self::add_expense_payment_columns(conn).await,
+            11 => Self::add_recurrence_schedules(conn).await,
+            12 => Self::create_expense_categories(conn).await,
+            13 => Self::create_fixed_assets(conn).await,
+            14 => Self::add_expense_service_period_columns(conn).await,
+            _ => unreachable!("unknown migration {}", version),
+        }
+    }
+
+    /// Write a consistent copy of the database (including uncheckpointed WAL
+    /// pages) to `<file>.v<version>-<timestamp>.bak`
+    async fn backup(&self, path: &Path) -> Result<PathBuf> {
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("cache.sqlite");
+        let backup = path.with_file_name(format!(
+            "{}.v{}-{}.bak",
+            file_name,
+            self.schema_version().await?,
+            Utc::now().format("%Y%m%dT%H%M%S%.3f")
+        ));
+        sqlx::query("VACUUM INTO ?")
+            .bind(backup.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("Failed to back up cache database to {}", backup.display()))?;
+        Ok(backup)
+    }
+
+    /// Whether the database has any tables (i.e. isn't freshly created)
+    async fn has_tables(&self) -> Result<bool> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.0 > 0)
+    }
+
+    /// Whether a table exists
+    async fn table_exists(&self, table: &str) -> Result<bool> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.0 > 0)
     }
 
-    /// Initialize database schema
-    async fn init_schema(&self) -> Result<()> {
+    /// Migration 1: create every table. Per-validator tables from before
+    /// multi-validator support are set aside and their rows copied into the
+    /// recreated, vote-account-keyed tables.
+    async fn create_tables(conn: &mut SqliteConnection) -> Result<()> {
+        let unscoped = Self::set_aside_unscoped_tables(conn).await?;
+
         sqlx::query(
             "
             -- Commission rewards per epoch
             CREATE TABLE IF NOT EXISTS epoch_rewards (
-                epoch INTEGER PRIMARY KEY,
+                vote_account TEXT NOT NULL,
+                epoch INTEGER NOT NULL,
                 amount_lamports INTEGER NOT NULL,
-                amount_sol REAL NOT NULL,
                 commission INTEGER NOT NULL,
                 effective_slot INTEGER NOT NULL,
                 date TEXT,
-                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (vote_account, epoch)
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
             "
             -- Leader slot fees per epoch
             CREATE TABLE IF NOT EXISTS leader_fees (
-                epoch INTEGER PRIMARY KEY,
+                vote_account TEXT NOT NULL,
+                epoch INTEGER NOT NULL,
                 leader_slots INTEGER NOT NULL,
                 blocks_produced INTEGER NOT NULL,
                 skipped_slots INTEGER NOT NULL,
+                -- Produced blocks whose fees couldn't be fetched; > 0 marks the epoch incomplete
+                unavailable_blocks INTEGER NOT NULL DEFAULT 0,
                 total_fees_lamports INTEGER NOT NULL,
-                total_fees_sol REAL NOT NULL,
                 date TEXT,
-                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (vote_account, epoch)
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
             "
             -- Jito MEV claims per epoch
             CREATE TABLE IF NOT EXISTS mev_claims (
-                epoch INTEGER PRIMARY KEY,
+                vote_account TEXT NOT NULL,
+                epoch INTEGER NOT NULL,
                 total_tips_lamports INTEGER NOT NULL,
                 commission_lamports INTEGER NOT NULL,
-                amount_sol REAL NOT NULL,
+                total_priority_fees_lamports INTEGER NOT NULL DEFAULT 0,
+                priority_fee_commission_lamports INTEGER NOT NULL DEFAULT 0,
                 date TEXT,
-                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (vote_account, epoch)
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
             "
             -- Vote transaction costs per epoch
             CREATE TABLE IF NOT EXISTS vote_costs (
-                epoch INTEGER PRIMARY KEY,
+                vote_account TEXT NOT NULL,
+                epoch INTEGER NOT NULL,
                 vote_count INTEGER NOT NULL,
                 total_fee_lamports INTEGER NOT NULL,
-                total_fee_sol REAL NOT NULL,
                 source TEXT NOT NULL,
                 date TEXT,
-                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (vote_account, epoch)
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
@@ -214,12 +642,12 @@ impl Cache {
             -- Historical SOL prices
             CREATE TABLE IF NOT EXISTS prices (
                 date TEXT PRIMARY KEY,
-                usd_price REAL NOT NULL,
+                usd_price_micros INTEGER NOT NULL,
                 fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
@@ -231,7 +659,7 @@ impl Cache {
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
@@ -243,14 +671,15 @@ impl Cache {
                 vendor TEXT NOT NULL,
                 category TEXT NOT NULL,
                 description TEXT NOT NULL,
-                amount_usd REAL NOT NULL,
+                amount_usd_micros INTEGER NOT NULL,
                 paid_with TEXT NOT NULL,
                 invoice_id TEXT,
+                validator TEXT,
                 created_at TEXT NOT NULL DEFAULT (datetime('now'))
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
@@ -261,21 +690,23 @@ impl Cache {
                 vendor TEXT NOT NULL,
                 category TEXT NOT NULL,
                 description TEXT NOT NULL,
-                amount_usd REAL NOT NULL,
+                amount_usd_micros INTEGER NOT NULL,
                 paid_with TEXT NOT NULL,
                 start_date TEXT NOT NULL,
                 end_date TEXT,
+                validator TEXT,
                 created_at TEXT NOT NULL DEFAULT (datetime('now'))
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query(
             "
             -- SOL transfers (cached per account to avoid re-fetching)
             CREATE TABLE IF NOT EXISTS sol_transfers (
+                vote_account TEXT NOT NULL,
                 signature TEXT NOT NULL,
                 slot INTEGER NOT NULL,
                 timestamp INTEGER,
@@ -283,159 +714,688 @@ impl Cache {
                 from_address TEXT NOT NULL,
                 to_address TEXT NOT NULL,
                 amount_lamports INTEGER NOT NULL,
-                amount_sol REAL NOT NULL,
                 from_label TEXT NOT NULL,
                 to_label TEXT NOT NULL,
                 from_category TEXT NOT NULL,
                 to_category TEXT NOT NULL,
                 account_key TEXT NOT NULL,
                 fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
-                PRIMARY KEY (signature, account_key)
+                PRIMARY KEY (vote_account, signature, account_key)
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         // Index for quick lookups by account
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_transfers_account ON sol_transfers(account_key)")
-            .execute(&self.pool)
-            .await?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_transfers_validator_account ON sol_transfers(vote_account, account_key)",
+        )
+        .execute(&mut *conn)
+        .await?;
 
         sqlx::query(
             "
             -- Track the highest slot checked per account (even if no transfers found)
             CREATE TABLE IF NOT EXISTS account_progress (
-                account_key TEXT PRIMARY KEY,
+                vote_account TEXT NOT NULL,
+                account_key TEXT NOT NULL,
                 highest_slot INTEGER NOT NULL,
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (vote_account, account_key)
             )
             ",
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
-        Ok(())
-    }
-
-    // =========================================================================
-    // Epoch Rewards (Commission)
-    // =========================================================================
-
-    /// Get cached epoch rewards
-    pub async fn get_epoch_rewards(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<EpochReward>> {
-        let rows: Vec<EpochRewardRow> = sqlx::query_as(
-            "SELECT epoch, amount_lamports, amount_sol, commission, effective_slot, date
-             FROM epoch_rewards
-             WHERE epoch >= ? AND epoch <= ?
-             ORDER BY epoch",
+        sqlx::query(
+            "
+            -- Specific-ID lot selections for withdrawals (persistent, not cache)
+            CREATE TABLE IF NOT EXISTS lot_selections (
+                withdrawal_signature TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                lot_id TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (withdrawal_signature, position)
+            )
+            ",
         )
-        .bind(start_epoch as i64)
-        .bind(end_epoch as i64)
-        .fetch_all(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| EpochReward {
-                epoch: r.epoch as u64,
-                amount_lamports: r.amount_lamports as u64,
-                amount_sol: r.amount_sol,
-                commission: r.commission as u8,
-                effective_slot: r.effective_slot as u64,
-                date: r.date,
-            })
-            .collect())
-    }
+        sqlx::query(
+            "
+            -- Account balances at a slot, from getBalance or transaction post-balances
+            CREATE TABLE IF NOT EXISTS balance_snapshots (
+                account TEXT NOT NULL,
+                slot INTEGER NOT NULL,
+                lamports INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (account, slot)
+            )
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
 
-    /// Get epochs that are missing from cache
-    pub async fn get_missing_reward_epochs(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<u64>> {
-        let rows: Vec<(i64,)> = sqlx::query_as("SELECT epoch FROM epoch_rewards WHERE epoch >= ? AND epoch <= ?")
-            .bind(start_epoch as i64)
-            .bind(end_epoch as i64)
-            .fetch_all(&self.pool)
-            .await?;
+        Self::restore_unscoped_tables(conn, &unscoped).await?;
 
-        let cached: Vec<u64> = rows.into_iter().map(|(e,)| e as u64).collect();
+        Ok(())
+    }
 
-        let missing: Vec<u64> = (start_epoch..=end_epoch).filter(|e| !cached.contains(e)).collect();
+    /// Rename per-validator tables created before multi-validator support (no
+    /// `vote_account` column) out of the way so they can be recreated with the new key
+    async fn set_aside_unscoped_tables(conn: &mut SqliteConnection) -> Result<Vec<&'static str>> {
+        let mut unscoped = Vec::new();
+        for &table in VALIDATOR_TABLES {
+            let exists: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+                .bind(table)
+                .fetch_one(&mut *conn)
+                .await?;
+            if exists.0 > 0 && !Self::column_exists(conn, table, "vote_account").await? {
+                sqlx::query(&format!("ALTER TABLE {} RENAME TO {}_unscoped", table, table))
+                    .execute(&mut *conn)
+                    .await?;
+                unscoped.push(table);
+            }
+        }
+        Ok(unscoped)
+    }
 
-        Ok(missing)
+    /// Copy rows from set-aside tables into the recreated ones with an empty vote
+    /// account; `adopt_unscoped_rows` later assigns them to the original validator.
+    /// MEV claims cached before priority fees were tracked are dropped so they are
+    /// re-fetched with priority fee data.
+    async fn restore_unscoped_tables(conn: &mut SqliteConnection, tables: &[&str]) -> Result<()> {
+        for &table in tables {
+            let old = format!("{}_unscoped", table);
+            let columns = match table {
+                "epoch_rewards" => "epoch, amount_lamports, commission, effective_slot, date, fetched_at",
+                "leader_fees" => {
+                    "epoch, leader_slots, blocks_produced, skipped_slots, total_fees_lamports, date, fetched_at"
+                }
+                "mev_claims" if Self::column_exists(conn, &old, "priority_fee_commission_lamports").await? => {
+                    "epoch, total_tips_lamports, commission_lamports, total_priority_fees_lamports, \
+                     priority_fee_commission_lamports, date, fetched_at"
+                }
+                "mev_claims" => "",
+                "vote_costs" => "epoch, vote_count, total_fee_lamports, source, date, fetched_at",
+                "sol_transfers" => {
+                    "signature, slot, timestamp, date, from_address, to_address, amount_lamports, from_label, \
+                     to_label, from_category, to_category, account_key, fetched_at"
+                }
+                "account_progress" => "account_key, highest_slot, updated_at",
+                _ => unreachable!("unknown validator table {}", table),
+            };
+
+            if !columns.is_empty() {
+                sqlx::query(&format!(
+                    "INSERT INTO {table} (vote_account, {columns}) SELECT '', {columns} FROM {old}"
+                ))
+                .execute(&mut *conn)
+                .await?;
+            }
+            sqlx::query(&format!("DROP TABLE {}", old)).execute(&mut *conn).await?;
+        }
+        Ok(())
     }
 
-    /// Store epoch rewards (in a transaction for atomicity)
-    pub async fn store_epoch_rewards(&self, rewards: &[EpochReward]) -> Result<()> {
-        if rewards.is_empty() {
-            return Ok(());
+    /// Migration 3: add the optional validator assignment to expense tables
+    /// created before it existed
+    async fn upgrade_expense_validator_columns(conn: &mut SqliteConnection) -> Result<()> {
+        for table in ["expenses", "recurring_expenses"] {
+            if !Self::column_exists(conn, table, "validator").await? {
+                sqlx::query(&format!("ALTER TABLE {} ADD COLUMN validator TEXT", table))
+                    .execute(&mut *conn)
+                    .await?;
+            }
         }
+        Ok(())
+    }
 
-        let mut tx = self.pool.begin().await?;
+    /// Migration 4: add the unavailable block count to leader fee tables created
+    /// before it existed
+    async fn upgrade_leader_fee_unavailable_column(conn: &mut SqliteConnection) -> Result<()> {
+        if !Self::column_exists(conn, "leader_fees", "unavailable_blocks").await? {
+            sqlx::query("ALTER TABLE leader_fees ADD COLUMN unavailable_blocks INTEGER NOT NULL DEFAULT 0")
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
 
-        for reward in rewards {
-            sqlx::query(
-                "INSERT OR REPLACE INTO epoch_rewards
-                 (epoch, amount_lamports, amount_sol, commission, effective_slot, date)
-                 VALUES (?, ?, ?, ?, ?, ?)",
-            )
-            .bind(reward.epoch as i64)
-            .bind(reward.amount_lamports as i64)
-            .bind(reward.amount_sol)
-            .bind(reward.commission as i64)
-            .bind(reward.effective_slot as i64)
-            .bind(&reward.date)
-            .execute(&mut *tx)
+    /// Migration 5: add a `source` column to every cached table that lacks one.
+    /// Rows cached earlier are `unknown`, except prices equal to the fallback
+    /// price (never a real close) and expenses, which were all entered by hand or
+    /// imported
+    async fn add_source_columns(conn: &mut SqliteConnection) -> Result<()> {
+        for table in ["epoch_rewards", "leader_fees", "mev_claims", "sol_transfers", "prices"] {
+            sqlx::query(&format!(
+                "ALTER TABLE {} ADD COLUMN source TEXT NOT NULL DEFAULT 'unknown'",
+                table
+            ))
+            .execute(&mut *conn)
             .await?;
         }
+        sqlx::query("ALTER TABLE expenses ADD COLUMN source TEXT NOT NULL DEFAULT 'manual'")
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("UPDATE prices SET source = 'fallback' WHERE usd_price_micros = ?")
+            .bind(constants::FALLBACK_SOL_PRICE.micros())
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
 
-        tx.commit().await?;
+    /// Migration 6: epoch start times, shared by every validator like prices
+    async fn create_epoch_boundaries(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            CREATE TABLE epoch_boundaries (
+                epoch INTEGER PRIMARY KEY,
+                first_block_slot INTEGER NOT NULL,
+                block_time INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
         Ok(())
     }
 
-    // =========================================================================
-    // Leader Fees
-    // =========================================================================
+    /// Migration 7: epoch numbers and start times differ between clusters, so
+    /// boundaries are keyed by cluster (existing ones are mainnet's). Each
+    /// cluster's epoch schedule is cached, and each validator's cluster
+    /// recorded so storing boundaries re-dates only that cluster's rows.
+    async fn add_cluster_tables(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            CREATE TABLE epoch_boundaries_v7 (
+                cluster TEXT NOT NULL,
+                epoch INTEGER NOT NULL,
+                first_block_slot INTEGER NOT NULL,
+                block_time INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (cluster, epoch)
+            );
+            INSERT INTO epoch_boundaries_v7 (cluster, epoch, first_block_slot, block_time, source, fetched_at)
+                SELECT 'mainnet-beta', epoch, first_block_slot, block_time, source, fetched_at FROM epoch_boundaries;
+            DROP TABLE epoch_boundaries;
+            ALTER TABLE epoch_boundaries_v7 RENAME TO epoch_boundaries;
+
+            CREATE TABLE epoch_schedules (
+                cluster TEXT PRIMARY KEY,
+                slots_per_epoch INTEGER NOT NULL,
+                leader_schedule_slot_offset INTEGER NOT NULL,
+                warmup INTEGER NOT NULL,
+                first_normal_epoch INTEGER NOT NULL,
+                first_normal_slot INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                fetched_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
 
-    /// Get cached leader fees
-    pub async fn get_leader_fees(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<EpochLeaderFees>> {
-        let rows: Vec<LeaderFeesRow> = sqlx::query_as(
-            "SELECT epoch, leader_slots, blocks_produced, skipped_slots,
-                    total_fees_lamports, total_fees_sol, date
-             FROM leader_fees
-             WHERE epoch >= ? AND epoch <= ?
-             ORDER BY epoch",
+            CREATE TABLE validator_clusters (
+                vote_account TEXT PRIMARY KEY,
+                cluster TEXT NOT NULL
+            );
+            ",
         )
-        .bind(start_epoch as i64)
-        .bind(end_epoch as i64)
-        .fetch_all(&self.pool)
+        .execute(&mut *conn)
         .await?;
-
-        Ok(rows
-            .into_iter()
-            .map(|r| EpochLeaderFees {
-                epoch: r.epoch as u64,
-                leader_slots: r.leader_slots as u64,
-                blocks_produced: r.blocks_produced as u64,
-                skipped_slots: r.skipped_slots as u64,
-                total_fees_lamports: r.total_fees_lamports as u64,
-                total_fees_sol: r.total_fees_sol,
-                date: r.date,
-            })
-            .collect())
+        Ok(())
     }
 
-    /// Get epochs missing leader fee data
-    pub async fn get_missing_leader_fee_epochs(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<u64>> {
-        let rows: Vec<(i64,)> = sqlx::query_as("SELECT epoch FROM leader_fees WHERE epoch >= ? AND epoch <= ?")
-            .bind(start_epoch as i64)
-            .bind(end_epoch as i64)
-            .fetch_all(&self.pool)
-            .await?;
-
-        let cached: Vec<u64> = rows.into_iter().map(|(e,)| e as u64).collect();
+    /// Migration 8: bookkeeping reviews need a trail of every expense change, so
+    /// triggers copy each insert, update and delete into `expense_audit` (which
+    /// itself rejects updates and deletes)
+    async fn create_expense_audit(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            CREATE TABLE expense_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                table_name TEXT NOT NULL,
+                row_id INTEGER NOT NULL,
+                action TEXT NOT NULL,
+                old_values TEXT,
+                new_values TEXT,
+                changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX idx_expense_audit_row ON expense_audit (table_name, row_id);
+
+            CREATE TRIGGER expense_audit_no_update BEFORE UPDATE ON expense_audit
+            BEGIN
+                SELECT RAISE(ABORT, 'expense_audit is append-only');
+            END;
+            CREATE TRIGGER expense_audit_no_delete BEFORE DELETE ON expense_audit
+            BEGIN
+                SELECT RAISE(ABORT, 'expense_audit is append-only');
+            END;
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
 
-        let missing: Vec<u64> = (start_epoch..=end_epoch).filter(|e| !cached.contains(e)).collect();
+        for (table, columns) in AUDITED_TABLES {
+            Self::create_audit_triggers(conn, table, "id", columns).await?;
+        }
+        Ok(())
+    }
 
-        Ok(missing)
+    /// (Re)create the triggers logging a table's changes to `expense_audit`,
+    /// for the listed columns the table has, under the row ID in `row_id`
+    async fn create_audit_triggers(
+        conn: &mut SqliteConnection,
+        table: &str,
+        row_id: &str,
+        columns: &[&str],
+    ) -> Result<()> {
+        let existing: Vec<(String,)> = sqlx::query_as("SELECT name FROM pragma_table_info(?)")
+            .bind(table)
+            .fetch_all(&mut *conn)
+            .await?;
+        let columns: Vec<&str> = columns
+            .iter()
+            .copied()
+            .filter(|c| existing.iter().any(|(name,)| name == c))
+            .collect();
+        let values = |row: &str| {
+            let pairs: Vec<String> = columns.iter().map(|c| format!("'{c}', {row}.{c}")).collect();
+            format!("json_object({})", pairs.join(", "))
+        };
+        let (old, new) = (values("OLD"), values("NEW"));
+        sqlx::query(&format!(
+            "
+            DROP TRIGGER IF EXISTS {table}_audit_insert;
+            DROP TRIGGER IF EXISTS {table}_audit_update;
+            DROP TRIGGER IF EXISTS {table}_audit_delete;
+
+            CREATE TRIGGER {table}_audit_insert AFTER INSERT ON {table}
+            BEGIN
+                INSERT INTO expense_audit (table_name, row_id, action, new_values)
+                VALUES ('{table}', NEW.{row_id}, 'insert', {new});
+            END;
+            CREATE TRIGGER {table}_audit_update AFTER UPDATE ON {table}
+            BEGIN
+                INSERT INTO expense_audit (table_name, row_id, action, old_values, new_values)
+                VALUES ('{table}', NEW.{row_id}, 'update', {old}, {new});
+            END;
+            CREATE TRIGGER {table}_audit_delete AFTER DELETE ON {table}
+            BEGIN
+                INSERT INTO expense_audit (table_name, row_id, action, old_values)
+                VALUES ('{table}', OLD.{row_id}, 'delete', {old});
+            END;
+            "
+        ))
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Migration 9: documents attached to expenses, by content hash (the files
+    /// themselves live in the data directory's attachment store)
+    async fn create_expense_attachments(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            CREATE TABLE expense_attachments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                expense_id INTEGER NOT NULL,
+                sha256 TEXT NOT NULL,
+                filename TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                attached_at TEXT NOT NULL DEFAULT (datetime('now')),
+                UNIQUE(expense_id, sha256)
+            );
+            CREATE INDEX idx_expense_attachments_expense ON expense_attachments (expense_id);
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Migration 10: the transfer (and SOL amount) that paid an expense
+    async fn add_expense_payment_columns(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            ALTER TABLE expenses ADD COLUMN tx_signature TEXT;
+            ALTER TABLE expenses ADD COLUMN amount_lamports INTEGER;
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
+        let (table, columns) = AUDITED_TABLES[0];
+        Self::create_audit_triggers(conn, table, "id", columns).await
+    }
+
+    /// Migration 11: recurrence cadences, billing days and first-charge
+    /// proration on recurring expenses, plus their price history and skipped
+    /// or overridden charges
+    async fn add_recurrence_schedules(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            ALTER TABLE recurring_expenses ADD COLUMN cadence TEXT NOT NULL DEFAULT 'monthly';
+            ALTER TABLE recurring_expenses ADD COLUMN billing_day INTEGER;
+            ALTER TABLE recurring_expenses ADD COLUMN prorate INTEGER NOT NULL DEFAULT 0;
+
+            CREATE TABLE recurring_expense_amounts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recurring_id INTEGER NOT NULL,
+                effective_date TEXT NOT NULL,
+                amount_usd_micros INTEGER NOT NULL,
+                UNIQUE(recurring_id, effective_date)
+            );
+            CREATE TABLE recurring_expense_overrides (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recurring_id INTEGER NOT NULL,
+                date TEXT NOT NULL,
+                amount_usd_micros INTEGER,
+                UNIQUE(recurring_id, date)
+            );
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        let (table, columns) = AUDITED_TABLES[1];
+        Self::create_audit_triggers(conn, table, "id", columns).await?;
+        for (table, columns) in AUDITED_SCHEDULE_TABLES {
+            Self::create_audit_triggers(conn, table, "recurring_id", columns).await?;
+        }
+        Ok(())
+    }
+
+    /// Migration 12: known expense categories (`Parent:Child` paths) with the
+    /// tax line each is reported on
+    async fn create_expense_categories(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            CREATE TABLE expense_categories (
+                name TEXT PRIMARY KEY,
+                tax_line TEXT,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Migration 13: the fixed asset register (capital purchases depreciated
+    /// over their useful life), with its changes audited
+    async fn create_fixed_assets(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            CREATE TABLE fixed_assets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                description TEXT NOT NULL,
+                vendor TEXT NOT NULL,
+                category TEXT NOT NULL,
+                purchase_date TEXT NOT NULL,
+                cost_usd_micros INTEGER NOT NULL,
+                salvage_usd_micros INTEGER NOT NULL DEFAULT 0,
+                life_years INTEGER NOT NULL,
+                method TEXT NOT NULL,
+                paid_with TEXT NOT NULL,
+                validator TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        let (table, columns) = AUDITED_ASSET_TABLE;
+        Self::create_audit_triggers(conn, table, "id", columns).await
+    }
+
+    /// Migration 14: the service period a prepaid expense covers
+    async fn add_expense_service_period_columns(conn: &mut SqliteConnection) -> Result<()> {
+        sqlx::query(
+            "
+            ALTER TABLE expenses ADD COLUMN service_start TEXT;
+            ALTER TABLE expenses ADD COLUMN service_end TEXT;
+            ",
+        )
+        .execute(&mut *conn)
+        .await?;
+        let (table, columns) = AUDITED_TABLES[0];
+        Self::create_audit_triggers(conn, table, "id", columns).await
+    }
+
+    /// Migration 2: convert databases created before exact money types: drop the
+    /// redundant REAL SOL columns (lamports are already stored) and move REAL USD
+    /// amounts to integer micro-dollar columns
+    async fn upgrade_money_columns(conn: &mut SqliteConnection) -> Result<()> {
+        for (table, column) in [
+            ("epoch_rewards", "amount_sol"),
+            ("leader_fees", "total_fees_sol"),
+            ("mev_claims", "amount_sol"),
+            ("vote_costs", "total_fee_sol"),
+            ("sol_transfers", "amount_sol"),
+        ] {
+            if Self::column_exists(conn, table, column).await? {
+                sqlx::query(&format!("ALTER TABLE {} DROP COLUMN {}", table, column))
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+
+        for (table, old, new) in [
+            ("prices", "usd_price", "usd_price_micros"),
+            ("expenses", "amount_usd", "amount_usd_micros"),
+            ("recurring_expenses", "amount_usd", "amount_usd_micros"),
+        ] {
+            if !Self::column_exists(conn, table, old).await? {
+                continue;
+            }
+            sqlx::query(&format!(
+                "ALTER TABLE {} ADD COLUMN {} INTEGER NOT NULL DEFAULT 0",
+                table, new
+            ))
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query(&format!(
+                "UPDATE {} SET {} = CAST(ROUND({} * 1000000) AS INTEGER)",
+                table, new, old
+            ))
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query(&format!("ALTER TABLE {} DROP COLUMN {}", table, old))
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a table has a column
+    async fn column_exists(conn: &mut SqliteConnection, table: &str, column: &str) -> Result<bool> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_one(&mut *conn)
+            .await?;
+        Ok(row.0 > 0)
+    }
+
+    // =========================================================================
+    // Multi-validator Support
+    // =========================================================================
+
+    /// Assign rows cached before multi-validator support to `vote_account` (the
+    /// validator from the legacy `[validator]` block). No-op once adopted.
+    pub async fn adopt_unscoped_rows(&self, vote_account: &Pubkey) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for table in VALIDATOR_TABLES {
+            sqlx::query(&format!(
+                "UPDATE OR IGNORE {} SET vote_account = ? WHERE vote_account = ''",
+                table
+            ))
+            .bind(vote_account.to_string())
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query(&format!("DELETE FROM {} WHERE vote_account = ''", table))
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Record which cluster each configured validator (vote account) runs on.
+    /// Validators never recorded are treated as mainnet.
+    pub async fn record_validator_clusters(
+        &self,
+        validators: impl IntoIterator<Item = (Pubkey, Cluster)>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (vote_account, cluster) in validators {
+            sqlx::query("INSERT OR REPLACE INTO validator_clusters (vote_account, cluster) VALUES (?, ?)")
+                .bind(vote_account.to_string())
+                .bind(cluster.as_str())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // Epoch Rewards (Commission)
+    // =========================================================================
+
+    /// Get cached epoch rewards
+    pub async fn get_epoch_rewards(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<EpochReward>> {
+        let rows: Vec<EpochRewardRow> = sqlx::query_as(
+            "SELECT epoch, amount_lamports, commission, effective_slot, date, source, fetched_at
+             FROM epoch_rewards
+             WHERE vote_account = ? AND epoch >= ? AND epoch <= ?
+             ORDER BY epoch",
+        )
+        .bind(&self.vote_account)
+        .bind(start_epoch as i64)
+        .bind(end_epoch as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| EpochReward {
+                epoch: r.epoch as u64,
+                amount_lamports: Lamports(r.amount_lamports as u64),
+                commission: r.commission as u8,
+                effective_slot: r.effective_slot as u64,
+                date: r.date,
+                provenance: Provenance::cached(&r.source, r.fetched_at),
+            })
+            .collect())
+    }
+
+    /// Get epochs that are missing from cache
+    pub async fn get_missing_reward_epochs(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<u64>> {
+        let rows: Vec<(i64,)> =
+            sqlx::query_as("SELECT epoch FROM epoch_rewards WHERE vote_account = ? AND epoch >= ? AND epoch <= ?")
+                .bind(&self.vote_account)
+                .bind(start_epoch as i64)
+                .bind(end_epoch as i64)
+                .fetch_all(&self.pool)
+                .await?;
+
+        let cached: Vec<u64> = rows.into_iter().map(|(e,)| e as u64).collect();
+
+        let missing: Vec<u64> = (start_epoch..=end_epoch).filter(|e| !cached.contains(e)).collect();
+
+        Ok(missing)
+    }
+
+    /// Store epoch rewards (in a transaction for atomicity)
+    pub async fn store_epoch_rewards(&self, rewards: &[EpochReward]) -> Result<()> {
+        if rewards.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        for reward in rewards {
+            sqlx::query(
+                "INSERT OR REPLACE INTO epoch_rewards
+                 (vote_account, epoch, amount_lamports, commission, effective_slot, date, source, fetched_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, COALESCE(?, datetime('now')))",
+            )
+            .bind(&self.vote_account)
+            .bind(reward.epoch as i64)
+            .bind(reward.amount_lamports.0 as i64)
+            .bind(reward.commission as i64)
+            .bind(reward.effective_slot as i64)
+            .bind(&reward.date)
+            .bind(reward.provenance.source.as_str())
+            .bind(&reward.provenance.fetched_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        Self::apply_epoch_boundaries(&mut tx, "epoch_rewards").await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // Leader Fees
+    // =========================================================================
+
+    /// Get cached leader fees
+    pub async fn get_leader_fees(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<EpochLeaderFees>> {
+        let rows: Vec<LeaderFeesRow> = sqlx::query_as(
+            "SELECT epoch, leader_slots, blocks_produced, skipped_slots,
+                    unavailable_blocks, total_fees_lamports, date, source, fetched_at
+             FROM leader_fees
+             WHERE vote_account = ? AND epoch >= ? AND epoch <= ?
+             ORDER BY epoch",
+        )
+        .bind(&self.vote_account)
+        .bind(start_epoch as i64)
+        .bind(end_epoch as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| EpochLeaderFees {
+                epoch: r.epoch as u64,
+                leader_slots: r.leader_slots as u64,
+                blocks_produced: r.blocks_produced as u64,
+                skipped_slots: r.skipped_slots as u64,
+                unavailable_blocks: r.unavailable_blocks as u64,
+                total_fees_lamports: Lamports(r.total_fees_lamports as u64),
+                date: r.date,
+                provenance: Provenance::cached(&r.source, r.fetched_at),
+            })
+            .collect())
+    }
+
+    /// Get epochs missing leader fee data (including incomplete epochs with unavailable blocks)
+    pub async fn get_missing_leader_fee_epochs(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<u64>> {
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            "SELECT epoch FROM leader_fees
+             WHERE vote_account = ? AND epoch >= ? AND epoch <= ? AND unavailable_blocks = 0",
+        )
+        .bind(&self.vote_account)
+        .bind(start_epoch as i64)
+        .bind(end_epoch as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let cached: Vec<u64> = rows.into_iter().map(|(e,)| e as u64).collect();
+
+        let missing: Vec<u64> = (start_epoch..=end_epoch).filter(|e| !cached.contains(e)).collect();
+
+        Ok(missing)
     }
 
     /// Store leader fees (in a transaction for atomicity)
@@ -449,20 +1409,25 @@ impl Cache {
         for fee in fees {
             sqlx::query(
                 "INSERT OR REPLACE INTO leader_fees
-                 (epoch, leader_slots, blocks_produced, skipped_slots, total_fees_lamports, total_fees_sol, date)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                 (vote_account, epoch, leader_slots, blocks_produced, skipped_slots, unavailable_blocks,
+                  total_fees_lamports, date, source, fetched_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, datetime('now')))",
             )
+            .bind(&self.vote_account)
             .bind(fee.epoch as i64)
             .bind(fee.leader_slots as i64)
             .bind(fee.blocks_produced as i64)
             .bind(fee.skipped_slots as i64)
-            .bind(fee.total_fees_lamports as i64)
-            .bind(fee.total_fees_sol)
+            .bind(fee.unavailable_blocks as i64)
+            .bind(fee.total_fees_lamports.0 as i64)
             .bind(&fee.date)
+            .bind(fee.provenance.source.as_str())
+            .bind(&fee.provenance.fetched_at)
             .execute(&mut *tx)
             .await?;
         }
 
+        Self::apply_epoch_boundaries(&mut tx, "leader_fees").await?;
         tx.commit().await?;
         Ok(())
     }
@@ -474,11 +1439,13 @@ impl Cache {
     /// Get cached MEV claims
     pub async fn get_mev_claims(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<MevClaim>> {
         let rows: Vec<MevClaimRow> = sqlx::query_as(
-            "SELECT epoch, total_tips_lamports, commission_lamports, amount_sol, date
+            "SELECT epoch, total_tips_lamports, commission_lamports,
+                    total_priority_fees_lamports, priority_fee_commission_lamports, date, source, fetched_at
              FROM mev_claims
-             WHERE epoch >= ? AND epoch <= ?
+             WHERE vote_account = ? AND epoch >= ? AND epoch <= ?
              ORDER BY epoch",
         )
+        .bind(&self.vote_account)
         .bind(start_epoch as i64)
         .bind(end_epoch as i64)
         .fetch_all(&self.pool)
@@ -488,10 +1455,12 @@ impl Cache {
             .into_iter()
             .map(|r| MevClaim {
                 epoch: r.epoch as u64,
-                total_tips_lamports: r.total_tips_lamports as u64,
-                commission_lamports: r.commission_lamports as u64,
-                amount_sol: r.amount_sol,
+                total_tips_lamports: Lamports(r.total_tips_lamports as u64),
+                commission_lamports: Lamports(r.commission_lamports as u64),
+                total_priority_fees_lamports: Lamports(r.total_priority_fees_lamports as u64),
+                priority_fee_commission_lamports: Lamports(r.priority_fee_commission_lamports as u64),
                 date: r.date,
+                provenance: Provenance::cached(&r.source, r.fetched_at),
             })
             .collect())
     }
@@ -507,18 +1476,24 @@ impl Cache {
         for claim in claims {
             sqlx::query(
                 "INSERT OR REPLACE INTO mev_claims
-                 (epoch, total_tips_lamports, commission_lamports, amount_sol, date)
-                 VALUES (?, ?, ?, ?, ?)",
+                 (vote_account, epoch, total_tips_lamports, commission_lamports,
+                  total_priority_fees_lamports, priority_fee_commission_lamports, date, source, fetched_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, datetime('now')))",
             )
+            .bind(&self.vote_account)
             .bind(claim.epoch as i64)
-            .bind(claim.total_tips_lamports as i64)
-            .bind(claim.commission_lamports as i64)
-            .bind(claim.amount_sol)
+            .bind(claim.total_tips_lamports.0 as i64)
+            .bind(claim.commission_lamports.0 as i64)
+            .bind(claim.total_priority_fees_lamports.0 as i64)
+            .bind(claim.priority_fee_commission_lamports.0 as i64)
             .bind(&claim.date)
+            .bind(claim.provenance.source.as_str())
+            .bind(&claim.provenance.fetched_at)
             .execute(&mut *tx)
             .await?;
         }
 
+        Self::apply_epoch_boundaries(&mut tx, "mev_claims").await?;
         tx.commit().await?;
         Ok(())
     }
@@ -530,11 +1505,12 @@ impl Cache {
     /// Get cached vote costs
     pub async fn get_vote_costs(&self, start_epoch: u64, end_epoch: u64) -> Result<Vec<EpochVoteCost>> {
         let rows: Vec<VoteCostRow> = sqlx::query_as(
-            "SELECT epoch, vote_count, total_fee_lamports, total_fee_sol, source, date
+            "SELECT epoch, vote_count, total_fee_lamports, source, date, fetched_at
              FROM vote_costs
-             WHERE epoch >= ? AND epoch <= ?
+             WHERE vote_account = ? AND epoch >= ? AND epoch <= ?
              ORDER BY epoch",
         )
+        .bind(&self.vote_account)
         .bind(start_epoch as i64)
         .bind(end_epoch as i64)
         .fetch_all(&self.pool)
@@ -545,10 +1521,9 @@ impl Cache {
             .map(|r| EpochVoteCost {
                 epoch: r.epoch as u64,
                 vote_count: r.vote_count as u64,
-                total_fee_lamports: r.total_fee_lamports as u64,
-                total_fee_sol: r.total_fee_sol,
-                source: r.source,
+                total_fee_lamports: Lamports(r.total_fee_lamports as u64),
                 date: r.date,
+                provenance: Provenance::cached(&r.source, r.fetched_at),
             })
             .collect())
     }
@@ -564,34 +1539,167 @@ impl Cache {
         for cost in costs {
             sqlx::query(
                 "INSERT OR REPLACE INTO vote_costs
-                 (epoch, vote_count, total_fee_lamports, total_fee_sol, source, date)
-                 VALUES (?, ?, ?, ?, ?, ?)",
+                 (vote_account, epoch, vote_count, total_fee_lamports, source, date, fetched_at)
+                 VALUES (?, ?, ?, ?, ?, ?, COALESCE(?, datetime('now')))",
             )
+            .bind(&self.vote_account)
             .bind(cost.epoch as i64)
             .bind(cost.vote_count as i64)
-            .bind(cost.total_fee_lamports as i64)
-            .bind(cost.total_fee_sol)
-            .bind(&cost.source)
+            .bind(cost.total_fee_lamports.0 as i64)
+            .bind(cost.provenance.source.as_str())
             .bind(&cost.date)
+            .bind(&cost.provenance.fetched_at)
             .execute(&mut *tx)
             .await?;
         }
 
+        Self::apply_epoch_boundaries(&mut tx, "vote_costs").await?;
         tx.commit().await?;
         Ok(())
     }
 
+    // =========================================================================
+    // Epoch Boundaries
+    // =========================================================================
+
+    /// Get every cached epoch boundary of this validator's cluster
+    pub async fn get_epoch_boundaries(&self) -> Result<Vec<EpochBoundary>> {
+        let rows: Vec<(i64, i64, i64, String, String)> = sqlx::query_as(
+            "SELECT epoch, first_block_slot, block_time, source, fetched_at
+             FROM epoch_boundaries
+             WHERE cluster = ?
+             ORDER BY epoch",
+        )
+        .bind(self.cluster.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(epoch, first_block_slot, block_time, source, fetched_at)| EpochBoundary {
+                    epoch: epoch as u64,
+                    first_block_slot: first_block_slot as u64,
+                    block_time,
+                    provenance: Provenance::cached(&source, fetched_at),
+                },
+            )
+            .collect())
+    }
+
+    /// Store epoch boundaries of this validator's cluster and re-date the cached
+    /// rows of every validator on it for those epochs
+    pub async fn store_epoch_boundaries(&self, boundaries: &[EpochBoundary]) -> Result<()> {
+        if boundaries.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        for boundary in boundaries {
+            sqlx::query(
+                "INSERT OR REPLACE INTO epoch_boundaries
+                 (cluster, epoch, first_block_slot, block_time, source, fetched_at)
+                 VALUES (?, ?, ?, ?, ?, COALESCE(?, datetime('now')))",
+            )
+            .bind(self.cluster.as_str())
+            .bind(boundary.epoch as i64)
+            .bind(boundary.first_block_slot as i64)
+            .bind(boundary.block_time)
+            .bind(boundary.provenance.source.as_str())
+            .bind(&boundary.provenance.fetched_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for table in EPOCH_DATED_TABLES {
+            Self::apply_epoch_boundaries(&mut tx, table).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Set the date of a per-epoch table's rows to the day their epoch started
+    /// on their validator's cluster, for epochs with a known boundary
+    async fn apply_epoch_boundaries(conn: &mut SqliteConnection, table: &str) -> Result<()> {
+        sqlx::query(&format!(
+            "UPDATE {table} SET date = date(b.block_time, 'unixepoch')
+             FROM epoch_boundaries b
+             WHERE b.epoch = {table}.epoch
+               AND b.cluster = COALESCE(
+                   (SELECT cluster FROM validator_clusters v WHERE v.vote_account = {table}.vote_account),
+                   'mainnet-beta')
+               AND {table}.date IS NOT date(b.block_time, 'unixepoch')"
+        ))
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Cached epoch schedule of a cluster
+    pub async fn get_epoch_schedule(&self, cluster: Cluster) -> Result<Option<EpochSchedule>> {
+        let row: Option<(i64, i64, bool, i64, i64)> = sqlx::query_as(
+            "SELECT slots_per_epoch, leader_schedule_slot_offset, warmup, first_normal_epoch, first_normal_slot
+             FROM epoch_schedules
+             WHERE cluster = ?",
+        )
+        .bind(cluster.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(
+            |(slots_per_epoch, leader_schedule_slot_offset, warmup, first_normal_epoch, first_normal_slot)| {
+                EpochSchedule {
+                    slots_per_epoch: slots_per_epoch as u64,
+                    leader_schedule_slot_offset: leader_schedule_slot_offset as u64,
+                    warmup,
+                    first_normal_epoch: first_normal_epoch as u64,
+                    first_normal_slot: first_normal_slot as u64,
+                }
+            },
+        ))
+    }
+
+    /// Store a cluster's epoch schedule (fetched from its RPC)
+    pub async fn store_epoch_schedule(&self, cluster: Cluster, schedule: &EpochSchedule) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO epoch_schedules
+             (cluster, slots_per_epoch, leader_schedule_slot_offset, warmup, first_normal_epoch, first_normal_slot, source)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(cluster.as_str())
+        .bind(schedule.slots_per_epoch as i64)
+        .bind(schedule.leader_schedule_slot_offset as i64)
+        .bind(schedule.warmup)
+        .bind(schedule.first_normal_epoch as i64)
+        .bind(schedule.first_normal_slot as i64)
+        .bind(DataSource::Rpc.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     // =========================================================================
     // Prices
     // =========================================================================
 
     /// Get cached prices
     pub async fn get_prices(&self) -> Result<PriceCache> {
-        let rows: Vec<(String, f64)> = sqlx::query_as("SELECT date, usd_price FROM prices")
-            .fetch_all(&self.pool)
-            .await?;
+        let rows: Vec<(String, i64, String, String)> =
+            sqlx::query_as("SELECT date, usd_price_micros, source, fetched_at FROM prices")
+                .fetch_all(&self.pool)
+                .await?;
 
-        Ok(rows.into_iter().collect())
+        Ok(rows
+            .into_iter()
+            .map(|(date, micros, source, fetched_at)| {
+                let price = Price {
+                    usd: Usd::from_micros(micros),
+                    provenance: Provenance::cached(&source, fetched_at),
+                };
+                (date, price)
+            })
+            .collect())
     }
 
     /// Store prices (in a transaction for atomicity)
@@ -603,11 +1711,16 @@ impl Cache {
         let mut tx = self.pool.begin().await?;
 
         for (date, price) in prices {
-            sqlx::query("INSERT OR REPLACE INTO prices (date, usd_price) VALUES (?, ?)")
-                .bind(date)
-                .bind(price)
-                .execute(&mut *tx)
-                .await?;
+            sqlx::query(
+                "INSERT OR REPLACE INTO prices (date, usd_price_micros, source, fetched_at)
+                 VALUES (?, ?, ?, COALESCE(?, datetime('now')))",
+            )
+            .bind(date)
+            .bind(price.usd.micros())
+            .bind(price.provenance.source.as_str())
+            .bind(&price.provenance.fetched_at)
+            .execute(&mut *tx)
+            .await?;
         }
 
         tx.commit().await?;
@@ -647,77 +1760,146 @@ impl Cache {
     /// Get all expenses
     pub async fn get_expenses(&self) -> Result<Vec<Expense>> {
         let rows: Vec<ExpenseRow> = sqlx::query_as(
-            "SELECT id, date, vendor, category, description, amount_usd, paid_with, invoice_id
+            "SELECT id, date, vendor, category, description, amount_usd_micros, paid_with, invoice_id, validator,
+                    tx_signature, amount_lamports, service_start, service_end, source, created_at
              FROM expenses
              ORDER BY date, id",
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| {
-                let category = match r.category.as_str() {
-                    "Hosting" => ExpenseCategory::Hosting,
-                    "Contractor" => ExpenseCategory::Contractor,
-                    "Hardware" => ExpenseCategory::Hardware,
-                    "Software" => ExpenseCategory::Software,
-                    "VoteFees" => ExpenseCategory::VoteFees,
-                    _ => ExpenseCategory::Other,
-                };
+        Ok(rows.into_iter().map(Expense::from).collect())
+    }
 
-                Expense {
-                    id: Some(r.id),
-                    date: r.date,
-                    vendor: r.vendor,
-                    category,
-                    description: r.description,
-                    amount_usd: r.amount_usd,
-                    paid_with: r.paid_with,
-                    invoice_id: r.invoice_id,
-                }
-            })
-            .collect())
+    /// Get one expense by ID
+    pub async fn get_expense(&self, id: i64) -> Result<Option<Expense>> {
+        let row: Option<ExpenseRow> = sqlx::query_as(
+            "SELECT id, date, vendor, category, description, amount_usd_micros, paid_with, invoice_id, validator,
+                    tx_signature, amount_lamports, service_start, service_end, source, created_at
+             FROM expenses
+             WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Expense::from))
     }
 
     /// Add a new expense, returns the ID
     pub async fn add_expense(&self, expense: &Expense) -> Result<i64> {
-        let category_str = match expense.category {
-            ExpenseCategory::Hosting => "Hosting",
-            ExpenseCategory::Contractor => "Contractor",
-            ExpenseCategory::Hardware => "Hardware",
-            ExpenseCategory::Software => "Software",
-            ExpenseCategory::VoteFees => "VoteFees",
-            ExpenseCategory::Other => "Other",
-        };
-
         let result = sqlx::query(
-            "INSERT INTO expenses (date, vendor, category, description, amount_usd, paid_with, invoice_id)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO expenses
+             (date, vendor, category, description, amount_usd_micros, paid_with, invoice_id, validator,
+              tx_signature, amount_lamports, service_start, service_end, source)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&expense.date)
         .bind(&expense.vendor)
-        .bind(category_str)
+        .bind(expense.category.as_str())
         .bind(&expense.description)
-        .bind(expense.amount_usd)
+        .bind(expense.amount_usd.micros())
         .bind(&expense.paid_with)
         .bind(&expense.invoice_id)
+        .bind(&expense.validator)
+        .bind(&expense.tx_signature)
+        .bind(expense.amount_lamports.map(|l| l.0 as i64))
+        .bind(&expense.service_start)
+        .bind(&expense.service_end)
+        .bind(expense.provenance.source.as_str())
         .execute(&self.pool)
         .await?;
 
         Ok(result.last_insert_rowid())
     }
 
-    /// Delete an expense by ID
+    /// Overwrite an expense's fields (by its ID), keeping its source and
+    /// creation time; returns false if it doesn't exist
+    pub async fn update_expense(&self, expense: &Expense) -> Result<bool> {
+        let id = expense.id.context("Expense has no ID")?;
+        let result = sqlx::query(
+            "UPDATE expenses
+             SET date = ?, vendor = ?, category = ?, description = ?, amount_usd_micros = ?, paid_with = ?,
+                 invoice_id = ?, validator = ?, tx_signature = ?, amount_lamports = ?, service_start = ?,
+                 service_end = ?
+             WHERE id = ?",
+        )
+        .bind(&expense.date)
+        .bind(&expense.vendor)
+        .bind(expense.category.as_str())
+        .bind(&expense.description)
+        .bind(expense.amount_usd.micros())
+        .bind(&expense.paid_with)
+        .bind(&expense.invoice_id)
+        .bind(&expense.validator)
+        .bind(&expense.tx_signature)
+        .bind(expense.amount_lamports.map(|l| l.0 as i64))
+        .bind(&expense.service_start)
+        .bind(&expense.service_end)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete an expense by ID, with its attachment records (stored documents
+    /// are kept, since another expense may share them)
     pub async fn delete_expense(&self, id: i64) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
         let result = sqlx::query("DELETE FROM expenses WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM expense_attachments WHERE expense_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a document attached to an expense; returns false if the same
+    /// contents are already attached to it
+    pub async fn add_attachment(&self, attachment: &Attachment) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO expense_attachments (expense_id, sha256, filename, size_bytes)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(attachment.expense_id)
+        .bind(&attachment.sha256)
+        .bind(&attachment.filename)
+        .bind(attachment.size_bytes as i64)
+        .execute(&self.pool)
+        .await?;
 
         Ok(result.rows_affected() > 0)
     }
 
+    /// Attachments of every expense, by expense ID, in the order attached
+    pub async fn get_attachments(&self) -> Result<HashMap<i64, Vec<Attachment>>> {
+        let rows: Vec<(i64, String, String, i64, String)> = sqlx::query_as(
+            "SELECT expense_id, sha256, filename, size_bytes, attached_at
+             FROM expense_attachments
+             ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut attachments: HashMap<i64, Vec<Attachment>> = HashMap::new();
+        for (expense_id, sha256, filename, size_bytes, attached_at) in rows {
+            attachments.entry(expense_id).or_default().push(Attachment {
+                expense_id,
+                sha256,
+                filename,
+                size_bytes: size_bytes as u64,
+                attached_at,
+            });
+        }
+        Ok(attachments)
+    }
+
     /// Import multiple expenses (for bulk import from CSV)
     pub async fn import_expenses(&self, expenses: &[Expense]) -> Result<usize> {
         let mut count = 0;
@@ -728,6 +1910,139 @@ impl Cache {
         Ok(count)
     }
 
+    // =========================================================================
+    // Fixed Assets
+    // =========================================================================
+
+    /// Get every asset in the register, oldest purchase first
+    pub async fn get_assets(&self) -> Result<Vec<Asset>> {
+        let rows: Vec<AssetRow> = sqlx::query_as(
+            "SELECT id, description, vendor, category, purchase_date, cost_usd_micros, salvage_usd_micros,
+                    life_years, method, paid_with, validator
+             FROM fixed_assets
+             ORDER BY purchase_date, id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Asset::from).collect())
+    }
+
+    /// Get one asset by ID
+    pub async fn get_asset(&self, id: i64) -> Result<Option<Asset>> {
+        let row: Option<AssetRow> = sqlx::query_as(
+            "SELECT id, description, vendor, category, purchase_date, cost_usd_micros, salvage_usd_micros,
+                    life_years, method, paid_with, validator
+             FROM fixed_assets
+             WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Asset::from))
+    }
+
+    /// Add an asset to the register, returns the ID
+    pub async fn add_asset(&self, asset: &Asset) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO fixed_assets
+             (description, vendor, category, purchase_date, cost_usd_micros, salvage_usd_micros, life_years, method,
+              paid_with, validator)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&asset.description)
+        .bind(&asset.vendor)
+        .bind(asset.category.as_str())
+        .bind(&asset.purchase_date)
+        .bind(asset.cost_usd.micros())
+        .bind(asset.salvage_usd.micros())
+        .bind(i64::from(asset.life_years))
+        .bind(asset.method.as_str())
+        .bind(&asset.paid_with)
+        .bind(&asset.validator)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Overwrite an asset's fields (by its ID); returns false if it doesn't exist
+    pub async fn update_asset(&self, asset: &Asset) -> Result<bool> {
+        let id = asset.id.context("Asset has no ID")?;
+        let result = sqlx::query(
+            "UPDATE fixed_assets
+             SET description = ?, vendor = ?, category = ?, purchase_date = ?, cost_usd_micros = ?,
+                 salvage_usd_micros = ?, life_years = ?, method = ?, paid_with = ?, validator = ?
+             WHERE id = ?",
+        )
+        .bind(&asset.description)
+        .bind(&asset.vendor)
+        .bind(asset.category.as_str())
+        .bind(&asset.purchase_date)
+        .bind(asset.cost_usd.micros())
+        .bind(asset.salvage_usd.micros())
+        .bind(i64::from(asset.life_years))
+        .bind(asset.method.as_str())
+        .bind(&asset.paid_with)
+        .bind(&asset.validator)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete an asset by ID
+    pub async fn delete_asset(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM fixed_assets WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // =========================================================================
+    // Expense Categories
+    // =========================================================================
+
+    /// Record categories (from config.toml), updating the tax lines of known
+    /// ones; categories no longer configured are kept for their expenses
+    pub async fn save_categories(&self, categories: &Categories) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for category in categories.iter() {
+            sqlx::query(
+                "INSERT INTO expense_categories (name, tax_line)
+                 VALUES (?, ?)
+                 ON CONFLICT (name) DO UPDATE SET tax_line = excluded.tax_line, updated_at = datetime('now')
+                 WHERE tax_line IS NOT excluded.tax_line",
+            )
+            .bind(category.name.as_str())
+            .bind(&category.tax_line)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Every recorded category, plus the built-in ones
+    pub async fn get_categories(&self) -> Result<Categories> {
+        let rows: Vec<(String, Option<String>)> =
+            sqlx::query_as("SELECT name, tax_line FROM expense_categories ORDER BY name")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(Categories::from_list(
+            rows.into_iter()
+                .map(|(name, tax_line)| Category {
+                    name: ExpenseCategory::new(name),
+                    tax_line,
+                })
+                .collect(),
+        ))
+    }
+
     // =========================================================================
     // Recurring Expenses
     // =========================================================================
@@ -735,77 +2050,233 @@ impl Cache {
     /// Get all recurring expenses
     pub async fn get_recurring_expenses(&self) -> Result<Vec<RecurringExpense>> {
         let rows: Vec<RecurringExpenseRow> = sqlx::query_as(
-            "SELECT id, vendor, category, description, amount_usd, paid_with, start_date, end_date
+            "SELECT id, vendor, category, description, amount_usd_micros, paid_with, start_date, end_date, validator,
+                    cadence, billing_day, prorate
              FROM recurring_expenses
              ORDER BY vendor, start_date",
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| {
-                let category = match r.category.as_str() {
-                    "Hosting" => ExpenseCategory::Hosting,
-                    "Contractor" => ExpenseCategory::Contractor,
-                    "Hardware" => ExpenseCategory::Hardware,
-                    "Software" => ExpenseCategory::Software,
-                    "VoteFees" => ExpenseCategory::VoteFees,
-                    _ => ExpenseCategory::Other,
-                };
-
-                RecurringExpense {
-                    id: Some(r.id),
-                    vendor: r.vendor,
-                    category,
-                    description: r.description,
-                    amount_usd: r.amount_usd,
-                    paid_with: r.paid_with,
-                    start_date: r.start_date,
-                    end_date: r.end_date,
-                }
-            })
-            .collect())
+        self.with_schedules(rows.into_iter().map(RecurringExpense::from).collect())
+            .await
+    }
+
+    /// Get one recurring expense by ID
+    pub async fn get_recurring_expense(&self, id: i64) -> Result<Option<RecurringExpense>> {
+        let row: Option<RecurringExpenseRow> = sqlx::query_as(
+            "SELECT id, vendor, category, description, amount_usd_micros, paid_with, start_date, end_date, validator,
+                    cadence, billing_day, prorate
+             FROM recurring_expenses
+             WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(self.with_schedules(vec![row.into()]).await?.pop())
+    }
+
+    /// Fill in recurring expenses' amount changes and skipped or overridden charges
+    async fn with_schedules(&self, mut recurring: Vec<RecurringExpense>) -> Result<Vec<RecurringExpense>> {
+        let amounts: Vec<(i64, String, i64)> = sqlx::query_as(
+            "SELECT recurring_id, effective_date, amount_usd_micros
+             FROM recurring_expense_amounts
+             ORDER BY effective_date",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let overrides: Vec<(i64, String, Option<i64>)> = sqlx::query_as(
+            "SELECT recurring_id, date, amount_usd_micros
+             FROM recurring_expense_overrides
+             ORDER BY date",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for expense in &mut recurring {
+            expense.amount_changes = amounts
+                .iter()
+                .filter(|(id, _, _)| Some(*id) == expense.id)
+                .map(|(_, effective_date, micros)| AmountChange {
+                    effective_date: effective_date.clone(),
+                    amount_usd: Usd::from_micros(*micros),
+                })
+                .collect();
+            expense.overrides = overrides
+                .iter()
+                .filter(|(id, _, _)| Some(*id) == expense.id)
+                .map(|(_, date, micros)| OccurrenceOverride {
+                    date: date.clone(),
+                    amount_usd: micros.map(Usd::from_micros),
+                })
+                .collect();
+        }
+        Ok(recurring)
     }
 
-    /// Add a new recurring expense, returns the ID
+    /// Add a new recurring expense, returns the ID (amount changes and
+    /// overrides are added separately)
     pub async fn add_recurring_expense(&self, expense: &RecurringExpense) -> Result<i64> {
-        let category_str = match expense.category {
-            ExpenseCategory::Hosting => "Hosting",
-            ExpenseCategory::Contractor => "Contractor",
-            ExpenseCategory::Hardware => "Hardware",
-            ExpenseCategory::Software => "Software",
-            ExpenseCategory::VoteFees => "VoteFees",
-            ExpenseCategory::Other => "Other",
-        };
-
         let result = sqlx::query(
-            "INSERT INTO recurring_expenses (vendor, category, description, amount_usd, paid_with, start_date, end_date)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO recurring_expenses
+             (vendor, category, description, amount_usd_micros, paid_with, start_date, end_date, validator,
+              cadence, billing_day, prorate)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&expense.vendor)
-        .bind(category_str)
+        .bind(expense.category.as_str())
         .bind(&expense.description)
-        .bind(expense.amount_usd)
+        .bind(expense.amount_usd.micros())
         .bind(&expense.paid_with)
         .bind(&expense.start_date)
         .bind(&expense.end_date)
+        .bind(&expense.validator)
+        .bind(expense.cadence.as_str())
+        .bind(expense.billing_day.map(i64::from))
+        .bind(expense.prorate)
         .execute(&self.pool)
         .await?;
 
         Ok(result.last_insert_rowid())
     }
 
-    /// Delete a recurring expense by ID
+    /// Overwrite a recurring expense's fields (by its ID), leaving its amount
+    /// changes and overrides alone; returns false if it doesn't exist
+    pub async fn update_recurring_expense(&self, expense: &RecurringExpense) -> Result<bool> {
+        let id = expense.id.context("Recurring expense has no ID")?;
+        let result = sqlx::query(
+            "UPDATE recurring_expenses
+             SET vendor = ?, category = ?, description = ?, amount_usd_micros = ?, paid_with = ?, start_date = ?,
+                 end_date = ?, validator = ?, cadence = ?, billing_day = ?, prorate = ?
+             WHERE id = ?",
+        )
+        .bind(&expense.vendor)
+        .bind(expense.category.as_str())
+        .bind(&expense.description)
+        .bind(expense.amount_usd.micros())
+        .bind(&expense.paid_with)
+        .bind(&expense.start_date)
+        .bind(&expense.end_date)
+        .bind(&expense.validator)
+        .bind(expense.cadence.as_str())
+        .bind(expense.billing_day.map(i64::from))
+        .bind(expense.prorate)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a recurring expense (and its amount changes and overrides) by ID
     pub async fn delete_recurring_expense(&self, id: i64) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
         let result = sqlx::query("DELETE FROM recurring_expenses WHERE id = ?")
             .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        for (table, _) in AUDITED_SCHEDULE_TABLES {
+            sqlx::query(&format!("DELETE FROM {} WHERE recurring_id = ?", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Charge a recurring expense a new amount from a date on (replacing any
+    /// change already effective that day)
+    pub async fn set_recurring_amount(&self, recurring_id: i64, change: &AmountChange) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO recurring_expense_amounts (recurring_id, effective_date, amount_usd_micros)
+             VALUES (?, ?, ?)
+             ON CONFLICT (recurring_id, effective_date) DO UPDATE SET amount_usd_micros = excluded.amount_usd_micros",
+        )
+        .bind(recurring_id)
+        .bind(&change.effective_date)
+        .bind(change.amount_usd.micros())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the amount change effective on a date; returns false if there is none
+    pub async fn remove_recurring_amount(&self, recurring_id: i64, effective_date: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM recurring_expense_amounts WHERE recurring_id = ? AND effective_date = ?")
+            .bind(recurring_id)
+            .bind(effective_date)
             .execute(&self.pool)
             .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Skip (no amount) or override one charge of a recurring expense
+    pub async fn set_recurring_override(&self, recurring_id: i64, adjustment: &OccurrenceOverride) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO recurring_expense_overrides (recurring_id, date, amount_usd_micros)
+             VALUES (?, ?, ?)
+             ON CONFLICT (recurring_id, date) DO UPDATE SET amount_usd_micros = excluded.amount_usd_micros",
+        )
+        .bind(recurring_id)
+        .bind(&adjustment.date)
+        .bind(adjustment.amount_usd.map(Usd::micros))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
 
+    /// Restore a skipped or overridden charge; returns false if it wasn't
+    pub async fn remove_recurring_override(&self, recurring_id: i64, date: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM recurring_expense_overrides WHERE recurring_id = ? AND date = ?")
+            .bind(recurring_id)
+            .bind(date)
+            .execute(&self.pool)
+            .await?;
         Ok(result.rows_affected() > 0)
     }
 
+    /// Logged changes to one expense, recurring expense or asset, oldest first (rows
+    /// deleted since keep their history)
+    pub async fn expense_history(&self, table: ExpenseTable, id: i64) -> Result<Vec<ExpenseChange>> {
+        let tables = table.logged_tables();
+        let placeholders = vec!["?"; tables.len()].join(", ");
+        let query = format!(
+            "SELECT table_name, action, old_values, new_values, changed_at
+             FROM expense_audit
+             WHERE table_name IN ({}) AND row_id = ?
+             ORDER BY id",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String)>(&query);
+        for table in tables {
+            query = query.bind(*table);
+        }
+        let rows = query.bind(id).fetch_all(&self.pool).await?;
+
+        let parse = |values: Option<String>| -> Result<_> {
+            values
+                .map(|json| serde_json::from_str(&json).context("Invalid audit values"))
+                .transpose()
+        };
+        rows.into_iter()
+            .map(|(table_name, action, old_values, new_values, changed_at)| {
+                Ok(ExpenseChange {
+                    table_name,
+                    action,
+                    changed_at,
+                    old_values: parse(old_values)?,
+                    new_values: parse(new_values)?,
+                })
+            })
+            .collect()
+    }
+
     // =========================================================================
     // SOL Transfers
     // =========================================================================
@@ -814,11 +2285,13 @@ impl Cache {
     pub async fn get_all_transfers(&self) -> Result<Vec<SolTransfer>> {
         let rows: Vec<SolTransferRow> = sqlx::query_as(
             "SELECT DISTINCT signature, slot, timestamp, date, from_address, to_address,
-                    amount_lamports, amount_sol, from_label, to_label,
-                    from_category, to_category
+                    amount_lamports, from_label, to_label,
+                    from_category, to_category, source, fetched_at
              FROM sol_transfers
+             WHERE vote_account = ?
              ORDER BY slot DESC",
         )
+        .bind(&self.vote_account)
         .fetch_all(&self.pool)
         .await?;
 
@@ -840,36 +2313,49 @@ impl Cache {
     /// Get the highest slot we've checked for an account (even if no transfers were found)
     /// This is useful for accounts with only versioned/undecodable transactions
     pub async fn get_account_progress(&self, account_key: &str) -> Result<Option<u64>> {
-        // Check both account_progress table and sol_transfers, use the higher value
         let progress_row: Option<(i64,)> =
-            sqlx::query_as("SELECT highest_slot FROM account_progress WHERE account_key = ?")
+            sqlx::query_as("SELECT highest_slot FROM account_progress WHERE vote_account = ? AND account_key = ?")
+                .bind(&self.vote_account)
                 .bind(account_key)
                 .fetch_optional(&self.pool)
                 .await?;
+        if let Some((slot,)) = progress_row {
+            return Ok(Some(slot as u64));
+        }
 
-        let transfer_row: Option<(i64,)> = sqlx::query_as("SELECT MAX(slot) FROM sol_transfers WHERE account_key = ?")
-            .bind(account_key)
-            .fetch_optional(&self.pool)
-            .await?;
+        // Transfers cached before progress was tracked: resume after the newest one
+        let transfer_row: Option<(Option<i64>,)> =
+            sqlx::query_as("SELECT MAX(slot) FROM sol_transfers WHERE vote_account = ? AND account_key = ?")
+                .bind(&self.vote_account)
+                .bind(account_key)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(transfer_row.and_then(|(s,)| s).filter(|&s| s > 0).map(|s| s as u64))
+    }
 
-        let progress_slot = progress_row.map(|(s,)| s as u64);
-        let transfer_slot = transfer_row.and_then(|(s,)| if s > 0 { Some(s as u64) } else { None });
+    /// All tracked accounts with the highest slot checked, by account key
+    pub async fn get_all_account_progress(&self) -> Result<Vec<(String, u64)>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT account_key, highest_slot FROM account_progress WHERE vote_account = ? ORDER BY account_key",
+        )
+        .bind(&self.vote_account)
+        .fetch_all(&self.pool)
+        .await?;
 
-        Ok(match (progress_slot, transfer_slot) {
-            (Some(p), Some(t)) => Some(p.max(t)),
-            (Some(p), None) => Some(p),
-            (None, Some(t)) => Some(t),
-            (None, None) => None,
-        })
+        Ok(rows.into_iter().map(|(key, slot)| (key, slot as u64)).collect())
     }
 
     /// Store the highest slot we've checked for an account
     pub async fn set_account_progress(&self, account_key: &str, highest_slot: u64) -> Result<()> {
-        sqlx::query("INSERT OR REPLACE INTO account_progress (account_key, highest_slot) VALUES (?, ?)")
-            .bind(account_key)
-            .bind(highest_slot as i64)
-            .execute(&self.pool)
-            .await?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO account_progress (vote_account, account_key, highest_slot) VALUES (?, ?, ?)",
+        )
+        .bind(&self.vote_account)
+        .bind(account_key)
+        .bind(highest_slot as i64)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
@@ -884,24 +2370,131 @@ impl Cache {
         for transfer in transfers {
             sqlx::query(
                 "INSERT OR REPLACE INTO sol_transfers
-                 (signature, slot, timestamp, date, from_address, to_address,
-                  amount_lamports, amount_sol, from_label, to_label,
-                  from_category, to_category, account_key)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                 (vote_account, signature, slot, timestamp, date, from_address, to_address,
+                  amount_lamports, from_label, to_label,
+                  from_category, to_category, account_key, source, fetched_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, datetime('now')))",
             )
+            .bind(&self.vote_account)
             .bind(&transfer.signature)
             .bind(transfer.slot as i64)
             .bind(transfer.timestamp)
             .bind(&transfer.date)
             .bind(transfer.from.to_string())
             .bind(transfer.to.to_string())
-            .bind(transfer.amount_lamports as i64)
-            .bind(transfer.amount_sol)
+            .bind(transfer.amount_lamports.0 as i64)
             .bind(&transfer.from_label)
             .bind(&transfer.to_label)
             .bind(category_to_string(&transfer.from_category))
             .bind(category_to_string(&transfer.to_category))
             .bind(account_key)
+            .bind(transfer.provenance.source.as_str())
+            .bind(&transfer.provenance.fetched_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // Lot Selections
+    // =========================================================================
+
+    /// Get all specific-ID lot selections (withdrawal signature -> lot IDs in order)
+    pub async fn get_lot_selections(&self) -> Result<HashMap<String, Vec<String>>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT withdrawal_signature, lot_id
+             FROM lot_selections
+             ORDER BY withdrawal_signature, position",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut selections: HashMap<String, Vec<String>> = HashMap::new();
+        for (signature, lot_id) in rows {
+            selections.entry(signature).or_default().push(lot_id);
+        }
+        Ok(selections)
+    }
+
+    /// Replace the lot selection for a withdrawal (in a transaction for atomicity)
+    pub async fn set_lot_selection(&self, signature: &str, lot_ids: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM lot_selections WHERE withdrawal_signature = ?")
+            .bind(signature)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, lot_id) in lot_ids.iter().enumerate() {
+            sqlx::query("INSERT INTO lot_selections (withdrawal_signature, position, lot_id) VALUES (?, ?, ?)")
+                .bind(signature)
+                .bind(position as i64)
+                .bind(lot_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Remove the lot selection for a withdrawal
+    pub async fn clear_lot_selection(&self, signature: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM lot_selections WHERE withdrawal_signature = ?")
+            .bind(signature)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // =========================================================================
+    // Balance Snapshots
+    // =========================================================================
+
+    /// Get all balance snapshots, oldest first
+    pub async fn get_balance_snapshots(&self) -> Result<Vec<BalanceSnapshot>> {
+        let rows: Vec<(String, i64, i64, String)> = sqlx::query_as(
+            "SELECT account, slot, lamports, source
+             FROM balance_snapshots
+             ORDER BY slot",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(account, slot, lamports, source)| {
+                Some(BalanceSnapshot {
+                    account: Pubkey::from_str(&account).ok()?,
+                    slot: slot as u64,
+                    lamports: Lamports(lamports as u64),
+                    source: SnapshotSource::parse(&source),
+                })
+            })
+            .collect())
+    }
+
+    /// Store balance snapshots (in a transaction for atomicity)
+    pub async fn store_balance_snapshots(&self, snapshots: &[BalanceSnapshot]) -> Result<()> {
+        if snapshots.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        for snapshot in snapshots {
+            sqlx::query(
+                "INSERT OR REPLACE INTO balance_snapshots (account, slot, lamports, source)
+                 VALUES (?, ?, ?, ?)",
+            )
+            .bind(snapshot.account.to_string())
+            .bind(snapshot.slot as i64)
+            .bind(snapshot.lamports.0 as i64)
+            .bind(snapshot.source.as_str())
             .execute(&mut *tx)
             .await?;
         }
@@ -910,27 +2503,131 @@ impl Cache {
         Ok(())
     }
 
+    // =========================================================================
+    // Cache Management
+    // =========================================================================
+
+    /// Delete cached rows for an epoch range so they are fetched again, returning
+    /// how many rows were removed (rewound, for `account_progress`). Prices are
+    /// matched by the dates the epochs span; transfers by slot, with account
+    /// progress rewound to just before the range so the next fetch walks back
+    /// over it.
+    pub async fn invalidate(&self, table: CacheTable, epochs: &EpochRange) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let removed = match table {
+            CacheTable::EpochRewards | CacheTable::LeaderFees | CacheTable::MevClaims | CacheTable::VoteCosts => {
+                sqlx::query(&format!(
+                    "DELETE FROM {} WHERE vote_account = ? AND epoch >= ? AND epoch <= ?",
+                    table.name()
+                ))
+                .bind(&self.vote_account)
+                .bind(epochs.start as i64)
+                .bind(epochs.end as i64)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+            }
+            CacheTable::Prices => {
+                let (from, to) = epochs.dates();
+                sqlx::query("DELETE FROM prices WHERE date >= ? AND date <= ?")
+                    .bind(from)
+                    .bind(to)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected()
+            }
+            CacheTable::SolTransfers => {
+                let removed =
+                    sqlx::query("DELETE FROM sol_transfers WHERE vote_account = ? AND slot >= ? AND slot <= ?")
+                        .bind(&self.vote_account)
+                        .bind(epochs.first_slot(&self.epoch_schedule) as i64)
+                        .bind(epochs.last_slot(&self.epoch_schedule) as i64)
+                        .execute(&mut *tx)
+                        .await?
+                        .rows_affected();
+                self.rewind_account_progress(&mut tx, epochs).await?;
+                removed
+            }
+            CacheTable::AccountProgress => self.rewind_account_progress(&mut tx, epochs).await?,
+            CacheTable::EpochBoundaries => {
+                sqlx::query("DELETE FROM epoch_boundaries WHERE cluster = ? AND epoch >= ? AND epoch <= ?")
+                    .bind(self.cluster.as_str())
+                    .bind(epochs.start as i64)
+                    .bind(epochs.end as i64)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected()
+            }
+        };
+        tx.commit().await?;
+        Ok(removed)
+    }
+
+    /// Move account progress at or past the start of `epochs` back to the slot
+    /// before it. Accounts whose progress is only implied by cached transfers
+    /// get an explicit row first, since `get_account_progress` would otherwise
+    /// resume after the newest transfer.
+    async fn rewind_account_progress(&self, conn: &mut SqliteConnection, epochs: &EpochRange) -> Result<u64> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO account_progress (vote_account, account_key, highest_slot)
+             SELECT vote_account, account_key, MAX(slot) FROM sol_transfers
+             WHERE vote_account = ? GROUP BY account_key",
+        )
+        .bind(&self.vote_account)
+        .execute(&mut *conn)
+        .await?;
+
+        let rewound = sqlx::query(
+            "UPDATE account_progress SET highest_slot = ?, updated_at = datetime('now')
+             WHERE vote_account = ? AND highest_slot >= ?",
+        )
+        .bind(epochs.first_slot(&self.epoch_schedule).saturating_sub(1) as i64)
+        .bind(&self.vote_account)
+        .bind(epochs.first_slot(&self.epoch_schedule) as i64)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+        Ok(rewound)
+    }
+
+    /// Rebuild the database file, reclaiming space left by deleted rows
+    pub async fn vacuum(&self) -> Result<()> {
+        sqlx::query("VACUUM").execute(&self.pool).await?;
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     // =========================================================================
     // Utilities
     // =========================================================================
 
-    /// Get cache statistics
+    /// Get cache statistics (per-validator tables counted for this handle's validator)
     pub async fn stats(&self) -> Result<CacheStats> {
-        let epoch_rewards: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM epoch_rewards")
+        let epoch_rewards: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM epoch_rewards WHERE vote_account = ?")
+            .bind(&self.vote_account)
             .fetch_one(&self.pool)
             .await?;
-        let leader_fees: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM leader_fees")
+        let leader_fees: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM leader_fees WHERE vote_account = ?")
+            .bind(&self.vote_account)
             .fetch_one(&self.pool)
             .await?;
-        let mev_claims: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM mev_claims")
+        let mev_claims: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM mev_claims WHERE vote_account = ?")
+            .bind(&self.vote_account)
             .fetch_one(&self.pool)
             .await?;
-        let vote_costs: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM vote_costs")
+        let vote_costs: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM vote_costs WHERE vote_account = ?")
+            .bind(&self.vote_account)
             .fetch_one(&self.pool)
             .await?;
         let prices: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM prices")
             .fetch_one(&self.pool)
             .await?;
+        let epoch_boundaries: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM epoch_boundaries WHERE cluster = ?")
+            .bind(self.cluster.as_str())
+            .fetch_one(&self.pool)
+            .await?;
         let expenses: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM expenses")
             .fetch_one(&self.pool)
             .await?;
@@ -938,10 +2635,12 @@ impl Cache {
             .fetch_one(&self.pool)
             .await
             .unwrap_or((0,));
-        let transfers: (i64,) = sqlx::query_as("SELECT COUNT(DISTINCT signature) FROM sol_transfers")
-            .fetch_one(&self.pool)
-            .await
-            .unwrap_or((0,));
+        let transfers: (i64,) =
+            sqlx::query_as("SELECT COUNT(DISTINCT signature) FROM sol_transfers WHERE vote_account = ?")
+                .bind(&self.vote_account)
+                .fetch_one(&self.pool)
+                .await
+                .unwrap_or((0,));
 
         Ok(CacheStats {
             epoch_rewards: epoch_rewards.0 as u64,
@@ -949,6 +2648,7 @@ impl Cache {
             mev_claims: mev_claims.0 as u64,
             vote_costs: vote_costs.0 as u64,
             prices: prices.0 as u64,
+            epoch_boundaries: epoch_boundaries.0 as u64,
             expenses: expenses.0 as u64,
             recurring_expenses: recurring_expenses.0 as u64,
             transfers: transfers.0 as u64,
@@ -972,12 +2672,12 @@ fn row_to_transfer(r: SolTransferRow) -> Option<SolTransfer> {
         date: r.date,
         from,
         to,
-        amount_lamports: r.amount_lamports as u64,
-        amount_sol: r.amount_sol,
+        amount_lamports: Lamports(r.amount_lamports as u64),
         from_label: r.from_label,
         to_label: r.to_label,
         from_category: string_to_category(&r.from_category),
         to_category: string_to_category(&r.to_category),
+        provenance: Provenance::cached(&r.source, r.fetched_at),
     })
 }
 
@@ -1011,6 +2711,96 @@ fn string_to_category(s: &str) -> AddressCategory {
     }
 }
 
+/// Per-epoch tables dated by the start of their epoch
+const EPOCH_DATED_TABLES: [&str; 4] = ["epoch_rewards", "leader_fees", "mev_claims", "vote_costs"];
+
+/// Cached tables that can be inspected, invalidated and refreshed by epoch range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum CacheTable {
+    EpochRewards,
+    LeaderFees,
+    MevClaims,
+    VoteCosts,
+    Prices,
+    SolTransfers,
+    AccountProgress,
+    EpochBoundaries,
+}
+
+impl CacheTable {
+    /// SQLite table name
+    pub fn name(self) -> &'static str {
+        match self {
+            CacheTable::EpochRewards => "epoch_rewards",
+            CacheTable::LeaderFees => "leader_fees",
+            CacheTable::MevClaims => "mev_claims",
+            CacheTable::VoteCosts => "vote_costs",
+            CacheTable::Prices => "prices",
+            CacheTable::SolTransfers => "sol_transfers",
+            CacheTable::AccountProgress => "account_progress",
+            CacheTable::EpochBoundaries => "epoch_boundaries",
+        }
+    }
+}
+
+/// Inclusive epoch range, written "a..b" (or "a" for a single epoch)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl EpochRange {
+    pub fn contains(&self, epoch: u64) -> bool {
+        (self.start..=self.end).contains(&epoch)
+    }
+
+    pub fn first_slot(&self, schedule: &EpochSchedule) -> u64 {
+        schedule.get_first_slot_in_epoch(self.start)
+    }
+
+    pub fn last_slot(&self, schedule: &EpochSchedule) -> u64 {
+        schedule.get_last_slot_in_epoch(self.end)
+    }
+
+    /// First and last day (YYYY-MM-DD) the epochs span, using the approximate
+    /// epoch-to-date calibration
+    pub fn dates(&self) -> (String, String) {
+        (epoch_to_date(self.start), epoch_to_date(self.end + 1))
+    }
+}
+
+impl FromStr for EpochRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = |part: &str| {
+            part.trim()
+                .parse::<u64>()
+                .with_context(|| format!("Invalid epoch '{}' in range '{}'", part, s))
+        };
+        let (start, end) = match s.split_once("..") {
+            Some((start, end)) => (parse(start)?, parse(end)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if start > end {
+            bail!("Epoch range '{}' ends before it starts", s);
+        }
+        Ok(EpochRange { start, end })
+    }
+}
+
+impl std::fmt::Display for EpochRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}..{}", self.start, self.end)
+        }
+    }
+}
+
 /// Cache statistics
 #[derive(Debug)]
 pub struct CacheStats {
@@ -1019,6 +2809,7 @@ pub struct CacheStats {
     pub mev_claims: u64,
     pub vote_costs: u64,
     pub prices: u64,
+    pub epoch_boundaries: u64,
     pub expenses: u64,
     pub recurring_expenses: u64,
     pub transfers: u64,
@@ -1028,15 +2819,585 @@ impl std::fmt::Display for CacheStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} rewards, {} leader fees, {} MEV claims, {} vote costs, {} transfers, {} prices, {} expenses, {} recurring",
+            "{} rewards, {} leader fees, {} MEV claims, {} vote costs, {} transfers, {} prices, {} epoch boundaries, \
+             {} expenses, {} recurring",
             self.epoch_rewards,
             self.leader_fees,
             self.mev_claims,
             self.vote_costs,
             self.transfers,
             self.prices,
+            self.epoch_boundaries,
             self.expenses,
             self.recurring_expenses
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh database path in a per-test temp directory
+    fn temp_db(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("validator-accounting-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("cache.sqlite")
+    }
+
+    fn backups(path: &Path) -> Vec<std::path::PathBuf> {
+        std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "bak"))
+            .collect()
+    }
+
+    /// Handle scoped to a validator on `cluster`, with the cluster's default schedule
+    fn scoped(cache: &Cache, vote_account: &Pubkey, cluster: Cluster) -> Cache {
+        Cache {
+            pool: cache.pool.clone(),
+            vote_account: vote_account.to_string(),
+            cluster,
+            epoch_schedule: cluster.default_epoch_schedule(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_database_migrates_without_backup() {
+        let path = temp_db("new");
+        let cache = Cache::open(&path).await.unwrap();
+
+        assert_eq!(
+            cache.schema_version().await.unwrap(),
+            MIGRATIONS.last().unwrap().version
+        );
+        assert!(cache.pending_migrations().await.unwrap().is_empty());
+        assert!(backups(&path).is_empty());
+
+        // Reopening is a no-op
+        drop(cache);
+        Cache::open(&path).await.unwrap();
+        assert!(backups(&path).is_empty());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_legacy_database_is_backed_up_and_upgraded() {
+        let path = temp_db("legacy");
+        {
+            let legacy = Cache::open_without_migrating(&path).await.unwrap();
+            for statement in [
+                "CREATE TABLE expenses (id INTEGER PRIMARY KEY AUTOINCREMENT, date TEXT NOT NULL, vendor TEXT NOT NULL,
+                 category TEXT NOT NULL, description TEXT NOT NULL, amount_usd REAL NOT NULL, paid_with TEXT NOT NULL,
+                 invoice_id TEXT, created_at TEXT NOT NULL DEFAULT (datetime('now')))",
+                "INSERT INTO expenses (date, vendor, category, description, amount_usd, paid_with)
+                 VALUES ('2025-01-15', 'Latitude', 'Hosting', 'Server', 1234.56, 'Card')",
+                "CREATE TABLE leader_fees (epoch INTEGER PRIMARY KEY, leader_slots INTEGER NOT NULL,
+                 blocks_produced INTEGER NOT NULL, skipped_slots INTEGER NOT NULL, total_fees_lamports INTEGER NOT NULL,
+                 total_fees_sol REAL NOT NULL, date TEXT, fetched_at TEXT NOT NULL DEFAULT (datetime('now')))",
+                "INSERT INTO leader_fees (epoch, leader_slots, blocks_produced, skipped_slots, total_fees_lamports,
+                 total_fees_sol) VALUES (900, 4, 3, 1, 5000, 0.000005)",
+            ] {
+                sqlx::query(statement).execute(&legacy.pool).await.unwrap();
+            }
+
+            let pending = legacy.pending_migrations().await.unwrap();
+            assert_eq!(pending.len(), MIGRATIONS.len());
+            assert_eq!(legacy.schema_version().await.unwrap(), 0);
+        }
+
+        let cache = Cache::open(&path).await.unwrap();
+        assert_eq!(
+            cache.schema_version().await.unwrap(),
+            MIGRATIONS.last().unwrap().version
+        );
+        assert_eq!(backups(&path).len(), 1);
+
+        let expenses = cache.get_expenses().await.unwrap();
+        assert_eq!(expenses.len(), 1);
+        assert_eq!(expenses[0].amount_usd, Usd::from_cents(123_456));
+        assert_eq!(expenses[0].validator, None);
+
+        let vote_account = Pubkey::new_unique();
+        cache.adopt_unscoped_rows(&vote_account).await.unwrap();
+        let fees = scoped(&cache, &vote_account, Cluster::MainnetBeta)
+            .get_leader_fees(900, 900)
+            .await
+            .unwrap();
+        assert_eq!(fees.len(), 1);
+        assert_eq!(fees[0].total_fees_lamports, Lamports(5000));
+        assert_eq!(fees[0].unavailable_blocks, 0);
+
+        // The backup still holds the legacy schema
+        let backup = Cache::open_without_migrating(&backups(&path)[0]).await.unwrap();
+        assert_eq!(backup.pending_migrations().await.unwrap().len(), MIGRATIONS.len());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_parse_epoch_range() {
+        assert_eq!(
+            "880..890".parse::<EpochRange>().unwrap(),
+            EpochRange { start: 880, end: 890 }
+        );
+        assert_eq!(
+            "900".parse::<EpochRange>().unwrap(),
+            EpochRange { start: 900, end: 900 }
+        );
+        assert!("890..880".parse::<EpochRange>().is_err());
+        assert!("880..".parse::<EpochRange>().is_err());
+
+        let range = EpochRange { start: 900, end: 903 };
+        let mainnet = EpochSchedule::without_warmup();
+        assert_eq!(range.first_slot(&mainnet), 900 * 432_000);
+        assert_eq!(range.last_slot(&mainnet), 904 * 432_000 - 1);
+        assert_eq!(range.dates(), ("2025-12-24".to_string(), "2026-01-01".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_provenance_round_trips_and_keeps_fetch_time() {
+        let path = temp_db("provenance");
+        let cache = scoped(
+            &Cache::open(&path).await.unwrap(),
+            &Pubkey::new_unique(),
+            Cluster::MainnetBeta,
+        );
+
+        let reward = EpochReward {
+            epoch: 900,
+            amount_lamports: Lamports(1_000),
+            commission: 5,
+            effective_slot: 0,
+            date: None,
+            provenance: Provenance::new(DataSource::Dune),
+        };
+        cache.store_epoch_rewards(&[reward]).await.unwrap();
+        let cached = cache.get_epoch_rewards(900, 900).await.unwrap().remove(0);
+        assert_eq!(cached.provenance.source, DataSource::Dune);
+        let fetched_at = cached.provenance.fetched_at.clone().expect("fetch time recorded");
+
+        // Re-storing a cached row keeps its original fetch time
+        sqlx::query("UPDATE epoch_rewards SET fetched_at = '2020-01-01 00:00:00'")
+            .execute(&cache.pool)
+            .await
+            .unwrap();
+        let cached = cache.get_epoch_rewards(900, 900).await.unwrap();
+        cache.store_epoch_rewards(&cached).await.unwrap();
+        let restored = cache.get_epoch_rewards(900, 900).await.unwrap().remove(0);
+        assert_eq!(restored.provenance.fetched_at.as_deref(), Some("2020-01-01 00:00:00"));
+        assert_ne!(fetched_at, "2020-01-01 00:00:00");
+
+        let prices = PriceCache::from([(
+            "2025-12-01".to_string(),
+            Price::new(constants::FALLBACK_SOL_PRICE, DataSource::Fallback),
+        )]);
+        cache.store_prices(&prices).await.unwrap();
+        assert_eq!(
+            cache.get_prices().await.unwrap()["2025-12-01"].provenance.source,
+            DataSource::Fallback
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_epoch_boundaries_redate_cached_rows() {
+        let path = temp_db("boundaries");
+        let cache = scoped(
+            &Cache::open(&path).await.unwrap(),
+            &Pubkey::new_unique(),
+            Cluster::MainnetBeta,
+        );
+        let reward = |epoch: u64| EpochReward {
+            epoch,
+            amount_lamports: Lamports(1_000),
+            commission: 5,
+            effective_slot: 0,
+            date: Some(epoch_to_date(epoch)),
+            provenance: Provenance::new(DataSource::Rpc),
+        };
+        cache.store_epoch_rewards(&[reward(904)]).await.unwrap();
+        assert_eq!(
+            cache.get_epoch_rewards(904, 904).await.unwrap()[0].date.as_deref(),
+            Some("2026-01-01")
+        );
+
+        // Epoch 904 actually started at 23:00 on Dec 31
+        let boundary = |epoch: u64, block_time: i64| EpochBoundary {
+            epoch,
+            first_block_slot: epoch * 432_000,
+            block_time,
+            provenance: Provenance::new(DataSource::Rpc),
+        };
+        cache
+            .store_epoch_boundaries(&[boundary(904, 1767222000), boundary(905, 1767394800)])
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.get_epoch_rewards(904, 904).await.unwrap()[0].date.as_deref(),
+            Some("2025-12-31")
+        );
+
+        // Rows stored after the boundary take its date too
+        cache.store_epoch_rewards(&[reward(905)]).await.unwrap();
+        assert_eq!(
+            cache.get_epoch_rewards(905, 905).await.unwrap()[0].date.as_deref(),
+            Some("2026-01-02")
+        );
+        assert_eq!(cache.get_epoch_boundaries().await.unwrap().len(), 2);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_epoch_boundaries_are_per_cluster() {
+        let path = temp_db("clusters");
+        let cache = Cache::open(&path).await.unwrap();
+        let mainnet = scoped(&cache, &Pubkey::new_unique(), Cluster::MainnetBeta);
+        let testnet_vote = Pubkey::new_unique();
+        let testnet = scoped(&cache, &testnet_vote, Cluster::Testnet);
+
+        cache
+            .record_validator_clusters([(testnet_vote, Cluster::Testnet)])
+            .await
+            .unwrap();
+
+        let reward = |epoch: u64| EpochReward {
+            epoch,
+            amount_lamports: Lamports(1_000),
+            commission: 5,
+            effective_slot: 0,
+            date: Some(epoch_to_date(epoch)),
+            provenance: Provenance::new(DataSource::Rpc),
+        };
+        mainnet.store_epoch_rewards(&[reward(904)]).await.unwrap();
+        testnet.store_epoch_rewards(&[reward(904)]).await.unwrap();
+
+        // A testnet boundary for epoch 904 only re-dates the testnet validator's rows
+        let boundary = EpochBoundary {
+            epoch: 904,
+            first_block_slot: 0,
+            block_time: 1764547200,
+            provenance: Provenance::new(DataSource::Rpc),
+        };
+        testnet.store_epoch_boundaries(&[boundary]).await.unwrap();
+        assert_eq!(
+            testnet.get_epoch_rewards(904, 904).await.unwrap()[0].date.as_deref(),
+            Some("2025-12-01")
+        );
+        assert_eq!(
+            mainnet.get_epoch_rewards(904, 904).await.unwrap()[0].date.as_deref(),
+            Some("2026-01-01")
+        );
+        assert!(mainnet.get_epoch_boundaries().await.unwrap().is_empty());
+
+        assert!(cache.get_epoch_schedule(Cluster::Testnet).await.unwrap().is_none());
+        let schedule = Cluster::Testnet.default_epoch_schedule();
+        cache.store_epoch_schedule(Cluster::Testnet, &schedule).await.unwrap();
+        assert_eq!(
+            cache.get_epoch_schedule(Cluster::Testnet).await.unwrap(),
+            Some(schedule)
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_epoch_range() {
+        let path = temp_db("invalidate");
+        let vote_account = Pubkey::new_unique();
+        let cache = scoped(&Cache::open(&path).await.unwrap(), &vote_account, Cluster::MainnetBeta);
+
+        let rewards: Vec<_> = (900..=905)
+            .map(|epoch| EpochReward {
+                epoch,
+                amount_lamports: Lamports(1_000),
+                commission: 5,
+                effective_slot: epoch * 432_000,
+                date: None,
+                provenance: Provenance::new(DataSource::Rpc),
+            })
+            .collect();
+        cache.store_epoch_rewards(&rewards).await.unwrap();
+        let range = EpochRange { start: 901, end: 903 };
+        assert_eq!(cache.invalidate(CacheTable::EpochRewards, &range).await.unwrap(), 3);
+        assert_eq!(
+            cache.get_missing_reward_epochs(900, 905).await.unwrap(),
+            vec![901, 902, 903]
+        );
+
+        // Transfers before, inside and after the range; progress only implied by them
+        let transfers: Vec<_> = [900, 902, 905]
+            .iter()
+            .map(|epoch| SolTransfer {
+                signature: format!("sig-{}", epoch),
+                slot: epoch * 432_000 + 10,
+                timestamp: None,
+                date: None,
+                from: Pubkey::new_unique(),
+                to: vote_account,
+                amount_lamports: Lamports(5),
+                from_label: "from".to_string(),
+                to_label: "to".to_string(),
+                from_category: AddressCategory::Unknown,
+                to_category: AddressCategory::ValidatorSelf,
+                provenance: Provenance::new(DataSource::Rpc),
+            })
+            .collect();
+        cache.store_transfers(&transfers, "vote").await.unwrap();
+        assert_eq!(
+            cache.get_account_progress("vote").await.unwrap(),
+            Some(905 * 432_000 + 10)
+        );
+
+        assert_eq!(cache.invalidate(CacheTable::SolTransfers, &range).await.unwrap(), 1);
+        assert_eq!(cache.get_all_transfers().await.unwrap().len(), 2);
+        assert_eq!(
+            cache.get_account_progress("vote").await.unwrap(),
+            Some(range.first_slot(&cache.epoch_schedule) - 1)
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_expense_changes_are_audited() {
+        let path = temp_db("audit");
+        let cache = Cache::open(&path).await.unwrap();
+        let mut expense = Expense {
+            id: None,
+            date: "2025-12-01".to_string(),
+            vendor: "Latitdue".to_string(),
+            category: ExpenseCategory::HOSTING,
+            description: "Server".to_string(),
+            amount_usd: Usd::from_cents(10_000),
+            paid_with: "USD".to_string(),
+            invoice_id: None,
+            validator: None,
+            tx_signature: None,
+            amount_lamports: None,
+            paid_date: None,
+            service_start: None,
+            service_end: None,
+            provenance: Provenance::manual(),
+        };
+        let id = cache.add_expense(&expense).await.unwrap();
+
+        // Fix the vendor typo and amount in place
+        expense.id = Some(id);
+        expense.vendor = "Latitude".to_string();
+        expense.amount_usd = Usd::from_cents(12_000);
+        assert!(cache.update_expense(&expense).await.unwrap());
+        assert_eq!(cache.get_expense(id).await.unwrap().unwrap().vendor, "Latitude");
+        assert!(cache.delete_expense(id).await.unwrap());
+
+        let history = cache.expense_history(ExpenseTable::Expenses, id).await.unwrap();
+        let actions: Vec<&str> = history.iter().map(|c| c.action.as_str()).collect();
+        assert_eq!(actions, ["insert", "update", "delete"]);
+        assert_eq!(
+            history[1].changed_fields(),
+            [
+                ("amount_usd".to_string(), "100.00".to_string(), "120.00".to_string()),
+                ("vendor".to_string(), "Latitdue".to_string(), "Latitude".to_string()),
+            ]
+        );
+        assert!(
+            cache
+                .expense_history(ExpenseTable::RecurringExpenses, id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // The log itself can't be rewritten
+        assert!(
+            sqlx::query("DELETE FROM expense_audit")
+                .execute(&cache.pool)
+                .await
+                .is_err()
+        );
+        assert!(
+            sqlx::query("UPDATE expense_audit SET action = 'insert'")
+                .execute(&cache.pool)
+                .await
+                .is_err()
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recurring_schedule_round_trips_and_is_audited() {
+        let path = temp_db("recurring");
+        let cache = Cache::open(&path).await.unwrap();
+        let mut domain = RecurringExpense {
+            id: None,
+            vendor: "Namecheap".to_string(),
+            category: ExpenseCategory::SOFTWARE,
+            description: "Domain".to_string(),
+            amount_usd: Usd::from_cents(1_500),
+            paid_with: "Credit Card".to_string(),
+            start_date: "2025-06-10".to_string(),
+            end_date: None,
+            validator: None,
+            cadence: Cadence::Annual,
+            billing_day: None,
+            prorate: false,
+            amount_changes: Vec::new(),
+            overrides: Vec::new(),
+        };
+        let id = cache.add_recurring_expense(&domain).await.unwrap();
+        domain.id = Some(id);
+        domain.billing_day = Some(1);
+        assert!(cache.update_recurring_expense(&domain).await.unwrap());
+
+        let change = AmountChange {
+            effective_date: "2026-06-01".to_string(),
+            amount_usd: Usd::from_cents(1_800),
+        };
+        cache.set_recurring_amount(id, &change).await.unwrap();
+        let skip = OccurrenceOverride {
+            date: "2027-06-01".to_string(),
+            amount_usd: None,
+        };
+        cache.set_recurring_override(id, &skip).await.unwrap();
+
+        let stored = cache.get_recurring_expense(id).await.unwrap().unwrap();
+        assert_eq!(stored.cadence, Cadence::Annual);
+        assert_eq!(stored.billing_day, Some(1));
+        assert_eq!(stored.amount_changes, [change]);
+        assert_eq!(stored.overrides, [skip]);
+
+        let history = cache
+            .expense_history(ExpenseTable::RecurringExpenses, id)
+            .await
+            .unwrap();
+        let subjects: Vec<Option<&str>> = history.iter().map(|c| c.subject()).collect();
+        assert_eq!(subjects, [None, None, Some("amount change"), Some("charge")]);
+        assert_eq!(
+            history[3].changed_fields(),
+            [
+                ("amount_usd".to_string(), "-".to_string(), "skipped".to_string()),
+                ("date".to_string(), "-".to_string(), "2027-06-01".to_string()),
+            ]
+        );
+
+        // Deleting the expense deletes its schedule
+        assert!(cache.delete_recurring_expense(id).await.unwrap());
+        for table in ["recurring_expense_amounts", "recurring_expense_overrides"] {
+            let (rows,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table))
+                .fetch_one(&cache.pool)
+                .await
+                .unwrap();
+            assert_eq!(rows, 0);
+        }
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_attachments_follow_their_expense() {
+        let path = temp_db("attachments");
+        let cache = Cache::open(&path).await.unwrap();
+        let expense = Expense {
+            id: None,
+            date: "2025-12-01".to_string(),
+            vendor: "Latitude".to_string(),
+            category: ExpenseCategory::HOSTING,
+            description: "Server".to_string(),
+            amount_usd: Usd::from_cents(10_000),
+            paid_with: "USD".to_string(),
+            invoice_id: None,
+            validator: None,
+            tx_signature: None,
+            amount_lamports: None,
+            paid_date: None,
+            service_start: None,
+            service_end: None,
+            provenance: Provenance::manual(),
+        };
+        let id = cache.add_expense(&expense).await.unwrap();
+        let attachment = Attachment {
+            expense_id: id,
+            sha256: "ab".repeat(32),
+            filename: "invoice.pdf".to_string(),
+            size_bytes: 1024,
+            attached_at: String::new(),
+        };
+
+        // Attaching the same contents again is a no-op
+        assert!(cache.add_attachment(&attachment).await.unwrap());
+        assert!(!cache.add_attachment(&attachment).await.unwrap());
+        let attachments = cache.get_attachments().await.unwrap();
+        assert_eq!(attachments[&id].len(), 1);
+        assert_eq!(attachments[&id][0].filename, "invoice.pdf");
+
+        assert!(cache.delete_expense(id).await.unwrap());
+        assert!(cache.get_attachments().await.unwrap().is_empty());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_assets_round_trip_and_are_audited() {
+        let path = temp_db("assets");
+        let cache = Cache::open(&path).await.unwrap();
+        let mut asset = Asset {
+            id: None,
+            description: "NVMe drives".to_string(),
+            vendor: "Micron".to_string(),
+            category: ExpenseCategory::HARDWARE,
+            purchase_date: "2025-11-12".to_string(),
+            cost_usd: Usd::from_dollars(3_600),
+            salvage_usd: Usd::ZERO,
+            life_years: 5,
+            method: DepreciationMethod::Macrs,
+            paid_with: "Credit Card".to_string(),
+            validator: None,
+        };
+        let id = cache.add_asset(&asset).await.unwrap();
+        asset.id = Some(id);
+        assert_eq!(cache.get_assets().await.unwrap(), vec![asset.clone()]);
+
+        asset.cost_usd = Usd::from_dollars(3_400);
+        assert!(cache.update_asset(&asset).await.unwrap());
+        assert_eq!(cache.get_asset(id).await.unwrap(), Some(asset));
+        assert!(cache.delete_asset(id).await.unwrap());
+        assert!(cache.get_asset(id).await.unwrap().is_none());
+
+        let history = cache.expense_history(ExpenseTable::Assets, id).await.unwrap();
+        let actions: Vec<&str> = history.iter().map(|change| change.action.as_str()).collect();
+        assert_eq!(actions, ["insert", "update", "delete"]);
+        assert_eq!(
+            history[1].changed_fields(),
+            vec![("cost_usd".to_string(), "3600.00".to_string(), "3400.00".to_string())]
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_categories_outlive_their_config() {
+        let path = temp_db("categories");
+        let cache = Cache::open(&path).await.unwrap();
+        let configured = Categories::from_list(vec![
+            Category {
+                name: ExpenseCategory::new("Hosting:Bandwidth"),
+                tax_line: None,
+            },
+            Category {
+                name: ExpenseCategory::HARDWARE,
+                tax_line: Some("Form 4562".to_string()),
+            },
+        ]);
+        cache.save_categories(&configured).await.unwrap();
+        // A later config without the subcategory doesn't forget it
+        cache.save_categories(&Categories::default()).await.unwrap();
+
+        let categories = cache.get_categories().await.unwrap();
+        let bandwidth = categories.resolve("Hosting:Bandwidth").unwrap();
+        assert_eq!(
+            categories.tax_line(&bandwidth),
+            Some("Schedule C line 20b: Rent or lease of other business property")
+        );
+        assert_eq!(
+            categories.tax_line(&ExpenseCategory::HARDWARE),
+            Some("Schedule C line 13: Depreciation and section 179 expense")
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+//! Expense categories: the built-in ones plus a configurable hierarchy
+//!
+//! Categories are named by their path (`Parent:Child` for subcategories) and
+//! map to the tax form line their expenses are reported on; subcategories
+//! without a line of their own use their parent's. `[categories]` in
+//! config.toml adds categories and overrides the built-in lines. The resulting
+//! set is saved to the database, so expense commands run without a config (and
+//! expenses recorded under a category since removed from it) still resolve.
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::expenses::ExpenseCategory;
+
+/// Built-in categories and their default tax lines (IRS Schedule C)
+const BUILTIN: [(ExpenseCategory, &str); 6] = [
+    (
+        ExpenseCategory::HOSTING,
+        "Schedule C line 20b: Rent or lease of other business property",
+    ),
+    (ExpenseCategory::CONTRACTOR, "Schedule C line 11: Contract labor"),
+    (
+        ExpenseCategory::HARDWARE,
+        "Schedule C line 13: Depreciation and section 179 expense",
+    ),
+    (ExpenseCategory::SOFTWARE, "Schedule C line 18: Office expense"),
+    (ExpenseCategory::VOTE_FEES, "Schedule C line 27a: Other expenses"),
+    (ExpenseCategory::OTHER, "Schedule C line 27a: Other expenses"),
+];
+
+/// `[categories."<path>"]` in config.toml
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryConfig {
+    /// Tax form line (default: the parent category's)
+    #[serde(default)]
+    pub tax_line: Option<String>,
+}
+
+/// A known expense category
+#[derive(Debug, Clone, PartialEq)]
+pub struct Category {
+    pub name: ExpenseCategory,
+    /// Its own tax line (None = the parent's)
+    pub tax_line: Option<String>,
+}
+
+/// Every known category, parents before their subcategories
+#[derive(Debug, Clone)]
+pub struct Categories {
+    categories: Vec<Category>,
+}
+
+impl Default for Categories {
+    /// The built-in categories
+    fn default() -> Self {
+        Self::from_list(Vec::new())
+    }
+}
+
+impl Categories {
+    /// Built-in categories plus (or overridden by) the configured ones
+    pub fn from_config(config: &BTreeMap<String, CategoryConfig>) -> Result<Self> {
+        let mut configured = Vec::new();
+        for (name, category) in config {
+            if name
+                .split(':')
+                .any(|part| part.trim().is_empty() || part.trim() != part)
+            {
+                anyhow::bail!(
+                    "Invalid category name '{}' (use Parent:Child, without empty parts or surrounding spaces)",
+                    name
+                );
+            }
+            configured.push(Category {
+                name: ExpenseCategory::new(name.as_str()),
+                tax_line: category.tax_line.clone(),
+            });
+        }
+        let categories = Self::from_list(configured);
+
+        for category in &categories.categories {
+            if let Some(parent) = category.name.parent()
+                && categories.get(parent.as_str()).is_none()
+            {
+                anyhow::bail!(
+                    "Category '{}' has no parent category '{}' (add [categories.\"{}\"])",
+                    category.name,
+                    parent,
+                    parent
+                );
+            }
+        }
+        Ok(categories)
+    }
+
+    /// Built-in categories plus a list of others; a listed built-in category
+    /// keeps its default tax line unless it has its own
+    pub fn from_list(list: Vec<Category>) -> Self {
+        let mut categories: Vec<Category> = BUILTIN
+            .iter()
+            .map(|(name, tax_line)| Category {
+                name: name.clone(),
+                tax_line: Some(tax_line.to_string()),
+            })
+            .collect();
+        for category in list {
+            match categories.iter_mut().find(|c| c.name == category.name) {
+                Some(existing) => {
+                    if category.tax_line.is_some() {
+                        existing.tax_line = category.tax_line;
+                    }
+                }
+                None => categories.push(category),
+            }
+        }
+        categories.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { categories }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Category> {
+        self.categories.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Category> {
+        self.categories.iter().find(|c| c.name.as_str() == name)
+    }
+
+    /// The category a user typed (case-insensitive, ignoring spaces, dashes
+    /// and underscores, so "vote-fees" is VoteFees)
+    pub fn resolve(&self, input: &str) -> Result<ExpenseCategory> {
+        let normalize = |name: &str| {
+            name.chars()
+                .filter(|c| !matches!(c, ' ' | '-' | '_'))
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        };
+        let wanted = normalize(input);
+        match self.categories.iter().find(|c| normalize(c.name.as_str()) == wanted) {
+            Some(category) => Ok(category.name.clone()),
+            None => {
+                let names: Vec<&str> = self.categories.iter().map(|c| c.name.as_str()).collect();
+                anyhow::bail!(
+                    "Invalid category '{}'. Use: {} (add others under [categories] in config.toml)",
+                    input,
+                    names.join(", ")
+                )
+            }
+        }
+    }
+
+    /// Tax line of a category: its own, or its nearest ancestor's
+    pub fn tax_line(&self, category: &ExpenseCategory) -> Option<&str> {
+        let mut current = Some(category.clone());
+        while let Some(category) = current {
+            if let Some(tax_line) = self.get(category.as_str()).and_then(|c| c.tax_line.as_deref()) {
+                return Some(tax_line);
+            }
+            current = category.parent();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(entries: &[(&str, Option<&str>)]) -> BTreeMap<String, CategoryConfig> {
+        entries
+            .iter()
+            .map(|(name, tax_line)| {
+                (
+                    name.to_string(),
+                    CategoryConfig {
+                        tax_line: tax_line.map(str::to_string),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_configured_hierarchy_and_tax_lines() {
+        let categories = Categories::from_config(&config(&[
+            ("Hosting:Bandwidth", Some("Schedule C line 25: Utilities")),
+            ("Hosting:Colocation", None),
+            (
+                "Professional Fees",
+                Some("Schedule C line 17: Legal and professional services"),
+            ),
+            ("Professional Fees:Accounting", None),
+            ("Contractor", Some("Form 1099-NEC payments")),
+        ]))
+        .unwrap();
+
+        let bandwidth = categories.resolve("hosting:bandwidth").unwrap();
+        assert_eq!(bandwidth.as_str(), "Hosting:Bandwidth");
+        assert_eq!(bandwidth.top_level(), "Hosting");
+        assert_eq!(categories.tax_line(&bandwidth), Some("Schedule C line 25: Utilities"));
+
+        // Subcategories without a line use their parent's
+        let colocation = categories.resolve("Hosting:Colocation").unwrap();
+        assert_eq!(
+            categories.tax_line(&colocation),
+            Some("Schedule C line 20b: Rent or lease of other business property")
+        );
+        let accounting = categories.resolve("professional-fees:accounting").unwrap();
+        assert_eq!(
+            categories.tax_line(&accounting),
+            Some("Schedule C line 17: Legal and professional services")
+        );
+
+        // Built-in lines can be overridden, and built-in names still resolve
+        assert_eq!(
+            categories.tax_line(&ExpenseCategory::CONTRACTOR),
+            Some("Form 1099-NEC payments")
+        );
+        assert_eq!(categories.resolve("vote_fees").unwrap(), ExpenseCategory::VOTE_FEES);
+        assert!(categories.resolve("Travel").is_err());
+    }
+
+    #[test]
+    fn test_subcategories_need_a_parent() {
+        assert!(Categories::from_config(&config(&[("Travel:Conferences", None)])).is_err());
+        assert!(Categories::from_config(&config(&[("Travel:", None)])).is_err());
+        assert!(Categories::from_config(&config(&[("Travel", None), ("Travel:Conferences", None)])).is_ok());
+    }
+}
@@ -0,0 +1,98 @@
+//! Solana clusters and what each one supports
+//!
+//! Most of the tracker was written for mainnet validators. Testnet and devnet
+//! validators share the on-chain parts (rewards, leader fees, vote costs and
+//! transfers) but have no Jito block engine, no SFDP vote cost reimbursements
+//! and no Dune coverage, and their epochs are numbered and timed differently.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::epoch_schedule::EpochSchedule;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use crate::config::Endpoints;
+use crate::constants;
+
+/// Cluster a validator runs on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cluster {
+    #[default]
+    MainnetBeta,
+    Testnet,
+    Devnet,
+}
+
+impl Cluster {
+    /// Config, cache and display name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Cluster::MainnetBeta => "mainnet-beta",
+            Cluster::Testnet => "testnet",
+            Cluster::Devnet => "devnet",
+        }
+    }
+
+    /// RPC URL used when a validator has no `rpc_url`: Helius for mainnet and
+    /// devnet (which keep full transaction history), the public endpoint for
+    /// testnet, which Helius doesn't serve
+    pub fn default_rpc_url(self, endpoints: &Endpoints, helius_api_key: &str) -> String {
+        match self {
+            Cluster::MainnetBeta => format!("{}{}", endpoints.helius_rpc, helius_api_key),
+            Cluster::Testnet => endpoints.testnet_rpc.clone(),
+            Cluster::Devnet => format!("{}{}", endpoints.helius_devnet_rpc, helius_api_key),
+        }
+    }
+
+    /// Epoch schedule assumed until the cluster's own is fetched with
+    /// `getEpochSchedule`: mainnet started at full-length epochs, testnet and
+    /// devnet with the default warmup of doubling epochs
+    pub fn default_epoch_schedule(self) -> EpochSchedule {
+        match self {
+            Cluster::MainnetBeta => EpochSchedule::without_warmup(),
+            Cluster::Testnet | Cluster::Devnet => EpochSchedule::default(),
+        }
+    }
+
+    /// Known epoch start to extrapolate dates from when no boundary is cached
+    pub fn reference_epoch(self) -> Option<(u64, i64)> {
+        match self {
+            Cluster::MainnetBeta => Some((constants::REFERENCE_EPOCH as u64, constants::REFERENCE_EPOCH_TIMESTAMP)),
+            Cluster::Testnet | Cluster::Devnet => None,
+        }
+    }
+
+    /// Whether Jito MEV and priority fee distributions are tracked (the kobe
+    /// API only covers mainnet)
+    pub fn has_jito(self) -> bool {
+        self == Cluster::MainnetBeta
+    }
+
+    /// Address SFDP vote cost reimbursements are paid from (the program only
+    /// reimburses mainnet vote costs)
+    pub fn sfdp_reimbursement(self) -> Option<Pubkey> {
+        match self {
+            Cluster::MainnetBeta => {
+                Some(Pubkey::from_str(constants::SFDP_REIMBURSEMENT).expect("Invalid SFDP address"))
+            }
+            Cluster::Testnet | Cluster::Devnet => None,
+        }
+    }
+
+    /// Whether Dune indexes the cluster, for backfilling pruned RPC data
+    pub fn has_dune(self) -> bool {
+        self == Cluster::MainnetBeta
+    }
+
+    /// Whether the labeled addresses in `addresses` (Solana Foundation, Jito,
+    /// exchanges) are meaningful: they are mainnet accounts
+    pub fn has_known_addresses(self) -> bool {
+        self == Cluster::MainnetBeta
+    }
+}
+
+impl std::fmt::Display for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
@@ -3,28 +3,58 @@
 use anyhow::{Context, Result};
 use chrono::Datelike;
 use serde::Deserialize;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::str::FromStr;
 
+use crate::categories::CategoryConfig;
+use crate::cluster::Cluster;
 use crate::constants;
+use crate::statements::StatementFormat;
 
 // =============================================================================
 // File-based Configuration (config.toml)
 // =============================================================================
 
 /// Configuration loaded from config.toml
+///
+/// Validators are listed as `[[validators]]` blocks; a single legacy
+/// `[validator]` block is still accepted and treated as the first validator.
 #[derive(Debug, Deserialize)]
 pub struct FileConfig {
-    pub validator: ValidatorConfig,
+    #[serde(default)]
+    pub validator: Option<ValidatorConfig>,
+    #[serde(default)]
+    pub validators: Vec<ValidatorConfig>,
     pub api_keys: ApiKeys,
     #[serde(default)]
     pub notion: Option<NotionConfig>,
+    #[serde(default)]
+    pub accounts: ChartOfAccounts,
+    /// Request limits per RPC provider, keyed by host (e.g. "helius-rpc.com")
+    #[serde(default)]
+    pub rpc_limits: HashMap<String, RpcLimits>,
+    #[serde(default)]
+    pub endpoints: Endpoints,
+    #[serde(default)]
+    pub reporting: Reporting,
+    /// Statement CSV column mappings for `expense import-statement`, by name
+    #[serde(default)]
+    pub statement_formats: HashMap<String, StatementFormat>,
+    /// Expense categories beyond the built-in ones, by `Parent:Child` path
+    #[serde(default)]
+    pub categories: BTreeMap<String, CategoryConfig>,
 }
 
 /// Validator-specific configuration
 #[derive(Debug, Deserialize)]
 pub struct ValidatorConfig {
+    /// Short name used for `--validator` and per-validator output directories
+    /// (defaults to the first 8 characters of the vote account)
+    #[serde(default)]
+    pub name: Option<String>,
     /// Vote account address
     pub vote_account: String,
     /// Identity account address
@@ -42,6 +72,28 @@ pub struct ValidatorConfig {
     /// SFDP acceptance date (optional - only if in SFDP program)
     #[serde(default)]
     pub sfdp_acceptance_date: Option<String>,
+    /// Relative weight for allocating shared expenses (default 1 = even split)
+    #[serde(default = "default_expense_share")]
+    pub expense_share: u32,
+    /// Cluster the validator runs on ("mainnet-beta", "testnet" or "devnet")
+    #[serde(default)]
+    pub cluster: Cluster,
+    /// RPC URL override for this validator (defaults to the cluster's endpoint)
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+}
+
+fn default_expense_share() -> u32 {
+    1
+}
+
+impl ValidatorConfig {
+    /// Configured name, or the vote account prefix
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.vote_account.chars().take(8).collect())
+    }
 }
 
 /// API keys section
@@ -60,6 +112,232 @@ pub struct NotionConfig {
     pub hours_database_id: String,
 }
 
+/// Chart of accounts for double-entry journal export
+///
+/// Every field has a sensible default, so the `[accounts]` section is optional
+/// and only needs the entries you want to rename.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ChartOfAccounts {
+    /// Asset account holding the vote account balance
+    pub vote_account: String,
+    /// Asset account holding the identity balance
+    pub identity: String,
+    /// Asset account holding the withdraw authority balance
+    pub withdraw_authority: String,
+    /// Income account for inflation commission
+    pub commission_income: String,
+    /// Income account for Jito MEV commission
+    pub mev_income: String,
+    /// Income account for Jito (BAM) priority fee commission
+    pub priority_fee_income: String,
+    /// Income account for leader slot fees
+    pub leader_fee_income: String,
+    /// Expense account for vote transaction fees
+    pub vote_fees: String,
+    /// Contra-expense account for the SFDP share of vote fees
+    pub sfdp_offset: String,
+    /// Receivable for SFDP reimbursements not yet received
+    pub sfdp_receivable: String,
+    /// Contra-expense account for the difference between an SFDP
+    /// reimbursement's value and the coverage it settles
+    pub sfdp_true_up: String,
+    /// Equity account for capital contributed from the personal wallet
+    pub owner_contributions: String,
+    /// Equity account for withdrawals to exchanges or the personal wallet
+    pub owner_draws: String,
+    /// Income account for unrecognized incoming transfers
+    pub uncategorized_income: String,
+    /// Expense account for unrecognized outgoing transfers
+    pub uncategorized_expense: String,
+    /// Expense account per expense category (e.g. Hosting = "Expenses:Hosting");
+    /// unmapped subcategories become sub-accounts of their parent's
+    pub expense_categories: HashMap<String, String>,
+    /// Funding account per `paid_with` value (e.g. "Credit Card" = "Liabilities:CreditCard")
+    pub payment_methods: HashMap<String, String>,
+    /// Funding account when `paid_with` has no mapping
+    pub default_payment: String,
+    /// Asset account capital purchases in the asset register are recorded to
+    pub fixed_assets: String,
+    /// Contra-asset account monthly depreciation is credited to
+    pub accumulated_depreciation: String,
+    /// Asset account prepaid expenses are paid into and amortized out of
+    pub prepaid_expenses: String,
+}
+
+impl Default for ChartOfAccounts {
+    fn default() -> Self {
+        let expense_categories = [
+            ("Hosting", "Expenses:Hosting"),
+            ("Contractor", "Expenses:Contractor"),
+            ("Hardware", "Expenses:Hardware"),
+            ("Software", "Expenses:Software"),
+            ("VoteFees", "Expenses:Validator:VoteFees"),
+            ("Other", "Expenses:Other"),
+        ];
+        let payment_methods = [
+            ("USD", "Assets:Bank:Checking"),
+            ("Paid", "Assets:Bank:Checking"),
+            ("Credit Card", "Liabilities:CreditCard"),
+            ("Unpaid", "Liabilities:AccountsPayable"),
+            ("SOL", "Liabilities:AccountsPayable"),
+        ];
+
+        Self {
+            vote_account: "Assets:Validator:VoteAccount".to_string(),
+            identity: "Assets:Validator:Identity".to_string(),
+            withdraw_authority: "Assets:Validator:WithdrawAuthority".to_string(),
+            commission_income: "Income:Validator:Commission".to_string(),
+            mev_income: "Income:Validator:MEV".to_string(),
+            priority_fee_income: "Income:Validator:JitoPriorityFees".to_string(),
+            leader_fee_income: "Income:Validator:LeaderFees".to_string(),
+            vote_fees: "Expenses:Validator:VoteFees".to_string(),
+            sfdp_offset: "Expenses:Validator:VoteFees:SFDPOffset".to_string(),
+            sfdp_receivable: "Assets:Receivable:SFDP".to_string(),
+            sfdp_true_up: "Expenses:Validator:VoteFees:SFDPTrueUp".to_string(),
+            owner_contributions: "Equity:Owner:Contributions".to_string(),
+            owner_draws: "Equity:Owner:Draws".to_string(),
+            uncategorized_income: "Income:Uncategorized".to_string(),
+            uncategorized_expense: "Expenses:Uncategorized".to_string(),
+            expense_categories: expense_categories
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            payment_methods: payment_methods
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            default_payment: "Assets:Bank:Checking".to_string(),
+            fixed_assets: "Assets:FixedAssets:Equipment".to_string(),
+            accumulated_depreciation: "Assets:FixedAssets:AccumulatedDepreciation".to_string(),
+            prepaid_expenses: "Assets:PrepaidExpenses".to_string(),
+        }
+    }
+}
+
+/// Request limits for an RPC provider
+///
+/// Bulk fetches (leader fees) start at `requests_per_second`, halve the rate on
+/// every 429 down to `min_requests_per_second`, and climb back on success.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RpcLimits {
+    /// Maximum sustained request rate
+    pub requests_per_second: f64,
+    /// Floor the rate backs off to when rate limited
+    pub min_requests_per_second: f64,
+    /// Requests that may be sent back-to-back before pacing kicks in
+    pub burst: u32,
+    /// Maximum requests in flight at once
+    pub concurrency: usize,
+}
+
+impl Default for RpcLimits {
+    /// Conservative limits for public or unknown endpoints
+    fn default() -> Self {
+        Self {
+            requests_per_second: 8.0,
+            min_requests_per_second: 1.0,
+            burst: 4,
+            concurrency: 4,
+        }
+    }
+}
+
+impl RpcLimits {
+    /// Limits for paid Helius plans (the default endpoint)
+    fn helius() -> Self {
+        Self {
+            requests_per_second: 40.0,
+            min_requests_per_second: 2.0,
+            burst: 10,
+            concurrency: 12,
+        }
+    }
+
+    /// Limits for an RPC URL: a configured entry whose key matches the URL's host
+    /// (or a parent domain of it), else built-in defaults for the provider
+    pub fn for_url(url: &str, configured: &HashMap<String, RpcLimits>) -> Self {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let matches = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+
+        configured
+            .iter()
+            .filter(|(domain, _)| matches(domain))
+            // Most specific entry wins
+            .max_by_key(|(domain, _)| domain.len())
+            .map(|(_, limits)| limits.clone())
+            .unwrap_or_else(|| {
+                if matches("helius-rpc.com") {
+                    Self::helius()
+                } else {
+                    Self::default()
+                }
+            })
+    }
+}
+
+/// Base URLs of the external services, for paid plans, proxies or local stand-ins
+///
+/// Every field has a default, so the `[endpoints]` section is optional and only
+/// needs the services you want to redirect.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Endpoints {
+    /// Default mainnet RPC URL prefix; the Helius API key is appended
+    pub helius_rpc: String,
+    /// Default devnet RPC URL prefix; the Helius API key is appended
+    pub helius_devnet_rpc: String,
+    /// Default testnet RPC URL
+    pub testnet_rpc: String,
+    /// Jito kobe API
+    pub jito: String,
+    /// CoinGecko API (e.g. https://pro-api.coingecko.com/api/v3 for pro plans)
+    pub coingecko: String,
+    /// Header carrying the CoinGecko key ("x-cg-demo-api-key" or "x-cg-pro-api-key")
+    pub coingecko_api_key_header: String,
+    /// Dune Analytics API
+    pub dune: String,
+    /// Header carrying the Dune key
+    pub dune_api_key_header: String,
+    /// Notion API
+    pub notion: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            helius_rpc: constants::HELIUS_RPC_BASE.to_string(),
+            helius_devnet_rpc: constants::HELIUS_DEVNET_RPC_BASE.to_string(),
+            testnet_rpc: constants::TESTNET_RPC_URL.to_string(),
+            jito: constants::JITO_API_BASE.to_string(),
+            coingecko: constants::COINGECKO_API_BASE.to_string(),
+            coingecko_api_key_header: constants::COINGECKO_API_KEY_HEADER.to_string(),
+            dune: constants::DUNE_API_BASE.to_string(),
+            dune_api_key_header: constants::DUNE_API_KEY_HEADER.to_string(),
+            notion: constants::NOTION_API_BASE.to_string(),
+        }
+    }
+}
+
+/// Reporting periods (`[reporting]`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Reporting {
+    /// Month the fiscal year starts in (1-12); fiscal years are named by the
+    /// calendar year they end in, so with 7 FY2026 runs July 2025 - June 2026
+    pub fiscal_year_start: u32,
+}
+
+impl Default for Reporting {
+    fn default() -> Self {
+        Self { fiscal_year_start: 1 }
+    }
+}
+
 impl FileConfig {
     /// Load configuration from a TOML file
     pub fn load(path: &Path) -> Result<Self> {
@@ -68,20 +346,48 @@ impl FileConfig {
 
         toml::from_str(&content).with_context(|| {
             "Failed to parse config.toml. Check for:\n\
-             - Missing required fields (validator.vote_account, validator.identity, etc.)\n\
+             - Missing required fields (vote_account, identity, etc. in each [[validators]] block)\n\
              - Invalid TOML syntax (missing quotes, brackets, etc.)\n\
              - Incorrect data types (strings vs numbers)\n\n\
              See config.toml.example for the expected format."
         })
     }
+
+    /// All configured validators: the legacy `[validator]` block first, then `[[validators]]`
+    pub fn validator_configs(&self) -> Result<Vec<&ValidatorConfig>> {
+        let validators: Vec<&ValidatorConfig> = self.validator.iter().chain(&self.validators).collect();
+        if validators.is_empty() {
+            anyhow::bail!("No validators configured. Add a [[validators]] block to config.toml.");
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for validator in &validators {
+            let name = validator.display_name();
+            // Names become output directory names
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                anyhow::bail!("Invalid validator name '{}': use letters, digits, '-' or '_'", name);
+            }
+            if !names.insert(name) {
+                anyhow::bail!("Duplicate validator name '{}' in config.toml", validator.display_name());
+            }
+        }
+
+        if validators.iter().all(|v| v.expense_share == 0) {
+            anyhow::bail!("At least one validator needs a non-zero expense_share");
+        }
+
+        Ok(validators)
+    }
 }
 
 // =============================================================================
 // Runtime Configuration
 // =============================================================================
 
-/// Main configuration struct with parsed values
+/// Main configuration struct with parsed values (one per validator)
 pub struct Config {
+    /// Validator name (see `ValidatorConfig::display_name`)
+    pub name: String,
     /// Vote account pubkey
     pub vote_account: Pubkey,
     /// Validator identity pubkey
@@ -90,12 +396,16 @@ pub struct Config {
     pub withdraw_authority: Pubkey,
     /// Personal wallet (for detecting seeding transactions)
     pub personal_wallet: Pubkey,
+    /// Cluster the validator runs on
+    pub cluster: Cluster,
+    /// The cluster's epoch schedule: its built-in default until the schedule
+    /// from `getEpochSchedule` is loaded from the cache or fetched
+    pub epoch_schedule: EpochSchedule,
     /// RPC URL
     pub rpc_url: String,
     /// CoinGecko API key
     pub coingecko_api_key: String,
     /// Dune Analytics API key (optional, for backfilling pruned data)
-    #[allow(dead_code)]
     pub dune_api_key: Option<String>,
     /// Commission percentage
     pub commission_percent: u8,
@@ -105,14 +415,54 @@ pub struct Config {
     pub sfdp_acceptance_date: Option<String>,
     /// Bootstrap date (for finding initial seeding)
     pub bootstrap_date: String,
+    /// Chart of accounts for journal export
+    pub accounts: ChartOfAccounts,
+    /// Relative weight for allocating shared expenses
+    pub expense_share: u32,
+    /// Request limits for `rpc_url`
+    pub rpc_limits: RpcLimits,
+    /// External service base URLs
+    pub endpoints: Endpoints,
 }
 
 impl Config {
-    /// Create config from file config and optional RPC URL override
-    pub fn from_file(file_config: &FileConfig, rpc_url: Option<String>) -> Result<Self> {
-        let validator = &file_config.validator;
+    /// A mainnet validator with fresh addresses and SFDP from December 2025,
+    /// for tests; override fields with `Config { .., ..Config::for_test() }`
+    #[cfg(test)]
+    pub fn for_test() -> Self {
+        Self {
+            name: "test".to_string(),
+            vote_account: Pubkey::new_unique(),
+            identity: Pubkey::new_unique(),
+            withdraw_authority: Pubkey::new_unique(),
+            personal_wallet: Pubkey::new_unique(),
+            cluster: Cluster::MainnetBeta,
+            epoch_schedule: EpochSchedule::without_warmup(),
+            rpc_url: String::new(),
+            coingecko_api_key: String::new(),
+            dune_api_key: None,
+            commission_percent: 5,
+            first_reward_epoch: 900,
+            sfdp_acceptance_date: Some("2025-12-01".to_string()),
+            bootstrap_date: "2025-11-01".to_string(),
+            accounts: ChartOfAccounts::default(),
+            expense_share: 1,
+            rpc_limits: RpcLimits::default(),
+            endpoints: Endpoints::default(),
+        }
+    }
+
+    /// Create config for one validator from file config and optional RPC URL override
+    pub fn from_file(file_config: &FileConfig, validator: &ValidatorConfig, rpc_url: Option<String>) -> Result<Self> {
+        let rpc_url = rpc_url.or_else(|| validator.rpc_url.clone()).unwrap_or_else(|| {
+            validator
+                .cluster
+                .default_rpc_url(&file_config.endpoints, &file_config.api_keys.helius)
+        });
 
         Ok(Self {
+            name: validator.display_name(),
+
             // Parse validator addresses from config
             vote_account: Pubkey::from_str(&validator.vote_account).with_context(|| "Invalid vote_account address")?,
             identity: Pubkey::from_str(&validator.identity).with_context(|| "Invalid identity address")?,
@@ -121,9 +471,13 @@ impl Config {
             personal_wallet: Pubkey::from_str(&validator.personal_wallet)
                 .with_context(|| "Invalid personal_wallet address")?,
 
-            // Helius RPC endpoint (has historical transaction data)
-            rpc_url: rpc_url
-                .unwrap_or_else(|| format!("{}{}", constants::HELIUS_RPC_BASE, &file_config.api_keys.helius)),
+            // Cluster and its epoch schedule
+            cluster: validator.cluster,
+            epoch_schedule: validator.cluster.default_epoch_schedule(),
+
+            // RPC endpoint (Helius by default - has historical transaction data) and its limits
+            rpc_limits: RpcLimits::for_url(&rpc_url, &file_config.rpc_limits),
+            rpc_url,
 
             // CoinGecko API key for price lookups
             coingecko_api_key: file_config.api_keys.coingecko.clone(),
@@ -142,9 +496,35 @@ impl Config {
 
             // Bootstrap date (when validator was first set up)
             bootstrap_date: validator.bootstrap_date.clone(),
+
+            // Chart of accounts for the double-entry journal
+            accounts: file_config.accounts.clone(),
+
+            // Share of expenses not assigned to a specific validator
+            expense_share: validator.expense_share,
+
+            // External service base URLs
+            endpoints: file_config.endpoints.clone(),
         })
     }
 
+    /// Create configs for every configured validator
+    pub fn all_from_file(file_config: &FileConfig, rpc_url: Option<String>) -> Result<Vec<Self>> {
+        file_config
+            .validator_configs()?
+            .into_iter()
+            .map(|validator| {
+                Self::from_file(file_config, validator, rpc_url.clone())
+                    .with_context(|| format!("Invalid config for validator '{}'", validator.display_name()))
+            })
+            .collect()
+    }
+
+    /// Whether a `--validator` selector (name or vote account) refers to this validator
+    pub fn matches(&self, selector: &str) -> bool {
+        self.name == selector || self.vote_account.to_string() == selector
+    }
+
     /// Check if a pubkey is one of our validator accounts
     pub fn is_our_account(&self, pubkey: &Pubkey) -> bool {
         *pubkey == self.vote_account || *pubkey == self.identity || *pubkey == self.withdraw_authority
@@ -155,42 +535,79 @@ impl Config {
         self.is_our_account(pubkey) || *pubkey == self.personal_wallet
     }
 
-    /// Calculate SFDP vote cost coverage percentage for a given date
+    /// Calculate SFDP vote cost coverage in basis points for a given date
     /// Schedule from acceptance date:
     /// - Months 1-3: 100% coverage
     /// - Months 4-6: 75% coverage
     /// - Months 7-9: 50% coverage
     /// - Months 10-12: 25% coverage
     /// - After 12 months: 0%
-    pub fn sfdp_coverage_percent(&self, date: &chrono::NaiveDate) -> f64 {
+    ///
+    /// Only mainnet vote costs are reimbursed.
+    pub fn sfdp_coverage_bps(&self, date: &chrono::NaiveDate) -> u32 {
         use chrono::NaiveDate;
 
+        if self.cluster.sfdp_reimbursement().is_none() {
+            return 0;
+        }
+
         let Some(ref acceptance_str) = self.sfdp_acceptance_date else {
-            return 0.0; // Not in SFDP program
+            return 0; // Not in SFDP program
         };
 
         let Ok(acceptance) = NaiveDate::parse_from_str(acceptance_str, "%Y-%m-%d") else {
-            return 0.0; // Invalid date
+            return 0; // Invalid date
         };
 
         let months_diff = (date.year() - acceptance.year()) * 12 + (date.month() as i32 - acceptance.month() as i32);
 
         if months_diff < 0 {
-            0.0
+            0
         } else if months_diff < 3 {
-            1.0 // 100%
+            10_000 // 100%
         } else if months_diff < 6 {
-            0.75
+            7_500
         } else if months_diff < 9 {
-            0.50
+            5_000
         } else if months_diff < 12 {
-            0.25
+            2_500
         } else {
-            0.0
+            0
         }
     }
 }
 
+/// Pick the validators a command runs for: the one matching `selector`, or all of them
+pub fn select_validators<'a>(configs: &'a [Config], selector: Option<&str>) -> Result<Vec<&'a Config>> {
+    let Some(selector) = selector else {
+        return Ok(configs.iter().collect());
+    };
+
+    let selected: Vec<&Config> = configs.iter().filter(|c| c.matches(selector)).collect();
+    if selected.is_empty() {
+        let names: Vec<&str> = configs.iter().map(|c| c.name.as_str()).collect();
+        anyhow::bail!(
+            "No validator matches '{}'. Configured validators: {}",
+            selector,
+            names.join(", ")
+        );
+    }
+    Ok(selected)
+}
+
+/// Pick exactly one validator for commands that operate on a single validator
+pub fn select_validator<'a>(configs: &'a [Config], selector: Option<&str>) -> Result<&'a Config> {
+    let selected = select_validators(configs, selector)?;
+    if selected.len() > 1 {
+        let names: Vec<&str> = selected.iter().map(|c| c.name.as_str()).collect();
+        anyhow::bail!(
+            "Multiple validators configured ({}). Choose one with --validator <name>",
+            names.join(", ")
+        );
+    }
+    Ok(selected[0])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,17 +617,9 @@ mod tests {
     /// Create a minimal Config for testing SFDP calculations
     fn test_config(sfdp_date: Option<&str>) -> Config {
         Config {
-            vote_account: Pubkey::new_unique(),
-            identity: Pubkey::new_unique(),
-            withdraw_authority: Pubkey::new_unique(),
-            personal_wallet: Pubkey::new_unique(),
-            rpc_url: String::new(),
-            coingecko_api_key: String::new(),
-            dune_api_key: None,
             commission_percent: 10,
-            first_reward_epoch: 900,
             sfdp_acceptance_date: sfdp_date.map(|s| s.to_string()),
-            bootstrap_date: "2025-11-01".to_string(),
+            ..Config::for_test()
         }
     }
 
@@ -218,14 +627,14 @@ mod tests {
     fn test_sfdp_no_acceptance_date() {
         let config = test_config(None);
         let date = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&date), 0.0);
+        assert_eq!(config.sfdp_coverage_bps(&date), 0);
     }
 
     #[test]
     fn test_sfdp_before_acceptance() {
         let config = test_config(Some("2025-12-01"));
         let date = NaiveDate::from_ymd_opt(2025, 11, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&date), 0.0);
+        assert_eq!(config.sfdp_coverage_bps(&date), 0);
     }
 
     #[test]
@@ -234,15 +643,15 @@ mod tests {
 
         // Month 1 (same month as acceptance)
         let m1 = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m1), 1.0);
+        assert_eq!(config.sfdp_coverage_bps(&m1), 10_000);
 
         // Month 2
         let m2 = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m2), 1.0);
+        assert_eq!(config.sfdp_coverage_bps(&m2), 10_000);
 
         // Month 3
         let m3 = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m3), 1.0);
+        assert_eq!(config.sfdp_coverage_bps(&m3), 10_000);
     }
 
     #[test]
@@ -251,15 +660,15 @@ mod tests {
 
         // Month 4
         let m4 = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m4), 0.75);
+        assert_eq!(config.sfdp_coverage_bps(&m4), 7_500);
 
         // Month 5
         let m5 = NaiveDate::from_ymd_opt(2026, 4, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m5), 0.75);
+        assert_eq!(config.sfdp_coverage_bps(&m5), 7_500);
 
         // Month 6
         let m6 = NaiveDate::from_ymd_opt(2026, 5, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m6), 0.75);
+        assert_eq!(config.sfdp_coverage_bps(&m6), 7_500);
     }
 
     #[test]
@@ -268,11 +677,11 @@ mod tests {
 
         // Month 7
         let m7 = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m7), 0.50);
+        assert_eq!(config.sfdp_coverage_bps(&m7), 5_000);
 
         // Month 9
         let m9 = NaiveDate::from_ymd_opt(2026, 8, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m9), 0.50);
+        assert_eq!(config.sfdp_coverage_bps(&m9), 5_000);
     }
 
     #[test]
@@ -281,11 +690,11 @@ mod tests {
 
         // Month 10
         let m10 = NaiveDate::from_ymd_opt(2026, 9, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m10), 0.25);
+        assert_eq!(config.sfdp_coverage_bps(&m10), 2_500);
 
         // Month 12
         let m12 = NaiveDate::from_ymd_opt(2026, 11, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m12), 0.25);
+        assert_eq!(config.sfdp_coverage_bps(&m12), 2_500);
     }
 
     #[test]
@@ -294,17 +703,153 @@ mod tests {
 
         // Month 13 (12 months after December 2025 = December 2026)
         let m13 = NaiveDate::from_ymd_opt(2026, 12, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&m13), 0.0);
+        assert_eq!(config.sfdp_coverage_bps(&m13), 0);
 
         // Well after program ends
         let later = NaiveDate::from_ymd_opt(2027, 6, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&later), 0.0);
+        assert_eq!(config.sfdp_coverage_bps(&later), 0);
     }
 
     #[test]
     fn test_sfdp_invalid_acceptance_date() {
         let config = test_config(Some("invalid-date"));
         let date = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap();
-        assert_eq!(config.sfdp_coverage_percent(&date), 0.0);
+        assert_eq!(config.sfdp_coverage_bps(&date), 0);
+    }
+
+    const VALIDATOR_FIELDS: &str = r#"
+identity = "11111111111111111111111111111111"
+withdraw_authority = "11111111111111111111111111111111"
+personal_wallet = "11111111111111111111111111111111"
+commission_percent = 5
+jito_mev_commission_percent = 10
+first_reward_epoch = 900
+bootstrap_date = "2025-01-01"
+"#;
+
+    fn parse(validators: &str) -> FileConfig {
+        let content = format!("{}\n[api_keys]\nhelius = \"key\"\ncoingecko = \"key\"\n", validators);
+        toml::from_str(&content).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_block_is_first_validator() {
+        let file_config = parse(&format!(
+            "[validator]\nvote_account = \"Vote111111111111111111111111111111111111111\"\n{}\n\
+             [[validators]]\nname = \"testnet\"\nvote_account = \"{}\"\nexpense_share = 2\n\
+             rpc_url = \"https://api.testnet.solana.com\"\n{}",
+            VALIDATOR_FIELDS,
+            Pubkey::new_unique(),
+            VALIDATOR_FIELDS
+        ));
+
+        let configs = Config::all_from_file(&file_config, None).unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].name, "Vote1111");
+        assert_eq!(configs[0].expense_share, 1);
+        assert!(configs[0].rpc_url.starts_with(constants::HELIUS_RPC_BASE));
+        assert_eq!(configs[1].name, "testnet");
+        assert_eq!(configs[1].expense_share, 2);
+        assert_eq!(configs[1].rpc_url, "https://api.testnet.solana.com");
+
+        // A --rpc-url override applies to every validator
+        let configs = Config::all_from_file(&file_config, Some("http://localhost:8899".to_string())).unwrap();
+        assert!(configs.iter().all(|c| c.rpc_url == "http://localhost:8899"));
+    }
+
+    #[test]
+    fn test_select_validator() {
+        let mut testnet = test_config(None);
+        testnet.name = "testnet".to_string();
+        let configs = vec![test_config(None), testnet];
+        let testnet_vote = configs[1].vote_account.to_string();
+
+        let selected = select_validator(&configs, Some("testnet")).unwrap();
+        assert_eq!(selected.vote_account, configs[1].vote_account);
+        assert_eq!(select_validators(&configs, Some(&testnet_vote)).unwrap().len(), 1);
+        assert_eq!(select_validators(&configs, None).unwrap().len(), 2);
+
+        // Single-validator commands need a selector once several are configured
+        assert!(select_validator(&configs, None).is_err());
+        assert!(select_validators(&configs, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_rpc_limits_for_url() {
+        let mut configured = HashMap::new();
+        configured.insert(
+            "quiknode.pro".to_string(),
+            RpcLimits {
+                requests_per_second: 25.0,
+                ..RpcLimits::default()
+            },
+        );
+
+        let quicknode = RpcLimits::for_url("https://example.solana-mainnet.quiknode.pro/abc/", &configured);
+        assert_eq!(quicknode.requests_per_second, 25.0);
+
+        let helius = RpcLimits::for_url("https://mainnet.helius-rpc.com/?api-key=x", &configured);
+        assert_eq!(helius.requests_per_second, RpcLimits::helius().requests_per_second);
+
+        let public = RpcLimits::for_url("https://api.mainnet-beta.solana.com", &configured);
+        assert_eq!(public.requests_per_second, RpcLimits::default().requests_per_second);
+    }
+
+    #[test]
+    fn test_endpoints_override_defaults() {
+        let file_config = parse(&format!(
+            "[validator]\nvote_account = \"{}\"\n{}\n\
+             [endpoints]\nhelius_rpc = \"http://localhost:8899/?api-key=\"\n\
+             coingecko = \"https://pro-api.coingecko.com/api/v3\"\n\
+             coingecko_api_key_header = \"x-cg-pro-api-key\"\n",
+            Pubkey::new_unique(),
+            VALIDATOR_FIELDS
+        ));
+
+        let config = &Config::all_from_file(&file_config, None).unwrap()[0];
+        assert_eq!(config.rpc_url, "http://localhost:8899/?api-key=key");
+        assert_eq!(config.endpoints.coingecko_api_key_header, "x-cg-pro-api-key");
+        // Unlisted services keep their defaults
+        assert_eq!(config.endpoints.jito, constants::JITO_API_BASE);
+        assert_eq!(config.endpoints.dune_api_key_header, constants::DUNE_API_KEY_HEADER);
+    }
+
+    #[test]
+    fn test_cluster_selects_rpc_and_sfdp() {
+        let file_config = parse(&format!(
+            "[[validators]]\nname = \"main\"\nvote_account = \"{}\"\n{}\n\
+             [[validators]]\nname = \"test\"\ncluster = \"testnet\"\nvote_account = \"{}\"\n\
+             sfdp_acceptance_date = \"2025-12-01\"\n{}\n\
+             [[validators]]\nname = \"dev\"\ncluster = \"devnet\"\nvote_account = \"{}\"\n{}",
+            Pubkey::new_unique(),
+            VALIDATOR_FIELDS,
+            Pubkey::new_unique(),
+            VALIDATOR_FIELDS,
+            Pubkey::new_unique(),
+            VALIDATOR_FIELDS
+        ));
+
+        let configs = Config::all_from_file(&file_config, None).unwrap();
+        assert_eq!(configs[0].cluster, Cluster::MainnetBeta);
+        assert!(configs[0].rpc_url.starts_with(constants::HELIUS_RPC_BASE));
+        assert!(!configs[0].epoch_schedule.warmup);
+        assert_eq!(configs[1].rpc_url, constants::TESTNET_RPC_URL);
+        assert!(configs[1].epoch_schedule.warmup);
+        assert_eq!(configs[2].rpc_url, "https://devnet.helius-rpc.com/?api-key=key");
+
+        // SFDP doesn't reimburse testnet vote costs
+        let date = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap();
+        assert_eq!(configs[1].sfdp_coverage_bps(&date), 0);
+    }
+
+    #[test]
+    fn test_duplicate_validator_names_rejected() {
+        let block = format!(
+            "[[validators]]\nname = \"main\"\nvote_account = \"{}\"\n{}",
+            Pubkey::new_unique(),
+            VALIDATOR_FIELDS
+        );
+        let file_config = parse(&format!("{}\n{}", block, block));
+        assert!(file_config.validator_configs().is_err());
     }
 }
@@ -3,19 +3,39 @@
 //! This module contains universal constants that apply to all Solana validators.
 //! Validator-specific configuration is loaded from config.toml.
 
+use crate::money::Usd;
+
 // =============================================================================
-// API Endpoints
+// API Endpoints (defaults, overridable in the [endpoints] config section)
 // =============================================================================
 
-/// Helius RPC base URL (append API key)
+/// Helius mainnet RPC base URL (append API key)
 pub const HELIUS_RPC_BASE: &str = "https://mainnet.helius-rpc.com/?api-key=";
 
+/// Helius devnet RPC base URL (append API key)
+pub const HELIUS_DEVNET_RPC_BASE: &str = "https://devnet.helius-rpc.com/?api-key=";
+
+/// Public testnet RPC URL (Helius doesn't serve testnet)
+pub const TESTNET_RPC_URL: &str = "https://api.testnet.solana.com";
+
 /// Jito MEV API base URL
 pub const JITO_API_BASE: &str = "https://kobe.mainnet.jito.network/api/v1";
 
-/// CoinGecko API base URL
+/// CoinGecko API base URL (demo/free plan)
 pub const COINGECKO_API_BASE: &str = "https://api.coingecko.com/api/v3";
 
+/// Header carrying a CoinGecko demo plan key (pro plans use `x-cg-pro-api-key`)
+pub const COINGECKO_API_KEY_HEADER: &str = "x-cg-demo-api-key";
+
+/// Dune Analytics API base URL
+pub const DUNE_API_BASE: &str = "https://api.dune.com/api/v1";
+
+/// Header carrying the Dune API key
+pub const DUNE_API_KEY_HEADER: &str = "X-Dune-Api-Key";
+
+/// Notion API base URL
+pub const NOTION_API_BASE: &str = "https://api.notion.com/v1";
+
 /// CoinGecko historical price endpoint (append from/to timestamps)
 pub const COINGECKO_MARKET_CHART: &str = "/coins/solana/market_chart/range?vs_currency=usd";
 
@@ -26,22 +46,17 @@ pub const COINGECKO_SIMPLE_PRICE: &str = "/simple/price?ids=solana&vs_currencies
 // Solana Network Constants
 // =============================================================================
 
-/// Slots per epoch on mainnet
-pub const SLOTS_PER_EPOCH: u64 = 432_000;
-
-/// Approximate epoch duration in seconds (~2 days)
-pub const EPOCH_DURATION_SECONDS: i64 = 172_800;
-
-/// Lamports per SOL (code often uses 1e9 directly for brevity)
-#[allow(dead_code)]
-pub const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+/// Target slot duration in milliseconds, for estimating how long an epoch lasts
+/// (slots per epoch come from each cluster's epoch schedule)
+pub const SLOT_DURATION_MS: u64 = 400;
 
 // =============================================================================
 // Epoch to Date Calibration
-// Reference point for converting epoch numbers to approximate dates
+// Reference point for approximate mainnet epoch dates when no epoch boundary
+// is cached
 // =============================================================================
 
-/// Reference epoch for date calculation
+/// Reference mainnet epoch for date calculation
 pub const REFERENCE_EPOCH: i64 = 896;
 
 /// Unix timestamp for reference epoch (2025-12-16 00:00:00 UTC)
@@ -51,7 +66,7 @@ pub const REFERENCE_EPOCH_TIMESTAMP: i64 = 1765843200;
 // Known External Addresses (Solana ecosystem, not validator-specific)
 // =============================================================================
 
-/// SFDP vote cost reimbursement address (Solana Foundation)
+/// SFDP vote cost reimbursement address (Solana Foundation, mainnet only)
 pub const SFDP_REIMBURSEMENT: &str = "DtZWL3BPKa5hw7yQYvaFR29PcXThpLHVU2XAAZrcLiSe";
 
 // =============================================================================
@@ -77,6 +92,36 @@ pub const TREASURY_LEDGER_FILENAME: &str = "treasury_ledger.csv";
 /// Summary CSV filename
 pub const SUMMARY_FILENAME: &str = "summary.csv";
 
+/// Summary CSV across all validators (written when more than one is reported)
+pub const CONSOLIDATED_SUMMARY_FILENAME: &str = "consolidated_summary.csv";
+
+/// Beancount journal filename
+pub const JOURNAL_BEANCOUNT_FILENAME: &str = "journal.beancount";
+
+/// Ledger-cli / hledger journal filename
+pub const JOURNAL_LEDGER_FILENAME: &str = "journal.ledger";
+
+/// Realized capital gains CSV filename
+pub const CAPITAL_GAINS_FILENAME: &str = "capital_gains.csv";
+
+/// Open tax lots CSV filename
+pub const TAX_LOTS_FILENAME: &str = "tax_lots.csv";
+
+/// Fixed asset register (book values) CSV filename
+pub const FIXED_ASSETS_FILENAME: &str = "fixed_assets.csv";
+
+/// Balance reconciliation CSV filename
+pub const RECONCILIATION_FILENAME: &str = "reconciliation.csv";
+
+/// Content-addressed receipt store directory (under the data directory)
+pub const ATTACHMENTS_DIRNAME: &str = "attachments";
+
+/// Expense ledger filename inside a receipts export archive
+pub const RECEIPTS_LEDGER_FILENAME: &str = "expenses.csv";
+
+/// Statement import categorization rules (in the data directory)
+pub const STATEMENT_RULES_FILENAME: &str = "statement_rules.toml";
+
 // =============================================================================
 // Rate Limiting
 // =============================================================================
@@ -87,9 +132,6 @@ pub const RPC_SIGNATURE_DELAY_MS: u64 = 200;
 /// Delay between RPC transaction fetches (ms)
 pub const RPC_TRANSACTION_DELAY_MS: u64 = 100;
 
-/// Delay between block fetches for leader fees (ms)
-pub const BLOCK_FETCH_DELAY_MS: u64 = 50;
-
 /// Delay between epoch reward fetches (ms)
 pub const EPOCH_REWARD_DELAY_MS: u64 = 100;
 
@@ -105,7 +147,7 @@ pub const MAX_SIGNATURES_PER_ACCOUNT: usize = 2000;
 pub const MIN_TRANSFER_LAMPORTS: i64 = 1_000_000; // 0.001 SOL
 
 /// Fallback SOL price if API fails
-pub const FALLBACK_SOL_PRICE: f64 = 185.0;
+pub const FALLBACK_SOL_PRICE: Usd = Usd::from_dollars(185);
 
 /// Fallback date for missing dates in SFDP calculations
 /// Used when epoch->date conversion fails or date is unknown
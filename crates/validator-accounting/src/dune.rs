@@ -5,11 +5,13 @@
 //! - Leader slot fees (identity account)
 //! - Vote transaction costs (identity account)
 //! - SOL transfers (all tracked accounts)
+//! - Epoch boundaries (first block time of each epoch)
 //!
 //! API docs: https://docs.dune.com/api-reference/executions/endpoint/execute-query
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -19,13 +21,13 @@ use tokio::time::sleep;
 use crate::addresses::get_label;
 use crate::config::Config;
 use crate::constants;
+use crate::epochs::EpochBoundary;
 use crate::leader_fees::EpochLeaderFees;
+use crate::money::Lamports;
+use crate::provenance::{DataSource, Provenance};
 use crate::transactions::{SolTransfer, epoch_to_date};
 use crate::vote_costs::EpochVoteCost;
 
-/// Dune API base URL
-const DUNE_API_BASE: &str = "https://api.dune.com/api/v1";
-
 /// Timeout for query execution (5 minutes)
 const QUERY_TIMEOUT_SECS: u64 = 300;
 
@@ -73,6 +75,10 @@ struct QueryResult {
 /// Dune Analytics API client
 pub struct DuneClient {
     api_key: String,
+    /// API base URL (`[endpoints] dune`)
+    base_url: String,
+    /// Header carrying the API key
+    api_key_header: String,
     client: reqwest::Client,
     /// Vote account address (for inflation rewards queries)
     vote_account: String,
@@ -82,6 +88,8 @@ pub struct DuneClient {
     withdraw_authority: String,
     /// Commission percentage (for reward records)
     commission_percent: u8,
+    /// Epoch schedule (for approximate reward slots)
+    epoch_schedule: EpochSchedule,
 }
 
 impl DuneClient {
@@ -93,11 +101,14 @@ impl DuneClient {
     pub fn new(api_key: String, config: &Config) -> Self {
         Self {
             api_key,
+            base_url: config.endpoints.dune.trim_end_matches('/').to_string(),
+            api_key_header: config.endpoints.dune_api_key_header.clone(),
             client: reqwest::Client::new(),
             vote_account: config.vote_account.to_string(),
             identity: config.identity.to_string(),
             withdraw_authority: config.withdraw_authority.to_string(),
             commission_percent: config.commission_percent,
+            epoch_schedule: config.epoch_schedule.clone(),
         }
     }
 
@@ -111,7 +122,7 @@ impl DuneClient {
     /// Execute a SQL query and wait for results
     async fn execute_query(&self, sql: &str) -> Result<Vec<HashMap<String, serde_json::Value>>> {
         // Submit query
-        let execute_url = format!("{}/sql/execute", DUNE_API_BASE);
+        let execute_url = format!("{}/sql/execute", self.base_url);
         let request = ExecuteRequest {
             sql: sql.to_string(),
             performance: "medium".to_string(),
@@ -120,7 +131,7 @@ impl DuneClient {
         let response: ExecuteResponse = self
             .client
             .post(&execute_url)
-            .header("X-Dune-Api-Key", &self.api_key)
+            .header(&self.api_key_header, &self.api_key)
             .json(&request)
             .send()
             .await
@@ -136,7 +147,7 @@ impl DuneClient {
         sleep(Duration::from_secs(INITIAL_DELAY_SECS)).await;
 
         // Poll for results
-        let results_url = format!("{}/execution/{}/results", DUNE_API_BASE, execution_id);
+        let results_url = format!("{}/execution/{}/results", self.base_url, execution_id);
         let timeout = Duration::from_secs(QUERY_TIMEOUT_SECS);
         let start = std::time::Instant::now();
 
@@ -148,7 +159,7 @@ impl DuneClient {
             let response: ResultsResponse = self
                 .client
                 .get(&results_url)
-                .header("X-Dune-Api-Key", &self.api_key)
+                .header(&self.api_key_header, &self.api_key)
                 .send()
                 .await
                 .context("Failed to get Dune results")?
@@ -200,7 +211,7 @@ impl DuneClient {
             r#"
             SELECT
               FLOOR(block_slot / 432000) as epoch,
-              SUM(lamports) / 1e9 as reward_sol,
+              SUM(lamports) as reward_lamports,
               MIN(block_time) as reward_time
             FROM solana.rewards
             WHERE reward_type = 'Voting'
@@ -218,16 +229,15 @@ impl DuneClient {
         let mut rewards = Vec::new();
         for row in rows {
             let epoch = get_u64(&row, "epoch")?;
-            let reward_sol = get_f64(&row, "reward_sol")?;
-            let reward_lamports = sol_to_lamports(reward_sol);
+            let reward_lamports = Lamports(get_u64(&row, "reward_lamports")?);
 
             rewards.push(crate::transactions::EpochReward {
                 epoch,
-                effective_slot: epoch * constants::SLOTS_PER_EPOCH, // Approximate
+                effective_slot: self.epoch_schedule.get_first_slot_in_epoch(epoch), // Approximate
                 amount_lamports: reward_lamports,
-                amount_sol: reward_sol,
                 commission: self.commission_percent,
                 date: Some(epoch_to_date(epoch)),
+                provenance: Provenance::new(DataSource::Dune),
             });
         }
 
@@ -252,7 +262,7 @@ impl DuneClient {
             SELECT
               FLOOR(block_slot / 432000) as epoch,
               COUNT(*) as blocks_produced,
-              SUM(lamports) / 1e9 as total_fees_sol
+              SUM(lamports) as total_fees_lamports
             FROM solana.rewards
             WHERE reward_type = 'Fee'
               AND recipient = '{}'
@@ -270,17 +280,17 @@ impl DuneClient {
         for row in rows {
             let epoch = get_u64(&row, "epoch")?;
             let blocks_produced = get_u64(&row, "blocks_produced")?;
-            let total_fees_sol = get_f64(&row, "total_fees_sol")?;
-            let total_fees_lamports = sol_to_lamports(total_fees_sol);
+            let total_fees_lamports = Lamports(get_u64(&row, "total_fees_lamports")?);
 
             fees.push(EpochLeaderFees {
                 epoch,
                 leader_slots: blocks_produced, // We only have blocks, not assigned slots
                 blocks_produced,
                 skipped_slots: 0, // Can't determine from rewards table
+                unavailable_blocks: 0,
                 total_fees_lamports,
-                total_fees_sol,
                 date: Some(epoch_to_date(epoch)),
+                provenance: Provenance::new(DataSource::Dune),
             });
         }
 
@@ -304,7 +314,7 @@ impl DuneClient {
             SELECT
               FLOOR(block_slot / 432000) as epoch,
               COUNT(*) as vote_count,
-              SUM(fee) / 1e9 as total_fee_sol
+              SUM(fee) as total_fee_lamports
             FROM solana.vote_transactions
             WHERE signer = '{}'
               AND block_date >= DATE '{}'
@@ -321,16 +331,14 @@ impl DuneClient {
         for row in rows {
             let epoch = get_u64(&row, "epoch")?;
             let vote_count = get_u64(&row, "vote_count")?;
-            let total_fee_sol = get_f64(&row, "total_fee_sol")?;
-            let total_fee_lamports = sol_to_lamports(total_fee_sol);
+            let total_fee_lamports = Lamports(get_u64(&row, "total_fee_lamports")?);
 
             costs.push(EpochVoteCost {
                 epoch,
                 vote_count,
                 total_fee_lamports,
-                total_fee_sol,
-                source: "dune".to_string(),
                 date: Some(epoch_to_date(epoch)),
+                provenance: Provenance::new(DataSource::Dune),
             });
         }
 
@@ -373,7 +381,7 @@ impl DuneClient {
               FLOOR(block_slot / 432000) as epoch,
               from_owner,
               to_owner,
-              amount_display as amount_sol,
+              amount as amount_lamports,
               tx_id as signature,
               block_time
             FROM tokens_solana.transfers
@@ -397,14 +405,13 @@ impl DuneClient {
             let slot = get_u64(&row, "block_slot")?;
             let from_str = get_string(&row, "from_owner")?;
             let to_str = get_string(&row, "to_owner")?;
-            let amount_sol = get_f64(&row, "amount_sol")?;
+            let amount_lamports = Lamports(get_u64(&row, "amount_lamports")?);
             let signature = get_string(&row, "signature")?;
             let date = get_string_opt(&row, "block_date");
             let timestamp = get_timestamp_opt(&row, "block_time");
 
             // Skip tiny transfers (dust)
-            let amount_lamports = sol_to_lamports(amount_sol);
-            if (amount_lamports as i64) < constants::MIN_TRANSFER_LAMPORTS {
+            if (amount_lamports.0 as i64) < constants::MIN_TRANSFER_LAMPORTS {
                 continue;
             }
 
@@ -424,16 +431,57 @@ impl DuneClient {
                 from,
                 to,
                 amount_lamports,
-                amount_sol,
                 from_label: from_label_info.name,
                 to_label: to_label_info.name,
                 from_category: from_label_info.category,
                 to_category: to_label_info.category,
+                provenance: Provenance::new(DataSource::Dune),
             });
         }
 
         Ok(transfers)
     }
+
+    // =========================================================================
+    // Epoch Boundaries
+    // =========================================================================
+
+    /// Fetch each epoch's first block time from Dune
+    ///
+    /// Queries the solana.blocks table for the earliest block of every epoch.
+    pub async fn fetch_epoch_boundaries(&self, start_date: &str) -> Result<Vec<EpochBoundary>> {
+        Self::validate_date(start_date)?;
+        println!("  Querying Dune for epoch boundaries...");
+
+        let sql = format!(
+            r#"
+            SELECT
+              FLOOR(slot / 432000) as epoch,
+              MIN(slot) as first_block_slot,
+              TO_UNIXTIME(MIN(time)) as block_time
+            FROM solana.blocks
+            WHERE date >= DATE '{}'
+            GROUP BY FLOOR(slot / 432000)
+            ORDER BY epoch
+            "#,
+            start_date
+        );
+
+        let rows = self.execute_query(&sql).await?;
+        println!("    Found {} epoch boundaries", rows.len());
+
+        let mut boundaries = Vec::new();
+        for row in rows {
+            boundaries.push(EpochBoundary {
+                epoch: get_u64(&row, "epoch")?,
+                first_block_slot: get_u64(&row, "first_block_slot")?,
+                block_time: get_u64(&row, "block_time")? as i64,
+                provenance: Provenance::new(DataSource::Dune),
+            });
+        }
+
+        Ok(boundaries)
+    }
 }
 
 // =============================================================================
@@ -464,27 +512,6 @@ fn safe_f64_to_u64(f: f64) -> u64 {
     }
 }
 
-/// Safely convert SOL amount to lamports with overflow protection
-fn sol_to_lamports(sol: f64) -> u64 {
-    // Max SOL that fits in u64 lamports: ~18.446744073 billion SOL
-    // Use conservative bound to avoid precision issues
-    const MAX_SOL: f64 = 18_446_744_073.0;
-    if sol.is_nan() || sol < 0.0 {
-        0
-    } else if sol >= MAX_SOL {
-        u64::MAX
-    } else {
-        (sol * 1e9).round() as u64
-    }
-}
-
-/// Extract f64 from JSON value
-fn get_f64(row: &HashMap<String, serde_json::Value>, key: &str) -> Result<f64> {
-    row.get(key)
-        .and_then(|v| v.as_f64())
-        .ok_or_else(|| anyhow::anyhow!("Missing or invalid field: {}", key))
-}
-
 /// Extract string from JSON value
 fn get_string(row: &HashMap<String, serde_json::Value>, key: &str) -> Result<String> {
     row.get(key)
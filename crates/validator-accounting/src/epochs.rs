@@ -0,0 +1,245 @@
+//! Epoch calendar: when each epoch actually started
+//!
+//! Epoch length drifts with slot times, so a fixed two-day extrapolation puts
+//! rows near month boundaries in the wrong month. Each completed epoch's start
+//! is taken from the block time of its first confirmed block (RPC, or Dune when
+//! the RPC has pruned it) and cached; only epochs without a known boundary
+//! (normally just the in-progress one) are extrapolated from the nearest known
+//! boundary, at the cluster's epoch length.
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::collections::BTreeMap;
+
+use crate::config::Config;
+use crate::constants;
+use crate::jito::MevClaim;
+use crate::leader_fees::EpochLeaderFees;
+use crate::provenance::{DataSource, Provenance};
+use crate::rate_limit::LimitedRpc;
+use crate::transactions::EpochReward;
+use crate::vote_costs::EpochVoteCost;
+
+/// First confirmed block of an epoch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochBoundary {
+    pub epoch: u64,
+    /// Slot of the epoch's first confirmed block (the first slot may be skipped)
+    pub first_block_slot: u64,
+    /// Unix timestamp of that block
+    pub block_time: i64,
+    #[serde(flatten)]
+    pub provenance: Provenance,
+}
+
+/// RPC response for getBlocksWithLimit
+#[derive(Debug, Deserialize)]
+struct BlocksResponse {
+    result: Option<Vec<u64>>,
+    error: Option<serde_json::Value>,
+}
+
+/// RPC response for getBlockTime
+#[derive(Debug, Deserialize)]
+struct BlockTimeResponse {
+    result: Option<i64>,
+    error: Option<serde_json::Value>,
+}
+
+/// Fetch the cluster's epoch schedule
+pub fn fetch_epoch_schedule(config: &Config) -> Result<EpochSchedule> {
+    let client = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+    Ok(client.get_epoch_schedule()?)
+}
+
+/// Approximate length of an epoch in seconds (~2 days for 432,000 slots)
+pub fn epoch_seconds(schedule: &EpochSchedule) -> i64 {
+    (schedule.slots_per_epoch * constants::SLOT_DURATION_MS / 1000) as i64
+}
+
+/// Fetch an epoch's boundary from its first confirmed block
+pub async fn fetch_epoch_boundary(config: &Config, epoch: u64) -> Result<EpochBoundary> {
+    let rpc = LimitedRpc::new(&config.rpc_url, &config.rpc_limits);
+    let first_slot = config.epoch_schedule.get_first_slot_in_epoch(epoch);
+    let last_slot = config.epoch_schedule.get_last_slot_in_epoch(epoch);
+
+    let blocks: BlocksResponse = rpc
+        .call("getBlocksWithLimit", serde_json::json!([first_slot, 1]))
+        .await?;
+    if let Some(error) = blocks.error {
+        anyhow::bail!("getBlocksWithLimit failed: {}", error);
+    }
+    let first_block_slot = blocks
+        .result
+        .and_then(|slots| slots.first().copied())
+        .filter(|slot| *slot <= last_slot)
+        .with_context(|| format!("No confirmed block found in epoch {}", epoch))?;
+
+    let time: BlockTimeResponse = rpc.call("getBlockTime", serde_json::json!([first_block_slot])).await?;
+    if let Some(error) = time.error {
+        anyhow::bail!("getBlockTime failed: {}", error);
+    }
+    let block_time = time
+        .result
+        .with_context(|| format!("No block time for slot {}", first_block_slot))?;
+
+    Ok(EpochBoundary {
+        epoch,
+        first_block_slot,
+        block_time,
+        provenance: Provenance::new(DataSource::Rpc),
+    })
+}
+
+/// Epoch start times, exact where a boundary is known and extrapolated otherwise
+#[derive(Debug, Clone)]
+pub struct EpochCalendar {
+    /// Epoch -> start timestamp
+    starts: BTreeMap<u64, i64>,
+    /// Approximate epoch length, for extrapolating
+    epoch_seconds: i64,
+    /// Epoch start to extrapolate from when no boundary is known
+    reference: (u64, i64),
+}
+
+impl Default for EpochCalendar {
+    /// Mainnet calendar with no known boundaries
+    fn default() -> Self {
+        Self::new(
+            &[],
+            &EpochSchedule::without_warmup(),
+            (constants::REFERENCE_EPOCH as u64, constants::REFERENCE_EPOCH_TIMESTAMP),
+        )
+    }
+}
+
+impl EpochCalendar {
+    pub fn new(boundaries: &[EpochBoundary], schedule: &EpochSchedule, reference: (u64, i64)) -> Self {
+        Self {
+            starts: boundaries.iter().map(|b| (b.epoch, b.block_time)).collect(),
+            epoch_seconds: epoch_seconds(schedule),
+            reference,
+        }
+    }
+
+    pub fn contains(&self, epoch: u64) -> bool {
+        self.starts.contains_key(&epoch)
+    }
+
+    /// Start timestamp of an epoch: its boundary if known, otherwise extrapolated
+    /// from the closest earlier boundary (or later one, or the reference epoch)
+    pub fn start_time(&self, epoch: u64) -> i64 {
+        if let Some(&time) = self.starts.get(&epoch) {
+            return time;
+        }
+        let (known_epoch, known_time) = self
+            .starts
+            .range(..epoch)
+            .next_back()
+            .or_else(|| self.starts.range(epoch..).next())
+            .map(|(&e, &t)| (e, t))
+            .unwrap_or(self.reference);
+        extrapolate(known_epoch, known_time, epoch, self.epoch_seconds)
+    }
+
+    /// UTC date (YYYY-MM-DD) an epoch started
+    pub fn date(&self, epoch: u64) -> String {
+        format_date(self.start_time(epoch))
+    }
+
+    /// Set each row's date to the day its epoch started
+    pub fn apply<T: EpochDated>(&self, rows: &mut [T]) {
+        for row in rows {
+            let date = self.date(row.epoch());
+            row.set_date(date);
+        }
+    }
+}
+
+/// Start of `epoch` assuming `epoch_seconds`-long epochs after (or before) a known start
+pub fn extrapolate(known_epoch: u64, known_time: i64, epoch: u64, epoch_seconds: i64) -> i64 {
+    // Saturating arithmetic keeps extreme epoch values from overflowing
+    let to_i64 = |e: u64| e.min(i64::MAX as u64) as i64;
+    let epoch_diff = to_i64(epoch).saturating_sub(to_i64(known_epoch));
+    known_time.saturating_add(epoch_diff.saturating_mul(epoch_seconds))
+}
+
+/// YYYY-MM-DD for a Unix timestamp
+pub fn format_date(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Per-epoch rows dated by the epoch they belong to
+pub trait EpochDated {
+    fn epoch(&self) -> u64;
+    fn set_date(&mut self, date: String);
+}
+
+macro_rules! epoch_dated {
+    ($($row:ty),*) => {
+        $(impl EpochDated for $row {
+            fn epoch(&self) -> u64 {
+                self.epoch
+            }
+
+            fn set_date(&mut self, date: String) {
+                self.date = Some(date);
+            }
+        })*
+    };
+}
+
+epoch_dated!(EpochReward, MevClaim, EpochLeaderFees, EpochVoteCost);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boundary(epoch: u64, block_time: i64) -> EpochBoundary {
+        EpochBoundary {
+            epoch,
+            first_block_slot: EpochSchedule::without_warmup().get_first_slot_in_epoch(epoch),
+            block_time,
+            provenance: Provenance::new(DataSource::Rpc),
+        }
+    }
+
+    #[test]
+    fn test_known_boundaries_override_extrapolation() {
+        // Epoch 904 really started late on Dec 31, not on Jan 1
+        let calendar = EpochCalendar::new(
+            &[boundary(903, 1767024000), boundary(904, 1767222000)],
+            &EpochSchedule::without_warmup(),
+            (0, 0),
+        );
+        assert_eq!(calendar.date(904), "2025-12-31");
+        // The in-progress epoch extrapolates from the latest boundary
+        assert_eq!(calendar.date(905), "2026-01-02");
+        // Earlier epochs extrapolate back from the earliest boundary
+        assert_eq!(calendar.date(902), "2025-12-27");
+    }
+
+    #[test]
+    fn test_empty_calendar_uses_reference_epoch() {
+        let calendar = EpochCalendar::default();
+        assert_eq!(calendar.date(896), "2025-12-16");
+        assert_eq!(calendar.date(904), "2026-01-01");
+        assert!(!calendar.contains(896));
+    }
+
+    #[test]
+    fn test_calendar_uses_cluster_epoch_length_and_reference() {
+        // A cluster with quarter-length epochs, anchored at its current epoch
+        let schedule = EpochSchedule::custom(108_000, 108_000, false);
+        let calendar = EpochCalendar::new(&[], &schedule, (600, 1767225600));
+        assert_eq!(calendar.date(600), "2026-01-01");
+        assert_eq!(calendar.date(604), "2026-01-03");
+        assert_eq!(calendar.date(598), "2025-12-31");
+    }
+}
@@ -2,11 +2,29 @@
 //!
 //! Expenses are stored in the SQLite database and can be managed via CLI commands.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::path::Path;
 
+use crate::money::{Lamports, Usd};
+use crate::prices::{PriceCache, get_price};
+use crate::provenance::{DataSource, Provenance};
+use crate::transactions::SolTransfer;
+
+/// Days either side of an expense's date to look for its SOL payment
+const PAYMENT_MATCH_DAYS: i64 = 7;
+
+/// Largest difference between a transfer's USD value and an expense amount for
+/// it to be suggested as the payment, in basis points of the amount
+const PAYMENT_MATCH_TOLERANCE_BPS: u32 = 500;
+
+/// `paid_with` of a prepaid expense's monthly portions, which draw down the
+/// prepaid balance rather than being paid when incurred
+pub const PREPAID_PAID_WITH: &str = "Prepaid";
+
 /// Expense entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Expense {
@@ -17,32 +35,235 @@ pub struct Expense {
     pub vendor: String,
     pub category: ExpenseCategory,
     pub description: String,
-    pub amount_usd: f64,
+    pub amount_usd: Usd,
     pub paid_with: String, // "USD", "SOL", "Credit Card"
     pub invoice_id: Option<String>,
+    /// Vote account this expense belongs to (None = shared across validators)
+    #[serde(default)]
+    pub validator: Option<String>,
+    /// Signature of the on-chain transfer that paid this expense in SOL
+    #[serde(default)]
+    pub tx_signature: Option<String>,
+    /// SOL sent by that transfer; `amount_usd` is its value on the payment date
+    #[serde(default)]
+    pub amount_lamports: Option<Lamports>,
+    /// Date of that transfer (set by `value_sol_payments`), when the expense
+    /// is recognized on the cash basis
+    #[serde(skip)]
+    pub paid_date: Option<String>,
+    /// First day of the service a prepaid expense pays for (set with
+    /// `service_end`); its cost is then spread over the months of service
+    #[serde(default)]
+    pub service_start: Option<String>,
+    /// Last day of that service
+    #[serde(default)]
+    pub service_end: Option<String>,
+    /// Manual entry, file import or Notion hours log
+    #[serde(skip, default = "Provenance::manual")]
+    pub provenance: Provenance,
+}
+
+impl Expense {
+    /// Incurred but not yet paid (contractor hours not marked paid in Notion)
+    pub fn is_unpaid(&self) -> bool {
+        self.paid_with == "Unpaid"
+    }
+
+    /// Link the expense to the transfer that paid it, valued at `price` per SOL
+    pub fn link_payment(&mut self, transfer: &SolTransfer, price: Usd) {
+        self.tx_signature = Some(transfer.signature.clone());
+        self.amount_lamports = Some(transfer.amount_lamports);
+        self.amount_usd = transfer.amount_lamports.usd_value(price);
+        self.paid_with = "SOL".to_string();
+    }
+
+    /// First and last day of service of a prepaid expense (None for ordinary
+    /// expenses)
+    pub fn service_period(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let parse = |date: &Option<String>| NaiveDate::parse_from_str(date.as_deref()?, "%Y-%m-%d").ok();
+        Some((parse(&self.service_start)?, parse(&self.service_end)?))
+    }
+
+    /// Check a service period has both dates, in order
+    pub fn validate_service_period(&self) -> Result<()> {
+        match (&self.service_start, &self.service_end) {
+            (None, None) => Ok(()),
+            (Some(_), Some(_)) => {
+                let (start, end) = self
+                    .service_period()
+                    .context("Service period dates must be YYYY-MM-DD")?;
+                if end < start {
+                    anyhow::bail!("Service period ends ({}) before it starts ({})", end, start);
+                }
+                Ok(())
+            }
+            _ => anyhow::bail!("A service period needs both a start and an end date"),
+        }
+    }
+
+    /// Monthly portions of a prepaid expense: its amount split evenly over each
+    /// month of service (counted from the start, so a year from the 15th is 12
+    /// months), dated the day that month of service begins. None for ordinary
+    /// expenses.
+    pub fn amortization(&self) -> Vec<Expense> {
+        let Some((start, end)) = self.service_period() else {
+            return Vec::new();
+        };
+        let dates: Vec<NaiveDate> = (0..)
+            .map(|offset| month_date(start.year(), start.month(), offset, start.day()))
+            .take_while(|date| *date <= end)
+            .collect();
+        let amounts = self.amount_usd.allocate(&vec![1; dates.len()]);
+
+        let months = dates.len();
+        dates
+            .into_iter()
+            .zip(amounts)
+            .enumerate()
+            .map(|(index, (date, amount_usd))| Expense {
+                date: date.format("%Y-%m-%d").to_string(),
+                description: format!("{} (prepaid {}/{})", self.description, index + 1, months),
+                amount_usd,
+                paid_with: PREPAID_PAID_WITH.to_string(),
+                tx_signature: None,
+                amount_lamports: None,
+                paid_date: None,
+                service_start: None,
+                service_end: None,
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// Expenses dated when paid (cash basis): SOL-paid expenses on their payment
+/// transfer's date, which can fall in a later month than the invoice
+pub fn dated_as_paid(expenses: &[Expense]) -> Vec<Expense> {
+    expenses
+        .iter()
+        .map(|expense| Expense {
+            date: expense.paid_date.clone().unwrap_or_else(|| expense.date.clone()),
+            ..expense.clone()
+        })
+        .collect()
 }
 
-/// Expense category
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum ExpenseCategory {
-    Hosting,
-    Contractor,
-    Hardware,
-    Software,
-    VoteFees,
-    Other,
+/// Expenses as incurred (accrual basis): each prepaid expense replaced by its
+/// monthly portions up to `through`, beyond which the rest is still prepaid
+pub fn amortize_prepaid(expenses: &[Expense], through: NaiveDate) -> Vec<Expense> {
+    let through = through.format("%Y-%m-%d").to_string();
+    let mut incurred = Vec::new();
+    for expense in expenses {
+        if expense.service_period().is_some() {
+            incurred.extend(
+                expense
+                    .amortization()
+                    .into_iter()
+                    .filter(|portion| portion.date <= through),
+            );
+        } else {
+            incurred.push(expense.clone());
+        }
+    }
+    incurred
+}
+
+/// A transfer that could be an expense's SOL payment
+#[derive(Debug, Clone)]
+pub struct PaymentMatch<'a> {
+    pub transfer: &'a SolTransfer,
+    /// Transfer value on its date
+    pub usd_value: Usd,
+    /// Days between the expense and the transfer
+    pub days_apart: i64,
+}
+
+/// Outgoing transfers that could have paid an expense: within a week of its
+/// date and within 5% of its amount at the SOL price on the transfer date,
+/// closest amount first
+pub fn suggest_payments<'a>(
+    expense: &Expense,
+    candidates: &'a [SolTransfer],
+    prices: &PriceCache,
+) -> Vec<PaymentMatch<'a>> {
+    let Ok(expense_date) = NaiveDate::parse_from_str(&expense.date, "%Y-%m-%d") else {
+        return Vec::new();
+    };
+    let tolerance = expense.amount_usd.abs().scale_bps(PAYMENT_MATCH_TOLERANCE_BPS);
+
+    let mut matches: Vec<PaymentMatch> = candidates
+        .iter()
+        .filter_map(|transfer| {
+            let date = transfer.date.as_deref()?;
+            let days_apart = (NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()? - expense_date).num_days();
+            let usd_value = transfer.amount_lamports.usd_value(get_price(prices, date));
+            let close = days_apart.abs() <= PAYMENT_MATCH_DAYS && (usd_value - expense.amount_usd).abs() <= tolerance;
+            close.then_some(PaymentMatch {
+                transfer,
+                usd_value,
+                days_apart,
+            })
+        })
+        .collect();
+    matches.sort_by_key(|m| ((m.usd_value - expense.amount_usd).abs(), m.days_apart.abs()));
+    matches
+}
+
+/// Revalue SOL-paid expenses at the SOL price on their payment transfer's date
+/// (the same price the disposal of that SOL is valued at), and record that date
+/// as when they were paid
+pub fn value_sol_payments(expenses: &mut [Expense], transfers: &[SolTransfer], prices: &PriceCache) {
+    for expense in expenses {
+        let (Some(signature), Some(lamports)) = (&expense.tx_signature, expense.amount_lamports) else {
+            continue;
+        };
+        if let Some(date) = transfers
+            .iter()
+            .find(|t| &t.signature == signature)
+            .and_then(|t| t.date.as_deref())
+        {
+            expense.amount_usd = lamports.usd_value(get_price(prices, date));
+            expense.paid_date = Some(date.to_string());
+        }
+    }
+}
+
+/// Expense category: a top-level category, or `Parent:Child` for a
+/// subcategory (see `categories` for the hierarchy)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct ExpenseCategory(Cow<'static, str>);
+
+impl ExpenseCategory {
+    pub const HOSTING: ExpenseCategory = ExpenseCategory(Cow::Borrowed("Hosting"));
+    pub const CONTRACTOR: ExpenseCategory = ExpenseCategory(Cow::Borrowed("Contractor"));
+    pub const HARDWARE: ExpenseCategory = ExpenseCategory(Cow::Borrowed("Hardware"));
+    pub const SOFTWARE: ExpenseCategory = ExpenseCategory(Cow::Borrowed("Software"));
+    pub const VOTE_FEES: ExpenseCategory = ExpenseCategory(Cow::Borrowed("VoteFees"));
+    pub const OTHER: ExpenseCategory = ExpenseCategory(Cow::Borrowed("Other"));
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(Cow::Owned(name.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Top-level category ("Hosting" for "Hosting:Bandwidth")
+    pub fn top_level(&self) -> &str {
+        self.0.split(':').next().unwrap_or_default()
+    }
+
+    /// Parent category (None for top-level categories)
+    pub fn parent(&self) -> Option<ExpenseCategory> {
+        self.0.rsplit_once(':').map(|(parent, _)| ExpenseCategory::new(parent))
+    }
 }
 
 impl std::fmt::Display for ExpenseCategory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ExpenseCategory::Hosting => write!(f, "Hosting"),
-            ExpenseCategory::Contractor => write!(f, "Contractor"),
-            ExpenseCategory::Hardware => write!(f, "Hardware"),
-            ExpenseCategory::Software => write!(f, "Software"),
-            ExpenseCategory::VoteFees => write!(f, "Vote Fees"),
-            ExpenseCategory::Other => write!(f, "Other"),
-        }
+        f.pad(&self.0)
     }
 }
 
@@ -53,6 +274,7 @@ pub fn load_from_csv(path: &Path) -> Result<Vec<Expense>> {
     for result in rdr.deserialize() {
         let mut expense: Expense = result?;
         expense.id = None; // CSV imports don't have IDs
+        expense.provenance = Provenance::new(DataSource::Import);
         expenses.push(expense);
     }
     Ok(expenses)
@@ -70,29 +292,29 @@ pub fn export_to_csv(expenses: &[Expense], path: &Path) -> Result<()> {
 
 /// Calculate total expenses by category
 #[allow(dead_code)]
-pub fn expenses_by_category(expenses: &[Expense]) -> Vec<(ExpenseCategory, f64)> {
+pub fn expenses_by_category(expenses: &[Expense]) -> Vec<(ExpenseCategory, Usd)> {
     use std::collections::HashMap;
-    let mut totals: HashMap<ExpenseCategory, f64> = HashMap::new();
+    let mut totals: HashMap<ExpenseCategory, Usd> = HashMap::new();
 
     for expense in expenses {
-        *totals.entry(expense.category).or_insert(0.0) += expense.amount_usd;
+        *totals.entry(expense.category.clone()).or_default() += expense.amount_usd;
     }
 
     let mut result: Vec<_> = totals.into_iter().collect();
-    result.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    result.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
     result
 }
 
 /// Calculate total expenses by month
 #[allow(dead_code)]
-pub fn expenses_by_month(expenses: &[Expense]) -> Vec<(String, f64)> {
+pub fn expenses_by_month(expenses: &[Expense]) -> Vec<(String, Usd)> {
     use std::collections::HashMap;
-    let mut totals: HashMap<String, f64> = HashMap::new();
+    let mut totals: HashMap<String, Usd> = HashMap::new();
 
     for expense in expenses {
         if let Ok(date) = NaiveDate::parse_from_str(&expense.date, "%Y-%m-%d") {
             let month = date.format("%Y-%m").to_string();
-            *totals.entry(month).or_insert(0.0) += expense.amount_usd;
+            *totals.entry(month).or_default() += expense.amount_usd;
         }
     }
 
@@ -102,10 +324,140 @@ pub fn expenses_by_month(expenses: &[Expense]) -> Vec<(String, f64)> {
 }
 
 /// Get total expenses
-pub fn total_expenses(expenses: &[Expense]) -> f64 {
+pub fn total_expenses(expenses: &[Expense]) -> Usd {
     expenses.iter().map(|e| e.amount_usd).sum()
 }
 
+/// Split expenses across validators given as (vote account, expense share).
+///
+/// Expenses assigned to a validator go to it in full; shared expenses are split
+/// by expense share in whole cents, so the per-validator amounts add back up to
+/// the original. Expenses assigned to a validator not in the list are skipped.
+pub fn allocate_expenses(expenses: &[Expense], validators: &[(String, u32)]) -> Vec<Vec<Expense>> {
+    let weights: Vec<u32> = validators.iter().map(|(_, share)| *share).collect();
+    let total_weight: u32 = weights.iter().sum();
+    let mut allocated = vec![Vec::new(); validators.len()];
+
+    for expense in expenses {
+        match &expense.validator {
+            Some(vote_account) => {
+                if let Some(index) = validators.iter().position(|(v, _)| v == vote_account) {
+                    allocated[index].push(expense.clone());
+                }
+            }
+            None if validators.len() == 1 => allocated[0].push(expense.clone()),
+            None => {
+                let shares = expense.amount_usd.allocate(&weights);
+                let sol_shares = expense.amount_lamports.map(|lamports| lamports.allocate(&weights));
+                for (index, share) in shares.into_iter().enumerate() {
+                    if weights[index] == 0 {
+                        continue;
+                    }
+                    let mut portion = expense.clone();
+                    portion.amount_usd = share;
+                    portion.amount_lamports = sol_shares.as_ref().map(|sol| sol[index]);
+                    portion.description =
+                        format!("{} (shared {}/{})", expense.description, weights[index], total_weight);
+                    allocated[index].push(portion);
+                }
+            }
+        }
+    }
+
+    allocated
+}
+
+/// How often a recurring expense is charged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Cadence {
+    Weekly,
+    /// Every two weeks
+    Biweekly,
+    #[default]
+    Monthly,
+    Quarterly,
+    Annual,
+}
+
+impl Cadence {
+    /// Config, database and display name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Cadence::Weekly => "weekly",
+            Cadence::Biweekly => "biweekly",
+            Cadence::Monthly => "monthly",
+            Cadence::Quarterly => "quarterly",
+            Cadence::Annual => "annual",
+        }
+    }
+
+    /// Charges per year (for monthly equivalents)
+    pub fn per_year(self) -> u64 {
+        match self {
+            Cadence::Weekly => 52,
+            Cadence::Biweekly => 26,
+            Cadence::Monthly => 12,
+            Cadence::Quarterly => 4,
+            Cadence::Annual => 1,
+        }
+    }
+
+    /// Months between charges, for cadences billed on a day of the month
+    pub fn months(self) -> Option<i32> {
+        match self {
+            Cadence::Weekly | Cadence::Biweekly => None,
+            Cadence::Monthly => Some(1),
+            Cadence::Quarterly => Some(3),
+            Cadence::Annual => Some(12),
+        }
+    }
+
+    /// Days between charges, for cadences billed on a weekday
+    fn days(self) -> i64 {
+        match self {
+            Cadence::Weekly => 7,
+            _ => 14,
+        }
+    }
+}
+
+impl std::fmt::Display for Cadence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// New amount of a recurring expense from a date on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountChange {
+    /// First charge date the amount applies to (YYYY-MM-DD)
+    pub effective_date: String,
+    pub amount_usd: Usd,
+}
+
+/// One charge of a recurring expense skipped or billed at a different amount
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OccurrenceOverride {
+    /// Scheduled charge date (YYYY-MM-DD)
+    pub date: String,
+    /// Amount actually charged (None = skipped)
+    pub amount_usd: Option<Usd>,
+}
+
+/// One scheduled charge of a recurring expense
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub date: NaiveDate,
+    /// Amount charged (what would have been, if skipped)
+    pub amount_usd: Usd,
+    /// (days charged, days in the billing period) for a prorated first charge
+    pub prorated: Option<(i64, i64)>,
+    /// Charged at an overridden amount
+    pub overridden: bool,
+    pub skipped: bool,
+}
+
 /// Recurring expense template
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecurringExpense {
@@ -115,24 +467,139 @@ pub struct RecurringExpense {
     pub vendor: String,
     pub category: ExpenseCategory,
     pub description: String,
-    pub amount_usd: f64,
+    /// Amount of each charge (until the first of `amount_changes`)
+    pub amount_usd: Usd,
     pub paid_with: String,
-    /// First month this expense applies (YYYY-MM-DD, day is used for billing day)
+    /// First charge (YYYY-MM-DD); its day is the billing day unless `billing_day` is set
     pub start_date: String,
-    /// Last month this expense applies (None = ongoing)
+    /// Last day (weekly and biweekly) or month (other cadences) this expense
+    /// applies (None = ongoing)
     pub end_date: Option<String>,
+    /// Vote account this expense belongs to (None = shared across validators)
+    #[serde(default)]
+    pub validator: Option<String>,
+    #[serde(default)]
+    pub cadence: Cadence,
+    /// Day of the month monthly, quarterly and annual charges are billed on
+    /// (None = start_date's day)
+    #[serde(default)]
+    pub billing_day: Option<u32>,
+    /// Charge the part of a billing period from start_date to the first
+    /// billing day, prorated by days
+    #[serde(default)]
+    pub prorate: bool,
+    /// Price history, oldest first
+    #[serde(default)]
+    pub amount_changes: Vec<AmountChange>,
+    /// Skipped and overridden charges
+    #[serde(default)]
+    pub overrides: Vec<OccurrenceOverride>,
 }
 
 impl RecurringExpense {
-    /// Get the billing day from start_date
+    /// Get the billing day: `billing_day`, or the day of start_date
     pub fn billing_day(&self) -> u32 {
+        self.billing_day.unwrap_or_else(|| {
+            NaiveDate::parse_from_str(&self.start_date, "%Y-%m-%d")
+                .map(|d| d.day())
+                .unwrap_or(1)
+        })
+    }
+
+    /// Check the schedule fields are consistent
+    pub fn validate(&self) -> Result<()> {
         NaiveDate::parse_from_str(&self.start_date, "%Y-%m-%d")
-            .map(|d| d.day())
-            .unwrap_or(1)
+            .map_err(|_| anyhow::anyhow!("Invalid start date '{}' (expected YYYY-MM-DD)", self.start_date))?;
+        if let Some(end_date) = &self.end_date {
+            NaiveDate::parse_from_str(end_date, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid end date '{}' (expected YYYY-MM-DD)", end_date))?;
+        }
+        if self.cadence.months().is_none() && (self.billing_day.is_some() || self.prorate) {
+            anyhow::bail!(
+                "{} expenses are charged every {} days from the start date; billing days and proration only apply \
+                 to monthly, quarterly and annual expenses",
+                self.cadence,
+                self.cadence.days()
+            );
+        }
+        if self.billing_day.is_some_and(|day| !(1..=31).contains(&day)) {
+            anyhow::bail!("Billing day must be 1-31");
+        }
+        Ok(())
+    }
+
+    /// Amount of a charge on a date (YYYY-MM-DD), after the amount changes effective by then
+    pub fn amount_on(&self, date: &str) -> Usd {
+        self.amount_changes
+            .iter()
+            .filter(|change| change.effective_date.as_str() <= date)
+            .max_by(|a, b| a.effective_date.cmp(&b.effective_date))
+            .map_or(self.amount_usd, |change| change.amount_usd)
+    }
+
+    /// Charges scheduled between two dates (inclusive), skipped ones included
+    pub fn schedule(&self, from: NaiveDate, to: NaiveDate) -> Vec<Occurrence> {
+        let start = NaiveDate::parse_from_str(&self.start_date, "%Y-%m-%d")
+            .unwrap_or_else(|_| NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        let end = self
+            .end_date
+            .as_ref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+
+        let mut dates: Vec<(NaiveDate, Option<(i64, i64)>)> = Vec::new();
+        match self.cadence.months() {
+            None => {
+                let mut date = start;
+                while date <= to && end.is_none_or(|end| date <= end) {
+                    dates.push((date, None));
+                    date += chrono::Duration::days(self.cadence.days());
+                }
+            }
+            Some(step) => {
+                let day = self.billing_day();
+                let charge = |n: i32| month_date(start.year(), start.month(), n * step, day);
+                // End dates apply by month: a charge in the end month still counts
+                let within_end =
+                    |date: NaiveDate| end.is_none_or(|end| (date.year(), date.month()) <= (end.year(), end.month()));
+
+                // The first billing day on or after the start date
+                let first = if charge(0) >= start { 0 } else { 1 };
+                if self.prorate && charge(first) > start && start <= to && within_end(start) {
+                    let days = (charge(first) - start).num_days();
+                    let period = (charge(first) - charge(first - 1)).num_days();
+                    dates.push((start, Some((days, period))));
+                }
+                let mut n = first;
+                while charge(n) <= to && within_end(charge(n)) {
+                    dates.push((charge(n), None));
+                    n += 1;
+                }
+            }
+        }
+
+        dates
+            .into_iter()
+            .filter(|(date, _)| *date >= from)
+            .map(|(date, prorated)| {
+                let key = date.format("%Y-%m-%d").to_string();
+                let amount = self.amount_on(&key);
+                let amount = prorated.map_or(amount, |(days, period)| amount.mul_div(days as u64, period as u64));
+                let adjustment = self.overrides.iter().find(|o| o.date == key);
+                Occurrence {
+                    date,
+                    amount_usd: adjustment.and_then(|o| o.amount_usd).unwrap_or(amount),
+                    prorated,
+                    overridden: adjustment.is_some_and(|o| o.amount_usd.is_some()),
+                    skipped: adjustment.is_some_and(|o| o.amount_usd.is_none()),
+                }
+            })
+            .collect()
     }
 }
 
 /// Expand recurring expenses into individual expense entries for a date range
+/// (every charge from the first of `start_month` to the end of `end_month`,
+/// less skipped ones)
 pub fn expand_recurring_expenses(
     recurring: &[RecurringExpense],
     start_month: &str, // YYYY-MM
@@ -144,63 +611,48 @@ pub fn expand_recurring_expenses(
         .unwrap_or_else(|_| NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
     let end = NaiveDate::parse_from_str(&format!("{}-01", end_month), "%Y-%m-%d")
         .unwrap_or_else(|_| NaiveDate::from_ymd_opt(2025, 12, 1).unwrap());
+    let end = month_date(end.year(), end.month(), 1, 1).pred_opt().unwrap();
 
     for rec in recurring {
-        let rec_start = NaiveDate::parse_from_str(&rec.start_date, "%Y-%m-%d")
-            .unwrap_or_else(|_| NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
-        let rec_end = rec
-            .end_date
-            .as_ref()
-            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
-
-        let billing_day = rec.billing_day();
-
-        // Iterate through each month in the range
-        let mut current = start;
-        while current <= end {
-            // Check if this month falls within the recurring expense's active period
-            let current_month_start = current;
-            let rec_start_month = NaiveDate::from_ymd_opt(rec_start.year(), rec_start.month(), 1).unwrap();
-
-            if current_month_start >= rec_start_month {
-                // Check end date if present
-                let within_end = rec_end.is_none_or(|end_date| {
-                    let end_month_start = NaiveDate::from_ymd_opt(end_date.year(), end_date.month(), 1).unwrap();
-                    current_month_start <= end_month_start
-                });
-
-                if within_end {
-                    // Generate expense for this month
-                    // Handle months where billing_day doesn't exist (e.g., Feb 30 -> Feb 28)
-                    let days_in_month = days_in_month(current.year(), current.month());
-                    let actual_day = billing_day.min(days_in_month);
-                    let expense_date = NaiveDate::from_ymd_opt(current.year(), current.month(), actual_day).unwrap();
-
-                    expenses.push(Expense {
-                        id: None,
-                        date: expense_date.format("%Y-%m-%d").to_string(),
-                        vendor: rec.vendor.clone(),
-                        category: rec.category,
-                        description: rec.description.clone(),
-                        amount_usd: rec.amount_usd,
-                        paid_with: rec.paid_with.clone(),
-                        invoice_id: None,
-                    });
-                }
+        for occurrence in rec.schedule(start, end) {
+            if occurrence.skipped {
+                continue;
             }
-
-            // Move to next month
-            current = if current.month() == 12 {
-                NaiveDate::from_ymd_opt(current.year() + 1, 1, 1).unwrap()
-            } else {
-                NaiveDate::from_ymd_opt(current.year(), current.month() + 1, 1).unwrap()
+            let description = match occurrence.prorated {
+                Some((days, period)) => format!("{} (prorated {}/{} days)", rec.description, days, period),
+                None => rec.description.clone(),
             };
+            expenses.push(Expense {
+                id: None,
+                date: occurrence.date.format("%Y-%m-%d").to_string(),
+                vendor: rec.vendor.clone(),
+                category: rec.category.clone(),
+                description,
+                amount_usd: occurrence.amount_usd,
+                paid_with: rec.paid_with.clone(),
+                invoice_id: None,
+                validator: rec.validator.clone(),
+                tx_signature: None,
+                amount_lamports: None,
+                paid_date: None,
+                service_start: None,
+                service_end: None,
+                provenance: Provenance::manual(),
+            });
         }
     }
 
     expenses
 }
 
+/// `day` of the month `offset` months after year/month, or the month's last
+/// day when it's shorter (a billing day of the 31st charges on Feb 28)
+fn month_date(year: i32, month: u32, offset: i32, day: u32) -> NaiveDate {
+    let months = year * 12 + month as i32 - 1 + offset;
+    let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+    NaiveDate::from_ymd_opt(year, month, day.min(days_in_month(year, month))).unwrap()
+}
+
 /// Get the number of days in a month
 fn days_in_month(year: i32, month: u32) -> u32 {
     match month {
@@ -216,3 +668,354 @@ fn days_in_month(year: i32, month: u32) -> u32 {
         _ => 30,
     }
 }
+
+/// Expense table changes are logged for (see `Cache::expense_history`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpenseTable {
+    Expenses,
+    RecurringExpenses,
+    /// The fixed asset register (see `assets`)
+    Assets,
+}
+
+impl ExpenseTable {
+    /// Tables whose logged changes make up a row's history: the table itself,
+    /// plus a recurring expense's amount changes and skipped or overridden charges
+    pub fn logged_tables(self) -> &'static [&'static str] {
+        match self {
+            ExpenseTable::Expenses => &["expenses"],
+            ExpenseTable::RecurringExpenses => &[
+                "recurring_expenses",
+                "recurring_expense_amounts",
+                "recurring_expense_overrides",
+            ],
+            ExpenseTable::Assets => &["fixed_assets"],
+        }
+    }
+}
+
+/// One logged insert, update or delete of an expense, recurring expense or asset
+#[derive(Debug, Clone)]
+pub struct ExpenseChange {
+    /// Table the changed row is in (see `ExpenseTable::logged_tables`)
+    pub table_name: String,
+    /// "insert", "update" or "delete"
+    pub action: String,
+    /// UTC, `YYYY-MM-DD HH:MM:SS`
+    pub changed_at: String,
+    /// Column values before the change (None for inserts)
+    pub old_values: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Column values after the change (None for deletes)
+    pub new_values: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+impl ExpenseChange {
+    /// What a change to a recurring expense's schedule was to ("amount change"
+    /// or "charge"); None for changes to the expense itself
+    pub fn subject(&self) -> Option<&'static str> {
+        match self.table_name.as_str() {
+            "recurring_expense_amounts" => Some("amount change"),
+            "recurring_expense_overrides" => Some("charge"),
+            _ => None,
+        }
+    }
+
+    /// Fields that differ between the old and new values, as (field, old, new)
+    /// display strings; every recorded field for inserts and deletes
+    pub fn changed_fields(&self) -> Vec<(String, String, String)> {
+        let empty = serde_json::Map::new();
+        let old = self.old_values.as_ref().unwrap_or(&empty);
+        let new = self.new_values.as_ref().unwrap_or(&empty);
+        let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+        fields.sort();
+        fields.dedup();
+
+        fields
+            .into_iter()
+            .filter(|field| old.get(*field) != new.get(*field))
+            .map(|field| {
+                let show = |values: &serde_json::Map<String, serde_json::Value>| match values.get(field) {
+                    Some(serde_json::Value::Null)
+                        if field == "amount_usd_micros" && self.subject() == Some("charge") =>
+                    {
+                        "skipped".to_string()
+                    }
+                    None | Some(serde_json::Value::Null) => "-".to_string(),
+                    Some(value) if field.ends_with("_usd_micros") => {
+                        Usd::from_micros(value.as_i64().unwrap_or_default()).to_string()
+                    }
+                    Some(value) if field == "amount_lamports" => {
+                        Lamports(value.as_u64().unwrap_or_default()).to_string()
+                    }
+                    Some(serde_json::Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                };
+                let name = match field.as_str() {
+                    "amount_lamports" => "amount_sol",
+                    field => field.strip_suffix("_micros").unwrap_or(field),
+                }
+                .to_string();
+                (name, show(old), show(new))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addresses::AddressCategory;
+    use crate::prices::Price;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn expense(amount: Usd, validator: Option<&str>) -> Expense {
+        Expense {
+            id: None,
+            date: "2025-12-01".to_string(),
+            vendor: "Latitude".to_string(),
+            category: ExpenseCategory::HOSTING,
+            description: "Server".to_string(),
+            amount_usd: amount,
+            paid_with: "USD".to_string(),
+            invoice_id: None,
+            validator: validator.map(str::to_string),
+            tx_signature: None,
+            amount_lamports: None,
+            paid_date: None,
+            service_start: None,
+            service_end: None,
+            provenance: Provenance::manual(),
+        }
+    }
+
+    #[test]
+    fn test_allocate_shared_and_assigned_expenses() {
+        let validators = vec![("main".to_string(), 2), ("testnet".to_string(), 1)];
+        let mut paid_in_sol = expense(Usd::from_cents(100_000), None);
+        paid_in_sol.amount_lamports = Some(Lamports(3_000_000_001));
+        let expenses = vec![
+            paid_in_sol,
+            expense(Usd::from_cents(5_000), Some("testnet")),
+            expense(Usd::from_cents(7_500), Some("retired")),
+        ];
+
+        let allocated = allocate_expenses(&expenses, &validators);
+
+        assert_eq!(allocated[0].len(), 1);
+        assert_eq!(allocated[0][0].amount_usd, Usd::from_cents(66_667));
+        assert_eq!(allocated[0][0].description, "Server (shared 2/3)");
+        assert_eq!(allocated[0][0].amount_lamports, Some(Lamports(2_000_000_001)));
+
+        assert_eq!(allocated[1].len(), 2);
+        assert_eq!(allocated[1][0].amount_usd, Usd::from_cents(33_333));
+        assert_eq!(allocated[1][0].amount_lamports, Some(Lamports(1_000_000_000)));
+        assert_eq!(allocated[1][1].amount_usd, Usd::from_cents(5_000));
+
+        // Shared portions add back up to the original amount
+        assert_eq!(
+            allocated[0][0].amount_usd + allocated[1][0].amount_usd,
+            Usd::from_cents(100_000)
+        );
+    }
+
+    #[test]
+    fn test_single_validator_keeps_shared_expenses_whole() {
+        let validators = vec![("main".to_string(), 1)];
+        let allocated = allocate_expenses(&[expense(Usd::from_cents(1_001), None)], &validators);
+        assert_eq!(allocated[0][0].amount_usd, Usd::from_cents(1_001));
+        assert_eq!(allocated[0][0].description, "Server");
+    }
+
+    #[test]
+    fn test_prepaid_expenses_amortize_over_months_of_service() {
+        let mut prepaid = expense(Usd::from_cents(100_000), None);
+        prepaid.service_start = Some("2026-01-15".to_string());
+        prepaid.service_end = Some("2027-01-14".to_string());
+        prepaid.validate_service_period().unwrap();
+
+        let portions = prepaid.amortization();
+        assert_eq!(portions.len(), 12);
+        assert_eq!(portions[0].date, "2026-01-15");
+        assert_eq!(portions[11].date, "2026-12-15");
+        assert_eq!(portions[0].description, "Server (prepaid 1/12)");
+        assert_eq!(portions[0].paid_with, PREPAID_PAID_WITH);
+        assert_eq!(portions[0].amount_usd, Usd::from_cents(8_334));
+        assert_eq!(portions[11].amount_usd, Usd::from_cents(8_333));
+        assert_eq!(
+            portions.iter().map(|p| p.amount_usd).fold(Usd::ZERO, |a, b| a + b),
+            Usd::from_cents(100_000)
+        );
+
+        // Only the months of service up to the report date are incurred
+        let ordinary = expense(Usd::from_cents(5_000), None);
+        let incurred = amortize_prepaid(
+            &[ordinary, prepaid.clone()],
+            NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+        );
+        let dates: Vec<&str> = incurred.iter().map(|e| e.date.as_str()).collect();
+        assert_eq!(dates, ["2025-12-01", "2026-01-15", "2026-02-15", "2026-03-15"]);
+
+        prepaid.service_end = Some("2025-12-31".to_string());
+        assert!(prepaid.validate_service_period().is_err());
+        prepaid.service_end = None;
+        assert!(prepaid.validate_service_period().is_err());
+    }
+
+    fn recurring(cadence: Cadence, start_date: &str, amount: Usd) -> RecurringExpense {
+        RecurringExpense {
+            id: None,
+            vendor: "Latitude".to_string(),
+            category: ExpenseCategory::HOSTING,
+            description: "Server".to_string(),
+            amount_usd: amount,
+            paid_with: "USD".to_string(),
+            start_date: start_date.to_string(),
+            end_date: None,
+            validator: None,
+            cadence,
+            billing_day: None,
+            prorate: false,
+            amount_changes: Vec::new(),
+            overrides: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_monthly_schedule_with_proration_price_change_and_overrides() {
+        // Started mid-January, billed on the 1st, repriced from March
+        let mut hosting = recurring(Cadence::Monthly, "2026-01-15", Usd::from_cents(31_000));
+        hosting.billing_day = Some(1);
+        hosting.prorate = true;
+        hosting.amount_changes.push(AmountChange {
+            effective_date: "2026-03-01".to_string(),
+            amount_usd: Usd::from_cents(40_000),
+        });
+        hosting.overrides = vec![
+            OccurrenceOverride {
+                date: "2026-03-01".to_string(),
+                amount_usd: None,
+            },
+            OccurrenceOverride {
+                date: "2026-04-01".to_string(),
+                amount_usd: Some(Usd::from_cents(35_000)),
+            },
+        ];
+
+        let expanded = expand_recurring_expenses(&[hosting.clone()], "2026-01", "2026-04");
+        let charges: Vec<(&str, Usd)> = expanded.iter().map(|e| (e.date.as_str(), e.amount_usd)).collect();
+        assert_eq!(
+            charges,
+            [
+                ("2026-01-15", Usd::from_cents(17_000)),
+                ("2026-02-01", Usd::from_cents(31_000)),
+                ("2026-04-01", Usd::from_cents(35_000)),
+            ]
+        );
+        assert_eq!(expanded[0].description, "Server (prorated 17/31 days)");
+
+        // Skipped charges are still scheduled, at the amount they would have been
+        let march = hosting.schedule(date("2026-03-01"), date("2026-03-31"));
+        assert!(march[0].skipped);
+        assert_eq!(march[0].amount_usd, Usd::from_cents(40_000));
+        assert_eq!(hosting.amount_on("2026-02-28"), Usd::from_cents(31_000));
+
+        // Without proration the first charge is the first billing day
+        hosting.prorate = false;
+        assert_eq!(hosting.schedule(date("2026-01-01"), date("2026-02-28")).len(), 1);
+    }
+
+    #[test]
+    fn test_weekly_quarterly_and_annual_cadences() {
+        let mut contractor = recurring(Cadence::Biweekly, "2026-01-02", Usd::from_cents(200_000));
+        contractor.end_date = Some("2026-02-13".to_string());
+        let quarterly = recurring(Cadence::Quarterly, "2026-01-31", Usd::from_cents(9_000));
+        let domain = recurring(Cadence::Annual, "2025-06-10", Usd::from_cents(1_500));
+        let weekly = recurring(Cadence::Weekly, "2026-12-28", Usd::from_cents(100));
+
+        let dates = |rec: &RecurringExpense| -> Vec<String> {
+            expand_recurring_expenses(std::slice::from_ref(rec), "2026-01", "2026-12")
+                .into_iter()
+                .map(|e| e.date)
+                .collect()
+        };
+        assert_eq!(
+            dates(&contractor),
+            ["2026-01-02", "2026-01-16", "2026-01-30", "2026-02-13"]
+        );
+        assert_eq!(
+            dates(&quarterly),
+            ["2026-01-31", "2026-04-30", "2026-07-31", "2026-10-31"]
+        );
+        assert_eq!(dates(&domain), ["2026-06-10"]);
+        assert_eq!(dates(&weekly), ["2026-12-28"]);
+
+        let mut invalid = weekly.clone();
+        invalid.billing_day = Some(1);
+        assert!(invalid.validate().is_err());
+        assert!(quarterly.validate().is_ok());
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn payment(signature: &str, date: &str, lamports: u64) -> SolTransfer {
+        SolTransfer {
+            signature: signature.to_string(),
+            slot: 0,
+            timestamp: None,
+            date: Some(date.to_string()),
+            from: Pubkey::new_unique(),
+            to: Pubkey::new_unique(),
+            amount_lamports: Lamports(lamports),
+            from_label: "Identity".to_string(),
+            to_label: "Unknown".to_string(),
+            from_category: AddressCategory::ValidatorSelf,
+            to_category: AddressCategory::Unknown,
+            provenance: Provenance::new(DataSource::Rpc),
+        }
+    }
+
+    #[test]
+    fn test_suggest_and_value_sol_payments() {
+        let prices: PriceCache = [
+            ("2025-12-03", Usd::from_cents(20_000)),
+            ("2025-12-20", Usd::from_cents(20_000)),
+            ("2025-12-05", Usd::from_cents(25_000)),
+        ]
+        .into_iter()
+        .map(|(date, price)| (date.to_string(), Price::new(price, DataSource::CoinGecko)))
+        .collect();
+        let transfers = vec![
+            payment("far", "2025-12-20", 5_000_000_000),
+            payment("close", "2025-12-03", 5_100_000_000),
+            payment("exact", "2025-12-03", 5_000_000_000),
+            payment("off", "2025-12-05", 5_000_000_000),
+        ];
+
+        // $1,000 expense: 5 SOL at $200 two days later, then 5.1 SOL ($1,020);
+        // too late, and 25% over at the later price, are not suggested
+        let mut paid = expense(Usd::from_cents(100_000), None);
+        let matches = suggest_payments(&paid, &transfers, &prices);
+        let signatures: Vec<&str> = matches.iter().map(|m| m.transfer.signature.as_str()).collect();
+        assert_eq!(signatures, vec!["exact", "close"]);
+        assert_eq!(matches[0].days_apart, 2);
+
+        paid.link_payment(matches[1].transfer, Usd::from_cents(20_000));
+        assert_eq!(paid.paid_with, "SOL");
+        assert_eq!(paid.amount_usd, Usd::from_cents(102_000));
+
+        // Revalued at the price on the transfer's date
+        paid.tx_signature = Some("off".to_string());
+        paid.amount_lamports = Some(Lamports(5_000_000_000));
+        let mut expenses = vec![paid, expense(Usd::from_cents(500), None)];
+        value_sol_payments(&mut expenses, &transfers, &prices);
+        assert_eq!(expenses[0].amount_usd, Usd::from_cents(125_000));
+        assert_eq!(expenses[0].paid_date.as_deref(), Some("2025-12-05"));
+        assert_eq!(expenses[1].amount_usd, Usd::from_cents(500));
+        assert_eq!(expenses[1].paid_date, None);
+        let as_paid = dated_as_paid(&expenses);
+        assert_eq!(as_paid[0].date, "2025-12-05");
+        assert_eq!(as_paid[1].date, expenses[1].date);
+    }
+}
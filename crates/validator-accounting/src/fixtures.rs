@@ -0,0 +1,343 @@
+//! Record/replay layer over the data sources
+//!
+//! `Fixtures` wraps a source: with a live source inside it records every
+//! successful response, and without one it replays them. Each source gets one
+//! JSON file in the fixture directory (`chain.json`, `mev.json`, ...) mapping a
+//! request key to its response, so fixtures are easy to review and hand-edit.
+//! A request with no recorded response fails, just like an unreachable service.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use futures::future::{BoxFuture, FutureExt};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::cluster::Cluster;
+use crate::config::{Config, NotionConfig};
+use crate::epochs::EpochBoundary;
+use crate::jito::MevClaim;
+use crate::leader_fees::EpochLeaderFees;
+use crate::money::Usd;
+use crate::notion::HoursLogEntry;
+use crate::reconcile::BalanceSnapshot;
+use crate::sources::{AnalyticsSource, ChainSource, HoursSource, MevSource, PriceSource, Sources};
+use crate::transactions::{EpochReward, FetchTransfersResult, SolTransfer};
+use crate::vote_costs::EpochVoteCost;
+
+/// Recorded responses for one source, keyed by request
+struct FixtureFile {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<String, serde_json::Value>>,
+}
+
+impl FixtureFile {
+    /// Load `<dir>/<name>.json`, or start empty if it doesn't exist yet
+    fn open(dir: &Path, name: &str) -> Result<Self> {
+        let path = dir.join(format!("{}.json", name));
+        let entries = if path.exists() {
+            let content =
+                std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    fn get<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+        let entries = self.entries.lock().unwrap();
+        let value = entries
+            .get(key)
+            .with_context(|| format!("No fixture recorded for '{}' in {}", key, self.path.display()))?;
+        serde_json::from_value(value.clone()).with_context(|| format!("Invalid fixture '{}'", key))
+    }
+
+    /// Store a response and rewrite the file (sorted keys keep diffs small)
+    fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key.to_string(), serde_json::to_value(value)?);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&*entries)? + "\n")
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// A source that records responses from `live`, or replays them when `live` is None
+pub struct Fixtures<S> {
+    live: Option<S>,
+    file: FixtureFile,
+}
+
+impl<S> Fixtures<S> {
+    fn recording(live: S, dir: &Path, name: &str) -> Result<Self> {
+        Ok(Self {
+            live: Some(live),
+            file: FixtureFile::open(dir, name)?,
+        })
+    }
+
+    fn replay(dir: &Path, name: &str) -> Result<Self> {
+        Ok(Self {
+            live: None,
+            file: FixtureFile::open(dir, name)?,
+        })
+    }
+
+    /// Run `fetch` on the live source and record the result, or replay it
+    fn through<'a, T, F>(&'a self, key: String, fetch: F) -> BoxFuture<'a, Result<T>>
+    where
+        S: Sync,
+        T: Serialize + DeserializeOwned + Send + 'a,
+        F: FnOnce(&'a S) -> BoxFuture<'a, Result<T>>,
+    {
+        match &self.live {
+            Some(live) => {
+                let response = fetch(live);
+                async move {
+                    let value = response.await?;
+                    self.file.put(&key, &value)?;
+                    Ok(value)
+                }
+                .boxed()
+            }
+            None => futures::future::ready(self.file.get(&key)).boxed(),
+        }
+    }
+}
+
+impl Sources {
+    /// Wrap every source so its responses are saved under `dir`
+    pub fn recording(self, dir: &Path) -> Result<Self> {
+        Ok(Self {
+            chain: Box::new(Fixtures::recording(self.chain, dir, "chain")?),
+            mev: Box::new(Fixtures::recording(self.mev, dir, "mev")?),
+            prices: Box::new(Fixtures::recording(self.prices, dir, "prices")?),
+            analytics: match self.analytics {
+                Some(analytics) => Some(Box::new(Fixtures::recording(analytics, dir, "analytics")?)),
+                None => None,
+            },
+            hours: Box::new(Fixtures::recording(self.hours, dir, "hours")?),
+        })
+    }
+
+    /// Sources that only answer from responses recorded under `dir`
+    pub fn replay(dir: &Path) -> Result<Self> {
+        anyhow::ensure!(dir.is_dir(), "Fixture directory {} not found", dir.display());
+        let analytics: Option<Box<dyn AnalyticsSource>> = if dir.join("analytics.json").exists() {
+            Some(Box::new(Fixtures::<Box<dyn AnalyticsSource>>::replay(
+                dir,
+                "analytics",
+            )?))
+        } else {
+            None
+        };
+        Ok(Self {
+            chain: Box::new(Fixtures::<Box<dyn ChainSource>>::replay(dir, "chain")?),
+            mev: Box::new(Fixtures::<Box<dyn MevSource>>::replay(dir, "mev")?),
+            prices: Box::new(Fixtures::<Box<dyn PriceSource>>::replay(dir, "prices")?),
+            analytics,
+            hours: Box::new(Fixtures::<Box<dyn HoursSource>>::replay(dir, "hours")?),
+        })
+    }
+}
+
+/// "start-end" for an epoch range (end "latest" when open)
+fn epoch_range(start_epoch: u64, end_epoch: Option<u64>) -> String {
+    match end_epoch {
+        Some(end) => format!("{}-{}", start_epoch, end),
+        None => format!("{}-latest", start_epoch),
+    }
+}
+
+/// Key for a cluster-wide request, prefixed with the cluster off mainnet
+fn cluster_key(config: &Config, key: String) -> String {
+    match config.cluster {
+        Cluster::MainnetBeta => key,
+        cluster => format!("{}/{}", cluster, key),
+    }
+}
+
+impl ChainSource for Fixtures<Box<dyn ChainSource>> {
+    fn current_epoch<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<u64>> {
+        let key = cluster_key(config, "current_epoch".to_string());
+        self.through(key, move |live| live.current_epoch(config))
+    }
+
+    fn epoch_schedule<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<EpochSchedule>> {
+        let key = cluster_key(config, "epoch_schedule".to_string());
+        self.through(key, move |live| live.epoch_schedule(config))
+    }
+
+    fn inflation_rewards<'a>(
+        &'a self,
+        config: &'a Config,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> BoxFuture<'a, Result<Vec<EpochReward>>> {
+        let key = format!(
+            "inflation_rewards/{}/{}",
+            config.vote_account,
+            epoch_range(start_epoch, end_epoch)
+        );
+        self.through(key, move |live| live.inflation_rewards(config, start_epoch, end_epoch))
+    }
+
+    fn current_epoch_rewards<'a>(
+        &'a self,
+        config: &'a Config,
+        current_epoch: u64,
+    ) -> BoxFuture<'a, Result<Vec<EpochReward>>> {
+        let key = format!("current_epoch_rewards/{}/{}", config.vote_account, current_epoch);
+        self.through(key, move |live| live.current_epoch_rewards(config, current_epoch))
+    }
+
+    fn leader_fees<'a>(
+        &'a self,
+        config: &'a Config,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> BoxFuture<'a, Result<Vec<EpochLeaderFees>>> {
+        let key = format!(
+            "leader_fees/{}/{}",
+            config.identity,
+            epoch_range(start_epoch, end_epoch)
+        );
+        self.through(key, move |live| live.leader_fees(config, start_epoch, end_epoch))
+    }
+
+    fn sol_transfers<'a>(
+        &'a self,
+        config: &'a Config,
+        verbose: bool,
+    ) -> BoxFuture<'a, Result<(Vec<SolTransfer>, Vec<BalanceSnapshot>)>> {
+        let key = format!("sol_transfers/{}", config.vote_account);
+        self.through(key, move |live| live.sol_transfers(config, verbose))
+    }
+
+    fn transfers_for_account<'a>(
+        &'a self,
+        config: &'a Config,
+        account: Pubkey,
+        label: &'a str,
+        stop_at_slot: Option<u64>,
+        verbose: bool,
+    ) -> BoxFuture<'a, Result<FetchTransfersResult>> {
+        let stop = stop_at_slot.map_or("all".to_string(), |slot| slot.to_string());
+        let key = format!("transfers_for_account/{}/{}", account, stop);
+        self.through(key, move |live| {
+            live.transfers_for_account(config, account, label, stop_at_slot, verbose)
+        })
+    }
+
+    fn epoch_boundary<'a>(&'a self, config: &'a Config, epoch: u64) -> BoxFuture<'a, Result<EpochBoundary>> {
+        let key = cluster_key(config, format!("epoch_boundary/{}", epoch));
+        self.through(key, move |live| live.epoch_boundary(config, epoch))
+    }
+}
+
+impl MevSource for Fixtures<Box<dyn MevSource>> {
+    fn mev_claims<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<Vec<MevClaim>>> {
+        let key = format!("mev_claims/{}", config.vote_account);
+        self.through(key, move |live| live.mev_claims(config))
+    }
+}
+
+impl PriceSource for Fixtures<Box<dyn PriceSource>> {
+    fn price_range(&self, from: NaiveDate, to: NaiveDate) -> BoxFuture<'_, Result<Vec<(String, Usd)>>> {
+        self.through(format!("price_range/{}/{}", from, to), move |live| {
+            live.price_range(from, to)
+        })
+    }
+
+    fn current_price(&self) -> BoxFuture<'_, Result<Usd>> {
+        self.through("current_price".to_string(), |live| live.current_price())
+    }
+}
+
+impl AnalyticsSource for Fixtures<Box<dyn AnalyticsSource>> {
+    fn inflation_rewards<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochReward>>> {
+        let key = format!("inflation_rewards/{}/{}", config.vote_account, since);
+        self.through(key, move |live| live.inflation_rewards(config, since))
+    }
+
+    fn leader_fees<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochLeaderFees>>> {
+        let key = format!("leader_fees/{}/{}", config.identity, since);
+        self.through(key, move |live| live.leader_fees(config, since))
+    }
+
+    fn vote_costs<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochVoteCost>>> {
+        let key = format!("vote_costs/{}/{}", config.identity, since);
+        self.through(key, move |live| live.vote_costs(config, since))
+    }
+
+    fn transfers<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<SolTransfer>>> {
+        let key = format!("transfers/{}/{}", config.withdraw_authority, since);
+        self.through(key, move |live| live.transfers(config, since))
+    }
+
+    fn epoch_boundaries<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochBoundary>>> {
+        self.through(format!("epoch_boundaries/{}", since), move |live| {
+            live.epoch_boundaries(config, since)
+        })
+    }
+}
+
+impl HoursSource for Fixtures<Box<dyn HoursSource>> {
+    fn hours_log<'a>(&'a self, config: &'a NotionConfig) -> BoxFuture<'a, Result<Vec<HoursLogEntry>>> {
+        let key = format!("hours_log/{}", config.hours_database_id);
+        self.through(key, move |live| live.hours_log(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("validator-accounting-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    struct FixedPrices;
+
+    impl PriceSource for FixedPrices {
+        fn price_range(&self, from: NaiveDate, _to: NaiveDate) -> BoxFuture<'_, Result<Vec<(String, Usd)>>> {
+            futures::future::ready(Ok(vec![(from.to_string(), Usd::from_cents(14_250))])).boxed()
+        }
+
+        fn current_price(&self) -> BoxFuture<'_, Result<Usd>> {
+            futures::future::ready(Ok(Usd::from_cents(15_000))).boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recorded_responses_replay() {
+        let dir = fixture_dir("fixtures-roundtrip");
+        let from = NaiveDate::from_ymd_opt(2025, 11, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 11, 30).unwrap();
+
+        let recording = Fixtures::recording(Box::new(FixedPrices) as Box<dyn PriceSource>, &dir, "prices").unwrap();
+        let recorded = recording.price_range(from, to).await.unwrap();
+        assert_eq!(recording.current_price().await.unwrap(), Usd::from_cents(15_000));
+
+        let replay = Fixtures::<Box<dyn PriceSource>>::replay(&dir, "prices").unwrap();
+        assert_eq!(replay.price_range(from, to).await.unwrap(), recorded);
+        assert_eq!(replay.current_price().await.unwrap(), Usd::from_cents(15_000));
+
+        // Requests that were never recorded fail like an unreachable service
+        assert!(replay.price_range(to, to).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,26 +1,30 @@
 //! Jito MEV tip tracking via Jito API
 //!
 //! MEV tips are claimed to the vote account by Jito's merkle_root_upload_authority.
-//! We query Jito's API to get per-epoch MEV rewards for the validator.
+//! We query Jito's API to get per-epoch MEV rewards for the validator, along with
+//! the priority fee rewards paid out through Jito's block engine (BAM).
 
 use anyhow::Result;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio::time::sleep;
 
 use crate::config::Config;
-use crate::constants;
+use crate::money::Lamports;
+use crate::provenance::{DataSource, Provenance};
 use crate::transactions::epoch_to_date;
 
 /// MEV reward for a single epoch
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MevClaim {
     pub epoch: u64,
-    pub total_tips_lamports: u64,
-    #[allow(dead_code)]
-    pub commission_lamports: u64, // Validator's share (from API mev_commission_bps)
-    pub amount_sol: f64, // Commission in SOL
+    pub total_tips_lamports: Lamports,
+    pub commission_lamports: Lamports, // Validator's share (from API mev_commission_bps)
+    pub total_priority_fees_lamports: Lamports,
+    pub priority_fee_commission_lamports: Lamports, // Validator's share (from API priority_fee_commission_bps)
     pub date: Option<String>,
+    #[serde(flatten)]
+    pub provenance: Provenance,
 }
 
 /// Per-epoch MEV data from Jito API
@@ -30,18 +34,20 @@ struct JitoEpochData {
     mev_commission_bps: u64,
     mev_rewards: u64, // Total tips in lamports
     #[serde(default)]
-    #[allow(dead_code)]
     priority_fee_commission_bps: u64,
     #[serde(default)]
-    #[allow(dead_code)]
-    priority_fee_rewards: u64,
+    priority_fee_rewards: u64, // Total priority fees in lamports
 }
 
 /// Fetch MEV claims from Jito API with retry logic
 pub async fn fetch_mev_claims(config: &Config) -> Result<Vec<MevClaim>> {
     let client = reqwest::Client::new();
 
-    let url = format!("{}/validators/{}", constants::JITO_API_BASE, config.vote_account);
+    let url = format!(
+        "{}/validators/{}",
+        config.endpoints.jito.trim_end_matches('/'),
+        config.vote_account
+    );
     println!("    Querying Jito API...");
 
     // Retry with exponential backoff (longer delays for rate limiting)
@@ -98,35 +104,87 @@ fn process_jito_epochs(epochs: Vec<JitoEpochData>) -> Result<Vec<MevClaim>> {
 
     for epoch_data in epochs {
         // Validator commission is based on mev_commission_bps (1000 = 10%)
-        let commission_rate = epoch_data.mev_commission_bps as f64 / 10000.0;
-        let commission_lamports = (epoch_data.mev_rewards as f64 * commission_rate) as u64;
-        let amount_sol = commission_lamports as f64 / 1e9;
+        let commission_lamports = commission_share(epoch_data.mev_rewards, epoch_data.mev_commission_bps);
+        let total_tips_lamports = Lamports(epoch_data.mev_rewards);
+        let priority_fee_commission_lamports =
+            commission_share(epoch_data.priority_fee_rewards, epoch_data.priority_fee_commission_bps);
+        let total_priority_fees_lamports = Lamports(epoch_data.priority_fee_rewards);
         let date = epoch_to_date(epoch_data.epoch);
 
         claims.push(MevClaim {
             epoch: epoch_data.epoch,
-            total_tips_lamports: epoch_data.mev_rewards,
+            total_tips_lamports,
             commission_lamports,
-            amount_sol,
+            total_priority_fees_lamports,
+            priority_fee_commission_lamports,
             date: Some(date),
+            provenance: Provenance::new(DataSource::JitoApi),
         });
 
         println!(
             "      Epoch {}: {:.4} SOL tips -> {:.4} SOL commission ({}%)",
             epoch_data.epoch,
-            epoch_data.mev_rewards as f64 / 1e9,
-            amount_sol,
+            total_tips_lamports,
+            commission_lamports,
             epoch_data.mev_commission_bps / 100
         );
+        if total_priority_fees_lamports > Lamports::ZERO {
+            println!(
+                "      Epoch {}: {:.4} SOL priority fees -> {:.4} SOL commission ({}%)",
+                epoch_data.epoch,
+                total_priority_fees_lamports,
+                priority_fee_commission_lamports,
+                epoch_data.priority_fee_commission_bps / 100
+            );
+        }
     }
 
     // Sort by epoch
-    claims.sort_by(|a, b| a.epoch.cmp(&b.epoch));
+    claims.sort_by_key(|c| c.epoch);
 
     Ok(claims)
 }
 
-/// Get total MEV commission in SOL
-pub fn total_mev_sol(claims: &[MevClaim]) -> f64 {
-    claims.iter().map(|c| c.amount_sol).sum()
+/// Validator's share of a reward at a commission in basis points
+fn commission_share(lamports: u64, bps: u64) -> Lamports {
+    Lamports((lamports as u128 * bps as u128 / 10_000) as u64)
+}
+
+/// Get total MEV commission
+pub fn total_mev_sol(claims: &[MevClaim]) -> Lamports {
+    claims.iter().map(|c| c.commission_lamports).sum()
+}
+
+/// Get total priority fee commission
+pub fn total_priority_fees_sol(claims: &[MevClaim]) -> Lamports {
+    claims.iter().map(|c| c.priority_fee_commission_lamports).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_fees_carried_separately() {
+        let epochs: Vec<JitoEpochData> = serde_json::from_str(
+            r#"[{"epoch": 900, "mev_commission_bps": 1000, "mev_rewards": 5000000000,
+                 "priority_fee_commission_bps": 5000, "priority_fee_rewards": 300000000}]"#,
+        )
+        .unwrap();
+
+        let claims = process_jito_epochs(epochs).unwrap();
+        assert_eq!(claims[0].commission_lamports, Lamports(500_000_000));
+        assert_eq!(claims[0].total_priority_fees_lamports, Lamports(300_000_000));
+        assert_eq!(claims[0].priority_fee_commission_lamports, Lamports(150_000_000));
+        assert_eq!(total_priority_fees_sol(&claims), Lamports(150_000_000));
+    }
+
+    #[test]
+    fn test_priority_fields_default_to_zero() {
+        let epochs: Vec<JitoEpochData> =
+            serde_json::from_str(r#"[{"epoch": 900, "mev_commission_bps": 800, "mev_rewards": 1000}]"#).unwrap();
+
+        let claims = process_jito_epochs(epochs).unwrap();
+        assert_eq!(claims[0].priority_fee_commission_lamports, Lamports::ZERO);
+    }
 }
@@ -0,0 +1,878 @@
+//! Double-entry journal export (Beancount and Ledger-cli / hledger)
+//!
+//! Turns the same income, expense and treasury rows used by the CSV reports into
+//! balanced transactions against the configured chart of accounts. Validator
+//! accounts hold SOL; income, expense and equity accounts are kept in USD, with
+//! each SOL leg carrying its total USD value as an `@@` price so every
+//! transaction balances in USD.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::assets::{self, Asset};
+use crate::config::{ChartOfAccounts, Config};
+use crate::constants;
+use crate::expenses::{self, Expense, ExpenseCategory};
+use crate::jito::MevClaim;
+use crate::leader_fees::EpochLeaderFees;
+use crate::money::Lamports;
+use crate::prices::{PriceCache, get_price};
+use crate::reports::{settle_sfdp_reimbursements, value_vote_cost};
+use crate::transactions::{CategorizedTransfers, EpochReward, SolTransfer};
+use crate::vote_costs::EpochVoteCost;
+
+/// Units held by a single posting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    /// Signed lamports, optionally priced at a total USD cost in cents
+    Sol { lamports: i64, total_cents: Option<i64> },
+    /// Signed USD amount in cents
+    Usd { cents: i64 },
+}
+
+/// One leg of a journal transaction
+#[derive(Debug, Clone)]
+pub struct Posting {
+    pub account: String,
+    pub units: Units,
+}
+
+/// Balanced journal transaction
+#[derive(Debug, Clone)]
+pub struct JournalTransaction {
+    /// Date (YYYY-MM-DD)
+    pub date: String,
+    pub payee: String,
+    pub narration: String,
+    /// Metadata key/value pairs (epoch, signature, invoice, ...)
+    pub meta: Vec<(String, String)>,
+    pub postings: Vec<Posting>,
+}
+
+/// Inputs for the journal, mirroring the report data
+pub struct JournalInputs<'a> {
+    pub rewards: &'a [EpochReward],
+    pub categorized: &'a CategorizedTransfers,
+    pub mev_claims: &'a [MevClaim],
+    pub leader_fees: &'a [EpochLeaderFees],
+    pub vote_costs: &'a [EpochVoteCost],
+    pub expenses: &'a [Expense],
+    /// Asset register (purchases; their depreciation is among `expenses`)
+    pub assets: &'a [Asset],
+    /// Last day prepaid expenses are amortized through
+    pub recognized_through: NaiveDate,
+    pub prices: &'a PriceCache,
+    pub config: &'a Config,
+}
+
+/// Build balanced journal transactions sorted by date
+pub fn build_journal(inputs: &JournalInputs) -> Vec<JournalTransaction> {
+    let config = inputs.config;
+    let chart = &config.accounts;
+    let prices = inputs.prices;
+    let mut txs = Vec::new();
+
+    // Inflation commission lands in the vote account
+    for reward in inputs.rewards {
+        let date = date_or_fallback(reward.date.as_deref());
+        let cents = reward.amount_lamports.usd_value(get_price(prices, &date)).cents();
+        txs.push(JournalTransaction {
+            date,
+            payee: "Inflation Reward".to_string(),
+            narration: format!("Epoch {} commission ({}%)", reward.epoch, reward.commission),
+            meta: vec![("epoch".to_string(), reward.epoch.to_string())],
+            postings: vec![
+                sol_posting(&chart.vote_account, reward.amount_lamports.0 as i64, cents),
+                usd_posting(&chart.commission_income, -cents),
+            ],
+        });
+    }
+
+    // MEV: Jito API claims are the source of truth; deposits only as fallback
+    for claim in inputs.mev_claims {
+        let date = date_or_fallback(claim.date.as_deref());
+        let cents = claim.commission_lamports.usd_value(get_price(prices, &date)).cents();
+        txs.push(JournalTransaction {
+            date,
+            payee: "Jito".to_string(),
+            narration: format!("Epoch {} MEV commission", claim.epoch),
+            meta: vec![("epoch".to_string(), claim.epoch.to_string())],
+            postings: vec![
+                sol_posting(&chart.vote_account, claim.commission_lamports.0 as i64, cents),
+                usd_posting(&chart.mev_income, -cents),
+            ],
+        });
+    }
+    for claim in inputs.mev_claims {
+        if claim.priority_fee_commission_lamports == Lamports::ZERO {
+            continue;
+        }
+        let date = date_or_fallback(claim.date.as_deref());
+        let cents = claim
+            .priority_fee_commission_lamports
+            .usd_value(get_price(prices, &date))
+            .cents();
+        txs.push(JournalTransaction {
+            date,
+            payee: "Jito".to_string(),
+            narration: format!("Epoch {} priority fee commission", claim.epoch),
+            meta: vec![("epoch".to_string(), claim.epoch.to_string())],
+            postings: vec![
+                sol_posting(
+                    &chart.vote_account,
+                    claim.priority_fee_commission_lamports.0 as i64,
+                    cents,
+                ),
+                usd_posting(&chart.priority_fee_income, -cents),
+            ],
+        });
+    }
+    if inputs.mev_claims.is_empty() {
+        for transfer in &inputs.categorized.mev_deposits {
+            let account = asset_account(chart, config, &transfer.to);
+            txs.push(income_transfer(
+                transfer,
+                account,
+                &chart.mev_income,
+                "MEV deposit",
+                prices,
+            ));
+        }
+    }
+
+    // Leader fees land in the identity account
+    for fees in inputs.leader_fees {
+        if fees.total_fees_lamports == Lamports::ZERO {
+            continue;
+        }
+        let date = date_or_fallback(fees.date.as_deref());
+        let cents = fees.total_fees_lamports.usd_value(get_price(prices, &date)).cents();
+        txs.push(JournalTransaction {
+            date,
+            payee: "Leader Slots".to_string(),
+            narration: format!(
+                "Epoch {} block fees ({} blocks produced)",
+                fees.epoch, fees.blocks_produced
+            ),
+            meta: vec![("epoch".to_string(), fees.epoch.to_string())],
+            postings: vec![
+                sol_posting(&chart.identity, fees.total_fees_lamports.0 as i64, cents),
+                usd_posting(&chart.leader_fee_income, -cents),
+            ],
+        });
+    }
+
+    // Vote fees are paid by the identity; the SFDP share becomes a receivable
+    for cost in inputs.vote_costs {
+        if cost.total_fee_lamports == Lamports::ZERO {
+            continue;
+        }
+        let date = date_or_fallback(cost.date.as_deref());
+        let value = value_vote_cost(cost, prices, config);
+        let gross = value.gross_usd.cents();
+        let covered = value.sfdp_offset_usd.cents();
+
+        let mut postings = vec![
+            usd_posting(&chart.vote_fees, gross),
+            sol_posting(&chart.identity, -(cost.total_fee_lamports.0 as i64), gross),
+        ];
+        if covered != 0 {
+            postings.push(usd_posting(&chart.sfdp_receivable, covered));
+            postings.push(usd_posting(&chart.sfdp_offset, -covered));
+        }
+
+        txs.push(JournalTransaction {
+            date,
+            payee: "Solana Network".to_string(),
+            narration: format!("Epoch {} vote fees ({} votes)", cost.epoch, cost.vote_count),
+            meta: vec![
+                ("epoch".to_string(), cost.epoch.to_string()),
+                ("source".to_string(), cost.provenance.source.to_string()),
+            ],
+            postings,
+        });
+    }
+
+    // Off-chain expenses, with prepaid ones expensed month by month
+    for expense in expenses::amortize_prepaid(inputs.expenses, inputs.recognized_through) {
+        let cents = expense.amount_usd.cents();
+        let expense_account = expense_account(chart, &expense.category);
+        let funding = match expense.paid_with.as_str() {
+            assets::DEPRECIATION_PAID_WITH => &chart.accumulated_depreciation,
+            expenses::PREPAID_PAID_WITH => &chart.prepaid_expenses,
+            paid_with => payment_account(chart, paid_with),
+        };
+
+        let mut meta = Vec::new();
+        if let Some(invoice) = &expense.invoice_id {
+            meta.push(("invoice".to_string(), invoice.clone()));
+        }
+
+        txs.push(JournalTransaction {
+            date: expense.date,
+            payee: expense.vendor,
+            narration: expense.description,
+            meta,
+            postings: vec![usd_posting(&expense_account, cents), usd_posting(funding, -cents)],
+        });
+    }
+
+    // Prepaid expense payments, amortized through the expenses above
+    for expense in inputs.expenses {
+        let Some((start, end)) = expense.service_period() else {
+            continue;
+        };
+        let cents = expense.amount_usd.cents();
+        let mut meta = vec![("service_period".to_string(), format!("{} to {}", start, end))];
+        if let Some(invoice) = &expense.invoice_id {
+            meta.push(("invoice".to_string(), invoice.clone()));
+        }
+        txs.push(JournalTransaction {
+            date: expense.date.clone(),
+            payee: expense.vendor.clone(),
+            narration: expense.description.clone(),
+            meta,
+            postings: vec![
+                usd_posting(&chart.prepaid_expenses, cents),
+                usd_posting(payment_account(chart, &expense.paid_with), -cents),
+            ],
+        });
+    }
+
+    // Capital purchases, depreciated through the expenses above
+    for asset in inputs.assets {
+        let cents = asset.cost_usd.cents();
+        let mut meta = Vec::new();
+        if let Some(id) = asset.id {
+            meta.push(("asset".to_string(), id.to_string()));
+        }
+        txs.push(JournalTransaction {
+            date: asset.purchase_date.clone(),
+            payee: asset.vendor.clone(),
+            narration: asset.description.clone(),
+            meta,
+            postings: vec![
+                usd_posting(&chart.fixed_assets, cents),
+                usd_posting(payment_account(chart, &asset.paid_with), -cents),
+            ],
+        });
+    }
+
+    // Treasury movements
+    let categorized = inputs.categorized;
+    for transfer in &categorized.seeding {
+        let account = asset_account(chart, config, &transfer.to);
+        txs.push(income_transfer(
+            transfer,
+            account,
+            &chart.owner_contributions,
+            "Seeding",
+            prices,
+        ));
+    }
+    // SFDP reimbursements settle the receivable at the value the coverage was
+    // expected at; the rest of their value is a true-up
+    let settlements = settle_sfdp_reimbursements(inputs.vote_costs, &categorized.sfdp_reimbursements, prices, config);
+    for (transfer, settlement) in categorized.sfdp_reimbursements.iter().zip(settlements) {
+        let account = asset_account(chart, config, &transfer.to);
+        let mut postings = vec![
+            sol_posting(
+                account,
+                transfer.amount_lamports.0 as i64,
+                settlement.received_usd.cents(),
+            ),
+            usd_posting(&chart.sfdp_receivable, -settlement.settled_usd.cents()),
+        ];
+        let true_up = settlement.true_up_usd().cents();
+        if true_up != 0 {
+            postings.push(usd_posting(&chart.sfdp_true_up, -true_up));
+        }
+        txs.push(JournalTransaction {
+            date: date_or_fallback(transfer.date.as_deref()),
+            payee: transfer.from_label.clone(),
+            narration: format!("SFDP reimbursement from {}", transfer.from_label),
+            meta: signature_meta(transfer),
+            postings,
+        });
+    }
+    for transfer in &categorized.vote_funding {
+        let from = asset_account(chart, config, &transfer.from);
+        let to = asset_account(chart, config, &transfer.to);
+        let lamports = transfer.amount_lamports.0 as i64;
+        txs.push(JournalTransaction {
+            date: date_or_fallback(transfer.date.as_deref()),
+            payee: "Internal".to_string(),
+            narration: format!("Vote account funding from {}", transfer.from_label),
+            meta: signature_meta(transfer),
+            postings: vec![
+                Posting {
+                    account: to.to_string(),
+                    units: Units::Sol {
+                        lamports,
+                        total_cents: None,
+                    },
+                },
+                Posting {
+                    account: from.to_string(),
+                    units: Units::Sol {
+                        lamports: -lamports,
+                        total_cents: None,
+                    },
+                },
+            ],
+        });
+    }
+    for transfer in &categorized.withdrawals {
+        let account = asset_account(chart, config, &transfer.from);
+        txs.push(outgoing_transfer(
+            transfer,
+            account,
+            &chart.owner_draws,
+            "Withdrawal",
+            prices,
+        ));
+    }
+    // SOL expense payments settle the payable the expense was booked against
+    let sol_payable = chart.payment_methods.get("SOL").unwrap_or(&chart.default_payment);
+    for transfer in &categorized.expense_payments {
+        let account = asset_account(chart, config, &transfer.from);
+        txs.push(outgoing_transfer(
+            transfer,
+            account,
+            sol_payable,
+            "Expense payment",
+            prices,
+        ));
+    }
+    for transfer in &categorized.other {
+        if config.is_our_account(&transfer.to) {
+            let account = asset_account(chart, config, &transfer.to);
+            txs.push(income_transfer(
+                transfer,
+                account,
+                &chart.uncategorized_income,
+                "Uncategorized deposit",
+                prices,
+            ));
+        } else if config.is_our_account(&transfer.from) {
+            let account = asset_account(chart, config, &transfer.from);
+            txs.push(outgoing_transfer(
+                transfer,
+                account,
+                &chart.uncategorized_expense,
+                "Uncategorized payment",
+                prices,
+            ));
+        }
+    }
+
+    txs.sort_by(|a, b| a.date.cmp(&b.date));
+    txs
+}
+
+/// Write journal.beancount and journal.ledger
+pub fn write_journals(output_dir: &Path, transactions: &[JournalTransaction]) -> Result<()> {
+    let path = output_dir.join(constants::JOURNAL_BEANCOUNT_FILENAME);
+    std::fs::write(&path, render_beancount(transactions))?;
+    println!("  Generated: {}", path.display());
+
+    let path = output_dir.join(constants::JOURNAL_LEDGER_FILENAME);
+    std::fs::write(&path, render_ledger(transactions))?;
+    println!("  Generated: {}", path.display());
+
+    Ok(())
+}
+
+/// Render transactions as a Beancount file (with commodity and open directives)
+pub fn render_beancount(transactions: &[JournalTransaction]) -> String {
+    let mut out = String::new();
+    let open_date = opening_date(transactions);
+
+    out.push_str("option \"operating_currency\" \"USD\"\n\n");
+    let _ = writeln!(out, "{} commodity SOL", open_date);
+    let _ = writeln!(out, "{} commodity USD\n", open_date);
+    for account in accounts_used(transactions) {
+        let _ = writeln!(out, "{} open {}", open_date, account);
+    }
+
+    for tx in transactions {
+        let _ = writeln!(
+            out,
+            "\n{} * \"{}\" \"{}\"",
+            tx.date,
+            escape(&tx.payee),
+            escape(&tx.narration)
+        );
+        for (key, value) in &tx.meta {
+            let _ = writeln!(out, "  {}: \"{}\"", key, escape(value));
+        }
+        for posting in &tx.postings {
+            let _ = writeln!(out, "  {:<44} {}", posting.account, format_units(&posting.units));
+        }
+    }
+
+    out
+}
+
+/// Render transactions as a Ledger-cli / hledger file
+pub fn render_ledger(transactions: &[JournalTransaction]) -> String {
+    let mut out = String::new();
+
+    out.push_str("commodity SOL\n");
+    out.push_str("commodity USD\n\n");
+    for account in accounts_used(transactions) {
+        let _ = writeln!(out, "account {}", account);
+    }
+
+    for tx in transactions {
+        let _ = writeln!(out, "\n{} * {} | {}", tx.date, tx.payee, tx.narration);
+        for (key, value) in &tx.meta {
+            let _ = writeln!(out, "    ; {}: {}", key, value);
+        }
+        for posting in &tx.postings {
+            let _ = writeln!(out, "    {:<44} {}", posting.account, format_units(&posting.units));
+        }
+    }
+
+    out
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Map one of our pubkeys to its asset account (withdraw authority for anything else)
+fn asset_account<'a>(chart: &'a ChartOfAccounts, config: &Config, pubkey: &Pubkey) -> &'a str {
+    if *pubkey == config.vote_account {
+        &chart.vote_account
+    } else if *pubkey == config.identity {
+        &chart.identity
+    } else {
+        &chart.withdraw_authority
+    }
+}
+
+/// Funding account of a `paid_with` value
+fn payment_account<'a>(chart: &'a ChartOfAccounts, paid_with: &str) -> &'a str {
+    chart
+        .payment_methods
+        .get(paid_with)
+        .map_or(&chart.default_payment, |account| account)
+}
+
+/// Expense account of a category: its own mapping, else a sub-account of its
+/// top-level category's account, else `Expenses:<path>`
+fn expense_account(chart: &ChartOfAccounts, category: &ExpenseCategory) -> String {
+    if let Some(account) = chart.expense_categories.get(category.as_str()) {
+        return account.clone();
+    }
+    let mut parts = category.as_str().split(':').map(account_component);
+    let top_level = parts.next().unwrap_or_default();
+    let base = chart
+        .expense_categories
+        .get(category.top_level())
+        .cloned()
+        .unwrap_or_else(|| format!("Expenses:{}", top_level));
+    std::iter::once(base).chain(parts).collect::<Vec<_>>().join(":")
+}
+
+/// Category name as an account name component: capitalized words with
+/// anything but letters and digits dropped ("legal fees" -> "LegalFees"),
+/// which both Beancount and Ledger accept
+fn account_component(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// SOL arriving in one of our accounts, balanced against a USD account
+fn income_transfer(
+    transfer: &SolTransfer,
+    asset: &str,
+    counter: &str,
+    narration: &str,
+    prices: &PriceCache,
+) -> JournalTransaction {
+    let date = date_or_fallback(transfer.date.as_deref());
+    let cents = transfer.amount_lamports.usd_value(get_price(prices, &date)).cents();
+    JournalTransaction {
+        narration: format!("{} from {}", narration, transfer.from_label),
+        payee: transfer.from_label.clone(),
+        meta: signature_meta(transfer),
+        postings: vec![
+            sol_posting(asset, transfer.amount_lamports.0 as i64, cents),
+            usd_posting(counter, -cents),
+        ],
+        date,
+    }
+}
+
+/// SOL leaving one of our accounts, balanced against a USD account
+fn outgoing_transfer(
+    transfer: &SolTransfer,
+    asset: &str,
+    counter: &str,
+    narration: &str,
+    prices: &PriceCache,
+) -> JournalTransaction {
+    let date = date_or_fallback(transfer.date.as_deref());
+    let cents = transfer.amount_lamports.usd_value(get_price(prices, &date)).cents();
+    JournalTransaction {
+        narration: format!("{} to {}", narration, transfer.to_label),
+        payee: transfer.to_label.clone(),
+        meta: signature_meta(transfer),
+        postings: vec![
+            usd_posting(counter, cents),
+            sol_posting(asset, -(transfer.amount_lamports.0 as i64), cents),
+        ],
+        date,
+    }
+}
+
+fn signature_meta(transfer: &SolTransfer) -> Vec<(String, String)> {
+    vec![("signature".to_string(), transfer.signature.clone())]
+}
+
+fn sol_posting(account: &str, lamports: i64, total_cents: i64) -> Posting {
+    Posting {
+        account: account.to_string(),
+        units: Units::Sol {
+            lamports,
+            total_cents: Some(total_cents),
+        },
+    }
+}
+
+fn usd_posting(account: &str, cents: i64) -> Posting {
+    Posting {
+        account: account.to_string(),
+        units: Units::Usd { cents },
+    }
+}
+
+fn date_or_fallback(date: Option<&str>) -> String {
+    date.unwrap_or(constants::FALLBACK_DATE).to_string()
+}
+
+fn opening_date(transactions: &[JournalTransaction]) -> &str {
+    transactions
+        .iter()
+        .map(|t| t.date.as_str())
+        .min()
+        .unwrap_or(constants::FALLBACK_DATE)
+}
+
+fn accounts_used(transactions: &[JournalTransaction]) -> BTreeSet<&str> {
+    transactions
+        .iter()
+        .flat_map(|t| t.postings.iter().map(|p| p.account.as_str()))
+        .collect()
+}
+
+fn format_units(units: &Units) -> String {
+    match *units {
+        Units::Sol {
+            lamports,
+            total_cents: Some(total),
+        } => format!("{} SOL @@ {} USD", format_lamports(lamports), format_cents(total)),
+        Units::Sol {
+            lamports,
+            total_cents: None,
+        } => format!("{} SOL", format_lamports(lamports)),
+        Units::Usd { cents } => format!("{} USD", format_cents(cents)),
+    }
+}
+
+fn format_lamports(lamports: i64) -> String {
+    let sign = if lamports < 0 { "-" } else { "" };
+    let abs = lamports.unsigned_abs();
+    format!("{}{}.{:09}", sign, abs / 1_000_000_000, abs % 1_000_000_000)
+}
+
+fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.unsigned_abs();
+    format!("{}{}.{:02}", sign, abs / 100, abs % 100)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addresses::AddressCategory;
+    use crate::expenses::ExpenseCategory;
+    use crate::money::Usd;
+    use crate::prices::Price;
+    use crate::provenance::{DataSource, Provenance};
+
+    /// USD weight and unpriced SOL remainder; both are zero for a balanced transaction
+    fn residual(tx: &JournalTransaction) -> (i64, i64) {
+        let mut usd = 0;
+        let mut sol = 0;
+        for posting in &tx.postings {
+            match posting.units {
+                Units::Usd { cents } => usd += cents,
+                Units::Sol {
+                    lamports,
+                    total_cents: Some(total),
+                } => usd += total * lamports.signum(),
+                Units::Sol {
+                    lamports,
+                    total_cents: None,
+                } => sol += lamports,
+            }
+        }
+        (usd, sol)
+    }
+
+    fn transfer(config: &Config, to: Pubkey, lamports: u64) -> SolTransfer {
+        SolTransfer {
+            signature: "sig1".to_string(),
+            slot: 1,
+            timestamp: None,
+            date: Some("2025-12-10".to_string()),
+            from: config.personal_wallet,
+            to,
+            amount_lamports: Lamports(lamports),
+            from_label: "Personal Wallet".to_string(),
+            to_label: "Vote Account".to_string(),
+            from_category: AddressCategory::PersonalWallet,
+            to_category: AddressCategory::ValidatorSelf,
+            provenance: Provenance::new(DataSource::Rpc),
+        }
+    }
+
+    #[test]
+    fn test_all_transactions_balance() {
+        let config = Config::for_test();
+        let prices: PriceCache = [(
+            "2025-12-10".to_string(),
+            Price::new(Usd::from_cents(13_333), DataSource::CoinGecko),
+        )]
+        .into_iter()
+        .collect();
+        let rewards = vec![EpochReward {
+            epoch: 900,
+            amount_lamports: Lamports(1_234_567_891),
+            commission: 5,
+            effective_slot: 0,
+            date: Some("2025-12-10".to_string()),
+            provenance: Provenance::new(DataSource::Rpc),
+        }];
+        let vote_costs = vec![EpochVoteCost {
+            epoch: 900,
+            vote_count: 431_000,
+            total_fee_lamports: Lamports(2_155_000_000),
+            date: Some("2025-12-10".to_string()),
+            provenance: Provenance::new(DataSource::Rpc),
+        }];
+        let expenses = vec![Expense {
+            id: None,
+            date: "2025-12-01".to_string(),
+            vendor: "Host".to_string(),
+            category: ExpenseCategory::HOSTING,
+            description: "Server".to_string(),
+            amount_usd: "99.995".parse().unwrap(),
+            paid_with: "Credit Card".to_string(),
+            invoice_id: Some("INV-1".to_string()),
+            validator: None,
+            tx_signature: None,
+            amount_lamports: None,
+            paid_date: None,
+            service_start: None,
+            service_end: None,
+            provenance: Provenance::manual(),
+        }];
+        let categorized = CategorizedTransfers {
+            seeding: vec![transfer(&config, config.identity, 10_000_000_000)],
+            ..Default::default()
+        };
+
+        let txs = build_journal(&JournalInputs {
+            rewards: &rewards,
+            categorized: &categorized,
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &vote_costs,
+            expenses: &expenses,
+            assets: &[],
+            recognized_through: NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+            prices: &prices,
+            config: &config,
+        });
+
+        assert_eq!(txs.len(), 4);
+        for tx in &txs {
+            assert_eq!(residual(tx), (0, 0), "unbalanced: {:?}", tx);
+        }
+
+        // Sorted by date, expense first
+        assert_eq!(txs[0].postings[0].account, "Expenses:Hosting");
+        assert_eq!(txs[0].postings[1].account, "Liabilities:CreditCard");
+
+        // Full SFDP coverage in the first month creates an offsetting receivable
+        let vote = txs.iter().find(|t| t.payee == "Solana Network").unwrap();
+        assert_eq!(vote.postings.len(), 4);
+        assert_eq!(vote.postings[2].units, vote.postings[0].units);
+    }
+
+    #[test]
+    fn test_asset_purchase_and_depreciation() {
+        let config = Config::for_test();
+        let asset = Asset {
+            id: Some(7),
+            description: "NVMe drives".to_string(),
+            vendor: "Micron".to_string(),
+            category: ExpenseCategory::HARDWARE,
+            purchase_date: "2025-11-12".to_string(),
+            cost_usd: Usd::from_dollars(3_600),
+            salvage_usd: Usd::ZERO,
+            life_years: 3,
+            method: assets::DepreciationMethod::StraightLine,
+            paid_with: "Credit Card".to_string(),
+            validator: None,
+        };
+        let through = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        let depreciation = assets::depreciation_expenses(
+            std::slice::from_ref(&asset),
+            &crate::period::FiscalCalendar::default(),
+            through,
+        );
+
+        let txs = build_journal(&JournalInputs {
+            rewards: &[],
+            categorized: &CategorizedTransfers::default(),
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &[],
+            expenses: &depreciation,
+            assets: std::slice::from_ref(&asset),
+            recognized_through: through,
+            prices: &PriceCache::default(),
+            config: &config,
+        });
+
+        assert_eq!(txs.len(), 3);
+        assert_eq!(txs[0].postings[0].account, "Assets:FixedAssets:Equipment");
+        assert_eq!(txs[0].postings[0].units, Units::Usd { cents: 360_000 });
+        assert_eq!(txs[0].postings[1].account, "Liabilities:CreditCard");
+        assert_eq!(txs[1].date, "2025-11-30");
+        assert_eq!(txs[1].postings[0].account, "Expenses:Hardware");
+        assert_eq!(txs[1].postings[0].units, Units::Usd { cents: 10_000 });
+        assert_eq!(txs[1].postings[1].account, "Assets:FixedAssets:AccumulatedDepreciation");
+    }
+
+    #[test]
+    fn test_prepaid_expense_payment_and_amortization() {
+        let config = Config::for_test();
+        let expense = Expense {
+            id: Some(3),
+            date: "2025-11-20".to_string(),
+            vendor: "Grafana Labs".to_string(),
+            category: ExpenseCategory::SOFTWARE,
+            description: "Annual monitoring".to_string(),
+            amount_usd: Usd::from_dollars(1_200),
+            paid_with: "Credit Card".to_string(),
+            invoice_id: None,
+            validator: None,
+            tx_signature: None,
+            amount_lamports: None,
+            paid_date: None,
+            service_start: Some("2025-12-01".to_string()),
+            service_end: Some("2026-11-30".to_string()),
+            provenance: Provenance::manual(),
+        };
+
+        let txs = build_journal(&JournalInputs {
+            rewards: &[],
+            categorized: &CategorizedTransfers::default(),
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &[],
+            expenses: std::slice::from_ref(&expense),
+            assets: &[],
+            recognized_through: NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(),
+            prices: &PriceCache::default(),
+            config: &config,
+        });
+
+        // Paid into prepaid expenses, then expensed for each month of service so far
+        assert_eq!(txs.len(), 3);
+        assert_eq!(txs[0].postings[0].account, "Assets:PrepaidExpenses");
+        assert_eq!(txs[0].postings[0].units, Units::Usd { cents: 120_000 });
+        assert_eq!(txs[0].postings[1].account, "Liabilities:CreditCard");
+        for (tx, date) in txs[1..].iter().zip(["2025-12-01", "2026-01-01"]) {
+            assert_eq!(tx.date, date);
+            assert_eq!(tx.postings[0].account, "Expenses:Software");
+            assert_eq!(tx.postings[0].units, Units::Usd { cents: 10_000 });
+            assert_eq!(tx.postings[1].account, "Assets:PrepaidExpenses");
+        }
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let tx = JournalTransaction {
+            date: "2025-12-10".to_string(),
+            payee: "Inflation Reward".to_string(),
+            narration: "Epoch 900 \"commission\"".to_string(),
+            meta: vec![("epoch".to_string(), "900".to_string())],
+            postings: vec![
+                sol_posting("Assets:Validator:VoteAccount", 1_000_000_001, 15_050),
+                usd_posting("Income:Validator:Commission", -15_050),
+            ],
+        };
+
+        let beancount = render_beancount(std::slice::from_ref(&tx));
+        assert!(beancount.contains("2025-12-10 open Assets:Validator:VoteAccount"));
+        assert!(beancount.contains("2025-12-10 * \"Inflation Reward\" \"Epoch 900 \\\"commission\\\"\""));
+        assert!(beancount.contains("  epoch: \"900\""));
+        assert!(beancount.contains("1.000000001 SOL @@ 150.50 USD"));
+        assert!(beancount.contains("-150.50 USD"));
+
+        let ledger = render_ledger(&[tx]);
+        assert!(ledger.contains("account Income:Validator:Commission"));
+        assert!(ledger.contains("2025-12-10 * Inflation Reward | Epoch 900"));
+        assert!(ledger.contains("    ; epoch: 900"));
+    }
+
+    #[test]
+    fn test_format_negative_amounts() {
+        assert_eq!(format_lamports(-5_000), "-0.000005000");
+        assert_eq!(format_cents(-5), "-0.05");
+        assert_eq!(format_cents(12_345), "123.45");
+    }
+
+    #[test]
+    fn test_subcategory_expense_accounts() {
+        let mut chart = ChartOfAccounts::default();
+        chart
+            .expense_categories
+            .insert("Hosting:Colocation".to_string(), "Expenses:Colo".to_string());
+        let account = |name: &str| expense_account(&chart, &ExpenseCategory::new(name));
+        assert_eq!(account("Hosting"), "Expenses:Hosting");
+        assert_eq!(account("Hosting:Colocation"), "Expenses:Colo");
+        assert_eq!(
+            account("Hosting:bandwidth (egress)"),
+            "Expenses:Hosting:BandwidthEgress"
+        );
+        assert_eq!(account("VoteFees:Testnet"), "Expenses:Validator:VoteFees:Testnet");
+        assert_eq!(
+            account("Professional Fees:Accounting"),
+            "Expenses:ProfessionalFees:Accounting"
+        );
+    }
+}
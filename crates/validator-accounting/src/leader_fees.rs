@@ -6,14 +6,15 @@
 //! - 100% of priority fees
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
-use std::collections::HashMap;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::time::Duration;
-use tokio::time::sleep;
 
 use crate::config::Config;
-use crate::constants;
+use crate::money::Lamports;
+use crate::provenance::{DataSource, Provenance};
+use crate::rate_limit::LimitedRpc;
 use crate::transactions::epoch_to_date;
 
 /// Historical leader slot data from Dune Analytics JSON export
@@ -37,16 +38,27 @@ pub struct EpochSlotInfo {
 }
 
 /// Leader fee revenue for a single epoch
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EpochLeaderFees {
     pub epoch: u64,
     pub leader_slots: u64,
     pub blocks_produced: u64,
+    /// Leader slots where no block was produced
     pub skipped_slots: u64,
-    #[allow(dead_code)]
-    pub total_fees_lamports: u64,
-    pub total_fees_sol: f64,
+    /// Produced blocks whose fee reward couldn't be fetched (pruned or transient errors);
+    /// their fees are missing from `total_fees_lamports`
+    pub unavailable_blocks: u64,
+    pub total_fees_lamports: Lamports,
     pub date: Option<String>,
+    #[serde(flatten)]
+    pub provenance: Provenance,
+}
+
+impl EpochLeaderFees {
+    /// Every produced block was fetched; incomplete epochs are re-fetched instead of cached as final
+    pub fn is_complete(&self) -> bool {
+        self.unavailable_blocks == 0
+    }
 }
 
 /// RPC response for getLeaderSchedule
@@ -55,10 +67,37 @@ struct LeaderScheduleResponse {
     result: Option<HashMap<String, Vec<u64>>>,
 }
 
+/// RPC response for getBlockProduction
+#[derive(Debug, Deserialize)]
+struct BlockProductionResponse {
+    result: Option<RpcContextValue<BlockProductionValue>>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcContextValue<T> {
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockProductionValue {
+    /// Identity -> [leader slots, blocks produced]
+    by_identity: HashMap<String, (u64, u64)>,
+}
+
+/// RPC response for getBlocks
+#[derive(Debug, Deserialize)]
+struct BlocksResponse {
+    result: Option<Vec<u64>>,
+    error: Option<serde_json::Value>,
+}
+
 /// RPC response for getBlock
 #[derive(Debug, Deserialize)]
 struct BlockResponse {
     result: Option<BlockResult>,
+    error: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -80,25 +119,34 @@ pub async fn fetch_leader_fees(
     start_epoch: u64,
     end_epoch: Option<u64>,
 ) -> Result<Vec<EpochLeaderFees>> {
-    let client = reqwest::Client::new();
+    let rpc = LimitedRpc::new(&config.rpc_url, &config.rpc_limits);
 
-    // Get current epoch
-    let current_epoch = get_current_epoch(&client, &config.rpc_url).await?;
+    // Get current epoch (and slot, so the in-progress epoch only covers slots so far)
+    let (current_epoch, current_slot) = get_epoch_and_slot(&rpc).await?;
     let end = end_epoch.unwrap_or(current_epoch);
 
     let epoch_word = if start_epoch == end { "epoch" } else { "epochs" };
-    println!("    Fetching leader fees for {} {}-{}...", epoch_word, start_epoch, end);
+    println!(
+        "    Fetching leader fees for {} {}-{} (up to {} req/s, {} concurrent)...",
+        epoch_word, start_epoch, end, config.rpc_limits.requests_per_second, rpc.concurrency
+    );
 
     let mut all_fees = Vec::new();
 
     for epoch in start_epoch..=end {
-        match fetch_epoch_leader_fees(&client, config, epoch).await {
+        match fetch_epoch_leader_fees(&rpc, config, epoch, current_slot).await {
             Ok(fees) => {
                 if fees.leader_slots > 0 {
                     println!(
-                        "      Epoch {}: {} slots, {} blocks, {:.4} SOL",
-                        epoch, fees.leader_slots, fees.blocks_produced, fees.total_fees_sol
+                        "      Epoch {}: {} slots, {} blocks, {} skipped, {:.4} SOL",
+                        epoch, fees.leader_slots, fees.blocks_produced, fees.skipped_slots, fees.total_fees_lamports
                     );
+                    if !fees.is_complete() {
+                        println!(
+                            "        {} produced blocks unavailable - epoch will be re-fetched",
+                            fees.unavailable_blocks
+                        );
+                    }
                     all_fees.push(fees);
                 }
             }
@@ -106,108 +154,162 @@ pub async fn fetch_leader_fees(
                 eprintln!("      Epoch {}: Error - {}", epoch, e);
             }
         }
-
-        // Rate limiting between epochs
-        sleep(Duration::from_millis(100)).await;
     }
 
     Ok(all_fees)
 }
 
 /// Fetch leader fees for a single epoch
-async fn fetch_epoch_leader_fees(client: &reqwest::Client, config: &Config, epoch: u64) -> Result<EpochLeaderFees> {
-    let epoch_start_slot = epoch * constants::SLOTS_PER_EPOCH;
+///
+/// Block production (getBlockProduction) gives the produced/skipped counts and
+/// getBlocks identifies which leader slots have blocks, so only produced blocks
+/// are fetched. Produced blocks that can't be fetched are counted as unavailable
+/// rather than skipped.
+async fn fetch_epoch_leader_fees(
+    rpc: &LimitedRpc,
+    config: &Config,
+    epoch: u64,
+    current_slot: u64,
+) -> Result<EpochLeaderFees> {
+    let epoch_start_slot = config.epoch_schedule.get_first_slot_in_epoch(epoch);
+    let epoch_last_slot = config.epoch_schedule.get_last_slot_in_epoch(epoch).min(current_slot);
     let identity = config.identity.to_string();
 
-    // Get leader schedule for this epoch
-    let leader_slots = get_leader_schedule(client, &config.rpc_url, epoch_start_slot, &identity)
+    // Get leader schedule for this epoch (only slots that have already happened)
+    let leader_slots: Vec<u64> = get_leader_schedule(rpc, epoch_start_slot, &identity)
         .await
-        .context("Failed to get leader schedule")?;
+        .context("Failed to get leader schedule")?
+        .into_iter()
+        .map(|offset| epoch_start_slot + offset)
+        .filter(|slot| *slot <= epoch_last_slot)
+        .collect();
 
     if leader_slots.is_empty() {
-        return Ok(EpochLeaderFees {
-            epoch,
-            leader_slots: 0,
-            blocks_produced: 0,
-            skipped_slots: 0,
-            total_fees_lamports: 0,
-            total_fees_sol: 0.0,
-            date: Some(epoch_to_date(epoch)),
-        });
+        return Ok(summarize_epoch(epoch, 0, 0, &[]));
     }
 
-    // Convert slot offsets to absolute slots
-    let absolute_slots: Vec<u64> = leader_slots.iter().map(|offset| epoch_start_slot + offset).collect();
+    let produced = produced_slots(rpc, &leader_slots).await?;
 
-    // Fetch block rewards for each leader slot
-    let mut total_fees: u64 = 0;
-    let mut blocks_produced: u64 = 0;
-
-    for slot in &absolute_slots {
-        match get_block_fee_reward(client, &config.rpc_url, *slot, &identity).await {
-            Ok(Some(fee)) => {
-                total_fees += fee;
-                blocks_produced += 1;
-            }
-            Ok(None) => {
-                // Block was skipped or no fee reward
-            }
-            Err(_) => {
-                // Block data unavailable (pruned or skipped)
+    // Nodes only keep block production for recent epochs; older ones fall back to getBlocks
+    let (scheduled, blocks_produced) =
+        match get_block_production(rpc, &identity, epoch_start_slot, epoch_last_slot).await {
+            Ok(counts) => counts,
+            Err(e) => {
+                eprintln!("      Epoch {}: {:#}; using confirmed blocks instead", epoch, e);
+                (leader_slots.len() as u64, produced.len() as u64)
             }
-        }
-
-        // Rate limiting - be gentle with the RPC
-        sleep(Duration::from_millis(constants::BLOCK_FETCH_DELAY_MS)).await;
+        };
+    if blocks_produced != produced.len() as u64 {
+        eprintln!(
+            "      Epoch {}: {} blocks produced but {} confirmed blocks found",
+            epoch,
+            blocks_produced,
+            produced.len()
+        );
     }
 
-    let skipped = absolute_slots.len() as u64 - blocks_produced;
+    let fees = fetch_produced_fees(rpc, &produced, &identity).await;
+
+    Ok(summarize_epoch(epoch, scheduled, blocks_produced, &fees))
+}
+
+/// Leader slots that have a confirmed block (getBlocks over the leader slot span)
+async fn produced_slots(rpc: &LimitedRpc, leader_slots: &[u64]) -> Result<Vec<u64>> {
+    let (Some(first), Some(last)) = (leader_slots.iter().min(), leader_slots.iter().max()) else {
+        return Ok(Vec::new());
+    };
+    let blocks: HashSet<u64> = get_blocks(rpc, *first, *last)
+        .await
+        .context("Failed to list confirmed blocks")?
+        .into_iter()
+        .collect();
+    Ok(leader_slots
+        .iter()
+        .copied()
+        .filter(|slot| blocks.contains(slot))
+        .collect())
+}
+
+/// Fee rewards of the produced blocks that could be fetched (0 for blocks without a fee reward)
+async fn fetch_produced_fees(rpc: &LimitedRpc, produced: &[u64], identity: &str) -> Vec<u64> {
+    block_fee_rewards(rpc, produced, identity)
+        .filter_map(|result| async move { result.ok().map(|fee| fee.unwrap_or(0)) })
+        .collect()
+        .await
+}
 
-    Ok(EpochLeaderFees {
+/// Build an epoch's record from block production counts and the fetched fee rewards
+fn summarize_epoch(epoch: u64, leader_slots: u64, blocks_produced: u64, fetched_fees: &[u64]) -> EpochLeaderFees {
+    EpochLeaderFees {
         epoch,
-        leader_slots: absolute_slots.len() as u64,
+        leader_slots,
         blocks_produced,
-        skipped_slots: skipped,
-        total_fees_lamports: total_fees,
-        total_fees_sol: total_fees as f64 / 1e9,
+        skipped_slots: leader_slots.saturating_sub(blocks_produced),
+        unavailable_blocks: blocks_produced.saturating_sub(fetched_fees.len() as u64),
+        total_fees_lamports: Lamports(fetched_fees.iter().sum()),
         date: Some(epoch_to_date(epoch)),
-    })
+        provenance: Provenance::new(DataSource::Rpc),
+    }
 }
 
-/// Get current epoch from RPC
-async fn get_current_epoch(client: &reqwest::Client, rpc_url: &str) -> Result<u64> {
-    let body = serde_json::json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "getEpochInfo",
-        "params": []
-    });
+/// Fetch block fee rewards for many slots concurrently, yielding results as they complete
+///
+/// At most `rpc.concurrency` requests are in flight; the limiter paces them.
+fn block_fee_rewards<'a>(
+    rpc: &'a LimitedRpc,
+    slots: &'a [u64],
+    identity: &'a str,
+) -> impl futures::Stream<Item = Result<Option<u64>>> + 'a {
+    stream::iter(slots)
+        .map(move |slot| get_block_fee_reward(rpc, *slot, identity))
+        .buffer_unordered(rpc.concurrency)
+}
 
-    let response: serde_json::Value = client.post(rpc_url).json(&body).send().await?.json().await?;
+/// Get current epoch and absolute slot from RPC
+async fn get_epoch_and_slot(rpc: &LimitedRpc) -> Result<(u64, u64)> {
+    let response: serde_json::Value = rpc.call("getEpochInfo", serde_json::json!([])).await?;
 
-    response["result"]["epoch"]
-        .as_u64()
+    let epoch = response["result"]["epoch"].as_u64();
+    let slot = response["result"]["absoluteSlot"].as_u64();
+    epoch
+        .zip(slot)
         .ok_or_else(|| anyhow::anyhow!("Failed to get current epoch"))
 }
 
+/// Get (leader slots, blocks produced) for an identity over a slot range
+async fn get_block_production(rpc: &LimitedRpc, identity: &str, first_slot: u64, last_slot: u64) -> Result<(u64, u64)> {
+    let params = serde_json::json!([{
+        "identity": identity,
+        "range": {"firstSlot": first_slot, "lastSlot": last_slot}
+    }]);
+    let response: BlockProductionResponse = rpc.call("getBlockProduction", params).await?;
+    if let Some(error) = response.error {
+        anyhow::bail!("getBlockProduction failed: {}", error);
+    }
+
+    Ok(response
+        .result
+        .and_then(|r| r.value.by_identity.get(identity).copied())
+        .unwrap_or((0, 0)))
+}
+
+/// Get confirmed block slots in a range (at most 500,000 slots apart)
+async fn get_blocks(rpc: &LimitedRpc, first_slot: u64, last_slot: u64) -> Result<Vec<u64>> {
+    let response: BlocksResponse = rpc
+        .call("getBlocks", serde_json::json!([first_slot, last_slot]))
+        .await?;
+    if let Some(error) = response.error {
+        anyhow::bail!("getBlocks failed: {}", error);
+    }
+    response
+        .result
+        .ok_or_else(|| anyhow::anyhow!("getBlocks returned no result"))
+}
+
 /// Get leader schedule for a specific epoch
-async fn get_leader_schedule(
-    client: &reqwest::Client,
-    rpc_url: &str,
-    epoch_start_slot: u64,
-    identity: &str,
-) -> Result<Vec<u64>> {
-    let body = serde_json::json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "getLeaderSchedule",
-        "params": [
-            epoch_start_slot + 1,
-            {"identity": identity}
-        ]
-    });
-
-    let response: LeaderScheduleResponse = client.post(rpc_url).json(&body).send().await?.json().await?;
+async fn get_leader_schedule(rpc: &LimitedRpc, epoch_start_slot: u64, identity: &str) -> Result<Vec<u64>> {
+    let params = serde_json::json!([epoch_start_slot + 1, {"identity": identity}]);
+    let response: LeaderScheduleResponse = rpc.call("getLeaderSchedule", params).await?;
 
     Ok(response
         .result
@@ -216,31 +318,24 @@ async fn get_leader_schedule(
 }
 
 /// Get fee reward from a specific block
-async fn get_block_fee_reward(
-    client: &reqwest::Client,
-    rpc_url: &str,
-    slot: u64,
-    identity: &str,
-) -> Result<Option<u64>> {
-    let body = serde_json::json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "getBlock",
-        "params": [
-            slot,
-            {
-                "rewards": true,
-                "maxSupportedTransactionVersion": 0,
-                "transactionDetails": "none"
-            }
-        ]
-    });
-
-    let response: BlockResponse = client.post(rpc_url).json(&body).send().await?.json().await?;
+async fn get_block_fee_reward(rpc: &LimitedRpc, slot: u64, identity: &str) -> Result<Option<u64>> {
+    let params = serde_json::json!([
+        slot,
+        {
+            "rewards": true,
+            "maxSupportedTransactionVersion": 0,
+            "transactionDetails": "none"
+        }
+    ]);
+    let response: BlockResponse = rpc.call("getBlock", params).await?;
+    if let Some(error) = response.error {
+        anyhow::bail!("getBlock {} failed: {}", slot, error);
+    }
+    let result = response
+        .result
+        .ok_or_else(|| anyhow::anyhow!("Block {} not available", slot))?;
 
-    if let Some(result) = response.result
-        && let Some(rewards) = result.rewards
-    {
+    if let Some(rewards) = result.rewards {
         for reward in rewards {
             if reward.pubkey == identity && reward.reward_type.as_deref() == Some("Fee") && reward.lamports > 0 {
                 return Ok(Some(reward.lamports as u64));
@@ -251,9 +346,9 @@ async fn get_block_fee_reward(
     Ok(None)
 }
 
-/// Get total leader fees in SOL
-pub fn total_leader_fees_sol(fees: &[EpochLeaderFees]) -> f64 {
-    fees.iter().map(|f| f.total_fees_sol).sum()
+/// Get total leader fees
+pub fn total_leader_fees_sol(fees: &[EpochLeaderFees]) -> Lamports {
+    fees.iter().map(|f| f.total_fees_lamports).sum()
 }
 
 // =============================================================================
@@ -269,72 +364,41 @@ pub fn load_historical_slots(path: &Path) -> Result<HistoricalLeaderSlots> {
 
 /// Fetch leader fees for specific slots (used for historical data import)
 ///
-/// This queries each slot individually to get the fee reward. Useful when we
-/// have slot numbers from external sources (e.g., Dune Analytics) but need
-/// to get the actual fee amounts from RPC.
-pub async fn fetch_fees_for_slots(config: &Config, epoch: u64, slots: &[u64]) -> Result<EpochLeaderFees> {
-    let client = reqwest::Client::new();
+/// Useful when we have leader slot numbers from external sources (e.g., Dune
+/// Analytics) but need to get the actual fee amounts from RPC. getBlocks tells
+/// produced slots from skipped ones; only produced blocks are fetched.
+pub async fn fetch_fees_for_slots(
+    rpc: &LimitedRpc,
+    config: &Config,
+    epoch: u64,
+    slots: &[u64],
+) -> Result<EpochLeaderFees> {
     let identity = config.identity.to_string();
 
-    let mut total_fees: u64 = 0;
-    let mut blocks_produced: u64 = 0;
-    let mut skipped: u64 = 0;
-    let mut unavailable: u64 = 0;
-
     println!("      Fetching {} slots for epoch {}...", slots.len(), epoch);
 
-    for (i, slot) in slots.iter().enumerate() {
-        match get_block_fee_reward(&client, &config.rpc_url, *slot, &identity).await {
-            Ok(Some(fee)) => {
-                total_fees += fee;
-                blocks_produced += 1;
-            }
-            Ok(None) => {
-                // Block was skipped or no fee reward
-                skipped += 1;
-            }
-            Err(_) => {
-                // Block data unavailable (pruned)
-                unavailable += 1;
-            }
-        }
-
-        // Progress indicator every 20 slots
-        if (i + 1) % 20 == 0 {
-            println!(
-                "        Progress: {}/{} slots ({} blocks, {} skipped, {} unavailable)",
-                i + 1,
-                slots.len(),
-                blocks_produced,
-                skipped,
-                unavailable
-            );
-        }
-
-        // Rate limiting
-        sleep(Duration::from_millis(constants::BLOCK_FETCH_DELAY_MS)).await;
-    }
+    let produced = produced_slots(rpc, slots).await?;
+    println!(
+        "        {} produced, {} skipped; fetching produced blocks...",
+        produced.len(),
+        slots.len() - produced.len()
+    );
+    let fees = fetch_produced_fees(rpc, &produced, &identity).await;
+    let result = summarize_epoch(epoch, slots.len() as u64, produced.len() as u64, &fees);
 
     println!(
-        "      Epoch {} complete: {} blocks produced, {:.6} SOL in fees",
-        epoch,
-        blocks_produced,
-        total_fees as f64 / 1e9
+        "      Epoch {} complete: {} blocks produced, {} SOL in fees",
+        epoch, result.blocks_produced, result.total_fees_lamports
     );
 
-    if unavailable > 0 {
-        println!("      Warning: {} slots had unavailable/pruned block data", unavailable);
+    if !result.is_complete() {
+        println!(
+            "      Warning: {} produced blocks had unavailable/pruned block data",
+            result.unavailable_blocks
+        );
     }
 
-    Ok(EpochLeaderFees {
-        epoch,
-        leader_slots: slots.len() as u64,
-        blocks_produced,
-        skipped_slots: skipped + unavailable,
-        total_fees_lamports: total_fees,
-        total_fees_sol: total_fees as f64 / 1e9,
-        date: Some(epoch_to_date(epoch)),
-    })
+    Ok(result)
 }
 
 /// Import historical leader fees from Dune Analytics JSON export
@@ -353,6 +417,7 @@ pub async fn import_historical_leader_fees(config: &Config, json_path: &Path) ->
         }
     }
 
+    let rpc = LimitedRpc::new(&config.rpc_url, &config.rpc_limits);
     let mut results = Vec::new();
 
     // Process epochs in order
@@ -368,7 +433,7 @@ pub async fn import_historical_leader_fees(config: &Config, json_path: &Path) ->
         if let Some(slots) = historical.slots_by_epoch.get(&epoch_str)
             && !slots.is_empty()
         {
-            let fees = fetch_fees_for_slots(config, epoch, slots).await?;
+            let fees = fetch_fees_for_slots(&rpc, config, epoch, slots).await?;
             results.push(fees);
         }
     }
@@ -379,10 +444,34 @@ pub async fn import_historical_leader_fees(config: &Config, json_path: &Path) ->
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cluster::Cluster;
+
+    #[test]
+    fn test_summarize_separates_skipped_from_unavailable() {
+        // 12 leader slots, 10 produced, 8 of those fetched (one without a fee reward)
+        let fees = [5_000, 5_000, 0, 7_000, 5_000, 5_000, 5_000, 5_000];
+        let summary = summarize_epoch(900, 12, 10, &fees);
+
+        assert_eq!(summary.skipped_slots, 2);
+        assert_eq!(summary.unavailable_blocks, 2);
+        assert_eq!(summary.total_fees_lamports, Lamports(37_000));
+        assert!(!summary.is_complete());
+
+        let complete = summarize_epoch(900, 12, 8, &fees);
+        assert_eq!(complete.skipped_slots, 4);
+        assert!(complete.is_complete());
+    }
 
     #[test]
     fn test_epoch_slot_calculation() {
-        assert_eq!(904 * constants::SLOTS_PER_EPOCH, 390_528_000);
-        assert_eq!(912 * constants::SLOTS_PER_EPOCH, 393_984_000);
+        let mainnet = Cluster::MainnetBeta.default_epoch_schedule();
+        assert_eq!(mainnet.get_first_slot_in_epoch(904), 390_528_000);
+        assert_eq!(mainnet.get_first_slot_in_epoch(912), 393_984_000);
+
+        // Testnet and devnet start with short warmup epochs
+        let testnet = Cluster::Testnet.default_epoch_schedule();
+        assert_eq!(testnet.get_first_slot_in_epoch(14), 524_256);
+        assert_eq!(testnet.get_first_slot_in_epoch(15), 524_256 + 432_000);
+        assert_eq!(testnet.get_slots_in_epoch(1), 64);
     }
 }
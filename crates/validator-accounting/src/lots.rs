@@ -0,0 +1,680 @@
+//! Tax-lot cost basis tracking for SOL income
+//!
+//! Every commission reward, MEV claim and leader fee is ordinary income at its
+//! fair value on receipt, and that value becomes the cost basis of the SOL.
+//! SOL contributed from the personal wallet keeps its value when contributed.
+//! Withdrawals from our accounts dispose of lots using a selectable method,
+//! producing realized gains/losses with short/long-term holding periods.
+
+use chrono::{Months, NaiveDate};
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+use crate::jito::MevClaim;
+use crate::leader_fees::EpochLeaderFees;
+use crate::money::{Lamports, Usd};
+use crate::prices::{PriceCache, get_price};
+use crate::transactions::{CategorizedTransfers, EpochReward, SolTransfer};
+
+/// Lot selection method for disposals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LotMethod {
+    /// First in, first out (oldest lots first)
+    #[default]
+    Fifo,
+    /// Last in, first out (newest lots first)
+    Lifo,
+    /// Highest cost basis first (minimizes realized gains)
+    Hifo,
+    /// Lots chosen per withdrawal (see `lots select`), FIFO for any remainder
+    SpecificId,
+}
+
+impl std::fmt::Display for LotMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LotMethod::Fifo => write!(f, "FIFO"),
+            LotMethod::Lifo => write!(f, "LIFO"),
+            LotMethod::Hifo => write!(f, "HIFO"),
+            LotMethod::SpecificId => write!(f, "Specific ID"),
+        }
+    }
+}
+
+/// Holding period classification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldingTerm {
+    /// Held one year or less
+    Short,
+    /// Held more than one year
+    Long,
+    /// SOL beyond every known lot, with no basis or acquisition date
+    Unknown,
+}
+
+impl std::fmt::Display for HoldingTerm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HoldingTerm::Short => write!(f, "Short"),
+            HoldingTerm::Long => write!(f, "Long"),
+            HoldingTerm::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// A parcel of SOL acquired as income or contributed capital, with its cost basis
+#[derive(Debug, Clone)]
+pub struct TaxLot {
+    /// Stable identifier (e.g. "commission-900", "mev-900", "leader-900", "seed-5h3kX...")
+    pub id: String,
+    pub acquired: NaiveDate,
+    /// Where the SOL came from ("Commission", "Jito MEV", "Jito Priority Fees",
+    /// "Leader Fees", "Contributed Capital")
+    pub source: &'static str,
+    pub original_lamports: Lamports,
+    pub remaining_lamports: Lamports,
+    /// Fair value at receipt (USD) for the original amount
+    pub cost_basis_usd: Usd,
+    /// Basis not yet allocated to disposals (the last disposal takes the remainder exactly)
+    pub remaining_basis_usd: Usd,
+}
+
+impl TaxLot {
+    /// Cost basis per SOL (USD)
+    pub fn cost_per_sol(&self) -> Usd {
+        self.cost_basis_usd.per_sol(self.original_lamports)
+    }
+
+    /// Take cost basis for a portion of the remaining SOL, rounded to cents
+    fn take_basis(&mut self, lamports: Lamports) -> Usd {
+        let basis = if lamports >= self.remaining_lamports {
+            self.remaining_basis_usd
+        } else {
+            self.remaining_basis_usd.mul_div(lamports.0, self.remaining_lamports.0)
+        };
+        self.remaining_basis_usd -= basis;
+        basis
+    }
+}
+
+/// A realized gain/loss from disposing (part of) a lot
+#[derive(Debug, Clone)]
+pub struct RealizedGain {
+    pub disposal_date: NaiveDate,
+    pub signature: String,
+    /// Lot consumed, or None when the withdrawal exceeded all known lots
+    pub lot_id: Option<String>,
+    pub acquired: Option<NaiveDate>,
+    pub amount_lamports: Lamports,
+    pub cost_basis_usd: Usd,
+    pub proceeds_usd: Usd,
+    pub term: HoldingTerm,
+    /// Consumed in FIFO order under specific ID because the withdrawal's
+    /// selection didn't cover it
+    pub fallback: bool,
+}
+
+impl RealizedGain {
+    pub fn gain_usd(&self) -> Usd {
+        self.proceeds_usd - self.cost_basis_usd
+    }
+}
+
+/// Why a selected lot couldn't be consumed by its withdrawal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// No lot has the ID
+    Unknown,
+    /// The lot was acquired after the withdrawal
+    AcquiredLater,
+    /// Earlier disposals consumed the whole lot
+    UsedUp,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Unknown => write!(f, "not found"),
+            SkipReason::AcquiredLater => write!(f, "acquired after the withdrawal"),
+            SkipReason::UsedUp => write!(f, "already used up"),
+        }
+    }
+}
+
+/// A lot chosen for a withdrawal (see `lots select`) that it couldn't consume
+#[derive(Debug, Clone)]
+pub struct SkippedSelection {
+    pub signature: String,
+    pub lot_id: String,
+    pub reason: SkipReason,
+}
+
+/// Result of running disposals against the lot pool
+#[derive(Debug, Default)]
+pub struct LotReport {
+    pub gains: Vec<RealizedGain>,
+    /// Lots with SOL remaining after all disposals
+    pub open_lots: Vec<TaxLot>,
+    /// Selected lots that were skipped, in favor of FIFO
+    pub skipped: Vec<SkippedSelection>,
+}
+
+/// Build tax lots from every income row and capital contribution
+///
+/// Mirrors the income ledger: MEV deposits detected from transfers are only
+/// used when there is no Jito API data, to avoid double-counting. Seeding
+/// transfers are the owner's capital, so returning them isn't income.
+pub fn build_lots(
+    rewards: &[EpochReward],
+    categorized: &CategorizedTransfers,
+    mev_claims: &[MevClaim],
+    leader_fees: &[EpochLeaderFees],
+    prices: &PriceCache,
+) -> Vec<TaxLot> {
+    let mut lots = Vec::new();
+
+    for reward in rewards {
+        push_lot(
+            &mut lots,
+            format!("commission-{}", reward.epoch),
+            reward.date.as_deref(),
+            "Commission",
+            reward.amount_lamports,
+            prices,
+        );
+    }
+
+    if mev_claims.is_empty() {
+        for transfer in &categorized.mev_deposits {
+            push_lot(
+                &mut lots,
+                format!("mev-{}", &transfer.signature[..16]),
+                transfer.date.as_deref(),
+                "Jito MEV",
+                transfer.amount_lamports,
+                prices,
+            );
+        }
+    } else {
+        for claim in mev_claims {
+            push_lot(
+                &mut lots,
+                format!("mev-{}", claim.epoch),
+                claim.date.as_deref(),
+                "Jito MEV",
+                claim.commission_lamports,
+                prices,
+            );
+            push_lot(
+                &mut lots,
+                format!("jito-pf-{}", claim.epoch),
+                claim.date.as_deref(),
+                "Jito Priority Fees",
+                claim.priority_fee_commission_lamports,
+                prices,
+            );
+        }
+    }
+
+    for fees in leader_fees {
+        push_lot(
+            &mut lots,
+            format!("leader-{}", fees.epoch),
+            fees.date.as_deref(),
+            "Leader Fees",
+            fees.total_fees_lamports,
+            prices,
+        );
+    }
+
+    for transfer in &categorized.seeding {
+        push_lot(
+            &mut lots,
+            format!("seed-{}", &transfer.signature[..16]),
+            transfer.date.as_deref(),
+            "Contributed Capital",
+            transfer.amount_lamports,
+            prices,
+        );
+    }
+
+    // Oldest first; ties broken by ID so ordering is deterministic
+    lots.sort_by(|a, b| (a.acquired, &a.id).cmp(&(b.acquired, &b.id)));
+    lots
+}
+
+/// Add a lot if the row has a usable date and a non-zero amount
+fn push_lot(
+    lots: &mut Vec<TaxLot>,
+    id: String,
+    date: Option<&str>,
+    source: &'static str,
+    lamports: Lamports,
+    prices: &PriceCache,
+) {
+    let Some(date_str) = date else {
+        return;
+    };
+    let Ok(acquired) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") else {
+        return;
+    };
+    if lamports == Lamports::ZERO {
+        return;
+    }
+
+    // Same per-row rounding as the income ledger, so basis matches reported income
+    let basis = lamports.usd_value(get_price(prices, date_str));
+    lots.push(TaxLot {
+        id,
+        acquired,
+        source,
+        original_lamports: lamports,
+        remaining_lamports: lamports,
+        cost_basis_usd: basis,
+        remaining_basis_usd: basis,
+    });
+}
+
+/// Dispose of lots for each withdrawal (oldest withdrawal first)
+///
+/// `selections` maps a withdrawal signature to the lot IDs to consume, in
+/// order; it is only consulted for `LotMethod::SpecificId`. Selected lots that
+/// can't be consumed are reported in `LotReport::skipped`.
+pub fn dispose(
+    mut lots: Vec<TaxLot>,
+    withdrawals: &[SolTransfer],
+    prices: &PriceCache,
+    method: LotMethod,
+    selections: &HashMap<String, Vec<String>>,
+) -> LotReport {
+    let mut withdrawals: Vec<&SolTransfer> = withdrawals.iter().filter(|t| t.date.is_some()).collect();
+    withdrawals.sort_by_key(|t| t.slot);
+
+    let mut gains = Vec::new();
+    let mut skipped = Vec::new();
+
+    for withdrawal in withdrawals {
+        let date_str = withdrawal.date.as_deref().unwrap_or_default();
+        let Ok(disposal_date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") else {
+            continue;
+        };
+        let price = get_price(prices, date_str);
+        let mut remaining = withdrawal.amount_lamports;
+
+        let selection: &[String] = match method {
+            LotMethod::SpecificId => selections.get(&withdrawal.signature).map_or(&[], Vec::as_slice),
+            _ => &[],
+        };
+        for id in selection {
+            let reason = match lots.iter().find(|l| &l.id == id) {
+                None => Some(SkipReason::Unknown),
+                Some(lot) if lot.acquired > disposal_date => Some(SkipReason::AcquiredLater),
+                Some(lot) if lot.remaining_lamports == Lamports::ZERO => Some(SkipReason::UsedUp),
+                Some(_) => None,
+            };
+            if let Some(reason) = reason {
+                skipped.push(SkippedSelection {
+                    signature: withdrawal.signature.clone(),
+                    lot_id: id.clone(),
+                    reason,
+                });
+            }
+        }
+
+        // Only lots acquired on or before the withdrawal can be consumed
+        let order = lot_order(&lots, disposal_date, method, selection);
+
+        for idx in order {
+            if remaining == Lamports::ZERO {
+                break;
+            }
+            let lot = &mut lots[idx];
+            let take = lot.remaining_lamports.min(remaining);
+            if take == Lamports::ZERO {
+                continue;
+            }
+
+            let cost_basis_usd = lot.take_basis(take);
+            gains.push(RealizedGain {
+                disposal_date,
+                signature: withdrawal.signature.clone(),
+                lot_id: Some(lot.id.clone()),
+                acquired: Some(lot.acquired),
+                amount_lamports: take,
+                cost_basis_usd,
+                proceeds_usd: take.usd_value(price),
+                term: holding_term(lot.acquired, disposal_date),
+                fallback: method == LotMethod::SpecificId && !selection.contains(&lot.id),
+            });
+
+            lot.remaining_lamports -= take;
+            remaining -= take;
+        }
+
+        // Withdrawal exceeded every known lot (e.g. SOL from an untracked
+        // source) - basis and holding period unknown
+        if remaining > Lamports::ZERO {
+            gains.push(RealizedGain {
+                disposal_date,
+                signature: withdrawal.signature.clone(),
+                lot_id: None,
+                acquired: None,
+                amount_lamports: remaining,
+                cost_basis_usd: Usd::ZERO,
+                proceeds_usd: remaining.usd_value(price),
+                term: HoldingTerm::Unknown,
+                fallback: false,
+            });
+        }
+    }
+
+    let open_lots = lots
+        .into_iter()
+        .filter(|l| l.remaining_lamports > Lamports::ZERO)
+        .collect();
+
+    LotReport {
+        gains,
+        open_lots,
+        skipped,
+    }
+}
+
+/// Order in which available lots are consumed for a single disposal
+fn lot_order(lots: &[TaxLot], disposal_date: NaiveDate, method: LotMethod, selection: &[String]) -> Vec<usize> {
+    // Lots are kept sorted oldest first, so the natural order is FIFO
+    let mut available: Vec<usize> = (0..lots.len())
+        .filter(|&i| lots[i].acquired <= disposal_date && lots[i].remaining_lamports > Lamports::ZERO)
+        .collect();
+
+    match method {
+        LotMethod::Fifo => {}
+        LotMethod::Lifo => available.reverse(),
+        LotMethod::Hifo => {
+            available.sort_by_key(|&i| std::cmp::Reverse(lots[i].cost_per_sol()));
+        }
+        LotMethod::SpecificId => {
+            // Selected lots first (in the order given), then FIFO for the rest
+            let selected: Vec<usize> = selection
+                .iter()
+                .filter_map(|id| available.iter().copied().find(|&i| &lots[i].id == id))
+                .collect();
+            let rest = available.iter().copied().filter(|i| !selected.contains(i));
+            available = selected.iter().copied().chain(rest).collect();
+        }
+    }
+
+    available
+}
+
+/// Long-term if held more than one year
+pub fn holding_term(acquired: NaiveDate, disposed: NaiveDate) -> HoldingTerm {
+    match acquired.checked_add_months(Months::new(12)) {
+        Some(one_year) if disposed > one_year => HoldingTerm::Long,
+        _ => HoldingTerm::Short,
+    }
+}
+
+/// Total realized gain (USD) by holding term: (short, long)
+///
+/// Disposals of unknown basis are left out; see `unknown_basis_proceeds`.
+pub fn realized_totals(gains: &[RealizedGain]) -> (Usd, Usd) {
+    gains
+        .iter()
+        .fold((Usd::ZERO, Usd::ZERO), |(short, long), g| match g.term {
+            HoldingTerm::Short => (short + g.gain_usd(), long),
+            HoldingTerm::Long => (short, long + g.gain_usd()),
+            HoldingTerm::Unknown => (short, long),
+        })
+}
+
+/// Proceeds (USD) of disposals with no known lot, whose gain can't be computed
+pub fn unknown_basis_proceeds(gains: &[RealizedGain]) -> Usd {
+    gains
+        .iter()
+        .filter(|g| g.term == HoldingTerm::Unknown)
+        .map(|g| g.proceeds_usd)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addresses::AddressCategory;
+    use crate::prices::Price;
+    use crate::provenance::{DataSource, Provenance};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn lot(id: &str, date: &str, sol: u64, price: i64) -> TaxLot {
+        let lamports = Lamports(sol * 1_000_000_000);
+        let basis = Usd::from_dollars(sol as i64 * price);
+        TaxLot {
+            id: id.to_string(),
+            acquired: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            source: "Commission",
+            original_lamports: lamports,
+            remaining_lamports: lamports,
+            cost_basis_usd: basis,
+            remaining_basis_usd: basis,
+        }
+    }
+
+    fn withdrawal(signature: &str, date: &str, sol: u64) -> SolTransfer {
+        SolTransfer {
+            signature: signature.to_string(),
+            slot: 1,
+            timestamp: None,
+            date: Some(date.to_string()),
+            from: Pubkey::new_unique(),
+            to: Pubkey::new_unique(),
+            amount_lamports: Lamports(sol * 1_000_000_000),
+            from_label: String::new(),
+            to_label: String::new(),
+            from_category: AddressCategory::ValidatorSelf,
+            to_category: AddressCategory::Exchange,
+            provenance: Provenance::new(DataSource::Rpc),
+        }
+    }
+
+    fn test_lots() -> Vec<TaxLot> {
+        vec![
+            lot("commission-900", "2025-01-01", 1, 100),
+            lot("commission-901", "2025-02-01", 1, 300),
+            lot("commission-902", "2025-03-01", 1, 200),
+        ]
+    }
+
+    fn test_prices() -> PriceCache {
+        PriceCache::from([(
+            "2025-04-01".to_string(),
+            Price::new(Usd::from_dollars(250), DataSource::CoinGecko),
+        )])
+    }
+
+    #[test]
+    fn test_fifo_consumes_oldest_first() {
+        let report = dispose(
+            test_lots(),
+            &[withdrawal("sig1", "2025-04-01", 1)],
+            &test_prices(),
+            LotMethod::Fifo,
+            &HashMap::new(),
+        );
+        assert_eq!(report.gains.len(), 1);
+        assert_eq!(report.gains[0].lot_id.as_deref(), Some("commission-900"));
+        assert_eq!(report.gains[0].gain_usd(), Usd::from_dollars(150));
+        assert_eq!(report.open_lots.len(), 2);
+    }
+
+    #[test]
+    fn test_lifo_consumes_newest_first() {
+        let report = dispose(
+            test_lots(),
+            &[withdrawal("sig1", "2025-04-01", 1)],
+            &test_prices(),
+            LotMethod::Lifo,
+            &HashMap::new(),
+        );
+        assert_eq!(report.gains[0].lot_id.as_deref(), Some("commission-902"));
+    }
+
+    #[test]
+    fn test_hifo_consumes_highest_basis_first() {
+        let report = dispose(
+            test_lots(),
+            &[withdrawal("sig1", "2025-04-01", 1)],
+            &test_prices(),
+            LotMethod::Hifo,
+            &HashMap::new(),
+        );
+        assert_eq!(report.gains[0].lot_id.as_deref(), Some("commission-901"));
+        assert_eq!(report.gains[0].gain_usd(), Usd::from_dollars(-50));
+    }
+
+    #[test]
+    fn test_specific_id_then_fifo_remainder() {
+        let selections = HashMap::from([("sig1".to_string(), vec!["commission-902".to_string()])]);
+        let report = dispose(
+            test_lots(),
+            &[withdrawal("sig1", "2025-04-01", 2)],
+            &test_prices(),
+            LotMethod::SpecificId,
+            &selections,
+        );
+        assert_eq!(report.gains.len(), 2);
+        assert_eq!(report.gains[0].lot_id.as_deref(), Some("commission-902"));
+        assert!(!report.gains[0].fallback);
+        assert_eq!(report.gains[1].lot_id.as_deref(), Some("commission-900"));
+        assert!(report.gains[1].fallback);
+        assert!(report.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_specific_id_reports_lots_it_cannot_consume() {
+        let selections = HashMap::from([
+            ("sig1".to_string(), vec!["commission-900".to_string()]),
+            (
+                "sig2".to_string(),
+                vec![
+                    "commission-900".to_string(),
+                    "commission-99".to_string(),
+                    "commission-903".to_string(),
+                ],
+            ),
+        ]);
+        let mut lots = test_lots();
+        lots.push(lot("commission-903", "2025-05-01", 1, 100));
+        let withdrawals = [withdrawal("sig1", "2025-04-01", 1), withdrawal("sig2", "2025-04-01", 1)];
+        let report = dispose(lots, &withdrawals, &test_prices(), LotMethod::SpecificId, &selections);
+
+        let skipped: Vec<_> = report
+            .skipped
+            .iter()
+            .map(|s| (s.signature.as_str(), s.lot_id.as_str(), s.reason))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("sig2", "commission-900", SkipReason::UsedUp),
+                ("sig2", "commission-99", SkipReason::Unknown),
+                ("sig2", "commission-903", SkipReason::AcquiredLater),
+            ]
+        );
+        assert_eq!(report.gains[1].lot_id.as_deref(), Some("commission-901"));
+        assert!(report.gains[1].fallback);
+    }
+
+    #[test]
+    fn test_partial_lot_and_unknown_basis() {
+        let lots = vec![lot("commission-900", "2025-01-01", 2, 100)];
+        let report = dispose(
+            lots,
+            &[withdrawal("sig1", "2025-04-01", 3)],
+            &test_prices(),
+            LotMethod::Fifo,
+            &HashMap::new(),
+        );
+        assert_eq!(report.gains.len(), 2);
+        assert_eq!(report.gains[0].cost_basis_usd, Usd::from_dollars(200));
+        assert_eq!(report.gains[1].lot_id, None);
+        assert_eq!(report.gains[1].amount_lamports, Lamports(1_000_000_000));
+        assert_eq!(report.gains[1].term, HoldingTerm::Unknown);
+        assert!(report.open_lots.is_empty());
+
+        // Only the known lot's gain counts; the rest is proceeds of unknown basis
+        assert_eq!(realized_totals(&report.gains), (Usd::from_dollars(300), Usd::ZERO));
+        assert_eq!(unknown_basis_proceeds(&report.gains), Usd::from_dollars(250));
+    }
+
+    #[test]
+    fn test_seeded_capital_is_a_lot_at_its_contributed_value() {
+        let mut seed = withdrawal("5h3kXseedingtransfer", "2025-04-01", 2);
+        seed.from_category = AddressCategory::PersonalWallet;
+        seed.to_category = AddressCategory::ValidatorSelf;
+        let categorized = CategorizedTransfers {
+            seeding: vec![seed],
+            ..Default::default()
+        };
+        let lots = build_lots(&[], &categorized, &[], &[], &test_prices());
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].id, "seed-5h3kXseedingtran");
+        assert_eq!(lots[0].source, "Contributed Capital");
+        assert_eq!(lots[0].cost_basis_usd, Usd::from_dollars(500));
+
+        // Returning the capital at the same price realizes no gain
+        let report = dispose(
+            lots,
+            &[withdrawal("sig1", "2025-04-01", 2)],
+            &test_prices(),
+            LotMethod::Fifo,
+            &HashMap::new(),
+        );
+        assert_eq!(report.gains.len(), 1);
+        assert_eq!(report.gains[0].gain_usd(), Usd::ZERO);
+    }
+
+    #[test]
+    fn test_partial_disposals_allocate_basis_exactly() {
+        let mut lots = vec![lot("commission-900", "2025-01-01", 3, 0)];
+        lots[0].cost_basis_usd = Usd::from_dollars(100);
+        lots[0].remaining_basis_usd = Usd::from_dollars(100);
+        let withdrawals = [
+            withdrawal("sig1", "2025-04-01", 1),
+            withdrawal("sig2", "2025-04-01", 1),
+            withdrawal("sig3", "2025-04-01", 1),
+        ];
+        let report = dispose(lots, &withdrawals, &test_prices(), LotMethod::Fifo, &HashMap::new());
+
+        let bases: Vec<Usd> = report.gains.iter().map(|g| g.cost_basis_usd).collect();
+        assert_eq!(
+            bases,
+            vec![Usd::from_cents(3333), Usd::from_cents(3334), Usd::from_cents(3333)]
+        );
+        assert_eq!(bases.into_iter().sum::<Usd>(), Usd::from_dollars(100));
+    }
+
+    #[test]
+    fn test_lots_acquired_after_withdrawal_are_skipped() {
+        let report = dispose(
+            test_lots(),
+            &[withdrawal("sig1", "2025-01-15", 1)],
+            &test_prices(),
+            LotMethod::Lifo,
+            &HashMap::new(),
+        );
+        assert_eq!(report.gains[0].lot_id.as_deref(), Some("commission-900"));
+    }
+
+    #[test]
+    fn test_holding_term() {
+        let acquired = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(
+            holding_term(acquired, NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()),
+            HoldingTerm::Short
+        );
+        assert_eq!(
+            holding_term(acquired, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()),
+            HoldingTerm::Long
+        );
+    }
+}
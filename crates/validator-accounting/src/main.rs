@@ -4,28 +4,48 @@
 //! on-chain data and labeling known addresses.
 
 mod addresses;
+mod assets;
+mod attachments;
 mod cache;
+mod categories;
+mod cluster;
 mod config;
 mod constants;
 mod dune;
+mod epochs;
 mod expenses;
+mod fixtures;
 mod jito;
+mod journal;
 mod leader_fees;
+mod lots;
+mod money;
 mod notion;
+mod period;
 mod prices;
+mod provenance;
+mod rate_limit;
+mod reconcile;
 mod reports;
+mod sources;
+mod statements;
 mod transactions;
 mod vote_costs;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use solana_client::rpc_client::RpcClient;
-use solana_commitment_config::CommitmentConfig;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use cache::Cache;
+use assets::Asset;
+use attachments::AttachmentStore;
+use cache::{Cache, CacheTable, EpochRange};
+use categories::Categories;
 use config::FileConfig;
-use expenses::{Expense, ExpenseCategory, RecurringExpense};
+use expenses::{Expense, ExpenseTable, RecurringExpense};
+use money::{Lamports, Usd};
+use provenance::{DataSource, Provenance};
+use sources::{AnalyticsSource, Sources};
 
 /// Default config file name
 const CONFIG_FILE: &str = "config.toml";
@@ -92,6 +112,10 @@ struct Args {
     #[arg(short, long, default_value = "./output", global = true)]
     output_dir: PathBuf,
 
+    /// Validator to operate on, by name or vote account (default: all configured validators)
+    #[arg(long, global = true)]
+    validator: Option<String>,
+
     #[command(subcommand)]
     command: Option<Command>,
 
@@ -103,10 +127,30 @@ struct Args {
     #[arg(long)]
     end_epoch: Option<u64>,
 
-    /// Filter reports to a specific year (e.g., 2025)
-    #[arg(long)]
+    /// Limit reports to a fiscal year (e.g., 2025; see `fiscal_year_start`)
+    #[arg(long, conflicts_with_all = ["quarter", "from", "to"])]
     year: Option<i32>,
 
+    /// Limit reports to a fiscal quarter (e.g., 2026Q1)
+    #[arg(long, conflicts_with_all = ["from", "to"])]
+    quarter: Option<String>,
+
+    /// Limit reports to rows dated on or after this day (YYYY-MM-DD)
+    #[arg(long)]
+    from: Option<chrono::NaiveDate>,
+
+    /// Limit reports to rows dated on or before this day (YYYY-MM-DD)
+    #[arg(long)]
+    to: Option<chrono::NaiveDate>,
+
+    /// Lot selection method for withdrawals in capital_gains.csv
+    #[arg(long, value_enum, default_value_t = lots::LotMethod::Fifo)]
+    lot_method: lots::LotMethod,
+
+    /// When the summary recognizes revenue and expenses
+    #[arg(long, value_enum, default_value_t = reports::Basis::Accrual)]
+    basis: reports::Basis,
+
     /// RPC URL (uses private endpoint by default)
     #[arg(long)]
     rpc_url: Option<String>,
@@ -122,6 +166,14 @@ struct Args {
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// Save every fetched response to fixture files in this directory
+    #[arg(long, value_name = "DIR", conflicts_with = "replay_fixtures")]
+    record_fixtures: Option<PathBuf>,
+
+    /// Answer every fetch from fixture files in this directory (no network)
+    #[arg(long, value_name = "DIR")]
+    replay_fixtures: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -138,6 +190,21 @@ enum Command {
         action: RecurringCommand,
     },
 
+    /// Manage the fixed asset register (servers, drives) and its depreciation
+    Asset {
+        #[command(subcommand)]
+        action: AssetCommand,
+    },
+
+    /// List expense categories and the tax lines they're reported on
+    Categories,
+
+    /// Bundle expense receipts and invoices for the accountant
+    Receipts {
+        #[command(subcommand)]
+        action: ReceiptsCommand,
+    },
+
     /// Import historical leader slot data
     LeaderSlots {
         #[command(subcommand)]
@@ -155,6 +222,115 @@ enum Command {
         #[command(subcommand)]
         action: DuneCommand,
     },
+
+    /// Manage specific-ID tax lot selections for withdrawals
+    Lots {
+        #[command(subcommand)]
+        action: LotsCommand,
+    },
+
+    /// Reconcile on-chain balances against recorded flows per epoch
+    Reconcile {
+        /// First epoch to report (default: first epoch with rewards)
+        #[arg(long)]
+        start_epoch: Option<u64>,
+
+        /// Last epoch to report (default: current)
+        #[arg(long)]
+        end_epoch: Option<u64>,
+
+        /// Unexplained change (SOL) to ignore per account per epoch, e.g. transaction fees
+        #[arg(long, default_value = "0.001")]
+        tolerance: Lamports,
+
+        /// RPC URL (uses private endpoint by default)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+
+    /// Inspect, invalidate and refresh cached on-chain data
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+
+    /// Manage the cache database schema
+    Db {
+        #[command(subcommand)]
+        action: DbCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    /// Show cached row counts per validator
+    Stats,
+
+    /// List cached rows
+    Show {
+        table: CacheTable,
+
+        /// Epochs to show, e.g. 880..890 (default: all)
+        #[arg(long)]
+        epochs: Option<EpochRange>,
+    },
+
+    /// Delete cached rows so the next report run fetches them again
+    Invalidate {
+        table: CacheTable,
+
+        /// Epochs to invalidate, e.g. 880..890 (prices by the dates they span)
+        #[arg(long)]
+        epochs: EpochRange,
+    },
+
+    /// Refetch cached rows for an epoch range now
+    Refresh {
+        table: CacheTable,
+
+        /// Epochs to refetch, e.g. 880..890 (completed epochs only)
+        #[arg(long)]
+        epochs: EpochRange,
+
+        /// RPC URL (uses private endpoint by default)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+
+    /// Reclaim space left by deleted rows
+    Vacuum,
+}
+
+#[derive(Subcommand, Debug)]
+enum DbCommand {
+    /// Apply pending schema migrations (also runs automatically on every command)
+    Migrate {
+        /// Show pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum LotsCommand {
+    /// Show lot selections for withdrawals
+    List,
+
+    /// Choose which lots a withdrawal consumes (used with --lot-method specific-id)
+    Select {
+        /// Withdrawal transaction signature (or a unique prefix)
+        signature: String,
+
+        /// Lot IDs to consume, in order (see tax_lots.csv, e.g. commission-900)
+        #[arg(required = true)]
+        lot_ids: Vec<String>,
+    },
+
+    /// Remove the lot selection for a withdrawal
+    Clear {
+        /// Withdrawal transaction signature
+        signature: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -211,7 +387,7 @@ enum ExpenseCommand {
         #[arg(long)]
         vendor: String,
 
-        /// Category: Hosting, Contractor, Hardware, Software, VoteFees, Other
+        /// Category, e.g. Hosting or Hosting:Bandwidth (see `categories`)
         #[arg(long)]
         category: String,
 
@@ -221,7 +397,7 @@ enum ExpenseCommand {
 
         /// Amount in USD
         #[arg(long)]
-        amount: f64,
+        amount: Usd,
 
         /// Payment method (e.g., "Credit Card", "USD", "SOL")
         #[arg(long, default_value = "USD")]
@@ -230,6 +406,65 @@ enum ExpenseCommand {
         /// Invoice ID (optional)
         #[arg(long)]
         invoice_id: Option<String>,
+
+        /// First day of the service a prepayment covers (YYYY-MM-DD); the
+        /// amount is then spread evenly over its months of service
+        #[arg(long, requires = "service_end")]
+        service_start: Option<String>,
+
+        /// Last day of the service a prepayment covers (YYYY-MM-DD)
+        #[arg(long, requires = "service_start")]
+        service_end: Option<String>,
+    },
+
+    /// Correct fields of an expense, keeping its ID (reassign with --validator)
+    Edit {
+        /// Expense ID to edit
+        id: i64,
+
+        /// Date (YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Vendor name
+        #[arg(long)]
+        vendor: Option<String>,
+
+        /// Category, e.g. Hosting or Hosting:Bandwidth (see `categories`)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Amount in USD
+        #[arg(long)]
+        amount: Option<Usd>,
+
+        /// Payment method (e.g., "Credit Card", "USD", "SOL")
+        #[arg(long)]
+        paid_with: Option<String>,
+
+        /// Invoice ID ("" to clear)
+        #[arg(long)]
+        invoice_id: Option<String>,
+
+        /// First day of the service a prepayment covers (YYYY-MM-DD)
+        #[arg(long)]
+        service_start: Option<String>,
+
+        /// Last day of the service a prepayment covers (YYYY-MM-DD)
+        #[arg(long)]
+        service_end: Option<String>,
+
+        /// Expense it when paid again instead of over a service period
+        #[arg(long, conflicts_with_all = ["service_start", "service_end"])]
+        no_service_period: bool,
+
+        /// Share the expense across all validators again
+        #[arg(long)]
+        shared: bool,
     },
 
     /// Delete an expense by ID
@@ -238,12 +473,68 @@ enum ExpenseCommand {
         id: i64,
     },
 
+    /// Show every logged change to an expense
+    History {
+        /// Expense ID
+        id: i64,
+    },
+
+    /// Link a SOL-paid expense to the transfer that paid it (lists likely transfers without a signature)
+    Link {
+        /// Expense ID
+        id: i64,
+
+        /// Transaction signature, or a unique prefix of it (e.g. from treasury_ledger.csv)
+        signature: Option<String>,
+    },
+
+    /// Remove an expense's link to its SOL payment transfer
+    Unlink {
+        /// Expense ID
+        id: i64,
+
+        /// How it was paid instead (e.g. "Credit Card", "USD", or "Unpaid")
+        #[arg(long)]
+        paid_with: String,
+    },
+
+    /// Attach a receipt or invoice (copied into the data directory's attachment store)
+    Attach {
+        /// Expense ID
+        id: i64,
+
+        /// Document to attach (PDF, image, etc.)
+        file: PathBuf,
+    },
+
     /// Import expenses from CSV file
     Import {
         /// Path to CSV file
         file: PathBuf,
     },
 
+    /// Import charges from a bank or credit card statement CSV
+    ImportStatement {
+        /// Path to statement CSV file
+        file: PathBuf,
+
+        /// Column mapping: amex, capital-one, chase or a [statement_formats.<name>] entry
+        #[arg(long)]
+        format: String,
+
+        /// Categorization rules (default: statement_rules.toml in the data directory, if present)
+        #[arg(long)]
+        rules: Option<PathBuf>,
+
+        /// Show what would be imported without inserting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Insert without asking for confirmation
+        #[arg(long, conflicts_with = "dry_run")]
+        yes: bool,
+    },
+
     /// Export expenses to CSV file
     Export {
         /// Path to output CSV file
@@ -251,6 +542,20 @@ enum ExpenseCommand {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum ReceiptsCommand {
+    /// Write a zip of the year's expense ledger and every attached receipt
+    Export {
+        /// Fiscal year (see `fiscal_year_start`)
+        #[arg(long)]
+        year: i32,
+
+        /// Path to output zip file (default: receipts_<year>.zip in the output directory)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum RecurringCommand {
     /// List all recurring expenses
@@ -262,7 +567,7 @@ enum RecurringCommand {
         #[arg(long)]
         vendor: String,
 
-        /// Category: Hosting, Contractor, Hardware, Software, VoteFees, Other
+        /// Category, e.g. Hosting or Hosting:Bandwidth (see `categories`)
         #[arg(long)]
         category: String,
 
@@ -270,193 +575,1366 @@ enum RecurringCommand {
         #[arg(long)]
         description: String,
 
-        /// Monthly amount in USD
+        /// Amount in USD of each charge
         #[arg(long)]
-        amount: f64,
+        amount: Usd,
 
         /// Payment method (e.g., "Credit Card", "USD", "SOL")
         #[arg(long, default_value = "USD")]
         paid_with: String,
 
-        /// Start date (YYYY-MM-DD) - day is used as billing day each month
+        /// Start date (YYYY-MM-DD) - first charge, and the billing day unless --billing-day is set
         #[arg(long)]
         start_date: String,
 
         /// End date (YYYY-MM-DD) - optional, omit for ongoing expenses
         #[arg(long)]
         end_date: Option<String>,
+
+        /// How often the expense is charged
+        #[arg(long, value_enum, default_value_t = expenses::Cadence::Monthly)]
+        cadence: expenses::Cadence,
+
+        /// Day of the month charges are billed on (monthly, quarterly and annual)
+        #[arg(long)]
+        billing_day: Option<u32>,
+
+        /// Charge the days from the start date to the first billing day, prorated
+        #[arg(long)]
+        prorate: bool,
     },
 
-    /// Delete a recurring expense by ID
-    Delete {
-        /// Recurring expense ID to delete
+    /// Correct fields of a recurring expense, keeping its ID (reassign with --validator)
+    Edit {
+        /// Recurring expense ID to edit
         id: i64,
-    },
-}
 
-#[derive(Subcommand, Debug)]
-enum DuneCommand {
-    /// Import inflation rewards from Dune
-    Rewards {
-        /// Start date for query (YYYY-MM-DD)
+        /// Vendor name
         #[arg(long)]
-        since: String,
-    },
+        vendor: Option<String>,
 
-    /// Import leader slot fees from Dune
-    LeaderFees {
-        /// Start date for query (YYYY-MM-DD)
+        /// Category, e.g. Hosting or Hosting:Bandwidth (see `categories`)
         #[arg(long)]
-        since: String,
-    },
+        category: Option<String>,
 
-    /// Import vote transaction costs from Dune
-    VoteCosts {
-        /// Start date for query (YYYY-MM-DD)
+        /// Description
         #[arg(long)]
-        since: String,
-    },
+        description: Option<String>,
 
-    /// Import SOL transfers from Dune
-    Transfers {
-        /// Start date for query (YYYY-MM-DD)
+        /// Amount in USD of each charge, before any amount changes (see set-amount)
         #[arg(long)]
-        since: String,
-    },
+        amount: Option<Usd>,
 
-    /// Import all data types from Dune
-    All {
-        /// Start date for query (YYYY-MM-DD)
+        /// Payment method (e.g., "Credit Card", "USD", "SOL")
         #[arg(long)]
-        since: String,
-    },
-}
+        paid_with: Option<String>,
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
+        /// Start date (YYYY-MM-DD)
+        #[arg(long)]
+        start_date: Option<String>,
 
-    // Create directories
-    std::fs::create_dir_all(&args.data_dir)?;
-    std::fs::create_dir_all(&args.output_dir)?;
+        /// End date (YYYY-MM-DD)
+        #[arg(long, conflicts_with = "ongoing")]
+        end_date: Option<String>,
 
-    // Open cache database (in data directory)
-    let cache_path = args.data_dir.join(constants::CACHE_FILENAME);
-    let cache = Cache::open(&cache_path).await?;
+        /// Remove the end date
+        #[arg(long)]
+        ongoing: bool,
 
-    // Handle subcommands
-    if let Some(command) = args.command {
-        return handle_command(command, &cache, args.config.as_ref()).await;
-    }
+        /// Share the expense across all validators again
+        #[arg(long)]
+        shared: bool,
 
-    // No subcommand - run the main report generation
-    run_report_generation(args, cache).await
-}
+        /// How often the expense is charged
+        #[arg(long, value_enum)]
+        cadence: Option<expenses::Cadence>,
 
-/// Handle expense management subcommands
-async fn handle_command(command: Command, cache: &Cache, config_path: Option<&PathBuf>) -> Result<()> {
-    match command {
-        Command::Expense { action } => handle_expense_command(action, cache).await,
-        Command::Recurring { action } => handle_recurring_command(action, cache).await,
-        Command::LeaderSlots { action } => handle_leader_slots_command(action, cache, config_path).await,
-        Command::VoteCosts { action } => handle_vote_costs_command(action, cache).await,
-        Command::Dune { action } => handle_dune_command(action, cache, config_path).await,
-    }
-}
+        /// Day of the month charges are billed on (monthly, quarterly and annual)
+        #[arg(long, conflicts_with = "start_day_billing")]
+        billing_day: Option<u32>,
 
-/// Handle leader slots subcommands
-async fn handle_leader_slots_command(
-    action: LeaderSlotsCommand,
-    cache: &Cache,
-    config_path: Option<&PathBuf>,
-) -> Result<()> {
-    match action {
-        LeaderSlotsCommand::Import { file, rpc_url } => {
-            println!("Importing historical leader slot data from {}...\n", file.display());
+        /// Bill on the start date's day of the month again
+        #[arg(long)]
+        start_day_billing: bool,
 
-            // Load config file and initialize runtime configuration
-            let file_config = load_config_file(config_path)?;
-            let config = config::Config::from_file(&file_config, rpc_url)?;
-            println!("Using RPC: {}\n", mask_api_key(&config.rpc_url));
+        /// Charge the days from the start date to the first billing day, prorated
+        #[arg(long, conflicts_with = "no_prorate")]
+        prorate: bool,
 
-            // Import and fetch fees for historical slots
-            let fees = leader_fees::import_historical_leader_fees(&config, &file).await?;
+        /// Charge only whole billing periods
+        #[arg(long)]
+        no_prorate: bool,
+    },
 
-            if fees.is_empty() {
-                println!("\nNo leader fee data imported.");
-                return Ok(());
-            }
+    /// Change the amount charged from a date on (earlier charges keep the old amount)
+    SetAmount {
+        /// Recurring expense ID
+        id: i64,
 
-            // Store in cache
-            cache.store_leader_fees(&fees).await?;
+        /// First charge date the new amount applies to (YYYY-MM-DD)
+        #[arg(long)]
+        from: chrono::NaiveDate,
 
-            println!("\n=============================================");
-            println!("Import Summary:");
-            println!("=============================================");
+        /// New amount in USD of each charge
+        #[arg(long)]
+        amount: Usd,
+    },
 
-            let mut total_slots = 0u64;
-            let mut total_blocks = 0u64;
-            let mut total_fees = 0.0f64;
+    /// Remove an amount change
+    RemoveAmount {
+        /// Recurring expense ID
+        id: i64,
 
-            for fee in &fees {
-                println!(
-                    "  Epoch {}: {} slots, {} blocks, {:.6} SOL",
-                    fee.epoch, fee.leader_slots, fee.blocks_produced, fee.total_fees_sol
-                );
-                total_slots += fee.leader_slots;
-                total_blocks += fee.blocks_produced;
-                total_fees += fee.total_fees_sol;
-            }
+        /// Date the amount change is effective from (YYYY-MM-DD)
+        #[arg(long)]
+        from: chrono::NaiveDate,
+    },
 
-            println!("---------------------------------------------");
-            println!(
-                "  Total: {} slots, {} blocks, {:.6} SOL",
-                total_slots, total_blocks, total_fees
-            );
-            println!("\nData cached to database.");
+    /// Skip one charge of a recurring expense
+    Skip {
+        /// Recurring expense ID
+        id: i64,
 
-            Ok(())
-        }
+        /// Scheduled charge date (YYYY-MM-DD, see `recurring schedule`)
+        date: chrono::NaiveDate,
+    },
 
-        LeaderSlotsCommand::List => {
-            // Show all cached leader fee data (use a reasonable max epoch)
-            let fees = cache.get_leader_fees(0, 10_000).await?;
+    /// Record a different amount for one charge of a recurring expense
+    Override {
+        /// Recurring expense ID
+        id: i64,
 
-            if fees.is_empty() {
-                println!("No leader fee data cached.");
-                println!("\nUse 'validator-accounting leader-slots import <file.json>' to import data");
-            } else {
+        /// Scheduled charge date (YYYY-MM-DD, see `recurring schedule`)
+        date: chrono::NaiveDate,
+
+        /// Amount in USD actually charged
+        #[arg(long)]
+        amount: Usd,
+    },
+
+    /// Undo skipping or overriding a charge
+    Restore {
+        /// Recurring expense ID
+        id: i64,
+
+        /// Charge date (YYYY-MM-DD)
+        date: chrono::NaiveDate,
+    },
+
+    /// List the charges of a recurring expense in a fiscal year
+    Schedule {
+        /// Recurring expense ID
+        id: i64,
+
+        /// Fiscal year (default: the current one)
+        #[arg(long)]
+        year: Option<i32>,
+    },
+
+    /// Delete a recurring expense by ID
+    Delete {
+        /// Recurring expense ID to delete
+        id: i64,
+    },
+
+    /// Show every logged change to a recurring expense
+    History {
+        /// Recurring expense ID
+        id: i64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AssetCommand {
+    /// List assets with their book value today
+    List,
+
+    /// Record a capital purchase, depreciated over its useful life instead of
+    /// expensed when bought
+    Add {
+        /// Description (e.g. "EPYC 9254 server")
+        #[arg(long)]
+        description: String,
+
+        /// Vendor name
+        #[arg(long)]
+        vendor: String,
+
+        /// Category its depreciation is reported under (see `categories`)
+        #[arg(long, default_value = "Hardware")]
+        category: String,
+
+        /// Date bought and placed in service (YYYY-MM-DD)
+        #[arg(long)]
+        purchase_date: chrono::NaiveDate,
+
+        /// Cost in USD
+        #[arg(long)]
+        cost: Usd,
+
+        /// Useful life in years (the recovery period for MACRS: 3, 5 or 7)
+        #[arg(long)]
+        life: u32,
+
+        /// Depreciation method
+        #[arg(long, value_enum, default_value_t = assets::DepreciationMethod::StraightLine)]
+        method: assets::DepreciationMethod,
+
+        /// Value in USD expected at the end of its life (straight-line and declining balance)
+        #[arg(long, default_value = "0")]
+        salvage: Usd,
+
+        /// Payment method (e.g., "Credit Card", "USD")
+        #[arg(long, default_value = "USD")]
+        paid_with: String,
+    },
+
+    /// Correct fields of an asset, keeping its ID (reassign with --validator)
+    Edit {
+        /// Asset ID to edit
+        id: i64,
+
+        /// Description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Vendor name
+        #[arg(long)]
+        vendor: Option<String>,
+
+        /// Category its depreciation is reported under (see `categories`)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Date bought and placed in service (YYYY-MM-DD)
+        #[arg(long)]
+        purchase_date: Option<chrono::NaiveDate>,
+
+        /// Cost in USD
+        #[arg(long)]
+        cost: Option<Usd>,
+
+        /// Useful life in years
+        #[arg(long)]
+        life: Option<u32>,
+
+        /// Depreciation method
+        #[arg(long, value_enum)]
+        method: Option<assets::DepreciationMethod>,
+
+        /// Value in USD expected at the end of its life
+        #[arg(long)]
+        salvage: Option<Usd>,
+
+        /// Payment method (e.g., "Credit Card", "USD")
+        #[arg(long)]
+        paid_with: Option<String>,
+
+        /// Share the asset across all validators again
+        #[arg(long)]
+        shared: bool,
+    },
+
+    /// Show an asset's depreciation by fiscal year, or by month within --year
+    Schedule {
+        /// Asset ID
+        id: i64,
+
+        /// Fiscal year to show month by month
+        #[arg(long)]
+        year: Option<i32>,
+    },
+
+    /// Delete an asset by ID
+    Delete {
+        /// Asset ID to delete
+        id: i64,
+    },
+
+    /// Show every logged change to an asset
+    History {
+        /// Asset ID
+        id: i64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DuneCommand {
+    /// Import inflation rewards from Dune
+    Rewards {
+        /// Start date for query (YYYY-MM-DD)
+        #[arg(long)]
+        since: String,
+    },
+
+    /// Import leader slot fees from Dune
+    LeaderFees {
+        /// Start date for query (YYYY-MM-DD)
+        #[arg(long)]
+        since: String,
+    },
+
+    /// Import vote transaction costs from Dune
+    VoteCosts {
+        /// Start date for query (YYYY-MM-DD)
+        #[arg(long)]
+        since: String,
+    },
+
+    /// Import SOL transfers from Dune
+    Transfers {
+        /// Start date for query (YYYY-MM-DD)
+        #[arg(long)]
+        since: String,
+    },
+
+    /// Import all data types from Dune
+    All {
+        /// Start date for query (YYYY-MM-DD)
+        #[arg(long)]
+        since: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    // Create directories
+    std::fs::create_dir_all(&args.data_dir)?;
+    std::fs::create_dir_all(&args.output_dir)?;
+
+    // Open cache database (in data directory)
+    let cache_path = args.data_dir.join(constants::CACHE_FILENAME);
+
+    // Schema commands run before the cache is opened (opening applies migrations)
+    if let Some(Command::Db { action }) = args.command {
+        return handle_db_command(action, &cache_path).await;
+    }
+
+    let cache = Cache::open(&cache_path).await?;
+
+    // Handle subcommands
+    if let Some(command) = args.command {
+        let context = CommandContext {
+            cache: &cache,
+            cache_path: &cache_path,
+            config_path: args.config.as_ref(),
+            validator: args.validator.as_deref(),
+            data_dir: &args.data_dir,
+            output_dir: &args.output_dir,
+        };
+        return handle_command(command, &context).await;
+    }
+
+    // No subcommand - run the main report generation
+    run_report_generation(args, cache).await
+}
+
+/// Global options shared by subcommands
+struct CommandContext<'a> {
+    cache: &'a Cache,
+    cache_path: &'a Path,
+    config_path: Option<&'a PathBuf>,
+    /// `--validator` selector (name or vote account)
+    validator: Option<&'a str>,
+    data_dir: &'a Path,
+    output_dir: &'a Path,
+}
+
+impl CommandContext<'_> {
+    /// Load every configured validator, assigning cache rows from before
+    /// multi-validator support to the first one
+    async fn load_validators(&self, rpc_url: Option<String>) -> Result<(FileConfig, Vec<config::Config>)> {
+        let file_config = load_config_file(self.config_path)?;
+        let mut configs = config::Config::all_from_file(&file_config, rpc_url)?;
+        self.cache.adopt_unscoped_rows(&configs[0].vote_account).await?;
+        self.cache
+            .record_validator_clusters(configs.iter().map(|c| (c.vote_account, c.cluster)))
+            .await?;
+        for config in &mut configs {
+            if let Some(schedule) = self.cache.get_epoch_schedule(config.cluster).await? {
+                config.epoch_schedule = schedule;
+            }
+        }
+        Ok((file_config, configs))
+    }
+
+    /// Vote account that new expenses are assigned to (None = shared across validators)
+    fn expense_validator(&self) -> Result<Option<String>> {
+        let Some(selector) = self.validator else {
+            return Ok(None);
+        };
+        let file_config = load_config_file(self.config_path)?;
+        let configs = config::Config::all_from_file(&file_config, None)?;
+        let config = config::select_validator(&configs, Some(selector))?;
+        Ok(Some(config.vote_account.to_string()))
+    }
+
+    /// New validator assignment for an edited expense: None to keep it, Some(None)
+    /// with `--shared`, or the validator selected with `--validator`
+    fn edited_validator(&self, shared: bool) -> Result<Option<Option<String>>> {
+        if shared {
+            if self.validator.is_some() {
+                anyhow::bail!("--shared and --validator are mutually exclusive");
+            }
+            return Ok(Some(None));
+        }
+        Ok(self.expense_validator()?.map(Some))
+    }
+
+    /// Validator names by vote account, for display (empty if config can't be loaded)
+    fn validator_names(&self) -> HashMap<String, String> {
+        load_config_file(self.config_path)
+            .and_then(|file_config| config::Config::all_from_file(&file_config, None))
+            .map(|configs| {
+                configs
+                    .into_iter()
+                    .map(|c| (c.vote_account.to_string(), c.name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Known expense categories: the configured ones (recorded in the
+    /// database when there is a config file) and any recorded before
+    async fn categories(&self) -> Result<Categories> {
+        if let Ok(file_config) = load_config_file(self.config_path) {
+            let configured = Categories::from_config(&file_config.categories)?;
+            self.cache.save_categories(&configured).await?;
+        }
+        self.cache.get_categories().await
+    }
+
+    /// Fiscal calendar from the config file (calendar years without one)
+    fn fiscal_calendar(&self) -> Result<period::FiscalCalendar> {
+        let fiscal_year_start = load_config_file(self.config_path)
+            .map(|file_config| file_config.reporting.fiscal_year_start)
+            .unwrap_or(1);
+        period::FiscalCalendar::new(fiscal_year_start)
+    }
+}
+
+/// Display name for an expense's validator assignment
+fn expense_validator_label(validator: Option<&str>, names: &HashMap<String, String>) -> String {
+    match validator {
+        None => "shared".to_string(),
+        Some(vote_account) => names
+            .get(vote_account)
+            .cloned()
+            .unwrap_or_else(|| vote_account.chars().take(8).collect()),
+    }
+}
+
+/// Reports for a single configured validator go straight into the output directory;
+/// with several, each validator gets a subdirectory named after it
+fn validator_output_dir(output_dir: &Path, config: &config::Config, validator_count: usize) -> Result<PathBuf> {
+    if validator_count == 1 {
+        return Ok(output_dir.to_path_buf());
+    }
+    let dir = output_dir.join(&config.name);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Handle expense management subcommands
+async fn handle_command(command: Command, context: &CommandContext<'_>) -> Result<()> {
+    match command {
+        Command::Expense { action } => handle_expense_command(action, context).await,
+        Command::Recurring { action } => handle_recurring_command(action, context).await,
+        Command::Asset { action } => handle_asset_command(action, context).await,
+        Command::Categories => print_categories(context).await,
+        Command::Receipts { action } => handle_receipts_command(action, context).await,
+        Command::LeaderSlots { action } => handle_leader_slots_command(action, context).await,
+        Command::VoteCosts { action } => handle_vote_costs_command(action, context).await,
+        Command::Dune { action } => handle_dune_command(action, context).await,
+        Command::Lots { action } => handle_lots_command(action, context).await,
+        Command::Cache { action } => handle_cache_command(action, context).await,
+        Command::Reconcile {
+            start_epoch,
+            end_epoch,
+            tolerance,
+            rpc_url,
+        } => {
+            let options = ReconcileOptions {
+                start_epoch,
+                end_epoch,
+                tolerance,
+                rpc_url,
+            };
+            handle_reconcile_command(options, context).await
+        }
+        Command::Db { .. } => unreachable!("db commands are handled before the cache is opened"),
+    }
+}
+
+/// Options for the reconcile command
+struct ReconcileOptions {
+    start_epoch: Option<u64>,
+    end_epoch: Option<u64>,
+    tolerance: Lamports,
+    rpc_url: Option<String>,
+}
+
+/// Reconcile each selected validator
+async fn handle_reconcile_command(options: ReconcileOptions, context: &CommandContext<'_>) -> Result<()> {
+    let (_, configs) = context.load_validators(options.rpc_url.clone()).await?;
+    let selected = config::select_validators(&configs, context.validator)?;
+
+    for config in selected {
+        if configs.len() > 1 {
+            println!("=== {} ({}) ===\n", config.name, config.vote_account);
+        }
+        let output_dir = validator_output_dir(context.output_dir, config, configs.len())?;
+        let cache = context.cache.for_validator(config);
+        reconcile_validator(&options, &cache, config, &output_dir).await?;
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Snapshot current balances and reconcile each account per epoch
+async fn reconcile_validator(
+    options: &ReconcileOptions,
+    cache: &Cache,
+    config: &config::Config,
+    output_dir: &Path,
+) -> Result<()> {
+    println!("Using RPC: {}\n", mask_api_key(&config.rpc_url));
+
+    let current_epoch = transactions::fetch_current_epoch(config)?;
+
+    println!("Snapshotting current balances...");
+    let accounts = reconcile::reconcile_accounts(config);
+    let current = reconcile::fetch_balance_snapshots(config, &accounts)?;
+    cache.store_balance_snapshots(&current).await?;
+
+    let snapshots = cache.get_balance_snapshots().await?;
+    if !snapshots
+        .iter()
+        .any(|s| s.source == reconcile::SnapshotSource::Transaction)
+    {
+        println!("\n  No transaction balances cached yet; run the report with --no-cache to backfill them.");
+    }
+
+    let transfers = cache.get_all_transfers().await?;
+    let rewards = cache.get_epoch_rewards(0, current_epoch).await?;
+    let mev_claims = cache.get_mev_claims(0, current_epoch).await?;
+    let leader_fees = cache.get_leader_fees(0, current_epoch).await?;
+    let vote_costs = cache.get_vote_costs(0, current_epoch).await?;
+    let inputs = reconcile::ReconcileInputs {
+        transfers: &transfers,
+        rewards: &rewards,
+        mev_claims: &mev_claims,
+        leader_fees: &leader_fees,
+        vote_costs: &vote_costs,
+    };
+
+    let start_epoch = options.start_epoch.unwrap_or(config.first_reward_epoch);
+    let end_epoch = options.end_epoch.unwrap_or(current_epoch);
+    let rows: Vec<_> = reconcile::reconcile(config, &inputs, &snapshots, current_epoch)
+        .into_iter()
+        .filter(|r| r.epoch >= start_epoch && r.epoch <= end_epoch)
+        .collect();
+
+    if rows.is_empty() {
+        println!(
+            "\nNot enough balance snapshots to reconcile epochs {}-{}.",
+            start_epoch, end_epoch
+        );
+        println!("Run 'validator-accounting reconcile' again after the next epoch boundary.");
+        return Ok(());
+    }
+
+    for (label, account) in &accounts {
+        let account_rows: Vec<_> = rows.iter().filter(|r| r.account == *account).collect();
+        if account_rows.is_empty() {
+            continue;
+        }
+
+        println!("\n{} ({})", label, account);
+        println!(
+            "{:<8} {:>16} {:>16} {:>16} {:>16}  Status",
+            "Epoch", "Closing (SOL)", "Actual", "Expected", "Unexplained"
+        );
+        println!("{}", "-".repeat(90));
+        for row in account_rows {
+            println!(
+                "{:<8} {:>16.6} {:>16} {:>16} {:>16}  {}",
+                row.epoch,
+                row.closing.lamports,
+                reconcile::format_signed(row.actual_delta),
+                reconcile::format_signed(row.expected_delta),
+                reconcile::format_signed(row.unexplained()),
+                reconcile::status(row, options.tolerance),
+            );
+        }
+    }
+
+    let path = output_dir.join(constants::RECONCILIATION_FILENAME);
+    reconcile::write_reconciliation_csv(&rows, options.tolerance, &path)?;
+    println!("\n  Generated: {}", path.display());
+
+    let flagged = rows.iter().filter(|r| r.needs_review(options.tolerance)).count();
+    if flagged == 0 {
+        println!("\nAll completed epochs reconcile within {} SOL.", options.tolerance);
+    } else {
+        println!(
+            "\n{} account-epoch(s) with unexplained changes above {} SOL - check for missed transfers, \
+             missed claims or mis-categorized rows.",
+            flagged, options.tolerance
+        );
+    }
+
+    Ok(())
+}
+
+/// Handle tax lot selection subcommands
+async fn handle_lots_command(action: LotsCommand, context: &CommandContext<'_>) -> Result<()> {
+    let cache = context.cache;
+    match action {
+        LotsCommand::List => {
+            let selections = cache.get_lot_selections().await?;
+            if selections.is_empty() {
+                println!("No lot selections recorded.");
+                println!("\nUse 'validator-accounting lots select <signature> <lot-id>...' to choose lots");
+            } else {
+                let mut signatures: Vec<_> = selections.keys().collect();
+                signatures.sort();
+                for signature in signatures {
+                    println!("{}: {}", signature, selections[signature].join(", "));
+                }
+                println!("\n{} withdrawal(s) with selections", selections.len());
+            }
+            Ok(())
+        }
+
+        LotsCommand::Select { signature, lot_ids } => {
+            let signature = check_lot_selection(&signature, &lot_ids, context).await?;
+            cache.set_lot_selection(&signature, &lot_ids).await?;
+            println!("Withdrawal {} will consume: {}", signature, lot_ids.join(", "));
+            println!("(applies when reports run with --lot-method specific-id)");
+            Ok(())
+        }
+
+        LotsCommand::Clear { signature } => {
+            if cache.clear_lot_selection(&signature).await? {
+                println!("Cleared lot selection for {}", signature);
+            } else {
+                println!("No lot selection for {}", signature);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Check a lot selection against the cached data reports use: the signature
+/// must match one disposal (withdrawal or expense payment) from a validator's
+/// accounts, and every lot ID must be one of that validator's lots. Returns
+/// the disposal's full signature.
+async fn check_lot_selection(signature: &str, lot_ids: &[String], context: &CommandContext<'_>) -> Result<String> {
+    let cache = context.cache;
+    let (_, configs) = context.load_validators(None).await?;
+    let prices = cache.get_prices().await?;
+    let expenses = cache.get_expenses().await?;
+    let payment_signatures: HashSet<&str> = expenses.iter().filter_map(|e| e.tx_signature.as_deref()).collect();
+
+    let mut validators = Vec::new();
+    for config in &configs {
+        let transfers = cache.for_validator(config).get_all_transfers().await?;
+        let mut categorized = transactions::categorize_transfers(&transfers, config);
+        categorized.separate_expense_payments(&payment_signatures);
+        validators.push((config, categorized));
+    }
+    let candidates: Vec<_> = validators
+        .iter()
+        .flat_map(|(config, categorized)| {
+            categorized
+                .disposals()
+                .filter(|t| t.signature.starts_with(signature))
+                .map(move |t| (*config, categorized, t))
+        })
+        .collect();
+    let (config, categorized, disposal) = match candidates.as_slice() {
+        [candidate] => *candidate,
+        [] => anyhow::bail!(
+            "No cached withdrawal or expense payment matches '{}' (run a report to fetch transfers)",
+            signature
+        ),
+        _ => anyhow::bail!(
+            "'{}' matches {} disposals; give more of the signature",
+            signature,
+            candidates.len()
+        ),
+    };
+
+    // The same lots a report builds for this validator
+    let validator_cache = cache.for_validator(config);
+    let lots = lots::build_lots(
+        &validator_cache.get_epoch_rewards(0, 10_000).await?,
+        categorized,
+        &validator_cache.get_mev_claims(0, 10_000).await?,
+        &validator_cache.get_leader_fees(0, 10_000).await?,
+        &prices,
+    );
+    let unknown: Vec<&str> = lot_ids
+        .iter()
+        .filter(|id| !lots.iter().any(|lot| &&lot.id == id))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        anyhow::bail!(
+            "{} has no lot(s) {} (see tax_lots.csv for lot IDs)",
+            config.name,
+            unknown.join(", ")
+        );
+    }
+
+    let disposal_date = disposal
+        .date
+        .as_deref()
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    for lot in &lots {
+        if let Some(date) = disposal_date.filter(|&d| lot.acquired > d && lot_ids.contains(&lot.id)) {
+            eprintln!(
+                "Warning: lot {} was acquired {}, after the withdrawal on {}; reports will skip it",
+                lot.id, lot.acquired, date
+            );
+        }
+    }
+    Ok(disposal.signature.clone())
+}
+
+/// Handle cache management subcommands
+async fn handle_cache_command(action: CacheCommand, context: &CommandContext<'_>) -> Result<()> {
+    let rpc_url = match &action {
+        CacheCommand::Refresh { rpc_url, .. } => rpc_url.clone(),
+        _ => None,
+    };
+    let (_, configs) = context.load_validators(rpc_url).await?;
+    let selected = config::select_validators(&configs, context.validator)?;
+
+    match action {
+        CacheCommand::Stats => {
+            for config in &selected {
+                let stats = context.cache.for_validator(config).stats().await?;
+                println!("{} ({}): {}", config.name, config.vote_account, stats);
+            }
+            Ok(())
+        }
+
+        CacheCommand::Show { table, epochs } => {
+            if table == CacheTable::Prices {
+                return show_cached_prices(context.cache, epochs).await;
+            }
+            if table == CacheTable::EpochBoundaries {
+                let clusters = one_per_cluster(&selected);
+                for config in &clusters {
+                    if clusters.len() > 1 {
+                        println!("\n{}", config.cluster);
+                    }
+                    show_cached_epoch_boundaries(&context.cache.for_validator(config), epochs).await?;
+                }
+                return Ok(());
+            }
+            for config in &selected {
+                if selected.len() > 1 {
+                    println!("\n{} ({})", config.name, config.vote_account);
+                }
+                show_cached_rows(&context.cache.for_validator(config), table, epochs).await?;
+            }
+            Ok(())
+        }
+
+        CacheCommand::Invalidate { table, epochs } => {
+            if table == CacheTable::Prices {
+                let removed = context.cache.invalidate(table, &epochs).await?;
+                let (from, to) = epochs.dates();
+                println!("Invalidated {} cached prices from {} to {}", removed, from, to);
+                return Ok(());
+            }
+            if table == CacheTable::EpochBoundaries {
+                for config in one_per_cluster(&selected) {
+                    let removed = context.cache.for_validator(config).invalidate(table, &epochs).await?;
+                    println!(
+                        "Invalidated {} {} epoch boundaries for epochs {}",
+                        removed, config.cluster, epochs
+                    );
+                }
+                return Ok(());
+            }
+            for config in &selected {
+                let removed = context.cache.for_validator(config).invalidate(table, &epochs).await?;
+                println!(
+                    "{}: invalidated {} {} row(s) for epochs {}",
+                    config.name,
+                    removed,
+                    table.name(),
+                    epochs
+                );
+            }
+            println!("\nThe next report run will fetch them again.");
+            Ok(())
+        }
+
+        CacheCommand::Refresh { table, epochs, .. } => {
+            let sources = Sources::live(selected[0]);
+            if table == CacheTable::Prices {
+                return refresh_cached_prices(context.cache, &epochs, &sources).await;
+            }
+            if table == CacheTable::EpochBoundaries {
+                for config in one_per_cluster(&selected) {
+                    let cache = context.cache.for_validator(config);
+                    refresh_cached_epoch_boundaries(&cache, config, &epochs, &sources).await?;
+                }
+                return Ok(());
+            }
+            for config in &selected {
+                let cache = context.cache.for_validator(config);
+                refresh_cached_rows(&cache, config, table, &epochs, &sources).await?;
+            }
+            Ok(())
+        }
+
+        CacheCommand::Vacuum => {
+            // Database plus write-ahead log, which holds recent writes until checkpointed
+            let path = context.cache_path;
+            let size = || {
+                let wal = path.with_file_name(format!("{}-wal", constants::CACHE_FILENAME));
+                [path, wal.as_path()]
+                    .iter()
+                    .filter_map(|p| std::fs::metadata(p).ok())
+                    .map(|m| m.len())
+                    .sum::<u64>()
+            };
+            let before = size();
+            context.cache.vacuum().await?;
+            println!(
+                "Vacuumed {}: {} KB -> {} KB",
+                path.display(),
+                before / 1024,
+                size() / 1024
+            );
+            Ok(())
+        }
+    }
+}
+
+/// The first of the selected validators on each cluster, for cluster-wide tables
+fn one_per_cluster<'a>(selected: &[&'a config::Config]) -> Vec<&'a config::Config> {
+    let mut seen = std::collections::HashSet::new();
+    selected.iter().copied().filter(|c| seen.insert(c.cluster)).collect()
+}
+
+/// Print one validator's cached rows for a table
+async fn show_cached_rows(cache: &Cache, table: CacheTable, epochs: Option<EpochRange>) -> Result<()> {
+    let in_range = |epoch: u64| epochs.is_none_or(|r| r.contains(epoch));
+    let epoch_of = |slot: u64| cache.epoch_schedule().get_epoch(slot);
+    match table {
+        CacheTable::EpochRewards => {
+            let rewards: Vec<_> = cache
+                .get_epoch_rewards(0, 10_000)
+                .await?
+                .into_iter()
+                .filter(|r| in_range(r.epoch))
+                .collect();
+            println!(
+                "{:<8} {:<12} {:>12} {:>12} {:>16}  {}",
+                "Epoch", "Date", "Commission", "Slot", "Amount (SOL)", PROVENANCE_HEADER
+            );
+            println!("{}", "-".repeat(96));
+            for reward in &rewards {
+                println!(
+                    "{:<8} {:<12} {:>11}% {:>12} {:>16.9}  {}",
+                    reward.epoch,
+                    reward.date.as_deref().unwrap_or("-"),
+                    reward.commission,
+                    reward.effective_slot,
+                    reward.amount_lamports,
+                    provenance_columns(&reward.provenance),
+                );
+            }
+            println!("\n{} epoch(s) cached", rewards.len());
+        }
+
+        CacheTable::LeaderFees => {
+            let fees: Vec<_> = cache
+                .get_leader_fees(0, 10_000)
+                .await?
+                .into_iter()
+                .filter(|f| in_range(f.epoch))
+                .collect();
+            println!(
+                "{:<8} {:<12} {:>10} {:>10} {:>10} {:>12} {:>14}  {}",
+                "Epoch", "Date", "Slots", "Blocks", "Skipped", "Unavailable", "Fees (SOL)", PROVENANCE_HEADER
+            );
+            println!("{}", "-".repeat(115));
+            for fee in &fees {
+                println!(
+                    "{:<8} {:<12} {:>10} {:>10} {:>10} {:>12} {:>14.6}  {}",
+                    fee.epoch,
+                    fee.date.as_deref().unwrap_or("-"),
+                    fee.leader_slots,
+                    fee.blocks_produced,
+                    fee.skipped_slots,
+                    fee.unavailable_blocks,
+                    fee.total_fees_lamports,
+                    provenance_columns(&fee.provenance),
+                );
+            }
+            println!("\n{} epoch(s) cached", fees.len());
+        }
+
+        CacheTable::MevClaims => {
+            let claims: Vec<_> = cache
+                .get_mev_claims(0, 10_000)
+                .await?
+                .into_iter()
+                .filter(|c| in_range(c.epoch))
+                .collect();
+            println!(
+                "{:<8} {:<12} {:>14} {:>14} {:>16} {:>16}  {}",
+                "Epoch", "Date", "Tips (SOL)", "Commission", "Priority (SOL)", "Commission", PROVENANCE_HEADER
+            );
+            println!("{}", "-".repeat(117));
+            for claim in &claims {
+                println!(
+                    "{:<8} {:<12} {:>14.6} {:>14.6} {:>16.6} {:>16.6}  {}",
+                    claim.epoch,
+                    claim.date.as_deref().unwrap_or("-"),
+                    claim.total_tips_lamports,
+                    claim.commission_lamports,
+                    claim.total_priority_fees_lamports,
+                    claim.priority_fee_commission_lamports,
+                    provenance_columns(&claim.provenance),
+                );
+            }
+            println!("\n{} epoch(s) cached", claims.len());
+        }
+
+        CacheTable::VoteCosts => {
+            let costs: Vec<_> = cache
+                .get_vote_costs(0, 10_000)
+                .await?
+                .into_iter()
+                .filter(|c| in_range(c.epoch))
+                .collect();
+            println!(
+                "{:<8} {:<12} {:>12} {:>14}  {}",
+                "Epoch", "Date", "Votes", "Cost (SOL)", PROVENANCE_HEADER
+            );
+            println!("{}", "-".repeat(81));
+            for cost in &costs {
+                println!(
+                    "{:<8} {:<12} {:>12} {:>14.6}  {}",
+                    cost.epoch,
+                    cost.date.as_deref().unwrap_or("-"),
+                    cost.vote_count,
+                    cost.total_fee_lamports,
+                    provenance_columns(&cost.provenance),
+                );
+            }
+            println!("\n{} epoch(s) cached", costs.len());
+        }
+
+        CacheTable::SolTransfers => {
+            let mut transfers: Vec<_> = cache
+                .get_all_transfers()
+                .await?
+                .into_iter()
+                .filter(|t| in_range(epoch_of(t.slot)))
+                .collect();
+            transfers.sort_by_key(|t| t.slot);
+            println!(
+                "{:<8} {:<12} {:<22} {:<22} {:>16}  {}  Signature",
+                "Epoch", "Date", "From", "To", "Amount (SOL)", PROVENANCE_HEADER
+            );
+            println!("{}", "-".repeat(132));
+            for transfer in &transfers {
+                println!(
+                    "{:<8} {:<12} {:<22} {:<22} {:>16.9}  {}  {}",
+                    epoch_of(transfer.slot),
+                    transfer.date.as_deref().unwrap_or("-"),
+                    truncate(&transfer.from_label, 22),
+                    truncate(&transfer.to_label, 22),
+                    transfer.amount_lamports,
+                    provenance_columns(&transfer.provenance),
+                    transfer.signature,
+                );
+            }
+            println!("\n{} transfer(s) cached", transfers.len());
+        }
+
+        CacheTable::AccountProgress => {
+            let progress: Vec<_> = cache
+                .get_all_account_progress()
+                .await?
+                .into_iter()
+                .filter(|(_, slot)| in_range(epoch_of(*slot)))
+                .collect();
+            println!("{:<24} {:>14} {:>8}", "Account", "Highest Slot", "Epoch");
+            println!("{}", "-".repeat(48));
+            for (account, slot) in &progress {
+                println!("{:<24} {:>14} {:>8}", account, slot, epoch_of(*slot));
+            }
+            println!("\n{} account(s) tracked", progress.len());
+        }
+
+        CacheTable::Prices | CacheTable::EpochBoundaries => {
+            unreachable!("{} are shared, not per validator", table.name())
+        }
+    }
+    Ok(())
+}
+
+/// Header for the trailing provenance columns of `cache show`
+const PROVENANCE_HEADER: &str = "Source     Fetched            ";
+
+/// A cached row's source and fetch time, aligned under `PROVENANCE_HEADER`
+fn provenance_columns(provenance: &Provenance) -> String {
+    format!(
+        "{:<10} {:<19}",
+        provenance.source,
+        provenance.fetched_at.as_deref().unwrap_or("-")
+    )
+}
+
+/// Print cached daily prices, optionally limited to the dates some epochs span
+async fn show_cached_prices(cache: &Cache, epochs: Option<EpochRange>) -> Result<()> {
+    let range = epochs.map(|r| r.dates());
+    let mut prices: Vec<_> = cache
+        .get_prices()
+        .await?
+        .into_iter()
+        .filter(|(date, _)| range.as_ref().is_none_or(|(from, to)| date >= from && date <= to))
+        .collect();
+    prices.sort_by(|a, b| a.0.cmp(&b.0));
+
+    println!("{:<12} {:>12}  {}", "Date", "SOL/USD", PROVENANCE_HEADER);
+    println!("{}", "-".repeat(57));
+    for (date, price) in &prices {
+        println!(
+            "{:<12} {:>12.2}  {}",
+            date,
+            price.usd,
+            provenance_columns(&price.provenance)
+        );
+    }
+    println!("\n{} price(s) cached", prices.len());
+    Ok(())
+}
+
+/// Refetch one validator's rows for a range of completed epochs, replacing the cached ones
+async fn refresh_cached_rows(
+    cache: &Cache,
+    config: &config::Config,
+    table: CacheTable,
+    epochs: &EpochRange,
+    sources: &Sources,
+) -> Result<()> {
+    let current_epoch = sources.chain.current_epoch(config).await?;
+    if epochs.start >= current_epoch {
+        anyhow::bail!(
+            "Epoch {} is not complete yet (current epoch is {})",
+            epochs.start,
+            current_epoch
+        );
+    }
+    let epochs = EpochRange {
+        start: epochs.start,
+        end: epochs.end.min(current_epoch - 1),
+    };
+    println!("{}: refreshing {} for epochs {}...", config.name, table.name(), epochs);
+
+    // Fetch before invalidating so a failed request leaves the cache as it was
+    let refreshed = match table {
+        CacheTable::EpochRewards => {
+            let rewards = sources
+                .chain
+                .inflation_rewards(config, epochs.start, Some(epochs.end))
+                .await?;
+            cache.invalidate(table, &epochs).await?;
+            cache.store_epoch_rewards(&rewards).await?;
+            rewards.len()
+        }
+        CacheTable::LeaderFees => {
+            let fees = sources
+                .chain
+                .leader_fees(config, epochs.start, Some(epochs.end))
+                .await?;
+            cache.invalidate(table, &epochs).await?;
+            cache.store_leader_fees(&fees).await?;
+            fees.len()
+        }
+        CacheTable::MevClaims => {
+            if !config.cluster.has_jito() {
+                anyhow::bail!(
+                    "Jito MEV claims are only tracked on mainnet ({} is on {})",
+                    config.name,
+                    config.cluster
+                );
+            }
+            let claims: Vec<_> = sources
+                .mev
+                .mev_claims(config)
+                .await?
+                .into_iter()
+                .filter(|c| epochs.contains(c.epoch))
+                .collect();
+            cache.invalidate(table, &epochs).await?;
+            cache.store_mev_claims(&claims).await?;
+            claims.len()
+        }
+        CacheTable::VoteCosts => {
+            if !config.cluster.has_dune() {
+                anyhow::bail!(
+                    "Dune only indexes mainnet; use 'vote-costs import' / 'vote-costs estimate' for {}",
+                    config.name
+                );
+            }
+            let Some(analytics) = &sources.analytics else {
+                anyhow::bail!(
+                    "Refreshing vote costs requires a Dune API key (or use 'vote-costs import' / 'vote-costs estimate')"
+                );
+            };
+            let costs: Vec<_> = analytics
+                .vote_costs(config, &transactions::epoch_to_date(epochs.start))
+                .await?
+                .into_iter()
+                .filter(|c| epochs.contains(c.epoch))
+                .collect();
+            cache.invalidate(table, &epochs).await?;
+            cache.store_vote_costs(&costs).await?;
+            costs.len()
+        }
+        CacheTable::SolTransfers | CacheTable::AccountProgress => {
+            // Signatures are paged newest first, so everything since the range is walked again
+            cache.invalidate(table, &epochs).await?;
+            let transfers =
+                fetch_transfers_with_cache(cache, config, false, false, sources, &config.bootstrap_date).await?;
+            transfers
+                .iter()
+                .filter(|t| epochs.contains(config.epoch_schedule.get_epoch(t.slot)))
+                .count()
+        }
+        CacheTable::Prices | CacheTable::EpochBoundaries => {
+            unreachable!("{} are shared, not per validator", table.name())
+        }
+    };
+
+    println!("  Cached {} row(s) for epochs {}", refreshed, epochs);
+    Ok(())
+}
+
+/// Print cached epoch boundaries, optionally limited to a range of epochs
+async fn show_cached_epoch_boundaries(cache: &Cache, epochs: Option<EpochRange>) -> Result<()> {
+    let boundaries: Vec<_> = cache
+        .get_epoch_boundaries()
+        .await?
+        .into_iter()
+        .filter(|b| epochs.is_none_or(|r| r.contains(b.epoch)))
+        .collect();
+
+    println!(
+        "{:<8} {:<20} {:>14}  {}",
+        "Epoch", "Started (UTC)", "First Block", PROVENANCE_HEADER
+    );
+    println!("{}", "-".repeat(76));
+    for boundary in &boundaries {
+        let started = chrono::DateTime::from_timestamp(boundary.block_time, 0)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<8} {:<20} {:>14}  {}",
+            boundary.epoch,
+            started,
+            boundary.first_block_slot,
+            provenance_columns(&boundary.provenance)
+        );
+    }
+    println!(
+        "\n{} epoch boundar{} cached",
+        boundaries.len(),
+        if boundaries.len() == 1 { "y" } else { "ies" }
+    );
+    Ok(())
+}
+
+/// Refetch the boundaries of a range of started epochs
+async fn refresh_cached_epoch_boundaries(
+    cache: &Cache,
+    config: &config::Config,
+    epochs: &EpochRange,
+    sources: &Sources,
+) -> Result<()> {
+    let current_epoch = sources.chain.current_epoch(config).await?;
+    let end = epochs.end.min(current_epoch);
+    println!("Refreshing epoch boundaries for epochs {}..{}...", epochs.start, end);
+    let boundaries = fetch_epoch_boundaries(config, &(epochs.start..=end).collect::<Vec<_>>(), sources).await;
+
+    cache.invalidate(CacheTable::EpochBoundaries, epochs).await?;
+    cache.store_epoch_boundaries(&boundaries).await?;
+    println!("  Cached {} epoch boundaries", boundaries.len());
+    Ok(())
+}
+
+/// Refetch daily prices for the dates some epochs span
+async fn refresh_cached_prices(cache: &Cache, epochs: &EpochRange, sources: &Sources) -> Result<()> {
+    let (from, to) = epochs.dates();
+    println!("Refreshing prices from {} to {}...", from, to);
+    let parse = |date: &str| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d");
+    let prices: prices::PriceCache = sources
+        .prices
+        .price_range(parse(&from)?, parse(&to)?)
+        .await?
+        .into_iter()
+        .map(|(date, usd)| (date, prices::Price::new(usd, DataSource::CoinGecko)))
+        .collect();
+
+    cache.invalidate(CacheTable::Prices, epochs).await?;
+    cache.store_prices(&prices).await?;
+    println!("  Cached {} daily prices", prices.len());
+    Ok(())
+}
+
+/// Handle cache database schema subcommands
+async fn handle_db_command(action: DbCommand, cache_path: &Path) -> Result<()> {
+    match action {
+        DbCommand::Migrate { dry_run: true } => {
+            let pending = if cache_path.exists() {
+                Cache::open_without_migrating(cache_path)
+                    .await?
+                    .pending_migrations()
+                    .await?
+            } else {
+                cache::MIGRATIONS.iter().collect()
+            };
+
+            if pending.is_empty() {
+                println!("Cache database is up to date.");
+                return Ok(());
+            }
+            println!("Pending migrations for {}:", cache_path.display());
+            for migration in &pending {
+                println!("  {:>3}  {}", migration.version, migration.description);
+            }
+            println!(
+                "\n{} migration(s) would be applied (run without --dry-run to apply)",
+                pending.len()
+            );
+            Ok(())
+        }
+
+        DbCommand::Migrate { dry_run: false } => {
+            let cache = Cache::open(cache_path).await?;
+            println!("Cache database is at schema version {}.", cache.schema_version().await?);
+            Ok(())
+        }
+    }
+}
+
+/// Handle leader slots subcommands
+async fn handle_leader_slots_command(action: LeaderSlotsCommand, context: &CommandContext<'_>) -> Result<()> {
+    let rpc_url = match &action {
+        LeaderSlotsCommand::Import { rpc_url, .. } => rpc_url.clone(),
+        LeaderSlotsCommand::List => None,
+    };
+    let (_, configs) = context.load_validators(rpc_url).await?;
+    let config = config::select_validator(&configs, context.validator)?;
+    let cache = &context.cache.for_validator(config);
+
+    match action {
+        LeaderSlotsCommand::Import { file, .. } => {
+            println!("Importing historical leader slot data from {}...\n", file.display());
+            println!("Using RPC: {}\n", mask_api_key(&config.rpc_url));
+
+            // Import and fetch fees for historical slots
+            let fees = leader_fees::import_historical_leader_fees(config, &file).await?;
+
+            if fees.is_empty() {
+                println!("\nNo leader fee data imported.");
+                return Ok(());
+            }
+
+            // Store in cache
+            cache.store_leader_fees(&fees).await?;
+
+            println!("\n=============================================");
+            println!("Import Summary:");
+            println!("=============================================");
+
+            let mut total_slots = 0u64;
+            let mut total_blocks = 0u64;
+            let mut total_fees = Lamports::ZERO;
+
+            for fee in &fees {
+                println!(
+                    "  Epoch {}: {} slots, {} blocks, {:.6} SOL",
+                    fee.epoch, fee.leader_slots, fee.blocks_produced, fee.total_fees_lamports
+                );
+                total_slots += fee.leader_slots;
+                total_blocks += fee.blocks_produced;
+                total_fees += fee.total_fees_lamports;
+            }
+
+            println!("---------------------------------------------");
+            println!(
+                "  Total: {} slots, {} blocks, {:.6} SOL",
+                total_slots, total_blocks, total_fees
+            );
+            println!("\nData cached to database.");
+
+            Ok(())
+        }
+
+        LeaderSlotsCommand::List => {
+            // Show all cached leader fee data (use a reasonable max epoch)
+            let fees = cache.get_leader_fees(0, 10_000).await?;
+
+            if fees.is_empty() {
+                println!("No leader fee data cached.");
+                println!("\nUse 'validator-accounting leader-slots import <file.json>' to import data");
+            } else {
                 println!(
-                    "{:<8} {:<12} {:>10} {:>10} {:>10} {:>14}",
-                    "Epoch", "Date", "Slots", "Blocks", "Skipped", "Fees (SOL)"
+                    "{:<8} {:<12} {:>10} {:>10} {:>10} {:>12} {:>14}",
+                    "Epoch", "Date", "Slots", "Blocks", "Skipped", "Unavailable", "Fees (SOL)"
                 );
-                println!("{}", "-".repeat(70));
+                println!("{}", "-".repeat(83));
 
                 let mut total_slots = 0u64;
                 let mut total_blocks = 0u64;
-                let mut total_fees = 0.0f64;
+                let mut total_fees = Lamports::ZERO;
 
                 for fee in &fees {
                     println!(
-                        "{:<8} {:<12} {:>10} {:>10} {:>10} {:>14.6}",
+                        "{:<8} {:<12} {:>10} {:>10} {:>10} {:>12} {:>14.6}",
                         fee.epoch,
                         fee.date.as_deref().unwrap_or("-"),
                         fee.leader_slots,
                         fee.blocks_produced,
                         fee.skipped_slots,
-                        fee.total_fees_sol,
+                        fee.unavailable_blocks,
+                        fee.total_fees_lamports,
                     );
                     total_slots += fee.leader_slots;
                     total_blocks += fee.blocks_produced;
-                    total_fees += fee.total_fees_sol;
+                    total_fees += fee.total_fees_lamports;
                 }
 
-                println!("{}", "-".repeat(70));
+                println!("{}", "-".repeat(83));
                 println!(
-                    "{:<8} {:>12} {:>10} {:>10} {:>10} {:>14.6}",
-                    "Total", "", total_slots, total_blocks, "", total_fees
+                    "{:<8} {:>12} {:>10} {:>10} {:>10} {:>12} {:>14.6}",
+                    "Total", "", total_slots, total_blocks, "", "", total_fees
                 );
                 println!("\n{} epoch(s) cached", fees.len());
+                let incomplete = fees.iter().filter(|f| !f.is_complete()).count();
+                if incomplete > 0 {
+                    println!(
+                        "{} epoch(s) incomplete (unavailable blocks will be re-fetched)",
+                        incomplete
+                    );
+                }
             }
             Ok(())
         }
@@ -464,7 +1942,11 @@ async fn handle_leader_slots_command(
 }
 
 /// Handle vote costs subcommands
-async fn handle_vote_costs_command(action: VoteCostsCommand, cache: &Cache) -> Result<()> {
+async fn handle_vote_costs_command(action: VoteCostsCommand, context: &CommandContext<'_>) -> Result<()> {
+    let (_, configs) = context.load_validators(None).await?;
+    let config = config::select_validator(&configs, context.validator)?;
+    let cache = &context.cache.for_validator(config);
+
     match action {
         VoteCostsCommand::Import { file } => {
             println!("Importing vote cost data from {}...\n", file.display());
@@ -487,7 +1969,7 @@ async fn handle_vote_costs_command(action: VoteCostsCommand, cache: &Cache) -> R
             println!("{}", "-".repeat(60));
 
             let mut total_votes = 0u64;
-            let mut total_cost = 0.0f64;
+            let mut total_cost = Lamports::ZERO;
 
             for cost in &costs {
                 println!(
@@ -495,11 +1977,11 @@ async fn handle_vote_costs_command(action: VoteCostsCommand, cache: &Cache) -> R
                     cost.epoch,
                     cost.date.as_deref().unwrap_or("-"),
                     cost.vote_count,
-                    cost.total_fee_sol,
-                    cost.source,
+                    cost.total_fee_lamports,
+                    cost.provenance.source,
                 );
                 total_votes += cost.vote_count;
-                total_cost += cost.total_fee_sol;
+                total_cost += cost.total_fee_lamports;
             }
 
             println!("{}", "-".repeat(60));
@@ -524,7 +2006,7 @@ async fn handle_vote_costs_command(action: VoteCostsCommand, cache: &Cache) -> R
                 println!("{}", "-".repeat(60));
 
                 let mut total_votes = 0u64;
-                let mut total_cost = 0.0f64;
+                let mut total_cost = Lamports::ZERO;
 
                 for cost in &costs {
                     println!(
@@ -532,11 +2014,11 @@ async fn handle_vote_costs_command(action: VoteCostsCommand, cache: &Cache) -> R
                         cost.epoch,
                         cost.date.as_deref().unwrap_or("-"),
                         cost.vote_count,
-                        cost.total_fee_sol,
-                        cost.source,
+                        cost.total_fee_lamports,
+                        cost.provenance.source,
                     );
                     total_votes += cost.vote_count;
-                    total_cost += cost.total_fee_sol;
+                    total_cost += cost.total_fee_lamports;
                 }
 
                 println!("{}", "-".repeat(60));
@@ -550,217 +2032,1209 @@ async fn handle_vote_costs_command(action: VoteCostsCommand, cache: &Cache) -> R
             let epoch_word = if start == end { "epoch" } else { "epochs" };
             println!("Estimating vote costs for {} {}-{}...\n", epoch_word, start, end);
 
-            let estimates = vote_costs::estimate_vote_costs(start, end);
+            let estimates = vote_costs::estimate_vote_costs(start, end);
+
+            // Store in cache
+            cache.store_vote_costs(&estimates).await?;
+
+            println!(
+                "Estimated {} epochs at ~{:.3} SOL each:",
+                estimates.len(),
+                vote_costs::TYPICAL_COST_PER_EPOCH
+            );
+            println!(
+                "Total estimated cost: {:.6} SOL\n",
+                vote_costs::total_vote_costs_sol(&estimates)
+            );
+
+            println!("Data cached to database.");
+            Ok(())
+        }
+    }
+}
+
+/// Handle expense subcommands
+async fn handle_expense_command(action: ExpenseCommand, context: &CommandContext<'_>) -> Result<()> {
+    let cache = context.cache;
+    match action {
+        ExpenseCommand::List => {
+            let expenses = cache.get_expenses().await?;
+            let attachments = cache.get_attachments().await?;
+            let names = context.validator_names();
+            if expenses.is_empty() {
+                println!("No expenses recorded.");
+                println!("\nUse 'validator-accounting expense add' to add expenses");
+                println!("Or 'validator-accounting expense import <file.csv>' to import from CSV");
+            } else {
+                println!(
+                    "{:<4} {:<12} {:<15} {:<12} {:>10}  {:<10} {:<8} Description",
+                    "ID", "Date", "Vendor", "Category", "Amount", "Validator", "Receipt"
+                );
+                println!("{}", "-".repeat(100));
+
+                let mut total = Usd::ZERO;
+                let mut missing = Vec::new();
+                let mut prepaid = Vec::new();
+                for expense in &expenses {
+                    let id = expense.id.map(|i| i.to_string()).unwrap_or_default();
+                    let receipts = expense.id.and_then(|i| attachments.get(&i)).map_or(0, Vec::len);
+                    let receipt = match receipts {
+                        0 => {
+                            missing.push(id.clone());
+                            "MISSING".to_string()
+                        }
+                        1 => "yes".to_string(),
+                        n => format!("{} files", n),
+                    };
+                    println!(
+                        "{:<4} {:<12} {:<15} {:<12} ${:>9.2}  {:<10} {:<8} {}",
+                        id,
+                        expense.date,
+                        truncate(&expense.vendor, 14),
+                        expense.category,
+                        expense.amount_usd,
+                        truncate(&expense_validator_label(expense.validator.as_deref(), &names), 10),
+                        receipt,
+                        truncate(&expense.description, 30),
+                    );
+                    total += expense.amount_usd;
+                    if let Some((start, end)) = expense.service_period() {
+                        prepaid.push(format!(
+                            "#{} ({} to {}, {} months)",
+                            id,
+                            start,
+                            end,
+                            expense.amortization().len()
+                        ));
+                    }
+                }
+                println!("{}", "-".repeat(100));
+                println!("{:>54} ${:>9.2}", "Total:", total);
+                println!("\n{} expense(s)", expenses.len());
+                if !prepaid.is_empty() {
+                    println!(
+                        "{} prepaid, amortized over their service periods: {}",
+                        prepaid.len(),
+                        prepaid.join(", ")
+                    );
+                }
+                if !missing.is_empty() {
+                    println!(
+                        "{} without a receipt: #{} (attach with 'validator-accounting expense attach <id> <file>')",
+                        missing.len(),
+                        missing.join(", #")
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        ExpenseCommand::Add {
+            date,
+            vendor,
+            category,
+            description,
+            amount,
+            paid_with,
+            invoice_id,
+            service_start,
+            service_end,
+        } => {
+            let category = context.categories().await?.resolve(&category)?;
+
+            let expense = Expense {
+                id: None,
+                date,
+                vendor,
+                category,
+                description,
+                amount_usd: amount,
+                paid_with,
+                invoice_id,
+                validator: context.expense_validator()?,
+                tx_signature: None,
+                amount_lamports: None,
+                paid_date: None,
+                service_start,
+                service_end,
+                provenance: Provenance::manual(),
+            };
+            expense.validate_service_period()?;
+
+            let id = cache.add_expense(&expense).await?;
+            println!("Added expense #{}: {} - ${:.2}", id, expense.vendor, expense.amount_usd);
+            print_service_period(&expense);
+            Ok(())
+        }
+
+        ExpenseCommand::Edit {
+            id,
+            date,
+            vendor,
+            category,
+            description,
+            amount,
+            paid_with,
+            invoice_id,
+            service_start,
+            service_end,
+            no_service_period,
+            shared,
+        } => {
+            let Some(mut expense) = cache.get_expense(id).await? else {
+                anyhow::bail!("Expense #{} not found", id);
+            };
+            let validator = context.edited_validator(shared)?;
+            if [
+                &date,
+                &vendor,
+                &category,
+                &description,
+                &paid_with,
+                &invoice_id,
+                &service_start,
+                &service_end,
+            ]
+            .iter()
+            .all(|field| field.is_none())
+                && amount.is_none()
+                && validator.is_none()
+                && !no_service_period
+            {
+                anyhow::bail!("Nothing to change; pass the fields to edit (see `expense edit --help`)");
+            }
+
+            if let Some(date) = date {
+                expense.date = date;
+            }
+            if let Some(vendor) = vendor {
+                expense.vendor = vendor;
+            }
+            if let Some(category) = category {
+                expense.category = context.categories().await?.resolve(&category)?;
+            }
+            if let Some(description) = description {
+                expense.description = description;
+            }
+            if let Some(amount) = amount {
+                // Linked expenses are valued from the SOL their transfer sent
+                if expense.tx_signature.is_some() {
+                    anyhow::bail!(
+                        "Expense #{} is linked to its SOL payment, which sets its amount; unlink it first \
+                         (`expense unlink {} --paid-with <method>`) to enter one",
+                        id,
+                        id
+                    );
+                }
+                expense.amount_usd = amount;
+            }
+            if let Some(paid_with) = paid_with {
+                expense.paid_with = paid_with;
+            }
+            if let Some(invoice_id) = invoice_id {
+                expense.invoice_id = Some(invoice_id).filter(|i| !i.is_empty());
+            }
+            if let Some(validator) = validator {
+                expense.validator = validator;
+            }
+            if service_start.is_some() {
+                expense.service_start = service_start;
+            }
+            if service_end.is_some() {
+                expense.service_end = service_end;
+            }
+            if no_service_period {
+                expense.service_start = None;
+                expense.service_end = None;
+            }
+            expense.validate_service_period()?;
+
+            cache.update_expense(&expense).await?;
+            println!(
+                "Updated expense #{}: {} - ${:.2}",
+                id, expense.vendor, expense.amount_usd
+            );
+            print_service_period(&expense);
+            Ok(())
+        }
+
+        ExpenseCommand::Delete { id } => {
+            if cache.delete_expense(id).await? {
+                println!("Deleted expense #{}", id);
+            } else {
+                println!("Expense #{} not found", id);
+            }
+            Ok(())
+        }
+
+        ExpenseCommand::History { id } => print_expense_history(cache, ExpenseTable::Expenses, id).await,
+
+        ExpenseCommand::Link { id, signature } => link_expense_payment(id, signature.as_deref(), context).await,
+
+        ExpenseCommand::Unlink { id, paid_with } => {
+            let Some(mut expense) = cache.get_expense(id).await? else {
+                anyhow::bail!("Expense #{} not found", id);
+            };
+            if expense.tx_signature.take().is_none() {
+                anyhow::bail!("Expense #{} isn't linked to a transfer", id);
+            }
+            expense.amount_lamports = None;
+            expense.paid_with = paid_with;
+            cache.update_expense(&expense).await?;
+            println!(
+                "Unlinked expense #{} from its payment transfer (now paid with {})",
+                id, expense.paid_with
+            );
+            Ok(())
+        }
+
+        ExpenseCommand::Attach { id, file } => {
+            let Some(expense) = cache.get_expense(id).await? else {
+                anyhow::bail!("Expense #{} not found", id);
+            };
+            let attachment = AttachmentStore::new(context.data_dir).add(id, &file)?;
+            if cache.add_attachment(&attachment).await? {
+                println!(
+                    "Attached {} to expense #{} ({} - ${:.2}), sha256 {}",
+                    attachment.filename,
+                    id,
+                    expense.vendor,
+                    expense.amount_usd,
+                    &attachment.sha256[..12]
+                );
+            } else {
+                println!("{} is already attached to expense #{}", attachment.filename, id);
+            }
+            Ok(())
+        }
+
+        ExpenseCommand::Import { file } => {
+            let categories = context.categories().await?;
+            let mut expenses = expenses::load_from_csv(&file)?;
+            for expense in &mut expenses {
+                expense.category = categories
+                    .resolve(expense.category.as_str())
+                    .with_context(|| format!("Expense on {} from {}", expense.date, expense.vendor))?;
+                expense
+                    .validate_service_period()
+                    .with_context(|| format!("Expense on {} from {}", expense.date, expense.vendor))?;
+            }
+            let count = cache.import_expenses(&expenses).await?;
+            println!("Imported {} expenses from {}", count, file.display());
+            Ok(())
+        }
+
+        ExpenseCommand::ImportStatement {
+            file,
+            format,
+            rules,
+            dry_run,
+            yes,
+        } => {
+            let options = StatementImport {
+                file,
+                format,
+                rules,
+                dry_run,
+                yes,
+            };
+            import_statement(options, context).await
+        }
+
+        ExpenseCommand::Export { file } => {
+            let expenses = cache.get_expenses().await?;
+            expenses::export_to_csv(&expenses, &file)?;
+            println!("Exported {} expenses to {}", expenses.len(), file.display());
+            Ok(())
+        }
+    }
+}
+
+/// How a prepaid expense is spread over its service period (nothing for
+/// ordinary expenses)
+fn print_service_period(expense: &Expense) {
+    let Some((start, end)) = expense.service_period() else {
+        return;
+    };
+    let portions = expense.amortization();
+    println!(
+        "  Prepaid for {} to {}: expensed in {} monthly portion(s) of ${:.2}",
+        start,
+        end,
+        portions.len(),
+        portions[0].amount_usd
+    );
+}
+
+/// Link an expense to the outgoing transfer that paid it, or list the
+/// uncategorized transfers that could have
+async fn link_expense_payment(id: i64, signature: Option<&str>, context: &CommandContext<'_>) -> Result<()> {
+    let cache = context.cache;
+    let Some(mut expense) = cache.get_expense(id).await? else {
+        anyhow::bail!("Expense #{} not found", id);
+    };
+    let (_, configs) = context.load_validators(None).await?;
+    let prices = cache.get_prices().await?;
+    let linked: HashMap<String, i64> = cache
+        .get_expenses()
+        .await?
+        .into_iter()
+        .filter(|e| e.id != Some(id))
+        .filter_map(|e| Some((e.tx_signature?, e.id?)))
+        .collect();
+
+    // Outgoing transfers from each validator's accounts, and the uncategorized ones
+    let mut outgoing = Vec::new();
+    let mut uncategorized = Vec::new();
+    for config in &configs {
+        let transfers = cache.for_validator(config).get_all_transfers().await?;
+        let categorized = transactions::categorize_transfers(&transfers, config);
+        let ours =
+            |t: &&transactions::SolTransfer| config.is_our_account(&t.from) && !linked.contains_key(&t.signature);
+        outgoing.extend(categorized.disposals().filter(ours).cloned());
+        outgoing.extend(categorized.other.iter().filter(ours).cloned());
+        uncategorized.extend(categorized.other.iter().filter(ours).cloned());
+    }
+
+    let Some(signature) = signature else {
+        let matches = expenses::suggest_payments(&expense, &uncategorized, &prices);
+        if matches.is_empty() {
+            println!(
+                "No uncategorized outgoing transfers within a week and 5% of ${:.2} around {}",
+                expense.amount_usd, expense.date
+            );
+            return Ok(());
+        }
+        println!(
+            "Possible payments of expense #{} ({} - ${:.2} on {}):\n",
+            id, expense.vendor, expense.amount_usd, expense.date
+        );
+        println!(
+            "{:<18} {:<12} {:>14} {:>10} {:>5}  To",
+            "Signature", "Date", "SOL", "USD", "Days"
+        );
+        for m in &matches {
+            println!(
+                "{:<18} {:<12} {:>14.4} ${:>9.2} {:>+5}  {}",
+                &m.transfer.signature[..16],
+                m.transfer.date.as_deref().unwrap_or("unknown"),
+                m.transfer.amount_lamports,
+                m.usd_value,
+                m.days_apart,
+                truncate(&m.transfer.to_label, 30),
+            );
+        }
+        println!("\nLink one with 'validator-accounting expense link {} <signature>'", id);
+        return Ok(());
+    };
+
+    if let Some((_, other)) = linked.iter().find(|(s, _)| s.starts_with(signature)) {
+        anyhow::bail!("That transfer already paid expense #{}", other);
+    }
+    let candidates: Vec<_> = outgoing.iter().filter(|t| t.signature.starts_with(signature)).collect();
+    let transfer = match candidates.as_slice() {
+        [transfer] => *transfer,
+        [] => anyhow::bail!(
+            "No cached outgoing transfer from the validator accounts matches '{}' (run a report to fetch transfers)",
+            signature
+        ),
+        _ => anyhow::bail!(
+            "'{}' matches {} transfers; give more of the signature",
+            signature,
+            candidates.len()
+        ),
+    };
+    let date = transfer.date.as_deref().context("Transfer has no date")?;
+    let price = prices
+        .get(date)
+        .map(|p| p.usd)
+        .with_context(|| format!("No cached SOL price for {}; run a report to fetch prices", date))?;
+
+    let previous = expense.amount_usd;
+    expense.link_payment(transfer, price);
+    cache.update_expense(&expense).await?;
+    println!(
+        "Linked expense #{} to {}: {} SOL on {} at ${:.2}/SOL = ${:.2} (was ${:.2})",
+        id,
+        &transfer.signature[..16],
+        transfer.amount_lamports,
+        date,
+        price,
+        expense.amount_usd,
+        previous
+    );
+    Ok(())
+}
+
+/// Options for `expense import-statement`
+struct StatementImport {
+    file: PathBuf,
+    format: String,
+    rules: Option<PathBuf>,
+    dry_run: bool,
+    yes: bool,
+}
+
+/// Preview a statement import, then insert its new charges once confirmed
+async fn import_statement(options: StatementImport, context: &CommandContext<'_>) -> Result<()> {
+    let cache = context.cache;
+    let configured = load_config_file(context.config_path)
+        .map(|file_config| file_config.statement_formats)
+        .unwrap_or_default();
+    let format = statements::find_format(&options.format, &configured)?;
+    let lines = statements::load_statement(&options.file, &format)?;
+
+    let default_rules = context.data_dir.join(constants::STATEMENT_RULES_FILENAME);
+    let mut rules = match options.rules {
+        Some(path) => statements::Rules::load(&path)?,
+        None if default_rules.exists() => statements::Rules::load(&default_rules)?,
+        None => {
+            println!(
+                "No rules file ({} not found); charges are imported as Other under their statement description\n",
+                default_rules.display()
+            );
+            statements::Rules::default()
+        }
+    };
+    rules.resolve_categories(&context.categories().await?)?;
+
+    let existing = cache.get_expenses().await?;
+    let validator = context.expense_validator()?;
+    let planned = statements::plan_import(&lines, &rules, &existing, &format.paid_with, validator.as_deref());
+
+    println!(
+        "{:<12} {:>10}  {:<15} {:<12} {:<14} Statement description",
+        "Date", "Amount", "Vendor", "Category", "Status"
+    );
+    println!("{}", "-".repeat(99));
+    for row in &planned {
+        let status = match (row.status, row.matched) {
+            (statements::LineStatus::New, false) => "new (no rule)",
+            (status, _) => status.as_str(),
+        };
+        println!(
+            "{:<12} ${:>9.2}  {:<15} {:<12} {:<14} {}",
+            row.expense.date,
+            row.line.amount,
+            truncate(&row.expense.vendor, 14),
+            row.expense.category.to_string(),
+            status,
+            truncate(&row.line.description, 30),
+        );
+    }
+    println!("{}", "-".repeat(99));
+
+    let to_insert: Vec<&Expense> = planned
+        .iter()
+        .filter(|row| row.status == statements::LineStatus::New)
+        .map(|row| &row.expense)
+        .collect();
+    let count = |status| planned.iter().filter(|row| row.status == status).count();
+    println!(
+        "{} line(s): {} new, {} duplicate, {} ignored, {} credit",
+        planned.len(),
+        to_insert.len(),
+        count(statements::LineStatus::Duplicate),
+        count(statements::LineStatus::Ignored),
+        count(statements::LineStatus::Credit),
+    );
+
+    if options.dry_run {
+        println!("\nDry run: nothing imported");
+        return Ok(());
+    }
+    if to_insert.is_empty() {
+        println!("\nNothing to import");
+        return Ok(());
+    }
+    if !options.yes && !confirm(&format!("\nImport {} expense(s)?", to_insert.len()))? {
+        println!("Nothing imported");
+        return Ok(());
+    }
+
+    for expense in &to_insert {
+        cache.add_expense(expense).await?;
+    }
+    println!("Imported {} expenses from {}", to_insert.len(), options.file.display());
+    Ok(())
+}
+
+/// Ask a yes/no question on the terminal (default no)
+fn confirm(question: &str) -> Result<bool> {
+    use std::io::Write;
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Handle receipt subcommands
+async fn handle_receipts_command(action: ReceiptsCommand, context: &CommandContext<'_>) -> Result<()> {
+    let cache = context.cache;
+    match action {
+        ReceiptsCommand::Export { year, output } => {
+            let fiscal = context.fiscal_calendar()?;
+            let period = period::ReportPeriod::resolve(fiscal, Some(year), None, None, None)?;
+            let (first, last) = fiscal.year_range(year);
 
-            // Store in cache
-            cache.store_vote_costs(&estimates).await?;
+            // Recorded expenses plus the year's recurring charges, which can't have receipts
+            let mut expenses = cache.get_expenses().await?;
+            let recurring = cache.get_recurring_expenses().await?;
+            expenses.extend(expenses::expand_recurring_expenses(
+                &recurring,
+                &first.format("%Y-%m").to_string(),
+                &last.format("%Y-%m").to_string(),
+            ));
+            expenses.retain(|e| period.contains_str(&e.date));
+            expenses.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+
+            let label = fiscal.year_label(year);
+            let path = output.unwrap_or_else(|| context.output_dir.join(format!("receipts_{}.zip", label)));
+            let attachments = cache.get_attachments().await?;
+            let store = AttachmentStore::new(context.data_dir);
+            let export = attachments::export_receipts(&path, &expenses, &attachments, &store)?;
 
             println!(
-                "Estimated {} epochs at ~{:.3} SOL each:",
-                estimates.len(),
-                vote_costs::TYPICAL_COST_PER_EPOCH_SOL
-            );
-            println!(
-                "Total estimated cost: {:.6} SOL\n",
-                vote_costs::total_vote_costs_sol(&estimates)
+                "Exported {} expense(s) and {} receipt(s) for {} to {}",
+                export.expenses,
+                export.receipts,
+                label,
+                path.display()
             );
-
-            println!("Data cached to database.");
+            if !export.missing.is_empty() {
+                let ids: Vec<String> = export.missing.iter().map(|id| format!("#{}", id)).collect();
+                println!(
+                    "{} expense(s) have no receipt: {}",
+                    export.missing.len(),
+                    ids.join(", ")
+                );
+            }
             Ok(())
         }
     }
 }
 
-/// Handle expense subcommands
-async fn handle_expense_command(action: ExpenseCommand, cache: &Cache) -> Result<()> {
+/// Handle recurring expense subcommands
+async fn handle_recurring_command(action: RecurringCommand, context: &CommandContext<'_>) -> Result<()> {
+    let cache = context.cache;
     match action {
-        ExpenseCommand::List => {
-            let expenses = cache.get_expenses().await?;
-            if expenses.is_empty() {
-                println!("No expenses recorded.");
-                println!("\nUse 'validator-accounting expense add' to add expenses");
-                println!("Or 'validator-accounting expense import <file.csv>' to import from CSV");
+        RecurringCommand::List => {
+            let recurring = cache.get_recurring_expenses().await?;
+            let names = context.validator_names();
+            if recurring.is_empty() {
+                println!("No recurring expenses configured.");
+                println!("\nUse 'validator-accounting recurring add' to add recurring expenses");
             } else {
+                let today = chrono::Local::now().format("%Y-%m-%d").to_string();
                 println!(
-                    "{:<4} {:<12} {:<15} {:<12} {:>10}  Description",
-                    "ID", "Date", "Vendor", "Category", "Amount"
+                    "{:<4} {:<15} {:<12} {:<9} {:>10}  {:<12} {:<10} {:<10} Description",
+                    "ID", "Vendor", "Category", "Cadence", "Amount", "Start", "End", "Validator"
                 );
-                println!("{}", "-".repeat(80));
+                println!("{}", "-".repeat(111));
 
-                let mut total = 0.0;
-                for expense in &expenses {
+                let mut total = Usd::ZERO;
+                let mut adjusted = 0;
+                for expense in &recurring {
                     let id = expense.id.map(|i| i.to_string()).unwrap_or_default();
+                    let end = expense.end_date.as_deref().unwrap_or("ongoing");
+                    let amount = expense.amount_on(&today);
                     println!(
-                        "{:<4} {:<12} {:<15} {:<12} ${:>9.2}  {}",
+                        "{:<4} {:<15} {:<12} {:<9} ${:>9.2}  {:<12} {:<10} {:<10} {}",
                         id,
-                        expense.date,
                         truncate(&expense.vendor, 14),
-                        expense.category,
-                        expense.amount_usd,
-                        truncate(&expense.description, 30),
+                        expense.category.to_string(),
+                        expense.cadence,
+                        amount,
+                        &expense.start_date[..7], // Just show YYYY-MM
+                        if end == "ongoing" {
+                            end.to_string()
+                        } else {
+                            end[..7].to_string()
+                        },
+                        truncate(&expense_validator_label(expense.validator.as_deref(), &names), 10),
+                        truncate(&expense.description, 25),
+                    );
+                    total += amount.mul_div(expense.cadence.per_year(), 12);
+                    if !expense.amount_changes.is_empty() || !expense.overrides.is_empty() {
+                        adjusted += 1;
+                    }
+                }
+                println!("{}", "-".repeat(111));
+                println!("{:>53} ${:>9.2}/month", "Total:", total);
+                println!("\n{} recurring expense(s)", recurring.len());
+                if adjusted > 0 {
+                    println!(
+                        "{} with amount changes or skipped/overridden charges (see `recurring schedule <ID>`)",
+                        adjusted
                     );
-                    total += expense.amount_usd;
                 }
-                println!("{}", "-".repeat(80));
-                println!("{:>54} ${:>9.2}", "Total:", total);
-                println!("\n{} expense(s)", expenses.len());
             }
             Ok(())
         }
 
-        ExpenseCommand::Add {
-            date,
+        RecurringCommand::Add {
             vendor,
             category,
             description,
             amount,
             paid_with,
-            invoice_id,
+            start_date,
+            end_date,
+            cadence,
+            billing_day,
+            prorate,
         } => {
-            let category = parse_category(&category)?;
+            let category = context.categories().await?.resolve(&category)?;
 
-            let expense = Expense {
+            let expense = RecurringExpense {
                 id: None,
-                date,
-                vendor,
+                vendor: vendor.clone(),
                 category,
                 description,
                 amount_usd: amount,
                 paid_with,
-                invoice_id,
+                start_date,
+                end_date,
+                validator: context.expense_validator()?,
+                cadence,
+                billing_day,
+                prorate,
+                amount_changes: Vec::new(),
+                overrides: Vec::new(),
             };
+            expense.validate()?;
 
-            let id = cache.add_expense(&expense).await?;
-            println!("Added expense #{}: {} - ${:.2}", id, expense.vendor, expense.amount_usd);
+            let id = cache.add_recurring_expense(&expense).await?;
+            println!(
+                "Added recurring expense #{}: {} - ${:.2} {}",
+                id, vendor, amount, cadence
+            );
             Ok(())
         }
 
-        ExpenseCommand::Delete { id } => {
-            if cache.delete_expense(id).await? {
-                println!("Deleted expense #{}", id);
+        RecurringCommand::Edit {
+            id,
+            vendor,
+            category,
+            description,
+            amount,
+            paid_with,
+            start_date,
+            end_date,
+            ongoing,
+            shared,
+            cadence,
+            billing_day,
+            start_day_billing,
+            prorate,
+            no_prorate,
+        } => {
+            let Some(mut expense) = cache.get_recurring_expense(id).await? else {
+                anyhow::bail!("Recurring expense #{} not found", id);
+            };
+            let validator = context.edited_validator(shared)?;
+            if [&vendor, &category, &description, &paid_with, &start_date, &end_date]
+                .iter()
+                .all(|field| field.is_none())
+                && amount.is_none()
+                && cadence.is_none()
+                && billing_day.is_none()
+                && !(ongoing || start_day_billing || prorate || no_prorate)
+                && validator.is_none()
+            {
+                anyhow::bail!("Nothing to change; pass the fields to edit (see `recurring edit --help`)");
+            }
+
+            if let Some(vendor) = vendor {
+                expense.vendor = vendor;
+            }
+            if let Some(category) = category {
+                expense.category = context.categories().await?.resolve(&category)?;
+            }
+            if let Some(description) = description {
+                expense.description = description;
+            }
+            if let Some(amount) = amount {
+                expense.amount_usd = amount;
+            }
+            if let Some(paid_with) = paid_with {
+                expense.paid_with = paid_with;
+            }
+            if let Some(start_date) = start_date {
+                expense.start_date = start_date;
+            }
+            if end_date.is_some() || ongoing {
+                expense.end_date = end_date;
+            }
+            if let Some(validator) = validator {
+                expense.validator = validator;
+            }
+            if let Some(cadence) = cadence {
+                expense.cadence = cadence;
+            }
+            if billing_day.is_some() || start_day_billing {
+                expense.billing_day = billing_day;
+            }
+            if prorate || no_prorate {
+                expense.prorate = prorate;
+            }
+            expense.validate()?;
+
+            cache.update_recurring_expense(&expense).await?;
+            println!(
+                "Updated recurring expense #{}: {} - ${:.2} {}",
+                id, expense.vendor, expense.amount_usd, expense.cadence
+            );
+            Ok(())
+        }
+
+        RecurringCommand::SetAmount { id, from, amount } => {
+            let Some(expense) = cache.get_recurring_expense(id).await? else {
+                anyhow::bail!("Recurring expense #{} not found", id);
+            };
+            let effective_date = from.format("%Y-%m-%d").to_string();
+            let previous = expense.amount_on(&effective_date);
+            cache
+                .set_recurring_amount(
+                    id,
+                    &expenses::AmountChange {
+                        effective_date,
+                        amount_usd: amount,
+                    },
+                )
+                .await?;
+            println!(
+                "Recurring expense #{} ({}): ${:.2} -> ${:.2} from {}",
+                id, expense.vendor, previous, amount, from
+            );
+            Ok(())
+        }
+
+        RecurringCommand::RemoveAmount { id, from } => {
+            if cache
+                .remove_recurring_amount(id, &from.format("%Y-%m-%d").to_string())
+                .await?
+            {
+                println!("Removed the amount change from {} of recurring expense #{}", from, id);
             } else {
-                println!("Expense #{} not found", id);
+                println!("Recurring expense #{} has no amount change from {}", id, from);
             }
             Ok(())
         }
 
-        ExpenseCommand::Import { file } => {
-            let expenses = expenses::load_from_csv(&file)?;
-            let count = cache.import_expenses(&expenses).await?;
-            println!("Imported {} expenses from {}", count, file.display());
+        RecurringCommand::Skip { id, date } => set_recurring_override(cache, id, date, None).await,
+
+        RecurringCommand::Override { id, date, amount } => set_recurring_override(cache, id, date, Some(amount)).await,
+
+        RecurringCommand::Restore { id, date } => {
+            if cache
+                .remove_recurring_override(id, &date.format("%Y-%m-%d").to_string())
+                .await?
+            {
+                println!("Restored the {} charge of recurring expense #{}", date, id);
+            } else {
+                println!(
+                    "The {} charge of recurring expense #{} isn't skipped or overridden",
+                    date, id
+                );
+            }
             Ok(())
         }
 
-        ExpenseCommand::Export { file } => {
-            let expenses = cache.get_expenses().await?;
-            expenses::export_to_csv(&expenses, &file)?;
-            println!("Exported {} expenses to {}", expenses.len(), file.display());
+        RecurringCommand::Schedule { id, year } => {
+            let Some(expense) = cache.get_recurring_expense(id).await? else {
+                anyhow::bail!("Recurring expense #{} not found", id);
+            };
+            let fiscal = context.fiscal_calendar()?;
+            let year = year.unwrap_or_else(|| fiscal.year_of(chrono::Local::now().date_naive()));
+            let (first, last) = fiscal.year_range(year);
+            let schedule = expense.schedule(first, last);
+
+            println!(
+                "Recurring expense #{}: {} - {} ({}), {}\n",
+                id,
+                expense.vendor,
+                expense.description,
+                expense.cadence,
+                fiscal.year_label(year)
+            );
+            if schedule.is_empty() {
+                println!("No charges scheduled.");
+                return Ok(());
+            }
+            println!("{:<12} {:>10}  Note", "Date", "Amount");
+            println!("{}", "-".repeat(50));
+            let mut total = Usd::ZERO;
+            for occurrence in &schedule {
+                let note = if occurrence.skipped {
+                    "skipped".to_string()
+                } else if occurrence.overridden {
+                    "overridden".to_string()
+                } else if let Some((days, period)) = occurrence.prorated {
+                    format!("prorated {}/{} days", days, period)
+                } else {
+                    String::new()
+                };
+                let amount = if occurrence.skipped {
+                    "-".to_string()
+                } else {
+                    total += occurrence.amount_usd;
+                    format!("${:.2}", occurrence.amount_usd)
+                };
+                println!("{:<12} {:>10}  {}", occurrence.date, amount, note);
+            }
+            println!("{}", "-".repeat(50));
+            println!("{:<12} ${:>9.2}", "Total", total);
+            Ok(())
+        }
+
+        RecurringCommand::Delete { id } => {
+            if cache.delete_recurring_expense(id).await? {
+                println!("Deleted recurring expense #{}", id);
+            } else {
+                println!("Recurring expense #{} not found", id);
+            }
             Ok(())
         }
+
+        RecurringCommand::History { id } => print_expense_history(cache, ExpenseTable::RecurringExpenses, id).await,
     }
 }
 
-/// Handle recurring expense subcommands
-async fn handle_recurring_command(action: RecurringCommand, cache: &Cache) -> Result<()> {
+/// Handle fixed asset register subcommands
+async fn handle_asset_command(action: AssetCommand, context: &CommandContext<'_>) -> Result<()> {
+    let cache = context.cache;
     match action {
-        RecurringCommand::List => {
-            let recurring = cache.get_recurring_expenses().await?;
-            if recurring.is_empty() {
-                println!("No recurring expenses configured.");
-                println!("\nUse 'validator-accounting recurring add' to add recurring expenses");
-            } else {
+        AssetCommand::List => {
+            let assets = cache.get_assets().await?;
+            if assets.is_empty() {
+                println!("No assets recorded.");
+                println!("\nUse 'validator-accounting asset add' to record capital purchases");
+                return Ok(());
+            }
+            let fiscal = context.fiscal_calendar()?;
+            let today = chrono::Local::now().date_naive();
+            let names = context.validator_names();
+
+            println!(
+                "{:<4} {:<12} {:<15} {:<17} {:>4} {:>11} {:>11}  {:<10} Description",
+                "ID", "Purchased", "Vendor", "Method", "Life", "Cost", "Book value", "Validator"
+            );
+            println!("{}", "-".repeat(115));
+            let (mut cost, mut book_value) = (Usd::ZERO, Usd::ZERO);
+            for asset in &assets {
+                let value = asset.book_value(&fiscal, today);
                 println!(
-                    "{:<4} {:<15} {:<12} {:>10}  {:<12} {:<10} Description",
-                    "ID", "Vendor", "Category", "Amount", "Start", "End"
+                    "{:<4} {:<12} {:<15} {:<17} {:>3}y ${:>10.2} ${:>10.2}  {:<10} {}",
+                    asset.id.map(|i| i.to_string()).unwrap_or_default(),
+                    asset.purchase_date,
+                    truncate(&asset.vendor, 14),
+                    asset.method,
+                    asset.life_years,
+                    asset.cost_usd,
+                    value,
+                    truncate(&expense_validator_label(asset.validator.as_deref(), &names), 10),
+                    truncate(&asset.description, 25),
                 );
-                println!("{}", "-".repeat(90));
-
-                let mut total = 0.0;
-                for expense in &recurring {
-                    let id = expense.id.map(|i| i.to_string()).unwrap_or_default();
-                    let end = expense.end_date.as_deref().unwrap_or("ongoing");
-                    println!(
-                        "{:<4} {:<15} {:<12} ${:>9.2}  {:<12} {:<10} {}",
-                        id,
-                        truncate(&expense.vendor, 14),
-                        expense.category,
-                        expense.amount_usd,
-                        &expense.start_date[..7], // Just show YYYY-MM
-                        if end == "ongoing" {
-                            end.to_string()
-                        } else {
-                            end[..7].to_string()
-                        },
-                        truncate(&expense.description, 25),
-                    );
-                    total += expense.amount_usd;
-                }
-                println!("{}", "-".repeat(90));
-                println!("{:>43} ${:>9.2}/month", "Total:", total);
-                println!("\n{} recurring expense(s)", recurring.len());
+                cost += asset.cost_usd;
+                book_value += value;
             }
+            println!("{}", "-".repeat(115));
+            println!("{:>55} ${:>10.2} ${:>10.2}", "Total:", cost, book_value);
+            println!("\n{} asset(s), book values as of {}", assets.len(), today);
             Ok(())
         }
 
-        RecurringCommand::Add {
+        AssetCommand::Add {
+            description,
             vendor,
             category,
-            description,
-            amount,
+            purchase_date,
+            cost,
+            life,
+            method,
+            salvage,
             paid_with,
-            start_date,
-            end_date,
         } => {
-            let category = parse_category(&category)?;
-
-            let expense = RecurringExpense {
+            let asset = Asset {
                 id: None,
-                vendor: vendor.clone(),
-                category,
-                description,
-                amount_usd: amount,
+                description: description.clone(),
+                vendor,
+                category: context.categories().await?.resolve(&category)?,
+                purchase_date: purchase_date.format("%Y-%m-%d").to_string(),
+                cost_usd: cost,
+                salvage_usd: salvage,
+                life_years: life,
+                method,
                 paid_with,
-                start_date,
-                end_date,
+                validator: context.expense_validator()?,
+            };
+            asset.validate()?;
+
+            let id = cache.add_asset(&asset).await?;
+            println!(
+                "Added asset #{}: {} - ${:.2}, {} over {} years",
+                id, description, cost, method, life
+            );
+            Ok(())
+        }
+
+        AssetCommand::Edit {
+            id,
+            description,
+            vendor,
+            category,
+            purchase_date,
+            cost,
+            life,
+            method,
+            salvage,
+            paid_with,
+            shared,
+        } => {
+            let Some(mut asset) = cache.get_asset(id).await? else {
+                anyhow::bail!("Asset #{} not found", id);
+            };
+            let validator = context.edited_validator(shared)?;
+            if [&description, &vendor, &category, &paid_with]
+                .iter()
+                .all(|field| field.is_none())
+                && purchase_date.is_none()
+                && cost.is_none()
+                && life.is_none()
+                && method.is_none()
+                && salvage.is_none()
+                && validator.is_none()
+            {
+                anyhow::bail!("Nothing to change; pass the fields to edit (see `asset edit --help`)");
+            }
+
+            if let Some(description) = description {
+                asset.description = description;
+            }
+            if let Some(vendor) = vendor {
+                asset.vendor = vendor;
+            }
+            if let Some(category) = category {
+                asset.category = context.categories().await?.resolve(&category)?;
+            }
+            if let Some(purchase_date) = purchase_date {
+                asset.purchase_date = purchase_date.format("%Y-%m-%d").to_string();
+            }
+            if let Some(cost) = cost {
+                asset.cost_usd = cost;
+            }
+            if let Some(life) = life {
+                asset.life_years = life;
+            }
+            if let Some(method) = method {
+                asset.method = method;
+            }
+            if let Some(salvage) = salvage {
+                asset.salvage_usd = salvage;
+            }
+            if let Some(paid_with) = paid_with {
+                asset.paid_with = paid_with;
+            }
+            if let Some(validator) = validator {
+                asset.validator = validator;
+            }
+            asset.validate()?;
+
+            cache.update_asset(&asset).await?;
+            println!(
+                "Updated asset #{}: {} - ${:.2}, {} over {} years",
+                id, asset.description, asset.cost_usd, asset.method, asset.life_years
+            );
+            Ok(())
+        }
+
+        AssetCommand::Schedule { id, year } => {
+            let Some(asset) = cache.get_asset(id).await? else {
+                anyhow::bail!("Asset #{} not found", id);
             };
+            let fiscal = context.fiscal_calendar()?;
+            let schedule = asset.schedule(&fiscal);
+            println!(
+                "Asset #{}: {} - ${:.2}, {} over {} years from {}\n",
+                id, asset.description, asset.cost_usd, asset.method, asset.life_years, asset.purchase_date
+            );
+
+            // One row per fiscal year, or per month of the selected year
+            let mut rows: Vec<(String, Usd, Usd)> = Vec::new();
+            let mut accumulated = Usd::ZERO;
+            for charge in &schedule {
+                accumulated += charge.amount_usd;
+                let label = match year {
+                    Some(year) if fiscal.year_of(charge.date) != year => continue,
+                    Some(_) => charge.date.format("%Y-%m").to_string(),
+                    None => fiscal.year_label(fiscal.year_of(charge.date)),
+                };
+                match rows.last_mut() {
+                    Some((last, amount, book_value)) if *last == label => {
+                        *amount += charge.amount_usd;
+                        *book_value = asset.cost_usd - accumulated;
+                    }
+                    _ => rows.push((label, charge.amount_usd, asset.cost_usd - accumulated)),
+                }
+            }
+            if rows.is_empty() {
+                println!("No depreciation in that year.");
+                return Ok(());
+            }
+
+            let heading = if year.is_some() { "Month" } else { "Year" };
+            println!("{:<10} {:>13} {:>13}", heading, "Depreciation", "Book value");
+            println!("{}", "-".repeat(38));
+            let mut total = Usd::ZERO;
+            for (label, amount, book_value) in &rows {
+                println!("{:<10} ${:>12.2} ${:>12.2}", label, amount, book_value);
+                total += *amount;
+            }
+            println!("{}", "-".repeat(38));
+            println!("{:<10} ${:>12.2}", "Total", total);
+            Ok(())
+        }
+
+        AssetCommand::Delete { id } => {
+            if cache.delete_asset(id).await? {
+                println!("Deleted asset #{}", id);
+            } else {
+                println!("Asset #{} not found", id);
+            }
+            Ok(())
+        }
+
+        AssetCommand::History { id } => print_expense_history(cache, ExpenseTable::Assets, id).await,
+    }
+}
+
+/// Print the category hierarchy with each category's tax line and number of expenses
+async fn print_categories(context: &CommandContext<'_>) -> Result<()> {
+    let categories = context.categories().await?;
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for expense in context.cache.get_expenses().await? {
+        *counts.entry(expense.category.as_str().to_string()).or_default() += 1;
+    }
+
+    println!("{:<32} {:>8}  Tax line", "Category", "Expenses");
+    println!("{}", "-".repeat(100));
+    for category in categories.iter() {
+        let name = category.name.as_str();
+        let depth = name.matches(':').count();
+        let label = name.rsplit(':').next().unwrap_or(name);
+        let tax_line = match &category.tax_line {
+            Some(tax_line) => tax_line.clone(),
+            None => format!(
+                "{} (from {})",
+                categories.tax_line(&category.name).unwrap_or("-"),
+                category.name.parent().map(|p| p.to_string()).unwrap_or_default()
+            ),
+        };
+        println!(
+            "{:<32} {:>8}  {}",
+            format!("{}{}", "  ".repeat(depth), label),
+            counts.get(name).copied().unwrap_or_default(),
+            tax_line
+        );
+    }
+    println!("\nAdd categories and subcategories (Parent:Child) under [categories] in config.toml");
+    Ok(())
+}
+
+/// Skip (no amount) or override one scheduled charge of a recurring expense
+async fn set_recurring_override(cache: &Cache, id: i64, date: chrono::NaiveDate, amount: Option<Usd>) -> Result<()> {
+    let Some(expense) = cache.get_recurring_expense(id).await? else {
+        anyhow::bail!("Recurring expense #{} not found", id);
+    };
+    if expense.schedule(date, date).is_empty() {
+        anyhow::bail!(
+            "Recurring expense #{} has no charge on {} (see `recurring schedule {}`)",
+            id,
+            date,
+            id
+        );
+    }
+
+    cache
+        .set_recurring_override(
+            id,
+            &expenses::OccurrenceOverride {
+                date: date.format("%Y-%m-%d").to_string(),
+                amount_usd: amount,
+            },
+        )
+        .await?;
+    match amount {
+        Some(amount) => println!(
+            "Recorded the {} charge of recurring expense #{} ({}) as ${:.2}",
+            date, id, expense.vendor, amount
+        ),
+        None => println!(
+            "Skipped the {} charge of recurring expense #{} ({})",
+            date, id, expense.vendor
+        ),
+    }
+    Ok(())
+}
 
-            let id = cache.add_recurring_expense(&expense).await?;
-            println!("Added recurring expense #{}: {} - ${:.2}/month", id, vendor, amount);
-            Ok(())
-        }
+/// Print the audit trail of an expense, recurring expense or asset
+async fn print_expense_history(cache: &Cache, table: ExpenseTable, id: i64) -> Result<()> {
+    let history = cache.expense_history(table, id).await?;
+    let kind = match table {
+        ExpenseTable::Expenses => "Expense",
+        ExpenseTable::RecurringExpenses => "Recurring expense",
+        ExpenseTable::Assets => "Asset",
+    };
+    if history.is_empty() {
+        println!("No logged changes for {} #{}", kind.to_lowercase(), id);
+        return Ok(());
+    }
 
-        RecurringCommand::Delete { id } => {
-            if cache.delete_recurring_expense(id).await? {
-                println!("Deleted recurring expense #{}", id);
-            } else {
-                println!("Recurring expense #{} not found", id);
+    println!("{} #{}: {} change(s)\n", kind, id, history.len());
+    for change in &history {
+        match change.subject() {
+            Some(subject) => println!("{} UTC  {} {}", change.changed_at, change.action, subject),
+            None => println!("{} UTC  {}", change.changed_at, change.action),
+        }
+        let fields = change.changed_fields();
+        if fields.is_empty() {
+            println!("  (no field changes)");
+        }
+        for (field, old, new) in fields {
+            match change.action.as_str() {
+                "insert" => println!("  {:<14} {}", field, new),
+                "delete" => println!("  {:<14} {}", field, old),
+                _ => println!("  {:<14} {} -> {}", field, old, new),
             }
-            Ok(())
         }
     }
+    Ok(())
 }
 
 /// Handle Dune Analytics import subcommands
-async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Option<&PathBuf>) -> Result<()> {
+async fn handle_dune_command(action: DuneCommand, context: &CommandContext<'_>) -> Result<()> {
     // Load config to get API key and validator addresses
-    let file_config = load_config_file(config_path)?;
-    let config = config::Config::from_file(&file_config, None)?;
+    let (file_config, configs) = context.load_validators(None).await?;
+    let config = config::select_validator(&configs, context.validator)?;
+    let cache = &context.cache.for_validator(config);
 
     let api_key = file_config
         .api_keys
         .dune
         .ok_or_else(|| anyhow::anyhow!("Dune API key not configured in config.toml"))?;
 
-    let client = dune::DuneClient::new(api_key, &config);
+    let client = sources::LiveAnalytics::new(&api_key);
 
-    println!("Dune Analytics Import");
+    println!("Dune Analytics Import ({})", config.name);
     println!("=====================\n");
 
     match action {
         DuneCommand::Rewards { since } => {
             println!("Importing inflation rewards since {}...\n", since);
 
-            let rewards = client.fetch_inflation_rewards(&since).await?;
+            let rewards = client.inflation_rewards(config, &since).await?;
 
             if rewards.is_empty() {
                 println!("No rewards found.");
@@ -770,15 +3244,15 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
             cache.store_epoch_rewards(&rewards).await?;
 
             println!("\nImported {} epochs:", rewards.len());
-            let mut total_sol = 0.0;
+            let mut total_sol = Lamports::ZERO;
             for reward in &rewards {
                 println!(
                     "  Epoch {}: {:.6} SOL ({})",
                     reward.epoch,
-                    reward.amount_sol,
+                    reward.amount_lamports,
                     reward.date.as_deref().unwrap_or("-")
                 );
-                total_sol += reward.amount_sol;
+                total_sol += reward.amount_lamports;
             }
             println!("\nTotal: {:.6} SOL", total_sol);
             println!("\nData cached to database.");
@@ -787,7 +3261,7 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
         DuneCommand::LeaderFees { since } => {
             println!("Importing leader fees since {}...\n", since);
 
-            let fees = client.fetch_leader_fees(&since).await?;
+            let fees = client.leader_fees(config, &since).await?;
 
             if fees.is_empty() {
                 println!("No leader fees found.");
@@ -797,13 +3271,13 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
             cache.store_leader_fees(&fees).await?;
 
             println!("\nImported {} epochs:", fees.len());
-            let mut total_sol = 0.0;
+            let mut total_sol = Lamports::ZERO;
             for fee in &fees {
                 println!(
                     "  Epoch {}: {} blocks, {:.6} SOL",
-                    fee.epoch, fee.blocks_produced, fee.total_fees_sol
+                    fee.epoch, fee.blocks_produced, fee.total_fees_lamports
                 );
-                total_sol += fee.total_fees_sol;
+                total_sol += fee.total_fees_lamports;
             }
             println!("\nTotal: {:.6} SOL", total_sol);
             println!("\nData cached to database.");
@@ -812,7 +3286,7 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
         DuneCommand::VoteCosts { since } => {
             println!("Importing vote costs since {}...\n", since);
 
-            let costs = client.fetch_vote_costs(&since).await?;
+            let costs = client.vote_costs(config, &since).await?;
 
             if costs.is_empty() {
                 println!("No vote costs found.");
@@ -822,14 +3296,14 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
             cache.store_vote_costs(&costs).await?;
 
             println!("\nImported {} epochs:", costs.len());
-            let mut total_sol = 0.0;
+            let mut total_sol = Lamports::ZERO;
             let mut total_votes = 0u64;
             for cost in &costs {
                 println!(
                     "  Epoch {}: {} votes, {:.6} SOL",
-                    cost.epoch, cost.vote_count, cost.total_fee_sol
+                    cost.epoch, cost.vote_count, cost.total_fee_lamports
                 );
-                total_sol += cost.total_fee_sol;
+                total_sol += cost.total_fee_lamports;
                 total_votes += cost.vote_count;
             }
             println!("\nTotal: {} votes, {:.6} SOL", total_votes, total_sol);
@@ -839,7 +3313,7 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
         DuneCommand::Transfers { since } => {
             println!("Importing SOL transfers since {}...\n", since);
 
-            let transfers = client.fetch_transfers(&since).await?;
+            let transfers = client.transfers(config, &since).await?;
 
             if transfers.is_empty() {
                 println!("No transfers found.");
@@ -853,7 +3327,7 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
             for transfer in transfers.iter().take(10) {
                 println!(
                     "  {} -> {}: {:.6} SOL",
-                    transfer.from_label, transfer.to_label, transfer.amount_sol
+                    transfer.from_label, transfer.to_label, transfer.amount_lamports
                 );
             }
             if transfers.len() > 10 {
@@ -867,13 +3341,13 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
 
             // Rewards
             println!("--- Inflation Rewards ---");
-            let rewards = client.fetch_inflation_rewards(&since).await?;
+            let rewards = client.inflation_rewards(config, &since).await?;
             if !rewards.is_empty() {
                 cache.store_epoch_rewards(&rewards).await?;
                 println!(
                     "  Imported {} epochs, {:.6} SOL total\n",
                     rewards.len(),
-                    rewards.iter().map(|r| r.amount_sol).sum::<f64>()
+                    rewards.iter().map(|r| r.amount_lamports).sum::<Lamports>()
                 );
             } else {
                 println!("  No rewards found\n");
@@ -881,13 +3355,13 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
 
             // Leader fees
             println!("--- Leader Fees ---");
-            let fees = client.fetch_leader_fees(&since).await?;
+            let fees = client.leader_fees(config, &since).await?;
             if !fees.is_empty() {
                 cache.store_leader_fees(&fees).await?;
                 println!(
                     "  Imported {} epochs, {:.6} SOL total\n",
                     fees.len(),
-                    fees.iter().map(|f| f.total_fees_sol).sum::<f64>()
+                    fees.iter().map(|f| f.total_fees_lamports).sum::<Lamports>()
                 );
             } else {
                 println!("  No leader fees found\n");
@@ -895,13 +3369,13 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
 
             // Vote costs
             println!("--- Vote Costs ---");
-            let costs = client.fetch_vote_costs(&since).await?;
+            let costs = client.vote_costs(config, &since).await?;
             if !costs.is_empty() {
                 cache.store_vote_costs(&costs).await?;
                 println!(
                     "  Imported {} epochs, {:.6} SOL total\n",
                     costs.len(),
-                    costs.iter().map(|c| c.total_fee_sol).sum::<f64>()
+                    costs.iter().map(|c| c.total_fee_lamports).sum::<Lamports>()
                 );
             } else {
                 println!("  No vote costs found\n");
@@ -909,7 +3383,7 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
 
             // Transfers
             println!("--- SOL Transfers ---");
-            let transfers = client.fetch_transfers(&since).await?;
+            let transfers = client.transfers(config, &since).await?;
             if !transfers.is_empty() {
                 cache.store_transfers(&transfers, "dune").await?;
                 println!("  Imported {} transfers\n", transfers.len());
@@ -930,26 +3404,33 @@ async fn handle_dune_command(action: DuneCommand, cache: &Cache, config_path: Op
 
 /// Prepare Dune Analytics fallback for epochs that RPC couldn't fetch.
 /// Returns None if no fallback is needed or possible.
-/// Returns Some((client, start_date)) if ready to attempt Dune fetch.
-fn prepare_dune_fallback(
+/// Returns Some((analytics, start_date)) if ready to attempt Dune fetch.
+fn prepare_dune_fallback<'a>(
     rpc_failures: &[u64],
-    dune_api_key: Option<&str>,
     config: &config::Config,
-) -> Option<(dune::DuneClient, String)> {
+    analytics: Option<&'a dyn AnalyticsSource>,
+) -> Option<(&'a dyn AnalyticsSource, String)> {
     if rpc_failures.is_empty() {
         return None;
     }
+    if !config.cluster.has_dune() {
+        eprintln!(
+            "    Warning: {} epochs missing (Dune only indexes mainnet, not {})",
+            rpc_failures.len(),
+            config.cluster
+        );
+        return None;
+    }
 
-    match dune_api_key {
-        Some(api_key) => {
+    match analytics {
+        Some(analytics) => {
             println!(
                 "    RPC failed for {} epochs, falling back to Dune...",
                 rpc_failures.len()
             );
             let earliest_epoch = *rpc_failures.iter().min().unwrap();
             let start_date = transactions::epoch_to_date(earliest_epoch);
-            let client = dune::DuneClient::new(api_key.to_string(), config);
-            Some((client, start_date))
+            Some((analytics, start_date))
         }
         None => {
             eprintln!(
@@ -961,22 +3442,6 @@ fn prepare_dune_fallback(
     }
 }
 
-/// Parse expense category from string
-fn parse_category(s: &str) -> Result<ExpenseCategory> {
-    match s.to_lowercase().as_str() {
-        "hosting" => Ok(ExpenseCategory::Hosting),
-        "contractor" => Ok(ExpenseCategory::Contractor),
-        "hardware" => Ok(ExpenseCategory::Hardware),
-        "software" => Ok(ExpenseCategory::Software),
-        "votefees" | "vote_fees" | "vote-fees" => Ok(ExpenseCategory::VoteFees),
-        "other" => Ok(ExpenseCategory::Other),
-        _ => anyhow::bail!(
-            "Invalid category '{}'. Use: Hosting, Contractor, Hardware, Software, VoteFees, Other",
-            s
-        ),
-    }
-}
-
 /// Truncate string for display
 fn truncate(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
@@ -986,16 +3451,313 @@ fn truncate(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Income data fetched for one validator
+struct ValidatorIncome<'a> {
+    config: &'a config::Config,
+    rewards: Vec<transactions::EpochReward>,
+    transfers: Vec<transactions::SolTransfer>,
+    categorized: transactions::CategorizedTransfers,
+    mev_claims: Vec<jito::MevClaim>,
+    leader_fees: Vec<leader_fees::EpochLeaderFees>,
+    vote_costs: Vec<vote_costs::EpochVoteCost>,
+}
+
 /// Run the main report generation workflow
 async fn run_report_generation(args: Args, cache: Cache) -> Result<()> {
     println!("Block Parliament Validator Financial Tracker");
     println!("=============================================\n");
 
-    // Load config file and initialize runtime configuration
+    // Load config file and initialize runtime configuration for every validator
     let file_config = load_config_file(args.config.as_ref())?;
-    let config = config::Config::from_file(&file_config, args.rpc_url)?;
+    let period = period::ReportPeriod::resolve(
+        period::FiscalCalendar::new(file_config.reporting.fiscal_year_start)?,
+        args.year,
+        args.quarter.as_deref(),
+        args.from,
+        args.to,
+    )?;
+    let mut configs = config::Config::all_from_file(&file_config, args.rpc_url.clone())?;
+    cache.adopt_unscoped_rows(&configs[0].vote_account).await?;
+    cache
+        .record_validator_clusters(configs.iter().map(|c| (c.vote_account, c.cluster)))
+        .await?;
+    let selected_names: Vec<String> = config::select_validators(&configs, args.validator.as_deref())?
+        .iter()
+        .map(|c| c.name.clone())
+        .collect();
+
+    // Data sources (Dune fallback only if an API key is configured)
+    let sources = report_sources(&args, &configs[0])?;
+
+    // Epoch schedules of the selected validators' clusters
+    let selected_configs = configs.iter_mut().filter(|c| selected_names.contains(&c.name));
+    fetch_epoch_schedules(&cache, selected_configs, args.no_cache, &sources).await?;
+    let selected = config::select_validators(&configs, args.validator.as_deref())?;
+
+    // Steps 1-6: on-chain income and vote costs, per validator
+    let mut incomes = Vec::new();
+    for config in &selected {
+        let validator_cache = cache.for_validator(config);
+        incomes.push(fetch_validator_income(&validator_cache, config, &args, &sources).await?);
+    }
+
+    // Step 7: Fetch historical prices (with caching)
+    println!("Fetching historical SOL prices...");
+    let all_rewards: Vec<_> = incomes.iter().flat_map(|i| i.rewards.iter().cloned()).collect();
+    let all_transfers: Vec<_> = incomes.iter().flat_map(|i| i.transfers.iter().cloned()).collect();
+    let price_cache = fetch_prices_with_cache(
+        &cache,
+        &all_rewards,
+        &all_transfers,
+        sources.prices.as_ref(),
+        args.no_cache,
+    )
+    .await?;
+    println!("  Cached {} daily prices\n", price_cache.len());
+
+    // Step 8: Load expenses (database + recurring + Notion contractor hours)
+    println!("Loading expenses...");
+    cache
+        .save_categories(&Categories::from_config(&file_config.categories)?)
+        .await?;
+    let categories = cache.get_categories().await?;
+    let mut all_expenses = cache.get_expenses().await?;
+
+    // Transfers that paid SOL expenses are disposals, not unexplained outflows
+    let payment_signatures: HashSet<&str> = all_expenses.iter().filter_map(|e| e.tx_signature.as_deref()).collect();
+    if !payment_signatures.is_empty() {
+        for income in &mut incomes {
+            income.categorized.separate_expense_payments(&payment_signatures);
+        }
+        let linked = incomes
+            .iter()
+            .map(|i| i.categorized.expense_payments.len())
+            .sum::<usize>();
+        println!("  Matched {} SOL expense payment(s) to their transfers", linked);
+    }
+    expenses::value_sol_payments(&mut all_expenses, &all_transfers, &price_cache);
+
+    // Expand recurring expenses into individual entries for the report period
+    let recurring = cache.get_recurring_expenses().await?;
+    if !recurring.is_empty() {
+        // Determine month range from rewards data
+        let reward_dates = incomes
+            .iter()
+            .flat_map(|income| income.rewards.iter().filter_map(|r| r.date.as_ref()));
+        let first_date = reward_dates.clone().min();
+        let last_date = reward_dates.max();
+
+        if let (Some(start), Some(end)) = (first_date, last_date) {
+            let start_month = &start[..7]; // YYYY-MM
+            let end_month = &end[..7];
+            let expanded = expenses::expand_recurring_expenses(&recurring, start_month, end_month);
+            println!(
+                "  Expanded {} recurring expenses into {} charges",
+                recurring.len(),
+                expanded.len()
+            );
+            all_expenses.extend(expanded);
+        }
+    }
+
+    // Fetch contractor hours from Notion if configured
+    if let Some(notion_config) = &file_config.notion {
+        println!("  Fetching contractor hours from Notion...");
+        match sources.hours.hours_log(notion_config).await {
+            Ok(hours_entries) => {
+                let summary = notion::hours_summary(&hours_entries);
+                println!(
+                    "    Found {} entries: {:.1}h total (${:.2}), {:.1}h unpaid (${:.2})",
+                    summary.total_entries,
+                    summary.total_hours,
+                    summary.total_amount,
+                    summary.unpaid_hours,
+                    summary.unpaid_amount
+                );
+
+                // Convert hours to expenses and add to list
+                let contractor_expenses = notion::hours_to_expenses(&hours_entries);
+                all_expenses.extend(contractor_expenses);
+            }
+            Err(e) => {
+                eprintln!("    Warning: Failed to fetch Notion data: {}", e);
+            }
+        }
+    }
+
+    let total_expense = expenses::total_expenses(&all_expenses);
+    if all_expenses.is_empty() {
+        println!("  No expenses recorded");
+    } else {
+        println!(
+            "  Loaded {} expense entries totaling ${:.2}",
+            all_expenses.len(),
+            total_expense
+        );
+    }
+
+    // Assets are depreciated, and prepaid expenses amortized, through the end
+    // of the last month with rewards (the current month without any)
+    let all_assets = cache.get_assets().await?;
+    let last_reward = incomes
+        .iter()
+        .flat_map(|income| income.rewards.iter().filter_map(|r| r.date.as_deref()))
+        .max()
+        .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let recognized_through = assets::month_end(last_reward);
+    if !all_assets.is_empty() {
+        println!(
+            "  Depreciating {} asset(s) through {}",
+            all_assets.len(),
+            recognized_through
+        );
+    }
+    let prepaid = all_expenses.iter().filter(|e| e.service_period().is_some()).count();
+    if prepaid > 0 && args.basis == reports::Basis::Accrual {
+        println!(
+            "  Amortizing {} prepaid expense(s) over their service periods through {}",
+            prepaid, recognized_through
+        );
+    }
+    println!();
+
+    // Shared expenses are split across every configured validator, even when
+    // reporting on a subset, so each validator's share doesn't depend on --validator
+    let shares: Vec<(String, u32)> = configs
+        .iter()
+        .map(|c| (c.vote_account.to_string(), c.expense_share))
+        .collect();
+    let unknown_validator =
+        |validator: &Option<String>| validator.as_ref().is_some_and(|v| !shares.iter().any(|(s, _)| s == v));
+    let unknown = all_expenses.iter().filter(|e| unknown_validator(&e.validator)).count()
+        + all_assets.iter().filter(|a| unknown_validator(&a.validator)).count();
+    if unknown > 0 {
+        eprintln!(
+            "  Warning: {} expense(s) or asset(s) assigned to validators not in config.toml are excluded",
+            unknown
+        );
+    }
+    let selected_index = |config: &config::Config| {
+        configs
+            .iter()
+            .position(|c| c.vote_account == config.vote_account)
+            .unwrap()
+    };
+    let mut allocated = expenses::allocate_expenses(&all_expenses, &shares);
+    let mut validator_expenses: Vec<Vec<Expense>> = selected
+        .iter()
+        .map(|config| std::mem::take(&mut allocated[selected_index(config)]))
+        .collect();
+
+    // Assets are split like expenses and then depreciated, so each validator's
+    // book values add up with the depreciation it reports
+    let mut allocated = assets::allocate_assets(&all_assets, &shares);
+    let validator_assets: Vec<Vec<Asset>> = selected
+        .iter()
+        .map(|config| std::mem::take(&mut allocated[selected_index(config)]))
+        .collect();
+    for (expenses, assets) in validator_expenses.iter_mut().zip(&validator_assets) {
+        expenses.extend(assets::depreciation_expenses(
+            assets,
+            &period.fiscal,
+            recognized_through,
+        ));
+    }
+    if configs.len() > 1 {
+        for (config, expenses) in selected.iter().zip(&validator_expenses) {
+            println!(
+                "  {}: ${:.2} (share {})",
+                config.name,
+                expenses::total_expenses(expenses),
+                config.expense_share
+            );
+        }
+        println!();
+    }
+
+    // Step 9: Generate reports
+    let lot_selections = cache.get_lot_selections().await?;
+    if let Some(label) = &period.label {
+        println!("Generating reports for {}...", label);
+    } else {
+        println!("Generating reports...");
+    }
+    let report_data: Vec<_> = incomes
+        .iter()
+        .zip(&validator_expenses)
+        .zip(&validator_assets)
+        .map(|((income, expenses), assets)| reports::ReportData {
+            rewards: &income.rewards,
+            categorized: &income.categorized,
+            mev_claims: &income.mev_claims,
+            leader_fees: &income.leader_fees,
+            vote_costs: &income.vote_costs,
+            expenses,
+            prices: &price_cache,
+            config: income.config,
+            lot_method: args.lot_method,
+            lot_selections: &lot_selections,
+            basis: args.basis,
+            categories: &categories,
+            assets,
+            recognized_through,
+        })
+        .collect();
+    for data in &report_data {
+        let output_dir = validator_output_dir(&args.output_dir, data.config, configs.len())?;
+        reports::generate_all_reports(&output_dir, data, &period)?;
+    }
+    if report_data.len() > 1 {
+        reports::generate_consolidated_summary(&args.output_dir, &report_data, &period)?;
+    }
+
+    // Step 10: Print summary
+    for data in &report_data {
+        if configs.len() > 1 {
+            println!("\n{} ({})", data.config.name, data.config.vote_account);
+        }
+        reports::print_summary(data, &period);
+    }
+    if report_data.len() > 1 {
+        reports::print_consolidated_summary(&report_data, &period);
+    }
+
+    println!("\nDone! Reports written to: {}", args.output_dir.display());
+
+    Ok(())
+}
+
+/// Live data sources, wrapped for `--record-fixtures`, or fixtures only for `--replay-fixtures`
+fn report_sources(args: &Args, config: &config::Config) -> Result<Sources> {
+    if let Some(dir) = &args.replay_fixtures {
+        println!("Replaying recorded responses from {}\n", dir.display());
+        return Sources::replay(dir);
+    }
+
+    let sources = Sources::live(config);
+    match &args.record_fixtures {
+        Some(dir) => {
+            println!("Recording responses to {}\n", dir.display());
+            sources.recording(dir)
+        }
+        None => Ok(sources),
+    }
+}
+
+/// Fetch rewards, transfers, MEV, leader fees and vote costs for one validator (steps 1-6)
+async fn fetch_validator_income<'a>(
+    cache: &Cache,
+    config: &'a config::Config,
+    args: &Args,
+    sources: &Sources,
+) -> Result<ValidatorIncome<'a>> {
+    println!("Validator: {}", config.name);
     println!("Vote Account: {}", config.vote_account);
     println!("Identity: {}", config.identity);
+    if config.cluster != cluster::Cluster::MainnetBeta {
+        println!("Cluster: {}", config.cluster);
+    }
     println!("RPC: {}\n", mask_api_key(&config.rpc_url));
 
     // Show cache stats
@@ -1005,38 +3767,38 @@ async fn run_report_generation(args: Args, cache: Cache) -> Result<()> {
     }
 
     // Get current epoch to know what's "complete" vs "in progress"
-    let rpc_client = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
-    let current_epoch = rpc_client.get_epoch_info()?.epoch;
+    let current_epoch = sources.chain.current_epoch(config).await?;
     println!("Current epoch: {}\n", current_epoch);
 
     let start_epoch = args.start_epoch.unwrap_or(config.first_reward_epoch);
     let end_epoch = args.end_epoch.unwrap_or(current_epoch);
 
-    // Get Dune API key for fallback (if configured)
-    let dune_api_key = file_config.api_keys.dune.as_deref();
+    // When each epoch started, so rows land in the right month
+    let calendar = fetch_epoch_calendar(cache, config, start_epoch, current_epoch, args.no_cache, sources).await?;
 
     // Step 1: Fetch inflation rewards by epoch (with caching)
     println!("Fetching inflation rewards...");
-    let rewards = fetch_rewards_with_cache(
-        &cache,
-        &config,
+    let mut rewards = fetch_rewards_with_cache(
+        cache,
+        config,
         start_epoch,
         end_epoch,
         current_epoch,
         args.no_cache,
-        dune_api_key,
+        sources,
     )
     .await?;
+    calendar.apply(&mut rewards);
     println!("  Found {} epochs with rewards\n", rewards.len());
 
     // Step 2: Fetch all SOL transfers to/from our accounts (with caching)
     println!("Loading transaction history...");
     let transfers = fetch_transfers_with_cache(
-        &cache,
-        &config,
+        cache,
+        config,
         args.no_cache,
         args.verbose,
-        dune_api_key,
+        sources,
         &config.bootstrap_date,
     )
     .await?;
@@ -1044,7 +3806,7 @@ async fn run_report_generation(args: Args, cache: Cache) -> Result<()> {
 
     // Step 3: Categorize transfers
     println!("Categorizing transactions...");
-    let categorized = transactions::categorize_transfers(&transfers, &config);
+    let categorized = transactions::categorize_transfers(&transfers, config);
 
     println!("  Initial seeding: {} transfers", categorized.seeding.len());
     println!(
@@ -1058,35 +3820,52 @@ async fn run_report_generation(args: Args, cache: Cache) -> Result<()> {
 
     // Step 4: Fetch Jito MEV claims (with caching)
     println!("Fetching Jito MEV claims...");
-    let mev_claims = fetch_mev_with_cache(&cache, &config, start_epoch, end_epoch, current_epoch, args.no_cache)
+    let mut mev_claims = if config.cluster.has_jito() {
+        fetch_mev_with_cache(
+            cache,
+            config,
+            start_epoch,
+            end_epoch,
+            current_epoch,
+            args.no_cache,
+            sources.mev.as_ref(),
+        )
         .await
         .unwrap_or_else(|e| {
             eprintln!("  Warning: Failed to fetch MEV claims: {}", e);
             Vec::new()
-        });
+        })
+    } else {
+        println!("  Skipped: Jito MEV is only tracked on mainnet");
+        Vec::new()
+    };
+    calendar.apply(&mut mev_claims);
     let total_mev = jito::total_mev_sol(&mev_claims);
+    let total_priority_fees = jito::total_priority_fees_sol(&mev_claims);
     println!(
-        "  Found {} MEV claims totaling {:.6} SOL\n",
+        "  Found {} MEV claims totaling {:.6} SOL (+ {:.6} SOL priority fees)\n",
         mev_claims.len(),
-        total_mev
+        total_mev,
+        total_priority_fees
     );
 
-    // Step 5: Fetch leader slot fees (with caching - this is the slow one!)
+    // Step 5: Fetch leader slot fees (with caching - one getBlock per leader slot)
     println!("Fetching leader slot fees...");
-    let leader_fees = fetch_leader_fees_with_cache(
-        &cache,
-        &config,
+    let mut leader_fees = fetch_leader_fees_with_cache(
+        cache,
+        config,
         start_epoch,
         end_epoch,
         current_epoch,
         args.no_cache,
-        dune_api_key,
+        sources,
     )
     .await
     .unwrap_or_else(|e| {
         eprintln!("  Warning: Failed to fetch leader fees: {}", e);
         Vec::new()
     });
+    calendar.apply(&mut leader_fees);
     let total_leader_fees = leader_fees::total_leader_fees_sol(&leader_fees);
     println!(
         "  Found {} epochs with leader fees totaling {:.6} SOL\n",
@@ -1111,6 +3890,7 @@ async fn run_report_generation(args: Args, cache: Cache) -> Result<()> {
     }
     // Sort by epoch after adding estimates
     vote_costs.sort_by_key(|c| c.epoch);
+    calendar.apply(&mut vote_costs);
 
     if vote_costs.is_empty() {
         println!("  No vote costs cached (use 'vote-costs import' or 'vote-costs estimate' to add)\n");
@@ -1133,97 +3913,135 @@ async fn run_report_generation(args: Args, cache: Cache) -> Result<()> {
         }
     }
 
-    // Step 7: Load expenses (database + recurring + Notion contractor hours)
-    println!("Loading expenses...");
-    let mut all_expenses = cache.get_expenses().await?;
-    let _db_expense_count = all_expenses.len();
-
-    // Expand recurring expenses into individual entries for the report period
-    let recurring = cache.get_recurring_expenses().await?;
-    if !recurring.is_empty() {
-        // Determine month range from rewards data
-        let first_date = rewards.iter().filter_map(|r| r.date.as_ref()).min();
-        let last_date = rewards.iter().filter_map(|r| r.date.as_ref()).max();
+    Ok(ValidatorIncome {
+        config,
+        rewards,
+        transfers,
+        categorized,
+        mev_claims,
+        leader_fees,
+        vote_costs,
+    })
+}
 
-        if let (Some(start), Some(end)) = (first_date, last_date) {
-            let start_month = &start[..7]; // YYYY-MM
-            let end_month = &end[..7];
-            let expanded = expenses::expand_recurring_expenses(&recurring, start_month, end_month);
-            println!(
-                "  Expanded {} recurring expenses into {} monthly entries",
-                recurring.len(),
-                expanded.len()
-            );
-            all_expenses.extend(expanded);
+/// Set each validator's epoch schedule to its cluster's, fetching schedules
+/// missing from the cache (or every one with --no-cache) once per cluster.
+/// A cluster whose schedule can't be fetched keeps its built-in default.
+async fn fetch_epoch_schedules(
+    cache: &Cache,
+    configs: impl Iterator<Item = &mut config::Config>,
+    no_cache: bool,
+    sources: &Sources,
+) -> Result<()> {
+    let mut schedules: HashMap<cluster::Cluster, solana_sdk::epoch_schedule::EpochSchedule> = HashMap::new();
+    for config in configs {
+        if let Some(schedule) = schedules.get(&config.cluster) {
+            config.epoch_schedule = schedule.clone();
+            continue;
         }
+
+        let cached = if no_cache {
+            None
+        } else {
+            cache.get_epoch_schedule(config.cluster).await?
+        };
+        let schedule = match cached {
+            Some(schedule) => schedule,
+            None => match sources.chain.epoch_schedule(config).await {
+                Ok(schedule) => {
+                    cache.store_epoch_schedule(config.cluster, &schedule).await?;
+                    schedule
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Failed to fetch the {} epoch schedule ({}); assuming the default",
+                        config.cluster, e
+                    );
+                    config.epoch_schedule.clone()
+                }
+            },
+        };
+        schedules.insert(config.cluster, schedule.clone());
+        config.epoch_schedule = schedule;
     }
+    Ok(())
+}
 
-    // Fetch contractor hours from Notion if configured
-    if let Some(notion_config) = &file_config.notion {
-        println!("  Fetching contractor hours from Notion...");
-        match notion::fetch_hours_log(notion_config).await {
-            Ok(hours_entries) => {
-                let summary = notion::hours_summary(&hours_entries);
-                println!(
-                    "    Found {} entries: {:.1}h total (${:.2}), {:.1}h unpaid (${:.2})",
-                    summary.total_entries,
-                    summary.total_hours,
-                    summary.total_amount,
-                    summary.unpaid_hours,
-                    summary.unpaid_amount
-                );
+/// Load the epoch calendar, fetching boundaries missing from the cache for
+/// every started epoch from `start_epoch` (the in-progress epoch is
+/// extrapolated, since its rows are provisional anyway)
+async fn fetch_epoch_calendar(
+    cache: &Cache,
+    config: &config::Config,
+    start_epoch: u64,
+    current_epoch: u64,
+    no_cache: bool,
+    sources: &Sources,
+) -> Result<epochs::EpochCalendar> {
+    // Clusters without a known reference epoch anchor the in-progress epoch at
+    // the current time until some boundary is cached
+    let reference = config
+        .cluster
+        .reference_epoch()
+        .unwrap_or((current_epoch, chrono::Utc::now().timestamp()));
+    let calendar_from = |boundaries: &[epochs::EpochBoundary]| {
+        epochs::EpochCalendar::new(boundaries, &config.epoch_schedule, reference)
+    };
 
-                // Convert hours to expenses and add to list
-                let contractor_expenses = notion::hours_to_expenses(&hours_entries);
-                all_expenses.extend(contractor_expenses);
-            }
-            Err(e) => {
-                eprintln!("    Warning: Failed to fetch Notion data: {}", e);
-            }
-        }
+    let cached = cache.get_epoch_boundaries().await?;
+    let calendar = calendar_from(&cached);
+    let missing: Vec<u64> = (start_epoch..current_epoch)
+        .filter(|epoch| no_cache || !calendar.contains(*epoch))
+        .collect();
+    if missing.is_empty() {
+        return Ok(calendar);
     }
 
-    let total_expense = expenses::total_expenses(&all_expenses);
-    if all_expenses.is_empty() {
-        println!("  No expenses recorded\n");
-    } else {
-        println!(
-            "  Loaded {} expense entries totaling ${:.2}\n",
-            all_expenses.len(),
-            total_expense
+    println!("Fetching epoch boundaries...");
+    let fetched = fetch_epoch_boundaries(config, &missing, sources).await;
+    cache.store_epoch_boundaries(&fetched).await?;
+    if fetched.len() < missing.len() {
+        eprintln!(
+            "  Warning: {} epoch(s) have no boundary; their dates are extrapolated",
+            missing.len() - fetched.len()
         );
     }
+    println!("  Cached {} epoch boundaries\n", fetched.len());
 
-    // Step 8: Fetch historical prices (with caching)
-    println!("Fetching historical SOL prices...");
-    let price_cache =
-        fetch_prices_with_cache(&cache, &rewards, &transfers, &config.coingecko_api_key, args.no_cache).await?;
-    println!("  Cached {} daily prices\n", price_cache.len());
+    Ok(calendar_from(&cache.get_epoch_boundaries().await?))
+}
 
-    // Step 9: Generate reports
-    if let Some(year) = args.year {
-        println!("Generating reports for year {}...", year);
-    } else {
-        println!("Generating reports...");
+/// Fetch epoch boundaries one epoch at a time via RPC, falling back to Dune
+/// for epochs the RPC has pruned. Epochs neither can provide are left out.
+async fn fetch_epoch_boundaries(
+    config: &config::Config,
+    epochs: &[u64],
+    sources: &Sources,
+) -> Vec<epochs::EpochBoundary> {
+    let mut boundaries = Vec::new();
+    let mut rpc_failures = Vec::new();
+    for &epoch in epochs {
+        match sources.chain.epoch_boundary(config, epoch).await {
+            Ok(boundary) => boundaries.push(boundary),
+            Err(_) => rpc_failures.push(epoch),
+        }
     }
-    let report_data = reports::ReportData {
-        rewards: &rewards,
-        categorized: &categorized,
-        mev_claims: &mev_claims,
-        leader_fees: &leader_fees,
-        vote_costs: &vote_costs,
-        expenses: &all_expenses,
-        prices: &price_cache,
-        config: &config,
-    };
-    reports::generate_all_reports(&args.output_dir, &report_data, args.year)?;
 
-    // Step 10: Print summary
-    reports::print_summary(&report_data, args.year);
-
-    println!("\nDone! Reports written to: {}", args.output_dir.display());
+    if let Some((analytics, _)) = prepare_dune_fallback(&rpc_failures, config, sources.analytics.as_deref()) {
+        // Start a few epochs early: the extrapolated date can be days off, and
+        // the query must include each epoch's first block
+        let earliest = rpc_failures.iter().min().copied().unwrap_or_default();
+        let since = transactions::epoch_to_date(earliest.saturating_sub(3));
+        match analytics.epoch_boundaries(config, &since).await {
+            Ok(dune_boundaries) => {
+                boundaries.extend(dune_boundaries.into_iter().filter(|b| rpc_failures.contains(&b.epoch)));
+            }
+            Err(e) => eprintln!("    Warning: Dune fallback failed: {}", e),
+        }
+    }
 
-    Ok(())
+    boundaries.sort_by_key(|b| b.epoch);
+    boundaries
 }
 
 /// Fetch rewards with caching - only fetch missing epochs
@@ -1235,11 +4053,14 @@ async fn fetch_rewards_with_cache(
     end_epoch: u64,
     current_epoch: u64,
     no_cache: bool,
-    dune_api_key: Option<&str>,
+    sources: &Sources,
 ) -> Result<Vec<transactions::EpochReward>> {
     if no_cache {
         // Fetch everything fresh
-        let rewards = transactions::fetch_inflation_rewards(config, start_epoch, Some(end_epoch)).await?;
+        let rewards = sources
+            .chain
+            .inflation_rewards(config, start_epoch, Some(end_epoch))
+            .await?;
         cache.store_epoch_rewards(&rewards).await?;
         return Ok(rewards);
     }
@@ -1262,7 +4083,7 @@ async fn fetch_rewards_with_cache(
 
         // Fetch missing epochs one by one via RPC
         for epoch in &missing {
-            match transactions::fetch_inflation_rewards(config, *epoch, Some(*epoch)).await {
+            match sources.chain.inflation_rewards(config, *epoch, Some(*epoch)).await {
                 Ok(mut fetched) if !fetched.is_empty() => {
                     cache.store_epoch_rewards(&fetched).await?;
                     rewards.append(&mut fetched);
@@ -1275,8 +4096,10 @@ async fn fetch_rewards_with_cache(
         }
 
         // Fall back to Dune for epochs that RPC couldn't fetch
-        if let Some((dune_client, start_date)) = prepare_dune_fallback(&rpc_failures, dune_api_key, config) {
-            match dune_client.fetch_inflation_rewards(&start_date).await {
+        if let Some((analytics, start_date)) =
+            prepare_dune_fallback(&rpc_failures, config, sources.analytics.as_deref())
+        {
+            match analytics.inflation_rewards(config, &start_date).await {
                 Ok(dune_rewards) => {
                     // Filter to only the epochs we need
                     let needed: Vec<_> = dune_rewards
@@ -1298,11 +4121,11 @@ async fn fetch_rewards_with_cache(
                             .filter(|e| !filled_epochs.contains(e))
                             .map(|&epoch| transactions::EpochReward {
                                 epoch,
-                                effective_slot: epoch * constants::SLOTS_PER_EPOCH,
-                                amount_lamports: 0,
-                                amount_sol: 0.0,
+                                effective_slot: config.epoch_schedule.get_first_slot_in_epoch(epoch),
+                                amount_lamports: Lamports::ZERO,
                                 commission: config.commission_percent,
                                 date: Some(transactions::epoch_to_date(epoch)),
+                                provenance: Provenance::new(DataSource::Dune),
                             })
                             .collect();
 
@@ -1316,11 +4139,11 @@ async fn fetch_rewards_with_cache(
                             .iter()
                             .map(|&epoch| transactions::EpochReward {
                                 epoch,
-                                effective_slot: epoch * constants::SLOTS_PER_EPOCH,
-                                amount_lamports: 0,
-                                amount_sol: 0.0,
+                                effective_slot: config.epoch_schedule.get_first_slot_in_epoch(epoch),
+                                amount_lamports: Lamports::ZERO,
                                 commission: config.commission_percent,
                                 date: Some(transactions::epoch_to_date(epoch)),
+                                provenance: Provenance::new(DataSource::Dune),
                             })
                             .collect();
                         println!("    Caching {} epochs with no reward data", empty_epochs.len());
@@ -1337,7 +4160,7 @@ async fn fetch_rewards_with_cache(
     // Fetch current epoch if requested (always fresh, don't cache, no Dune fallback)
     // Note: Current epoch rewards are pending until epoch completion
     if end_epoch >= current_epoch
-        && let Ok(mut current_rewards) = transactions::fetch_current_epoch_rewards(config, current_epoch).await
+        && let Ok(mut current_rewards) = sources.chain.current_epoch_rewards(config, current_epoch).await
     {
         rewards.append(&mut current_rewards);
     }
@@ -1363,9 +4186,10 @@ async fn fetch_mev_with_cache(
     end_epoch: u64,
     current_epoch: u64,
     no_cache: bool,
+    mev: &dyn sources::MevSource,
 ) -> Result<Vec<jito::MevClaim>> {
     if no_cache {
-        let claims = jito::fetch_mev_claims(config).await?;
+        let claims = mev.mev_claims(config).await?;
         cache.store_mev_claims(&claims).await?;
         return Ok(claims);
     }
@@ -1389,7 +4213,7 @@ async fn fetch_mev_with_cache(
             "    Fetching from Jito API (need data through epoch {})...",
             completed_end
         );
-        let fresh_claims = jito::fetch_mev_claims(config).await?;
+        let fresh_claims = mev.mev_claims(config).await?;
 
         // Store completed epochs in cache
         let completed: Vec<_> = fresh_claims
@@ -1426,10 +4250,10 @@ async fn fetch_leader_fees_with_cache(
     end_epoch: u64,
     current_epoch: u64,
     no_cache: bool,
-    dune_api_key: Option<&str>,
+    sources: &Sources,
 ) -> Result<Vec<leader_fees::EpochLeaderFees>> {
     if no_cache {
-        let fees = leader_fees::fetch_leader_fees(config, start_epoch, Some(end_epoch)).await?;
+        let fees = sources.chain.leader_fees(config, start_epoch, Some(end_epoch)).await?;
         // Only cache completed epochs
         let completed: Vec<_> = fees.iter().filter(|f| f.epoch < current_epoch).cloned().collect();
         cache.store_leader_fees(&completed).await?;
@@ -1461,15 +4285,21 @@ async fn fetch_leader_fees_with_cache(
 
         let mut rpc_failures: Vec<u64> = Vec::new();
 
+        // Epochs cached with unavailable blocks are retried; if that fails the partial data stays
+        let incomplete: std::collections::HashSet<u64> =
+            fees.iter().filter(|f| !f.is_complete()).map(|f| f.epoch).collect();
+
         // Fetch missing epochs via RPC
         for epoch in &missing {
-            match leader_fees::fetch_leader_fees(config, *epoch, Some(*epoch)).await {
+            match sources.chain.leader_fees(config, *epoch, Some(*epoch)).await {
                 Ok(fetched) if !fetched.is_empty() => {
                     // Store completed epochs in cache
                     let completed: Vec<_> = fetched.iter().filter(|f| f.epoch < current_epoch).cloned().collect();
                     cache.store_leader_fees(&completed).await?;
+                    fees.retain(|f| f.epoch != *epoch);
                     fees.extend(fetched);
                 }
+                _ if incomplete.contains(epoch) => {}
                 _ => {
                     // RPC failed or returned empty - track for Dune fallback
                     rpc_failures.push(*epoch);
@@ -1478,8 +4308,10 @@ async fn fetch_leader_fees_with_cache(
         }
 
         // Fall back to Dune for epochs that RPC couldn't fetch
-        if let Some((dune_client, start_date)) = prepare_dune_fallback(&rpc_failures, dune_api_key, config) {
-            match dune_client.fetch_leader_fees(&start_date).await {
+        if let Some((analytics, start_date)) =
+            prepare_dune_fallback(&rpc_failures, config, sources.analytics.as_deref())
+        {
+            match analytics.leader_fees(config, &start_date).await {
                 Ok(dune_fees) => {
                     let needed: Vec<_> = dune_fees
                         .into_iter()
@@ -1504,9 +4336,10 @@ async fn fetch_leader_fees_with_cache(
                                 leader_slots: 0,
                                 blocks_produced: 0,
                                 skipped_slots: 0,
-                                total_fees_lamports: 0,
-                                total_fees_sol: 0.0,
+                                unavailable_blocks: 0,
+                                total_fees_lamports: Lamports::ZERO,
                                 date: Some(transactions::epoch_to_date(epoch)),
+                                provenance: Provenance::new(DataSource::Dune),
                             })
                             .collect();
 
@@ -1524,9 +4357,10 @@ async fn fetch_leader_fees_with_cache(
                                 leader_slots: 0,
                                 blocks_produced: 0,
                                 skipped_slots: 0,
-                                total_fees_lamports: 0,
-                                total_fees_sol: 0.0,
+                                unavailable_blocks: 0,
+                                total_fees_lamports: Lamports::ZERO,
                                 date: Some(transactions::epoch_to_date(epoch)),
+                                provenance: Provenance::new(DataSource::Dune),
                             })
                             .collect();
                         println!("    Caching {} epochs with no leader data", empty_epochs.len());
@@ -1542,7 +4376,10 @@ async fn fetch_leader_fees_with_cache(
 
     // Fetch current epoch (always fresh, don't cache)
     if need_current
-        && let Ok(current_fees) = leader_fees::fetch_leader_fees(config, current_epoch, Some(current_epoch)).await
+        && let Ok(current_fees) = sources
+            .chain
+            .leader_fees(config, current_epoch, Some(current_epoch))
+            .await
     {
         fees.extend(current_fees);
     }
@@ -1562,11 +4399,11 @@ async fn fetch_prices_with_cache(
     cache: &Cache,
     rewards: &[transactions::EpochReward],
     transfers: &[transactions::SolTransfer],
-    api_key: &str,
+    source: &dyn sources::PriceSource,
     no_cache: bool,
 ) -> Result<prices::PriceCache> {
     if no_cache {
-        let prices = prices::fetch_historical_prices(rewards, transfers, api_key).await?;
+        let prices = prices::fetch_historical_prices(rewards, transfers, source).await?;
         cache.store_prices(&prices).await?;
         return Ok(prices);
     }
@@ -1576,8 +4413,7 @@ async fn fetch_prices_with_cache(
     let cached_count = price_cache.len();
 
     // Fetch only missing prices (skips dates already in cache)
-    let new_prices =
-        prices::fetch_historical_prices_with_cache(rewards, transfers, api_key, Some(&price_cache)).await?;
+    let new_prices = prices::fetch_historical_prices_with_cache(rewards, transfers, source, Some(&price_cache)).await?;
 
     // Merge new prices into cache
     let new_count = new_prices.len();
@@ -1606,12 +4442,13 @@ async fn fetch_transfers_with_cache(
     config: &config::Config,
     no_cache: bool,
     verbose: bool,
-    dune_api_key: Option<&str>,
+    sources: &Sources,
     bootstrap_date: &str,
 ) -> Result<Vec<transactions::SolTransfer>> {
     if no_cache {
         // Fetch everything fresh
-        let transfers = transactions::fetch_sol_transfers(config, verbose).await?;
+        let (transfers, balances) = sources.chain.sol_transfers(config, verbose).await?;
+        cache.store_balance_snapshots(&balances).await?;
 
         // Store by account
         for (label, account) in transactions::get_tracked_accounts(config) {
@@ -1649,12 +4486,17 @@ async fn fetch_transfers_with_cache(
         }
 
         // Fetch new transfers (will stop when hitting already-cached slot)
-        match transactions::fetch_transfers_for_account(config, &account, label, latest_slot, verbose).await {
+        match sources
+            .chain
+            .transfers_for_account(config, account, label, latest_slot, verbose)
+            .await
+        {
             Ok(result) => {
                 // Store progress even if no transfers found (for accounts with only versioned txs)
                 if let Some(highest_slot) = result.highest_slot_seen {
                     cache.set_account_progress(label, highest_slot).await?;
                 }
+                cache.store_balance_snapshots(&result.balances).await?;
 
                 if !result.transfers.is_empty() {
                     if verbose {
@@ -1682,13 +4524,12 @@ async fn fetch_transfers_with_cache(
 
     // Fall back to Dune if RPC failed and we have few/no transfers
     if (rpc_failed || all_transfers.is_empty())
-        && dune_api_key.is_some()
-        && let Some(api_key) = dune_api_key
+        && config.cluster.has_dune()
+        && let Some(analytics) = &sources.analytics
     {
         println!("    Falling back to Dune for transfer history...");
 
-        let dune_client = dune::DuneClient::new(api_key.to_string(), config);
-        match dune_client.fetch_transfers(bootstrap_date).await {
+        match analytics.transfers(config, bootstrap_date).await {
             Ok(dune_transfers) => {
                 // Collect transfers that are actually new (not already seen)
                 let mut dune_new_transfers = Vec::new();
@@ -1721,7 +4562,153 @@ async fn fetch_transfers_with_cache(
     }
 
     // Sort by slot (newest first, matching the original behavior)
-    all_transfers.sort_by(|a, b| b.slot.cmp(&a.slot));
+    all_transfers.sort_by_key(|t| std::cmp::Reverse(t.slot));
 
     Ok(all_transfers)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expenses::ExpenseCategory;
+
+    /// Config, recorded responses and expected reports for the pipeline test
+    const PIPELINE_TESTDATA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/pipeline");
+
+    /// Compare every generated report with its snapshot; set UPDATE_SNAPSHOTS=1 to rewrite them
+    fn assert_snapshots(output_dir: &Path, snapshot_dir: &Path) {
+        let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+        if update {
+            let _ = std::fs::remove_dir_all(snapshot_dir);
+            std::fs::create_dir_all(snapshot_dir).unwrap();
+        }
+
+        let mut generated: Vec<_> = std::fs::read_dir(output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        generated.sort();
+
+        for name in &generated {
+            let actual = std::fs::read_to_string(output_dir.join(name)).unwrap();
+            let snapshot = snapshot_dir.join(name);
+            if update {
+                std::fs::write(&snapshot, &actual).unwrap();
+                continue;
+            }
+            let expected = std::fs::read_to_string(&snapshot)
+                .unwrap_or_else(|_| panic!("No snapshot for {:?}; run with UPDATE_SNAPSHOTS=1", name));
+            assert_eq!(actual, expected, "{:?} differs from its snapshot", name);
+        }
+
+        let snapshot_count = std::fs::read_dir(snapshot_dir).unwrap().count();
+        assert_eq!(generated.len(), snapshot_count, "reports and snapshots differ");
+    }
+
+    /// Replay the pipeline fixtures with extra report arguments and compare
+    /// the reports with the snapshots in `snapshots`
+    async fn replay_pipeline(name: &str, extra_args: &[&str], snapshots: &str) {
+        let testdata = Path::new(PIPELINE_TESTDATA);
+        let work_dir =
+            std::env::temp_dir().join(format!("validator-accounting-pipeline-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&work_dir);
+        let output_dir = work_dir.join("output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+
+        let mut argv: Vec<std::ffi::OsString> = vec![
+            "validator-accounting".into(),
+            "--config".into(),
+            testdata.join("config.toml").into(),
+            "--output-dir".into(),
+            output_dir.clone().into(),
+            "--replay-fixtures".into(),
+            testdata.join("fixtures").into(),
+            "--start-epoch".into(),
+            "902".into(),
+            "--end-epoch".into(),
+            "905".into(),
+        ];
+        argv.extend(extra_args.iter().map(Into::into));
+        let args = Args::parse_from(argv);
+
+        let cache = Cache::open(&work_dir.join(constants::CACHE_FILENAME)).await.unwrap();
+        cache
+            .add_expense(&Expense {
+                id: None,
+                date: "2025-12-30".to_string(),
+                vendor: "Latitude".to_string(),
+                category: ExpenseCategory::HOSTING,
+                description: "Bare metal server".to_string(),
+                amount_usd: Usd::from_cents(150_000),
+                paid_with: "Credit Card".to_string(),
+                invoice_id: Some("INV-1001".to_string()),
+                validator: None,
+                tx_signature: None,
+                amount_lamports: None,
+                paid_date: None,
+                service_start: None,
+                service_end: None,
+                provenance: Provenance::manual(),
+            })
+            .await
+            .unwrap();
+        cache
+            .add_expense(&Expense {
+                id: None,
+                date: "2026-01-05".to_string(),
+                vendor: "Grafana Labs".to_string(),
+                category: ExpenseCategory::SOFTWARE,
+                description: "Annual monitoring".to_string(),
+                amount_usd: Usd::from_dollars(1_200),
+                paid_with: "Credit Card".to_string(),
+                invoice_id: None,
+                validator: None,
+                tx_signature: None,
+                amount_lamports: None,
+                paid_date: None,
+                service_start: Some("2026-01-01".to_string()),
+                service_end: Some("2026-12-31".to_string()),
+                provenance: Provenance::manual(),
+            })
+            .await
+            .unwrap();
+        cache
+            .add_asset(&Asset {
+                id: None,
+                description: "EPYC server".to_string(),
+                vendor: "Dell".to_string(),
+                category: ExpenseCategory::HARDWARE,
+                purchase_date: "2025-12-15".to_string(),
+                cost_usd: Usd::from_dollars(6_000),
+                salvage_usd: Usd::ZERO,
+                life_years: 5,
+                method: assets::DepreciationMethod::StraightLine,
+                paid_with: "USD".to_string(),
+                validator: None,
+            })
+            .await
+            .unwrap();
+
+        run_report_generation(args, cache).await.unwrap();
+
+        assert_snapshots(&output_dir, &testdata.join(snapshots));
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_report_pipeline_replays_fixtures() {
+        replay_pipeline("all", &[], "snapshots").await;
+    }
+
+    #[tokio::test]
+    async fn test_report_pipeline_cash_basis_quarter() {
+        // Revenue is recognized on the 2026-01-03 withdrawal, so the quarter
+        // holds December's revenue too
+        replay_pipeline(
+            "cash",
+            &["--basis", "cash", "--quarter", "2026Q1"],
+            "snapshots-cash-2026q1",
+        )
+        .await;
+    }
+}
@@ -0,0 +1,465 @@
+//! Exact money types: lamport-denominated SOL and fixed-point USD
+//!
+//! Rounding rules used throughout the reports:
+//! - SOL amounts are whole lamports and are never rounded; CSVs print all 9 decimals.
+//! - USD is stored as signed micro-dollars (6 decimals), enough precision for SOL prices.
+//! - The USD value of a SOL amount is rounded to whole cents, half away from zero,
+//!   once per ledger row. Monthly and annual totals are sums of those rounded rows,
+//!   so they always add up to the ledgers exactly.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Lamports per SOL
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Micro-dollars per dollar
+const MICROS_PER_USD: i64 = 1_000_000;
+
+/// Micro-dollars per cent
+const MICROS_PER_CENT: i64 = 10_000;
+
+/// Integer division rounding half away from zero
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder.abs() * 2 >= denominator.abs() {
+        quotient + numerator.signum() * denominator.signum()
+    } else {
+        quotient
+    }
+}
+
+/// Split a non-negative amount into shares proportional to `weights` that sum
+/// exactly to it (largest remainder; ties go to the earlier share)
+fn allocate_units(amount: i128, weights: &[u32]) -> Vec<i128> {
+    let total_weight: i128 = weights.iter().map(|&w| w as i128).sum();
+    if total_weight == 0 {
+        return vec![0; weights.len()];
+    }
+
+    let mut shares: Vec<i128> = weights.iter().map(|&w| amount * w as i128 / total_weight).collect();
+    let mut leftover = amount - shares.iter().sum::<i128>();
+
+    let mut by_remainder: Vec<usize> = (0..weights.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(amount * weights[i] as i128 % total_weight));
+    for i in by_remainder {
+        if leftover == 0 {
+            break;
+        }
+        shares[i] += 1;
+        leftover -= 1;
+    }
+    shares
+}
+
+/// Format a non-negative fixed-point value with `scale` implied decimals, rounded to `places`
+fn format_fixed(value: u128, scale: u32, places: u32) -> String {
+    let places = places.min(scale);
+    let divisor = 10u128.pow(scale - places);
+    let rounded = (value + divisor / 2) / divisor;
+    if places == 0 {
+        return rounded.to_string();
+    }
+    let unit = 10u128.pow(places);
+    format!("{}.{:0width$}", rounded / unit, rounded % unit, width = places as usize)
+}
+
+/// Parse a decimal string into a fixed-point integer with `scale` decimals
+fn parse_fixed(s: &str, scale: u32) -> Result<i128> {
+    let trimmed = s.trim().replace(',', "");
+    let (negative, digits) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.as_str()),
+    };
+    let digits = digits.strip_prefix('$').unwrap_or(digits);
+
+    let (whole, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty() && frac.is_empty() {
+        bail!("Invalid amount: '{}'", s);
+    }
+    if !whole.chars().all(|c| c.is_ascii_digit()) || !frac.chars().all(|c| c.is_ascii_digit()) {
+        bail!("Invalid amount: '{}'", s);
+    }
+    if frac.len() > scale as usize {
+        bail!("Invalid amount: '{}' (at most {} decimal places)", s, scale);
+    }
+
+    let whole: i128 = if whole.is_empty() { 0 } else { whole.parse()? };
+    let frac_value: i128 = if frac.is_empty() {
+        0
+    } else {
+        frac.parse::<i128>()? * 10i128.pow(scale - frac.len() as u32)
+    };
+    let value = whole * 10i128.pow(scale) + frac_value;
+    Ok(if negative { -value } else { value })
+}
+
+// =============================================================================
+// Lamports
+// =============================================================================
+
+/// Exact SOL amount in lamports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Lamports(pub u64);
+
+impl Lamports {
+    pub const ZERO: Lamports = Lamports(0);
+
+    /// Convert a decimal SOL amount from an external source (e.g. Dune), rounding to the nearest lamport
+    pub fn from_sol_f64(sol: f64) -> Self {
+        if !sol.is_finite() || sol <= 0.0 {
+            return Lamports(0);
+        }
+        Lamports((sol * LAMPORTS_PER_SOL as f64).round().min(u64::MAX as f64) as u64)
+    }
+
+    /// USD value at a per-SOL price, rounded to whole cents (the per-row rounding rule)
+    pub fn usd_value(self, price: Usd) -> Usd {
+        let micros = div_round(self.0 as i128 * price.0 as i128, LAMPORTS_PER_SOL as i128);
+        Usd(micros as i64).round_cents()
+    }
+
+    /// Split into shares proportional to `weights` that sum exactly to the amount
+    pub fn allocate(self, weights: &[u32]) -> Vec<Lamports> {
+        allocate_units(self.0 as i128, weights)
+            .into_iter()
+            .map(|share| Lamports(share as u64))
+            .collect()
+    }
+}
+
+impl fmt::Display for Lamports {
+    /// SOL with 9 decimals by default; `{:.N}` rounds half up to N decimals
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let places = f.precision().unwrap_or(9) as u32;
+        f.pad_integral(true, "", &format_fixed(self.0 as u128, 9, places))
+    }
+}
+
+impl FromStr for Lamports {
+    type Err = anyhow::Error;
+
+    /// Parse a decimal SOL amount (e.g. "1.5") into lamports
+    fn from_str(s: &str) -> Result<Self> {
+        let value = parse_fixed(s, 9)?;
+        if value < 0 || value > u64::MAX as i128 {
+            bail!("SOL amount out of range: '{}'", s);
+        }
+        Ok(Lamports(value as u64))
+    }
+}
+
+impl Add for Lamports {
+    type Output = Lamports;
+    fn add(self, rhs: Lamports) -> Lamports {
+        Lamports(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Lamports {
+    fn add_assign(&mut self, rhs: Lamports) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Lamports {
+    type Output = Lamports;
+    fn sub(self, rhs: Lamports) -> Lamports {
+        Lamports(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Lamports {
+    fn sub_assign(&mut self, rhs: Lamports) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sum for Lamports {
+    fn sum<I: Iterator<Item = Lamports>>(iter: I) -> Lamports {
+        iter.fold(Lamports::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Lamports> for Lamports {
+    fn sum<I: Iterator<Item = &'a Lamports>>(iter: I) -> Lamports {
+        iter.copied().sum()
+    }
+}
+
+// =============================================================================
+// Usd
+// =============================================================================
+
+/// Fixed-point USD amount in signed micro-dollars
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Usd(i64);
+
+impl Usd {
+    pub const ZERO: Usd = Usd(0);
+
+    pub const fn from_micros(micros: i64) -> Self {
+        Usd(micros)
+    }
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Usd(cents * MICROS_PER_CENT)
+    }
+
+    pub const fn from_dollars(dollars: i64) -> Self {
+        Usd(dollars * MICROS_PER_USD)
+    }
+
+    /// Convert a float from an external API (e.g. CoinGecko), rounding to the nearest micro-dollar
+    pub fn from_f64(usd: f64) -> Self {
+        if !usd.is_finite() {
+            return Usd::ZERO;
+        }
+        Usd((usd * MICROS_PER_USD as f64).round() as i64)
+    }
+
+    pub const fn micros(self) -> i64 {
+        self.0
+    }
+
+    /// Whole cents, rounded half away from zero
+    pub fn cents(self) -> i64 {
+        div_round(self.0 as i128, MICROS_PER_CENT as i128) as i64
+    }
+
+    /// Round to whole cents, half away from zero
+    pub fn round_cents(self) -> Usd {
+        Usd::from_cents(self.cents())
+    }
+
+    pub const fn abs(self) -> Usd {
+        Usd(self.0.abs())
+    }
+
+    /// Share of this amount in basis points, rounded to whole cents
+    pub fn scale_bps(self, bps: u32) -> Usd {
+        Usd(div_round(self.0 as i128 * bps as i128, 10_000) as i64).round_cents()
+    }
+
+    /// `self * numerator / denominator`, rounded to whole cents (e.g. pro-rata cost basis)
+    pub fn mul_div(self, numerator: u64, denominator: u64) -> Usd {
+        if denominator == 0 {
+            return Usd::ZERO;
+        }
+        let micros = div_round(self.0 as i128 * numerator as i128, denominator as i128);
+        Usd(micros as i64).round_cents()
+    }
+
+    /// Split into whole-cent shares proportional to `weights` that sum exactly to the
+    /// cent-rounded amount (largest remainder; ties go to the earlier share)
+    pub fn allocate(self, weights: &[u32]) -> Vec<Usd> {
+        let cents = self.cents() as i128;
+        allocate_units(cents.abs(), weights)
+            .into_iter()
+            .map(|share| Usd::from_cents((share * cents.signum()) as i64))
+            .collect()
+    }
+
+    /// USD per SOL for an amount, at full micro-dollar precision
+    pub fn per_sol(self, amount: Lamports) -> Usd {
+        if amount.0 == 0 {
+            return Usd::ZERO;
+        }
+        Usd(div_round(self.0 as i128 * LAMPORTS_PER_SOL as i128, amount.0 as i128) as i64)
+    }
+
+    /// Exact decimal string with at least 2 decimals (used for CSV/serde round-trips)
+    fn to_exact_string(self) -> String {
+        let mut digits = format_fixed(self.0.unsigned_abs() as u128, 6, 6);
+        while digits.ends_with('0') && digits.len() - digits.find('.').unwrap_or(0) > 3 {
+            digits.pop();
+        }
+        if self.0 < 0 { format!("-{}", digits) } else { digits }
+    }
+}
+
+impl fmt::Display for Usd {
+    /// Dollars with 2 decimals by default; `{:.N}` rounds half away from zero to N decimals
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let places = f.precision().unwrap_or(2) as u32;
+        let digits = format_fixed(self.0.unsigned_abs() as u128, 6, places);
+        // Avoid printing "-0.00" for amounts that round to zero
+        let nonnegative = self.0 >= 0 || digits.chars().all(|c| c == '0' || c == '.');
+        f.pad_integral(nonnegative, "", &digits)
+    }
+}
+
+impl FromStr for Usd {
+    type Err = anyhow::Error;
+
+    /// Parse "1234.56", "$1,234.56" or "-5" (up to 6 decimals)
+    fn from_str(s: &str) -> Result<Self> {
+        let value = parse_fixed(s, 6)?;
+        if value.abs() > i64::MAX as i128 {
+            bail!("USD amount out of range: '{}'", s);
+        }
+        Ok(Usd(value as i64))
+    }
+}
+
+impl Serialize for Usd {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_exact_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Usd {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct UsdVisitor;
+
+        impl serde::de::Visitor<'_> for UsdVisitor {
+            type Value = Usd;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a decimal USD amount")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> std::result::Result<Usd, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> std::result::Result<Usd, E> {
+                Ok(Usd::from_f64(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> std::result::Result<Usd, E> {
+                Ok(Usd::from_dollars(v))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> std::result::Result<Usd, E> {
+                Ok(Usd::from_dollars(v as i64))
+            }
+        }
+
+        deserializer.deserialize_str(UsdVisitor)
+    }
+}
+
+impl Add for Usd {
+    type Output = Usd;
+    fn add(self, rhs: Usd) -> Usd {
+        Usd(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Usd {
+    fn add_assign(&mut self, rhs: Usd) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Usd {
+    type Output = Usd;
+    fn sub(self, rhs: Usd) -> Usd {
+        Usd(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Usd {
+    fn sub_assign(&mut self, rhs: Usd) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Usd {
+    type Output = Usd;
+    fn neg(self) -> Usd {
+        Usd(-self.0)
+    }
+}
+
+impl Sum for Usd {
+    fn sum<I: Iterator<Item = Usd>>(iter: I) -> Usd {
+        iter.fold(Usd::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Usd> for Usd {
+    fn sum<I: Iterator<Item = &'a Usd>>(iter: I) -> Usd {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lamports_display_is_exact() {
+        assert_eq!(Lamports(1_234_567_891).to_string(), "1.234567891");
+        assert_eq!(Lamports(5_000).to_string(), "0.000005000");
+        assert_eq!(format!("{:.4}", Lamports(1_234_550_000)), "1.2346");
+        assert_eq!(format!("{:>8.2}", Lamports(1_500_000_000)), "    1.50");
+    }
+
+    #[test]
+    fn test_usd_value_rounds_half_away_from_zero() {
+        // 1 SOL at $100.005 = $100.005 -> $100.01
+        let price: Usd = "100.005".parse().unwrap();
+        assert_eq!(Lamports(LAMPORTS_PER_SOL).usd_value(price), Usd::from_cents(10_001));
+        // 0.5 SOL at $0.01 = $0.005 -> $0.01
+        assert_eq!(Lamports(500_000_000).usd_value(Usd::from_cents(1)), Usd::from_cents(1));
+        assert_eq!(Usd::from_micros(-5_000).round_cents(), Usd::from_cents(-1));
+    }
+
+    #[test]
+    fn test_rounded_rows_sum_exactly() {
+        // Three rows that each round up would drift as floats; as cents the total is the row sum
+        let price: Usd = "133.333333".parse().unwrap();
+        let rows: Vec<Usd> = (0..300).map(|_| Lamports(12_345_678).usd_value(price)).collect();
+        let total: Usd = rows.iter().sum();
+        assert_eq!(total, Usd::from_cents(rows[0].cents() * 300));
+    }
+
+    #[test]
+    fn test_usd_parse_and_format() {
+        assert_eq!("$1,234.5".parse::<Usd>().unwrap(), Usd::from_cents(123_450));
+        assert_eq!("-5".parse::<Usd>().unwrap(), Usd::from_dollars(-5));
+        assert!("1.2345678".parse::<Usd>().is_err());
+        assert!("abc".parse::<Usd>().is_err());
+        assert_eq!(Usd::from_cents(-150).to_string(), "-1.50");
+        assert_eq!(Usd::from_micros(-1).to_string(), "0.00");
+        assert_eq!(format!("{:>8}", Usd::from_cents(99)), "    0.99");
+        assert_eq!(Usd::from_micros(1_234_500).to_exact_string(), "1.2345");
+        assert_eq!(Usd::from_dollars(7).to_exact_string(), "7.00");
+    }
+
+    #[test]
+    fn test_allocate_sums_exactly() {
+        let shares = Usd::from_cents(1000).allocate(&[1, 1, 1]);
+        assert_eq!(
+            shares,
+            vec![Usd::from_cents(334), Usd::from_cents(333), Usd::from_cents(333)]
+        );
+
+        let shares = Usd::from_cents(-1001).allocate(&[2, 1]);
+        assert_eq!(shares, vec![Usd::from_cents(-667), Usd::from_cents(-334)]);
+        assert_eq!(shares.iter().sum::<Usd>(), Usd::from_cents(-1001));
+
+        assert_eq!(Usd::from_dollars(5).allocate(&[0, 0]), vec![Usd::ZERO, Usd::ZERO]);
+
+        let shares = Lamports(1_000_000_001).allocate(&[1, 1]);
+        assert_eq!(shares, vec![Lamports(500_000_001), Lamports(500_000_000)]);
+    }
+
+    #[test]
+    fn test_scale_and_mul_div() {
+        assert_eq!(Usd::from_cents(1001).scale_bps(7_500), Usd::from_cents(751));
+        assert_eq!(Usd::from_cents(1000).mul_div(1, 3), Usd::from_cents(333));
+        assert_eq!(
+            Usd::from_dollars(150).per_sol(Lamports(2 * LAMPORTS_PER_SOL)),
+            Usd::from_dollars(75)
+        );
+    }
+}
@@ -4,22 +4,23 @@
 //! to expense records for the P&L report.
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::config::NotionConfig;
 use crate::expenses::{Expense, ExpenseCategory};
+use crate::money::Usd;
+use crate::provenance::{DataSource, Provenance};
 
-const NOTION_API_BASE: &str = "https://api.notion.com/v1";
 const NOTION_VERSION: &str = "2022-06-28";
 
 /// Hours log entry from Notion database
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HoursLogEntry {
     pub page_id: String,
     pub description: String,
     pub date: String,
     pub hours: f64,
-    pub amount_usd: f64,
+    pub amount_usd: Usd,
     pub paid: bool,
 }
 
@@ -104,10 +105,14 @@ enum FormulaValue {
 // API Functions
 // =============================================================================
 
-/// Fetch all hours log entries from Notion
-pub async fn fetch_hours_log(config: &NotionConfig) -> Result<Vec<HoursLogEntry>> {
+/// Fetch all hours log entries from the Notion API at `base_url`
+pub async fn fetch_hours_log(config: &NotionConfig, base_url: &str) -> Result<Vec<HoursLogEntry>> {
     let client = reqwest::Client::new();
-    let url = format!("{}/databases/{}/query", NOTION_API_BASE, config.hours_database_id);
+    let url = format!(
+        "{}/databases/{}/query",
+        base_url.trim_end_matches('/'),
+        config.hours_database_id
+    );
 
     let mut all_entries = Vec::new();
     let mut cursor: Option<String> = None;
@@ -180,10 +185,10 @@ fn parse_page_to_entry(page: &PageResult) -> Option<HoursLogEntry> {
     let amount_usd = match &page.properties.amount_earned.formula {
         FormulaValue::String { string: Some(s) } => {
             // Parse "$45.00" format
-            s.trim_start_matches('$').replace(',', "").parse::<f64>().unwrap_or(0.0)
+            s.parse::<Usd>().unwrap_or(Usd::ZERO)
         }
-        FormulaValue::Number { number: Some(n) } => *n,
-        _ => 0.0,
+        FormulaValue::Number { number: Some(n) } => Usd::from_f64(*n),
+        _ => Usd::ZERO,
     };
 
     if date.is_empty() {
@@ -208,11 +213,18 @@ pub fn hours_to_expenses(entries: &[HoursLogEntry]) -> Vec<Expense> {
             id: None,
             date: entry.date.clone(),
             vendor: "Contractor".to_string(),
-            category: ExpenseCategory::Contractor,
+            category: ExpenseCategory::CONTRACTOR,
             description: format!("{} ({:.1}h)", entry.description, entry.hours),
             amount_usd: entry.amount_usd,
             paid_with: if entry.paid { "Paid" } else { "Unpaid" }.to_string(),
             invoice_id: Some(entry.page_id.clone()),
+            validator: None,
+            tx_signature: None,
+            amount_lamports: None,
+            paid_date: None,
+            service_start: None,
+            service_end: None,
+            provenance: Provenance::new(DataSource::Notion),
         })
         .collect()
 }
@@ -220,9 +232,9 @@ pub fn hours_to_expenses(entries: &[HoursLogEntry]) -> Vec<Expense> {
 /// Get summary statistics for hours log
 pub fn hours_summary(entries: &[HoursLogEntry]) -> HoursSummary {
     let total_hours: f64 = entries.iter().map(|e| e.hours).sum();
-    let total_amount: f64 = entries.iter().map(|e| e.amount_usd).sum();
+    let total_amount: Usd = entries.iter().map(|e| e.amount_usd).sum();
     let unpaid_hours: f64 = entries.iter().filter(|e| !e.paid).map(|e| e.hours).sum();
-    let unpaid_amount: f64 = entries.iter().filter(|e| !e.paid).map(|e| e.amount_usd).sum();
+    let unpaid_amount: Usd = entries.iter().filter(|e| !e.paid).map(|e| e.amount_usd).sum();
 
     HoursSummary {
         total_entries: entries.len(),
@@ -237,9 +249,9 @@ pub fn hours_summary(entries: &[HoursLogEntry]) -> HoursSummary {
 pub struct HoursSummary {
     pub total_entries: usize,
     pub total_hours: f64,
-    pub total_amount: f64,
+    pub total_amount: Usd,
     pub unpaid_hours: f64,
-    pub unpaid_amount: f64,
+    pub unpaid_amount: Usd,
 }
 
 #[cfg(test)]
@@ -253,14 +265,14 @@ mod tests {
             description: "Setup work".to_string(),
             date: "2026-01-15".to_string(),
             hours: 2.5,
-            amount_usd: 37.50,
+            amount_usd: Usd::from_cents(3750),
             paid: false,
         }];
 
         let expenses = hours_to_expenses(&entries);
         assert_eq!(expenses.len(), 1);
-        assert_eq!(expenses[0].category, ExpenseCategory::Contractor);
-        assert_eq!(expenses[0].amount_usd, 37.50);
+        assert_eq!(expenses[0].category, ExpenseCategory::CONTRACTOR);
+        assert_eq!(expenses[0].amount_usd, Usd::from_cents(3750));
         assert!(expenses[0].description.contains("2.5h"));
     }
 }
@@ -0,0 +1,224 @@
+//! Reporting periods: fiscal years, quarters and arbitrary date ranges
+//!
+//! Every generated report is limited to one `ReportPeriod`. Fiscal years start
+//! in the configured `fiscal_year_start` month and are named by the calendar
+//! year they end in; quarters are numbered from the start of the fiscal year.
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+
+/// Fiscal year layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FiscalCalendar {
+    /// Month the fiscal year starts in (1-12)
+    start_month: u32,
+}
+
+impl Default for FiscalCalendar {
+    /// Calendar years
+    fn default() -> Self {
+        Self { start_month: 1 }
+    }
+}
+
+impl FiscalCalendar {
+    pub fn new(start_month: u32) -> Result<Self> {
+        if !(1..=12).contains(&start_month) {
+            anyhow::bail!("fiscal_year_start must be a month number (1-12), got {}", start_month);
+        }
+        Ok(Self { start_month })
+    }
+
+    /// Fiscal year a date falls in
+    pub fn year_of(&self, date: NaiveDate) -> i32 {
+        if self.start_month == 1 || date.month() < self.start_month {
+            date.year()
+        } else {
+            date.year() + 1
+        }
+    }
+
+    /// Fiscal year and quarter (1-4) a date falls in
+    pub fn quarter_of(&self, date: NaiveDate) -> (i32, u32) {
+        let months_in = (date.month() + 12 - self.start_month) % 12;
+        (self.year_of(date), months_in / 3 + 1)
+    }
+
+    /// First and last day of a fiscal year
+    pub fn year_range(&self, year: i32) -> (NaiveDate, NaiveDate) {
+        let first = self.month_start(year, 0);
+        (first, self.month_start(year, 12).pred_opt().unwrap())
+    }
+
+    /// First and last day of a fiscal quarter (1-4)
+    pub fn quarter_range(&self, year: i32, quarter: u32) -> (NaiveDate, NaiveDate) {
+        let offset = (quarter - 1) * 3;
+        let first = self.month_start(year, offset);
+        (first, self.month_start(year, offset + 3).pred_opt().unwrap())
+    }
+
+    /// Display name of a fiscal year: "2026" for calendar years, otherwise "FY2026"
+    pub fn year_label(&self, year: i32) -> String {
+        if self.start_month == 1 {
+            year.to_string()
+        } else {
+            format!("FY{}", year)
+        }
+    }
+
+    /// Display name of a fiscal quarter, in the `--quarter` format ("2026Q1", "FY2026Q1")
+    pub fn quarter_label(&self, year: i32, quarter: u32) -> String {
+        format!("{}Q{}", self.year_label(year), quarter)
+    }
+
+    /// First day of the month `offset` months into a fiscal year
+    fn month_start(&self, year: i32, offset: u32) -> NaiveDate {
+        let first_year = if self.start_month == 1 { year } else { year - 1 };
+        let months = (self.start_month - 1) + offset;
+        NaiveDate::from_ymd_opt(first_year + (months / 12) as i32, months % 12 + 1, 1).unwrap()
+    }
+}
+
+/// Date range every report is limited to (inclusive; open-ended when unset)
+#[derive(Debug, Clone, Default)]
+pub struct ReportPeriod {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Shown in summary headings ("2026", "2026Q1", "2026-01-15 to 2026-02-15")
+    pub label: Option<String>,
+    pub fiscal: FiscalCalendar,
+}
+
+impl ReportPeriod {
+    /// Period selected by `--year`, `--quarter` or `--from`/`--to` (at most one
+    /// of the three forms; clap rejects combinations)
+    pub fn resolve(
+        fiscal: FiscalCalendar,
+        year: Option<i32>,
+        quarter: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Self> {
+        if let Some(year) = year {
+            let (first, last) = fiscal.year_range(year);
+            return Ok(Self::between(fiscal, first, last, fiscal.year_label(year)));
+        }
+        if let Some(quarter) = quarter {
+            let (year, quarter) = parse_quarter(quarter)?;
+            let (first, last) = fiscal.quarter_range(year, quarter);
+            return Ok(Self::between(fiscal, first, last, fiscal.quarter_label(year, quarter)));
+        }
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            anyhow::bail!("--from {} is after --to {}", from, to);
+        }
+        let label = match (from, to) {
+            (Some(from), Some(to)) => Some(format!("{} to {}", from, to)),
+            (Some(from), None) => Some(format!("from {}", from)),
+            (None, Some(to)) => Some(format!("through {}", to)),
+            (None, None) => None,
+        };
+        Ok(Self {
+            from,
+            to,
+            label,
+            fiscal,
+        })
+    }
+
+    fn between(fiscal: FiscalCalendar, from: NaiveDate, to: NaiveDate, label: String) -> Self {
+        Self {
+            from: Some(from),
+            to: Some(to),
+            label: Some(label),
+            fiscal,
+        }
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+
+    /// Whether a date falls in the period
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+
+    /// Whether a YYYY-MM-DD row date falls in the period; undated rows
+    /// ("unknown" or unparsable) are only reported for unbounded periods
+    pub fn contains_str(&self, date: &str) -> bool {
+        match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => self.contains(date),
+            Err(_) => !self.is_bounded(),
+        }
+    }
+
+    /// Whether an optional row date falls in the period
+    pub fn contains_opt(&self, date: Option<&str>) -> bool {
+        date.map_or(!self.is_bounded(), |date| self.contains_str(date))
+    }
+}
+
+/// Parse a `--quarter` value: "2026Q1", "2026-Q1" or "FY2026Q1"
+fn parse_quarter(value: &str) -> Result<(i32, u32)> {
+    let invalid = || format!("Invalid quarter '{}' (expected e.g. 2026Q1)", value);
+    let upper = value.to_ascii_uppercase();
+    let trimmed = upper.strip_prefix("FY").unwrap_or(&upper);
+    let (year, quarter) = trimmed.split_once('Q').with_context(invalid)?;
+    let year: i32 = year.trim_end_matches('-').parse().with_context(invalid)?;
+    let quarter: u32 = quarter.parse().with_context(invalid)?;
+    if !(1..=4).contains(&quarter) {
+        anyhow::bail!(invalid());
+    }
+    Ok((year, quarter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_fiscal_year_starting_in_july() {
+        let fiscal = FiscalCalendar::new(7).unwrap();
+        assert_eq!(fiscal.year_of(date("2025-06-30")), 2025);
+        assert_eq!(fiscal.year_of(date("2025-07-01")), 2026);
+        assert_eq!(fiscal.quarter_of(date("2025-07-01")), (2026, 1));
+        assert_eq!(fiscal.quarter_of(date("2026-01-15")), (2026, 3));
+        assert_eq!(fiscal.quarter_of(date("2026-06-30")), (2026, 4));
+        assert_eq!(fiscal.year_range(2026), (date("2025-07-01"), date("2026-06-30")));
+        assert_eq!(fiscal.quarter_range(2026, 3), (date("2026-01-01"), date("2026-03-31")));
+        assert_eq!(fiscal.quarter_label(2026, 3), "FY2026Q3");
+        assert!(FiscalCalendar::new(13).is_err());
+    }
+
+    #[test]
+    fn test_calendar_year_quarters() {
+        let fiscal = FiscalCalendar::default();
+        assert_eq!(fiscal.quarter_of(date("2025-12-31")), (2025, 4));
+        assert_eq!(fiscal.year_range(2025), (date("2025-01-01"), date("2025-12-31")));
+        assert_eq!(fiscal.quarter_range(2026, 1), (date("2026-01-01"), date("2026-03-31")));
+        assert_eq!(fiscal.quarter_label(2026, 1), "2026Q1");
+    }
+
+    #[test]
+    fn test_resolve_period() {
+        let fiscal = FiscalCalendar::new(10).unwrap();
+        let quarter = ReportPeriod::resolve(fiscal, None, Some("fy2026q1"), None, None).unwrap();
+        assert_eq!(quarter.from, Some(date("2025-10-01")));
+        assert_eq!(quarter.to, Some(date("2025-12-31")));
+        assert_eq!(quarter.label.as_deref(), Some("FY2026Q1"));
+        assert!(ReportPeriod::resolve(fiscal, None, Some("2026Q5"), None, None).is_err());
+
+        let range = ReportPeriod::resolve(fiscal, None, None, Some(date("2026-01-15")), None).unwrap();
+        assert!(range.contains_str("2026-01-15"));
+        assert!(!range.contains_str("2026-01-14"));
+        assert!(!range.contains_str("unknown"));
+        assert!(ReportPeriod::default().contains_opt(None));
+        assert!(ReportPeriod::resolve(fiscal, None, None, Some(date("2026-02-01")), Some(date("2026-01-01"))).is_err());
+    }
+}
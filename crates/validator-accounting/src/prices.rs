@@ -7,11 +7,31 @@ use std::collections::HashMap;
 use std::time::Duration;
 use tokio::time::sleep;
 
+use crate::config::Config;
 use crate::constants;
+use crate::money::Usd;
+use crate::provenance::{DataSource, Provenance};
+use crate::sources::PriceSource;
 use crate::transactions::{EpochReward, SolTransfer};
 
-/// Price cache mapping date strings to USD prices
-pub type PriceCache = HashMap<String, f64>;
+/// Price cache mapping date strings to USD prices per SOL
+pub type PriceCache = HashMap<String, Price>;
+
+/// Daily SOL/USD price and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Price {
+    pub usd: Usd,
+    pub provenance: Provenance,
+}
+
+impl Price {
+    pub fn new(usd: Usd, source: DataSource) -> Self {
+        Self {
+            usd,
+            provenance: Provenance::new(source),
+        }
+    }
+}
 
 /// CoinGecko market chart response
 #[derive(Debug, Deserialize)]
@@ -30,21 +50,39 @@ struct SolanaPrice {
     usd: f64,
 }
 
+/// CoinGecko endpoint and the key header it expects (demo and pro plans differ)
+pub struct CoinGeckoApi {
+    base_url: String,
+    api_key_header: String,
+    api_key: String,
+}
+
+impl CoinGeckoApi {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            base_url: config.endpoints.coingecko.trim_end_matches('/').to_string(),
+            api_key_header: config.endpoints.coingecko_api_key_header.clone(),
+            api_key: config.coingecko_api_key.clone(),
+        }
+    }
+}
+
 /// Fetch historical prices for all dates in rewards and transfers.
 /// If `existing_prices` is provided, skip dates that are already cached.
 pub async fn fetch_historical_prices(
     rewards: &[EpochReward],
     transfers: &[SolTransfer],
-    api_key: &str,
+    source: &dyn PriceSource,
 ) -> Result<PriceCache> {
-    fetch_historical_prices_with_cache(rewards, transfers, api_key, None).await
+    fetch_historical_prices_with_cache(rewards, transfers, source, None).await
 }
 
-/// Fetch historical prices, skipping dates already in `existing_prices`.
+/// Fetch historical prices, skipping dates already in `existing_prices` (fallback
+/// prices are fetched again).
 pub async fn fetch_historical_prices_with_cache(
     rewards: &[EpochReward],
     transfers: &[SolTransfer],
-    api_key: &str,
+    source: &dyn PriceSource,
     existing_prices: Option<&PriceCache>,
 ) -> Result<PriceCache> {
     let mut cache = PriceCache::new();
@@ -57,7 +95,7 @@ pub async fn fetch_historical_prices_with_cache(
             && let Ok(d) = NaiveDate::parse_from_str(date, "%Y-%m-%d")
         {
             // Skip if already in existing cache
-            if existing_prices.is_some_and(|p| p.contains_key(date)) {
+            if existing_prices.is_some_and(|p| has_market_price(p, date)) {
                 continue;
             }
             if !dates.contains(&d) {
@@ -74,7 +112,7 @@ pub async fn fetch_historical_prices_with_cache(
             && let Ok(d) = NaiveDate::parse_from_str(date, "%Y-%m-%d")
         {
             // Skip if already in existing cache
-            if existing_prices.is_some_and(|p| p.contains_key(date)) {
+            if existing_prices.is_some_and(|p| has_market_price(p, date)) {
                 continue;
             }
             if d >= min_valid_date && !dates.contains(&d) {
@@ -86,10 +124,10 @@ pub async fn fetch_historical_prices_with_cache(
     if dates.is_empty() {
         // No dates to fetch, get current price if not cached
         let today = Utc::now().format("%Y-%m-%d").to_string();
-        if existing_prices.is_none_or(|p| !p.contains_key(&today))
-            && let Ok(price) = fetch_current_price(api_key).await
+        if existing_prices.is_none_or(|p| !has_market_price(p, &today))
+            && let Ok(price) = source.current_price().await
         {
-            cache.insert(today, price);
+            cache.insert(today, Price::new(price, DataSource::CoinGecko));
         }
         return Ok(cache);
     }
@@ -102,38 +140,41 @@ pub async fn fetch_historical_prices_with_cache(
     // Fetch historical prices from CoinGecko
     println!("    Fetching prices from {} to {}", min_date, max_date);
 
-    match fetch_price_range(*min_date, *max_date, api_key).await {
+    match source.price_range(*min_date, *max_date).await {
         Ok(prices) => {
             for (date, price) in prices {
-                cache.insert(date, price);
+                cache.insert(date, Price::new(price, DataSource::CoinGecko));
             }
         }
         Err(e) => {
             eprintln!("    ⚠️  WARNING: Failed to fetch historical prices: {}", e);
             eprintln!(
-                "    ⚠️  Using fallback price of ${:.2} for {} dates",
+                "    ⚠️  Using fallback price of ${} for {} dates",
                 constants::FALLBACK_SOL_PRICE,
                 dates.len()
             );
             eprintln!("    ⚠️  Financial reports may be inaccurate!");
             // Use fallback price
             for date in &dates {
-                cache.insert(date.format("%Y-%m-%d").to_string(), constants::FALLBACK_SOL_PRICE);
+                cache.insert(
+                    date.format("%Y-%m-%d").to_string(),
+                    Price::new(constants::FALLBACK_SOL_PRICE, DataSource::Fallback),
+                );
             }
         }
     }
 
     // Ensure current price is available
-    if let Ok(price) = fetch_current_price(api_key).await {
+    if let Ok(price) = source.current_price().await {
         let today = Utc::now().format("%Y-%m-%d").to_string();
-        cache.insert(today, price);
+        cache.insert(today, Price::new(price, DataSource::CoinGecko));
     }
 
     Ok(cache)
 }
 
 /// Fetch price range from CoinGecko
-async fn fetch_price_range(from: NaiveDate, to: NaiveDate, api_key: &str) -> Result<Vec<(String, f64)>> {
+pub async fn fetch_price_range(from: NaiveDate, to: NaiveDate, api: &CoinGeckoApi) -> Result<Vec<(String, Usd)>> {
     let client = reqwest::Client::new();
 
     // Convert dates to Unix timestamps
@@ -147,7 +188,7 @@ async fn fetch_price_range(from: NaiveDate, to: NaiveDate, api_key: &str) -> Res
 
     let url = format!(
         "{}{}&from={}&to={}",
-        constants::COINGECKO_API_BASE,
+        api.base_url,
         constants::COINGECKO_MARKET_CHART,
         from_ts,
         to_ts
@@ -167,7 +208,7 @@ async fn fetch_price_range(from: NaiveDate, to: NaiveDate, api_key: &str) -> Res
         match client
             .get(&url)
             .header("Accept", "application/json")
-            .header("x-cg-demo-api-key", api_key)
+            .header(&api.api_key_header, &api.api_key)
             .send()
             .await
         {
@@ -200,14 +241,14 @@ async fn fetch_price_range(from: NaiveDate, to: NaiveDate, api_key: &str) -> Res
         data.ok_or_else(|| last_error.unwrap_or_else(|| anyhow::anyhow!("Failed after {} retries", max_retries)))?;
 
     // Convert to date -> price map (use daily close price)
-    let mut daily_prices: HashMap<String, f64> = HashMap::new();
+    let mut daily_prices: HashMap<String, Usd> = HashMap::new();
 
     for [timestamp_ms, price] in data.prices {
         let timestamp = timestamp_ms as i64 / 1000;
         if let Some(dt) = chrono::DateTime::from_timestamp(timestamp, 0) {
             let date_str = dt.format("%Y-%m-%d").to_string();
             // Keep the latest price for each day (close price)
-            daily_prices.insert(date_str, price);
+            daily_prices.insert(date_str, Usd::from_f64(price));
         }
     }
 
@@ -215,10 +256,10 @@ async fn fetch_price_range(from: NaiveDate, to: NaiveDate, api_key: &str) -> Res
 }
 
 /// Fetch current SOL price with retry logic
-pub async fn fetch_current_price(api_key: &str) -> Result<f64> {
+pub async fn fetch_current_price(api: &CoinGeckoApi) -> Result<Usd> {
     let client = reqwest::Client::new();
 
-    let url = format!("{}{}", constants::COINGECKO_API_BASE, constants::COINGECKO_SIMPLE_PRICE);
+    let url = format!("{}{}", api.base_url, constants::COINGECKO_SIMPLE_PRICE);
 
     // Retry with exponential backoff
     let max_retries = 3;
@@ -233,7 +274,7 @@ pub async fn fetch_current_price(api_key: &str) -> Result<f64> {
         match client
             .get(&url)
             .header("Accept", "application/json")
-            .header("x-cg-demo-api-key", api_key)
+            .header(&api.api_key_header, &api.api_key)
             .send()
             .await
         {
@@ -243,7 +284,7 @@ pub async fn fetch_current_price(api_key: &str) -> Result<f64> {
                         Ok(data) => {
                             return data
                                 .solana
-                                .map(|s| s.usd)
+                                .map(|s| Usd::from_f64(s.usd))
                                 .ok_or_else(|| anyhow::anyhow!("No SOL price in response"));
                         }
                         Err(e) => {
@@ -266,9 +307,22 @@ pub async fn fetch_current_price(api_key: &str) -> Result<f64> {
     Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Failed after {} retries", max_retries)))
 }
 
+/// Whether a date has a fetched market price (not a fallback)
+fn has_market_price(cache: &PriceCache, date: &str) -> bool {
+    cache
+        .get(date)
+        .is_some_and(|p| p.provenance.source != DataSource::Fallback)
+}
+
+/// Where the price `get_price` returns for a date comes from: the cached row's
+/// source, or `Fallback` when the date isn't cached and a neighbour is used
+pub fn price_source(cache: &PriceCache, date: &str) -> DataSource {
+    cache.get(date).map_or(DataSource::Fallback, |p| p.provenance.source)
+}
+
 /// Get price for a specific date from cache, with fallback
-pub fn get_price(cache: &PriceCache, date: &str) -> f64 {
-    cache.get(date).copied().unwrap_or_else(|| {
+pub fn get_price(cache: &PriceCache, date: &str) -> Usd {
+    cache.get(date).map(|p| p.usd).unwrap_or_else(|| {
         // Try to find closest date
         if let Ok(target) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
             let mut closest_price = constants::FALLBACK_SOL_PRICE;
@@ -279,7 +333,7 @@ pub fn get_price(cache: &PriceCache, date: &str) -> f64 {
                     let diff = (target - cached_date).num_days().abs();
                     if diff < closest_diff {
                         closest_diff = diff;
-                        closest_price = *p;
+                        closest_price = p.usd;
                     }
                 }
             }
@@ -300,4 +354,26 @@ mod tests {
         let cache: PriceCache = Default::default();
         assert!(cache.is_empty());
     }
+
+    #[test]
+    fn test_price_source_marks_neighbour_prices_as_fallback() {
+        use super::*;
+        let cache = PriceCache::from([
+            (
+                "2025-12-01".to_string(),
+                Price::new(Usd::from_dollars(130), DataSource::CoinGecko),
+            ),
+            (
+                "2025-12-02".to_string(),
+                Price::new(constants::FALLBACK_SOL_PRICE, DataSource::Fallback),
+            ),
+        ]);
+        assert_eq!(price_source(&cache, "2025-12-01"), DataSource::CoinGecko);
+        assert_eq!(price_source(&cache, "2025-12-02"), DataSource::Fallback);
+        // Uncached dates borrow the nearest day's price
+        assert_eq!(get_price(&cache, "2025-11-30"), Usd::from_dollars(130));
+        assert_eq!(price_source(&cache, "2025-11-30"), DataSource::Fallback);
+        assert!(has_market_price(&cache, "2025-12-01"));
+        assert!(!has_market_price(&cache, "2025-12-02"));
+    }
 }
@@ -0,0 +1,111 @@
+//! Data provenance: where each cached row came from and when it was fetched
+//!
+//! Every income, expense, transfer and price row carries a `Provenance` so
+//! ledgers can show their source and the summary can separate observed values
+//! from estimates.
+
+use serde::{Deserialize, Serialize};
+
+/// Where a value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataSource {
+    /// Solana RPC
+    Rpc,
+    /// Dune Analytics query
+    Dune,
+    /// Jito kobe API
+    JitoApi,
+    /// CoinGecko market data
+    CoinGecko,
+    /// Notion hours log
+    Notion,
+    /// JSON or CSV file import
+    Import,
+    /// Calculated estimate (e.g. typical vote cost for an epoch)
+    Estimated,
+    /// Fallback or nearest-day price used when no price was fetched for the date
+    Fallback,
+    /// Entered by hand
+    Manual,
+    /// Cached before provenance was recorded
+    Unknown,
+}
+
+impl DataSource {
+    /// Stored and displayed name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DataSource::Rpc => "rpc",
+            DataSource::Dune => "dune",
+            DataSource::JitoApi => "jito_api",
+            DataSource::CoinGecko => "coingecko",
+            DataSource::Notion => "notion",
+            DataSource::Import => "import",
+            DataSource::Estimated => "estimated",
+            DataSource::Fallback => "fallback",
+            DataSource::Manual => "manual",
+            DataSource::Unknown => "unknown",
+        }
+    }
+
+    /// Parse a stored name (unrecognized names are `Unknown`)
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "rpc" => DataSource::Rpc,
+            "dune" => DataSource::Dune,
+            "jito_api" => DataSource::JitoApi,
+            "coingecko" => DataSource::CoinGecko,
+            "notion" => DataSource::Notion,
+            "import" => DataSource::Import,
+            "estimated" => DataSource::Estimated,
+            "fallback" => DataSource::Fallback,
+            "manual" => DataSource::Manual,
+            _ => DataSource::Unknown,
+        }
+    }
+
+    /// Whether the value is an estimate rather than an observation
+    pub fn is_estimated(self) -> bool {
+        matches!(self, DataSource::Estimated | DataSource::Fallback)
+    }
+}
+
+impl std::fmt::Display for DataSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Source and fetch time of a row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub source: DataSource,
+    /// When the row was fetched (UTC, `YYYY-MM-DD HH:MM:SS`); None until it is
+    /// first stored, when the cache records the current time
+    #[serde(skip)]
+    pub fetched_at: Option<String>,
+}
+
+impl Provenance {
+    /// Provenance for a value fetched just now
+    pub fn new(source: DataSource) -> Self {
+        Self {
+            source,
+            fetched_at: None,
+        }
+    }
+
+    /// Provenance loaded from a cached row
+    pub fn cached(source: &str, fetched_at: String) -> Self {
+        Self {
+            source: DataSource::parse(source),
+            fetched_at: Some(fetched_at),
+        }
+    }
+
+    /// Hand-entered data (the serde default for expenses)
+    pub fn manual() -> Self {
+        Self::new(DataSource::Manual)
+    }
+}
@@ -0,0 +1,235 @@
+//! Adaptive rate limiting for JSON-RPC requests
+//!
+//! Requests draw from a token bucket whose refill rate adapts to the provider:
+//! a 429 halves the rate, and each success adds back a small step until the
+//! configured ceiling is reached again (AIMD). Combined with a bounded number
+//! of in-flight requests, this lets bulk fetches such as leader fees run as
+//! fast as the provider allows without tripping its limits.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use crate::config::RpcLimits;
+
+/// Retries for a single throttled request before giving up
+const MAX_THROTTLE_RETRIES: u32 = 6;
+
+/// Successes needed to climb from the minimum rate back to the maximum
+const RECOVERY_STEPS: f64 = 50.0;
+
+/// Token bucket with an adjustable refill rate
+#[derive(Debug)]
+struct TokenBucket {
+    /// Current refill rate (requests per second)
+    rate: f64,
+    min_rate: f64,
+    max_rate: f64,
+    /// Bucket capacity
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limits: &RpcLimits, now: Instant) -> Self {
+        let max_rate = limits.requests_per_second.max(0.1);
+        let burst = limits.burst.max(1) as f64;
+        Self {
+            rate: max_rate,
+            min_rate: limits.min_requests_per_second.clamp(0.1, max_rate),
+            max_rate,
+            burst,
+            tokens: burst,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last_refill = now;
+    }
+
+    /// Take a token, or return how long to wait before one is available
+    fn try_acquire(&mut self, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+
+    /// Multiplicative decrease: halve the rate and drain the bucket
+    fn throttled(&mut self, now: Instant) {
+        self.refill(now);
+        self.rate = (self.rate / 2.0).max(self.min_rate);
+        self.tokens = 0.0;
+    }
+
+    /// Additive increase toward the configured ceiling
+    fn succeeded(&mut self) {
+        let step = (self.max_rate - self.min_rate) / RECOVERY_STEPS;
+        self.rate = (self.rate + step).min(self.max_rate);
+    }
+}
+
+/// Shared limiter for one RPC endpoint
+#[derive(Debug)]
+pub struct RateLimiter {
+    bucket: Mutex<TokenBucket>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: &RpcLimits) -> Self {
+        Self {
+            bucket: Mutex::new(TokenBucket::new(limits, Instant::now())),
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        loop {
+            let wait = self.bucket.lock().unwrap().try_acquire(Instant::now());
+            match wait {
+                None => return,
+                Some(delay) => sleep(delay).await,
+            }
+        }
+    }
+
+    /// Record a rate-limited response
+    pub fn throttled(&self) {
+        self.bucket.lock().unwrap().throttled(Instant::now());
+    }
+
+    /// Record a successful response
+    pub fn succeeded(&self) {
+        self.bucket.lock().unwrap().succeeded();
+    }
+
+    /// Current request rate (requests per second)
+    pub fn current_rate(&self) -> f64 {
+        self.bucket.lock().unwrap().rate
+    }
+}
+
+/// JSON-RPC client that sends every request through a shared `RateLimiter`
+pub struct LimitedRpc {
+    client: reqwest::Client,
+    url: String,
+    limiter: RateLimiter,
+    /// Maximum requests in flight at once
+    pub concurrency: usize,
+}
+
+impl LimitedRpc {
+    pub fn new(url: &str, limits: &RpcLimits) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            limiter: RateLimiter::new(limits),
+            concurrency: limits.concurrency.max(1),
+        }
+    }
+
+    /// Send a JSON-RPC request and decode the full response body, retrying
+    /// throttled requests (HTTP 429) with the limiter's reduced rate
+    pub async fn call<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        for attempt in 0..=MAX_THROTTLE_RETRIES {
+            self.limiter.acquire().await;
+
+            let response = self.client.post(&self.url).json(&body).send().await?;
+            if response.status().as_u16() == 429 {
+                self.limiter.throttled();
+                // Exponential pause on top of the reduced rate, in case the limit is a burst window
+                sleep(Duration::from_millis(250 * 2u64.pow(attempt))).await;
+                continue;
+            }
+
+            let response = response.error_for_status()?;
+            self.limiter.succeeded();
+            return response
+                .json()
+                .await
+                .with_context(|| format!("Failed to decode {} response", method));
+        }
+
+        anyhow::bail!(
+            "{} still rate limited after {} retries ({:.1} req/s)",
+            method,
+            MAX_THROTTLE_RETRIES,
+            self.limiter.current_rate()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(rps: f64, min: f64, burst: u32) -> RpcLimits {
+        RpcLimits {
+            requests_per_second: rps,
+            min_requests_per_second: min,
+            burst,
+            concurrency: 4,
+        }
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_then_paces() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&limits(10.0, 1.0, 2), start);
+
+        assert_eq!(bucket.try_acquire(start), None);
+        assert_eq!(bucket.try_acquire(start), None);
+        let wait = bucket.try_acquire(start).unwrap();
+        assert!((wait.as_secs_f64() - 0.1).abs() < 1e-9);
+
+        // One token refills after 100ms at 10 req/s
+        assert_eq!(bucket.try_acquire(start + Duration::from_millis(100)), None);
+    }
+
+    #[test]
+    fn test_throttle_halves_rate_down_to_minimum() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&limits(20.0, 4.0, 5), start);
+
+        bucket.throttled(start);
+        assert_eq!(bucket.rate, 10.0);
+        assert!(bucket.try_acquire(start).is_some(), "bucket is drained after a 429");
+
+        bucket.throttled(start);
+        bucket.throttled(start);
+        assert_eq!(bucket.rate, 4.0);
+    }
+
+    #[test]
+    fn test_successes_recover_to_maximum() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&limits(20.0, 4.0, 5), start);
+        for _ in 0..5 {
+            bucket.throttled(start);
+        }
+
+        bucket.succeeded();
+        assert!(bucket.rate > 4.0 && bucket.rate < 20.0);
+
+        for _ in 0..RECOVERY_STEPS as usize {
+            bucket.succeeded();
+        }
+        assert_eq!(bucket.rate, 20.0);
+    }
+}
@@ -0,0 +1,494 @@
+//! On-chain balance reconciliation per epoch
+//!
+//! Balance snapshots come from two places: `getBalance` for each account when
+//! `reconcile` runs, and the post-transaction balances of every transaction we
+//! fetch for the transfer history. For each account, the last snapshot in an
+//! epoch is that epoch's closing balance. The change between two closing
+//! balances is compared with the flows we have recorded for the account
+//! (transfers, commission, MEV claims, leader fees, vote fees); anything left
+//! over is an unexplained delta worth investigating before closing a month.
+//!
+//! Leader fees and vote fees accrue throughout an epoch, so they are prorated by
+//! slot when a snapshot falls mid-epoch.
+
+use anyhow::Result;
+use csv::Writer;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::jito::MevClaim;
+use crate::leader_fees::EpochLeaderFees;
+use crate::money::Lamports;
+use crate::transactions::{self, EpochReward, SolTransfer};
+use crate::vote_costs::EpochVoteCost;
+
+/// Where a balance snapshot came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotSource {
+    /// `getBalance` at the RPC's current slot
+    Rpc,
+    /// Post-balance of a fetched transaction
+    Transaction,
+}
+
+impl SnapshotSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotSource::Rpc => "rpc",
+            SnapshotSource::Transaction => "tx",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "rpc" => SnapshotSource::Rpc,
+            _ => SnapshotSource::Transaction,
+        }
+    }
+}
+
+/// Balance of an account at a slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    #[serde(with = "crate::addresses::pubkey_string")]
+    pub account: Pubkey,
+    pub slot: u64,
+    pub lamports: Lamports,
+    pub source: SnapshotSource,
+}
+
+impl BalanceSnapshot {
+    pub fn epoch(&self, schedule: &EpochSchedule) -> u64 {
+        schedule.get_epoch(self.slot)
+    }
+}
+
+/// Recorded data the expected balance changes are built from
+pub struct ReconcileInputs<'a> {
+    pub transfers: &'a [SolTransfer],
+    pub rewards: &'a [EpochReward],
+    pub mev_claims: &'a [MevClaim],
+    pub leader_fees: &'a [EpochLeaderFees],
+    pub vote_costs: &'a [EpochVoteCost],
+}
+
+/// Reconciliation of one account over one epoch (closing balance to closing balance)
+#[derive(Debug, Clone)]
+pub struct ReconcileRow {
+    pub label: &'static str,
+    pub account: Pubkey,
+    pub epoch: u64,
+    pub opening: BalanceSnapshot,
+    pub closing: BalanceSnapshot,
+    /// Signed lamports
+    pub actual_delta: i64,
+    /// Signed lamports
+    pub expected_delta: i64,
+    /// Closing snapshot is in the current (incomplete) epoch
+    pub in_progress: bool,
+}
+
+impl ReconcileRow {
+    /// Change the chain shows that our records don't explain
+    pub fn unexplained(&self) -> i64 {
+        self.actual_delta - self.expected_delta
+    }
+
+    /// Whether the unexplained delta exceeds the tolerance in a completed epoch
+    pub fn needs_review(&self, tolerance: Lamports) -> bool {
+        !self.in_progress && self.unexplained().unsigned_abs() > tolerance.0
+    }
+}
+
+/// When a recorded flow hits the account
+#[derive(Debug, Clone, Copy)]
+enum FlowTiming {
+    /// At a known slot
+    Slot(u64),
+    /// Spread evenly across an epoch's slots
+    Epoch(u64),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Flow {
+    timing: FlowTiming,
+    /// Signed lamports
+    lamports: i64,
+}
+
+/// Accounts to reconcile: vote account, identity, and our tracked accounts
+///
+/// The SFDP reimbursement address is tracked for transfer history but is not
+/// ours; its balance moves with payments to every other validator.
+pub fn reconcile_accounts(config: &Config) -> Vec<(&'static str, Pubkey)> {
+    let mut accounts = vec![("vote_account", config.vote_account), ("identity", config.identity)];
+    for (label, account) in transactions::get_tracked_accounts(config) {
+        if config.is_relevant_account(&account) && !accounts.iter().any(|(_, a)| *a == account) {
+            accounts.push((label, account));
+        }
+    }
+    accounts
+}
+
+/// Snapshot current balances via `getBalance`
+pub fn fetch_balance_snapshots(config: &Config, accounts: &[(&'static str, Pubkey)]) -> Result<Vec<BalanceSnapshot>> {
+    let client = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+
+    let mut snapshots = Vec::new();
+    for (label, account) in accounts {
+        let response = client.get_balance_with_commitment(account, CommitmentConfig::confirmed())?;
+        println!(
+            "    {}: {} SOL at slot {}",
+            label,
+            Lamports(response.value),
+            response.context.slot
+        );
+        snapshots.push(BalanceSnapshot {
+            account: *account,
+            slot: response.context.slot,
+            lamports: Lamports(response.value),
+            source: SnapshotSource::Rpc,
+        });
+    }
+    Ok(snapshots)
+}
+
+/// Reconcile every account for each epoch with two consecutive closing snapshots
+pub fn reconcile(
+    config: &Config,
+    inputs: &ReconcileInputs,
+    snapshots: &[BalanceSnapshot],
+    current_epoch: u64,
+) -> Vec<ReconcileRow> {
+    let mut rows = Vec::new();
+    let schedule = &config.epoch_schedule;
+
+    for (label, account) in reconcile_accounts(config) {
+        let flows = expected_flows(&account, config, inputs);
+        let closings = closing_snapshots(&account, snapshots, schedule);
+
+        for pair in closings.windows(2) {
+            let (opening, closing) = (&pair[0], &pair[1]);
+            rows.push(ReconcileRow {
+                label,
+                account,
+                epoch: closing.epoch(schedule),
+                opening: opening.clone(),
+                closing: closing.clone(),
+                actual_delta: closing.lamports.0 as i64 - opening.lamports.0 as i64,
+                expected_delta: expected_between(&flows, opening.slot, closing.slot, schedule),
+                in_progress: closing.epoch(schedule) >= current_epoch,
+            });
+        }
+    }
+
+    rows
+}
+
+/// Last snapshot in each epoch for an account, oldest first
+fn closing_snapshots(
+    account: &Pubkey,
+    snapshots: &[BalanceSnapshot],
+    schedule: &EpochSchedule,
+) -> Vec<BalanceSnapshot> {
+    let mut own: Vec<&BalanceSnapshot> = snapshots.iter().filter(|s| s.account == *account).collect();
+    own.sort_by_key(|s| s.slot);
+
+    let mut closings: Vec<BalanceSnapshot> = Vec::new();
+    for snapshot in own {
+        match closings.last_mut() {
+            Some(last) if last.epoch(schedule) == snapshot.epoch(schedule) => *last = snapshot.clone(),
+            _ => closings.push(snapshot.clone()),
+        }
+    }
+    closings
+}
+
+/// Every recorded flow into or out of an account
+fn expected_flows(account: &Pubkey, config: &Config, inputs: &ReconcileInputs) -> Vec<Flow> {
+    let mut flows = Vec::new();
+
+    for transfer in inputs.transfers {
+        let lamports = transfer.amount_lamports.0 as i64;
+        if transfer.to == *account {
+            flows.push(Flow {
+                timing: FlowTiming::Slot(transfer.slot),
+                lamports,
+            });
+        }
+        if transfer.from == *account {
+            flows.push(Flow {
+                timing: FlowTiming::Slot(transfer.slot),
+                lamports: -lamports,
+            });
+        }
+    }
+
+    if *account == config.vote_account {
+        for reward in inputs.rewards {
+            flows.push(Flow {
+                timing: FlowTiming::Slot(reward.effective_slot),
+                lamports: reward.amount_lamports.0 as i64,
+            });
+        }
+        // Jito claims an epoch's MEV and priority fee commission into the vote account
+        // during the next epoch (without API data, MEV deposits are already counted as transfers)
+        for claim in inputs.mev_claims {
+            flows.push(Flow {
+                timing: FlowTiming::Epoch(claim.epoch + 1),
+                lamports: (claim.commission_lamports + claim.priority_fee_commission_lamports).0 as i64,
+            });
+        }
+    }
+
+    if *account == config.identity {
+        for fees in inputs.leader_fees {
+            flows.push(Flow {
+                timing: FlowTiming::Epoch(fees.epoch),
+                lamports: fees.total_fees_lamports.0 as i64,
+            });
+        }
+        for cost in inputs.vote_costs {
+            flows.push(Flow {
+                timing: FlowTiming::Epoch(cost.epoch),
+                lamports: -(cost.total_fee_lamports.0 as i64),
+            });
+        }
+    }
+
+    flows
+}
+
+/// Sum of flows after `from_slot` up to and including `to_slot`
+fn expected_between(flows: &[Flow], from_slot: u64, to_slot: u64, schedule: &EpochSchedule) -> i64 {
+    flows
+        .iter()
+        .map(|flow| match flow.timing {
+            FlowTiming::Slot(slot) => {
+                if slot > from_slot && slot <= to_slot {
+                    flow.lamports
+                } else {
+                    0
+                }
+            }
+            FlowTiming::Epoch(epoch) => {
+                // Slots (first, last] of the epoch overlapping (from_slot, to_slot]
+                let first = schedule.get_first_slot_in_epoch(epoch);
+                let last = schedule.get_last_slot_in_epoch(epoch);
+                let start = from_slot.max(first.saturating_sub(1));
+                let end = to_slot.min(last);
+                if end <= start {
+                    0
+                } else {
+                    let slots = schedule.get_slots_in_epoch(epoch);
+                    (flow.lamports as i128 * (end - start) as i128 / slots as i128) as i64
+                }
+            }
+        })
+        .sum()
+}
+
+/// Write reconciliation rows to CSV
+pub fn write_reconciliation_csv(rows: &[ReconcileRow], tolerance: Lamports, path: &Path) -> Result<()> {
+    let mut wtr = Writer::from_path(path)?;
+
+    wtr.write_record([
+        "Account",
+        "Address",
+        "Epoch",
+        "Opening_Slot",
+        "Opening_SOL",
+        "Opening_Source",
+        "Closing_Slot",
+        "Closing_SOL",
+        "Closing_Source",
+        "Actual_Change_SOL",
+        "Expected_Change_SOL",
+        "Unexplained_SOL",
+        "Status",
+    ])?;
+
+    for row in rows {
+        wtr.write_record([
+            row.label,
+            &row.account.to_string(),
+            &row.epoch.to_string(),
+            &row.opening.slot.to_string(),
+            &row.opening.lamports.to_string(),
+            row.opening.source.as_str(),
+            &row.closing.slot.to_string(),
+            &row.closing.lamports.to_string(),
+            row.closing.source.as_str(),
+            &format_signed(row.actual_delta),
+            &format_signed(row.expected_delta),
+            &format_signed(row.unexplained()),
+            status(row, tolerance),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Short status label for a row
+pub fn status(row: &ReconcileRow, tolerance: Lamports) -> &'static str {
+    if row.in_progress {
+        "In progress"
+    } else if row.needs_review(tolerance) {
+        "UNEXPLAINED"
+    } else {
+        "OK"
+    }
+}
+
+/// Signed lamports as SOL with 9 decimals
+pub fn format_signed(lamports: i64) -> String {
+    let amount = Lamports(lamports.unsigned_abs());
+    if lamports < 0 {
+        format!("-{}", amount)
+    } else {
+        amount.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addresses::AddressCategory;
+    use crate::provenance::{DataSource, Provenance};
+
+    const SOL: u64 = 1_000_000_000;
+
+    fn test_config() -> Config {
+        Config {
+            sfdp_acceptance_date: None,
+            bootstrap_date: "2025-12-01".to_string(),
+            ..Config::for_test()
+        }
+    }
+
+    fn snapshot(account: Pubkey, slot: u64, sol: u64) -> BalanceSnapshot {
+        BalanceSnapshot {
+            account,
+            slot,
+            lamports: Lamports(sol * SOL),
+            source: SnapshotSource::Rpc,
+        }
+    }
+
+    fn epoch_end(epoch: u64) -> u64 {
+        EpochSchedule::without_warmup().get_last_slot_in_epoch(epoch)
+    }
+
+    fn empty_inputs<'a>(transfers: &'a [SolTransfer], rewards: &'a [EpochReward]) -> ReconcileInputs<'a> {
+        ReconcileInputs {
+            transfers,
+            rewards,
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &[],
+        }
+    }
+
+    #[test]
+    fn test_vote_account_reward_explains_delta() {
+        let config = test_config();
+        let rewards = vec![EpochReward {
+            epoch: 900,
+            amount_lamports: Lamports(2 * SOL),
+            commission: 5,
+            effective_slot: epoch_end(900) + 10,
+            date: None,
+            provenance: Provenance::new(DataSource::Rpc),
+        }];
+        let snapshots = vec![
+            snapshot(config.vote_account, epoch_end(900), 10),
+            // Superseded by the later snapshot in the same epoch
+            snapshot(config.vote_account, epoch_end(901) - 500, 11),
+            snapshot(config.vote_account, epoch_end(901), 12),
+        ];
+
+        let rows = reconcile(&config, &empty_inputs(&[], &rewards), &snapshots, 950);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].epoch, 901);
+        assert_eq!(rows[0].actual_delta, 2 * SOL as i64);
+        assert_eq!(rows[0].unexplained(), 0);
+        assert_eq!(status(&rows[0], Lamports::ZERO), "OK");
+    }
+
+    #[test]
+    fn test_missing_transfer_is_unexplained() {
+        let config = test_config();
+        let withdrawal = SolTransfer {
+            signature: "sig".to_string(),
+            slot: epoch_end(901) - 100,
+            timestamp: None,
+            date: None,
+            from: config.withdraw_authority,
+            to: config.personal_wallet,
+            amount_lamports: Lamports(3 * SOL),
+            from_label: String::new(),
+            to_label: String::new(),
+            from_category: AddressCategory::ValidatorSelf,
+            to_category: AddressCategory::PersonalWallet,
+            provenance: Provenance::new(DataSource::Rpc),
+        };
+        let snapshots = vec![
+            snapshot(config.withdraw_authority, epoch_end(900), 10),
+            snapshot(config.withdraw_authority, epoch_end(901), 5),
+            snapshot(config.personal_wallet, epoch_end(900), 0),
+            snapshot(config.personal_wallet, epoch_end(901), 3),
+        ];
+
+        let rows = reconcile(
+            &config,
+            &empty_inputs(std::slice::from_ref(&withdrawal), &[]),
+            &snapshots,
+            950,
+        );
+        let authority = rows.iter().find(|r| r.label == "withdraw_authority").unwrap();
+        assert_eq!(authority.expected_delta, -3 * SOL as i64);
+        assert_eq!(authority.unexplained(), -2 * SOL as i64);
+        assert!(authority.needs_review(Lamports(1_000_000)));
+
+        let wallet = rows.iter().find(|r| r.label == "personal_wallet").unwrap();
+        assert_eq!(wallet.unexplained(), 0);
+    }
+
+    #[test]
+    fn test_epoch_accruals_prorated_and_current_epoch_in_progress() {
+        let config = test_config();
+        let vote_costs = vec![EpochVoteCost {
+            epoch: 901,
+            vote_count: 0,
+            total_fee_lamports: Lamports(2 * SOL),
+            date: None,
+            provenance: Provenance::new(DataSource::Rpc),
+        }];
+        let inputs = ReconcileInputs {
+            transfers: &[],
+            rewards: &[],
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &vote_costs,
+        };
+        // Closing snapshot halfway through epoch 901
+        let halfway = epoch_end(900) + EpochSchedule::without_warmup().slots_per_epoch / 2;
+        let snapshots = vec![
+            snapshot(config.identity, epoch_end(900), 10),
+            snapshot(config.identity, halfway, 9),
+        ];
+
+        let rows = reconcile(&config, &inputs, &snapshots, 901);
+        assert_eq!(rows[0].expected_delta, -(SOL as i64));
+        assert_eq!(rows[0].unexplained(), 0);
+        assert!(rows[0].in_progress);
+        assert_eq!(status(&rows[0], Lamports::ZERO), "In progress");
+    }
+}
@@ -1,17 +1,26 @@
 //! Report generation (CSV outputs and console summary)
 
 use anyhow::Result;
+use chrono::NaiveDate;
+use clap::ValueEnum;
 use csv::Writer;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
 
+use crate::assets::Asset;
+use crate::categories::Categories;
 use crate::config::Config;
 use crate::constants;
-use crate::expenses::{Expense, ExpenseCategory};
+use crate::expenses::{self, Expense, ExpenseCategory};
 use crate::jito::MevClaim;
+use crate::journal::{self, JournalInputs};
 use crate::leader_fees::EpochLeaderFees;
-use crate::prices::{PriceCache, get_price};
-use crate::transactions::{CategorizedTransfers, EpochReward};
+use crate::lots::{self, HoldingTerm, LotMethod, LotReport};
+use crate::money::{Lamports, Usd};
+use crate::period::ReportPeriod;
+use crate::prices::{PriceCache, get_price, price_source};
+use crate::provenance::DataSource;
+use crate::transactions::{CategorizedTransfers, EpochReward, SolTransfer};
 use crate::vote_costs::EpochVoteCost;
 
 /// Bundled report data to reduce function argument counts
@@ -21,37 +30,136 @@ pub struct ReportData<'a> {
     pub mev_claims: &'a [MevClaim],
     pub leader_fees: &'a [EpochLeaderFees],
     pub vote_costs: &'a [EpochVoteCost],
+    /// Expenses as recorded (prepaid expenses as paid, see `incurred_expenses`)
     pub expenses: &'a [Expense],
     pub prices: &'a PriceCache,
     pub config: &'a Config,
+    /// Lot selection method for withdrawals
+    pub lot_method: LotMethod,
+    /// Specific-ID lot selections (withdrawal signature -> lot IDs)
+    pub lot_selections: &'a HashMap<String, Vec<String>>,
+    /// When summary revenue and expenses are recognized
+    pub basis: Basis,
+    /// Category hierarchy, for tax lines
+    pub categories: &'a Categories,
+    /// Asset register (with this validator's share of shared assets); their
+    /// depreciation is among `expenses`
+    pub assets: &'a [Asset],
+    /// Last day depreciation and prepaid expenses are recognized through, and
+    /// so the latest date book values are reported as of
+    pub recognized_through: NaiveDate,
 }
 
-/// Generate all CSV reports
-pub fn generate_all_reports(output_dir: &Path, data: &ReportData, year_filter: Option<i32>) -> Result<()> {
-    generate_income_ledger(
-        output_dir,
-        data.rewards,
-        data.categorized,
-        data.mev_claims,
-        data.leader_fees,
-        data.prices,
-    )?;
-    generate_expense_ledger(output_dir, data.expenses, data.vote_costs, data.prices, data.config)?;
-    generate_treasury_ledger(output_dir, data.categorized, data.prices)?;
-    generate_summary(output_dir, data, year_filter)?;
+/// Accounting basis for the summary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Basis {
+    /// Revenue when withdrawn or spent, expenses and SFDP reimbursements when paid
+    Cash,
+    /// Revenue at its epoch, expenses when incurred (with SFDP coverage
+    /// receivable and unpaid expenses payable)
+    #[default]
+    Accrual,
+}
+
+impl std::fmt::Display for Basis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Basis::Cash => write!(f, "cash"),
+            Basis::Accrual => write!(f, "accrual"),
+        }
+    }
+}
+
+impl ReportData<'_> {
+    /// Expenses on the report's basis: prepaid expenses amortized over their
+    /// months of service when accrual, paid amounts on their payment dates
+    /// when cash
+    pub fn incurred_expenses(&self) -> Vec<Expense> {
+        match self.basis {
+            Basis::Accrual => expenses::amortize_prepaid(self.expenses, self.recognized_through),
+            Basis::Cash => expenses::dated_as_paid(self.expenses),
+        }
+    }
+
+    /// Build tax lots from income rows and dispose of them against withdrawals,
+    /// as of the end of the period
+    ///
+    /// Lots and withdrawals before the period still count (they set the basis of
+    /// what remains); only the gains realized within the period are kept.
+    pub fn lot_report(&self, period: &ReportPeriod) -> LotReport {
+        let until = |date: Option<&str>| {
+            period.to.is_none_or(|to| {
+                date.and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                    .is_some_and(|d| d <= to)
+            })
+        };
+        let mut lots = lots::build_lots(
+            self.rewards,
+            self.categorized,
+            self.mev_claims,
+            self.leader_fees,
+            self.prices,
+        );
+        lots.retain(|lot| period.to.is_none_or(|to| lot.acquired <= to));
+        let disposals: Vec<_> = self
+            .categorized
+            .disposals()
+            .filter(|t| until(t.date.as_deref()))
+            .cloned()
+            .collect();
+        let mut report = lots::dispose(lots, &disposals, self.prices, self.lot_method, self.lot_selections);
+        report.gains.retain(|gain| period.contains(gain.disposal_date));
+        report
+    }
+}
+
+/// Generate all CSV reports, limited to the period
+pub fn generate_all_reports(output_dir: &Path, data: &ReportData, period: &ReportPeriod) -> Result<()> {
+    generate_income_ledger(output_dir, data, period)?;
+    generate_expense_ledger(output_dir, data, period)?;
+    generate_treasury_ledger(output_dir, data, period)?;
+    generate_summary(output_dir, data, period)?;
+    generate_fixed_assets(output_dir, data, period)?;
+
+    let lot_report = data.lot_report(period);
+    generate_capital_gains(output_dir, &lot_report, data.lot_method)?;
+    generate_tax_lots(output_dir, &lot_report)?;
+
+    let journal = journal::build_journal(&JournalInputs {
+        rewards: data.rewards,
+        categorized: data.categorized,
+        mev_claims: data.mev_claims,
+        leader_fees: data.leader_fees,
+        vote_costs: data.vote_costs,
+        expenses: data.expenses,
+        assets: data.assets,
+        recognized_through: data.recognized_through,
+        prices: data.prices,
+        config: data.config,
+    });
+    let journal: Vec<_> = journal.into_iter().filter(|tx| period.contains_str(&tx.date)).collect();
+    journal::write_journals(output_dir, &journal)?;
 
     Ok(())
 }
 
 /// Generate income_ledger.csv
-fn generate_income_ledger(
-    output_dir: &Path,
-    rewards: &[EpochReward],
-    categorized: &CategorizedTransfers,
-    mev_claims: &[MevClaim],
-    leader_fees: &[EpochLeaderFees],
-    prices: &PriceCache,
-) -> Result<()> {
+fn generate_income_ledger(output_dir: &Path, data: &ReportData, period: &ReportPeriod) -> Result<()> {
+    let (categorized, mev_claims, prices) = (data.categorized, data.mev_claims, data.prices);
+    let rewards = data.rewards.iter().filter(|r| period.contains_opt(r.date.as_deref()));
+    let mev_deposits = categorized
+        .mev_deposits
+        .iter()
+        .filter(|t| period.contains_opt(t.date.as_deref()));
+    let period_claims: Vec<&MevClaim> = mev_claims
+        .iter()
+        .filter(|c| period.contains_opt(c.date.as_deref()))
+        .collect();
+    let leader_fees = data
+        .leader_fees
+        .iter()
+        .filter(|f| period.contains_opt(f.date.as_deref()));
+
     let path = output_dir.join(constants::INCOME_LEDGER_FILENAME);
     let mut wtr = Writer::from_path(&path)?;
 
@@ -67,13 +175,14 @@ fn generate_income_ledger(
         "USD_Value",
         "Tx_Signature",
         "Notes",
+        "Data_Source",
     ])?;
 
     // Commission rewards
     for reward in rewards {
         let date = reward.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = reward.amount_sol * price;
+        let usd_value = reward.amount_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -81,11 +190,12 @@ fn generate_income_ledger(
             "Commission",
             "Vote Account",
             "Inflation Reward",
-            &format!("{:.6}", reward.amount_sol),
-            &format!("{:.2}", price),
-            &format!("{:.2}", usd_value),
+            &reward.amount_lamports.to_string(),
+            &price.to_string(),
+            &usd_value.to_string(),
             &format!("epoch-{}", reward.epoch),
             &format!("{}% commission on delegator rewards", reward.commission),
+            &data_source(reward.provenance.source, prices, date),
         ])?;
     }
 
@@ -97,7 +207,7 @@ fn generate_income_ledger(
 
     // MEV deposits (from transfer detection) - only when no Jito API data
     // These are fallback data when Jito API doesn't have epoch info
-    for transfer in &categorized.mev_deposits {
+    for transfer in mev_deposits {
         // Skip if we have Jito API data for this epoch (avoid double-counting)
         // Note: transfers don't have epoch directly, so we include them only if
         // mev_claims is empty (no API data at all)
@@ -107,7 +217,7 @@ fn generate_income_ledger(
 
         let date = transfer.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = transfer.amount_sol * price;
+        let usd_value = transfer.amount_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -115,19 +225,20 @@ fn generate_income_ledger(
             "Jito MEV",
             &transfer.from.to_string(),
             &transfer.from_label,
-            &format!("{:.6}", transfer.amount_sol),
-            &format!("{:.2}", price),
-            &format!("{:.2}", usd_value),
+            &transfer.amount_lamports.to_string(),
+            &price.to_string(),
+            &usd_value.to_string(),
             &transfer.signature[..16],
             "MEV tip distribution from Jito (fallback)",
+            &data_source(transfer.provenance.source, prices, date),
         ])?;
     }
 
     // MEV claims from Jito API (primary source)
-    for claim in mev_claims {
+    for claim in &period_claims {
         let date = claim.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = claim.amount_sol * price;
+        let usd_value = claim.commission_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -135,19 +246,47 @@ fn generate_income_ledger(
             "Jito MEV",
             "Jito Tip Distribution",
             "Vote Account",
-            &format!("{:.6}", claim.amount_sol),
-            &format!("{:.2}", price),
-            &format!("{:.2}", usd_value),
+            &claim.commission_lamports.to_string(),
+            &price.to_string(),
+            &usd_value.to_string(),
             &format!("epoch-{}", claim.epoch),
             &format!(
                 "{}% commission on {:.4} SOL tips",
-                if claim.total_tips_lamports > 0 {
-                    (claim.commission_lamports as f64 / claim.total_tips_lamports as f64 * 100.0).round() as u64
-                } else {
-                    0
-                },
-                claim.total_tips_lamports as f64 / 1e9
+                commission_percent(claim.commission_lamports, claim.total_tips_lamports),
+                claim.total_tips_lamports
+            ),
+            &data_source(claim.provenance.source, prices, date),
+        ])?;
+    }
+
+    // Jito (BAM) priority fee commission, reported separately from MEV tips
+    for claim in &period_claims {
+        if claim.priority_fee_commission_lamports == Lamports::ZERO {
+            continue;
+        }
+        let date = claim.date.as_deref().unwrap_or("unknown");
+        let price = get_price(prices, date);
+        let usd_value = claim.priority_fee_commission_lamports.usd_value(price);
+
+        wtr.write_record([
+            date,
+            &claim.epoch.to_string(),
+            "Jito Priority Fees",
+            "Jito Priority Fee Distribution",
+            "Vote Account",
+            &claim.priority_fee_commission_lamports.to_string(),
+            &price.to_string(),
+            &usd_value.to_string(),
+            &format!("epoch-{}", claim.epoch),
+            &format!(
+                "{}% commission on {:.4} SOL priority fees",
+                commission_percent(
+                    claim.priority_fee_commission_lamports,
+                    claim.total_priority_fees_lamports
+                ),
+                claim.total_priority_fees_lamports
             ),
+            &data_source(claim.provenance.source, prices, date),
         ])?;
     }
 
@@ -155,7 +294,7 @@ fn generate_income_ledger(
     for fees in leader_fees {
         let date = fees.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = fees.total_fees_sol * price;
+        let usd_value = fees.total_fees_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -163,14 +302,22 @@ fn generate_income_ledger(
             "Leader Fees",
             "Identity Account",
             "Block Production",
-            &format!("{:.6}", fees.total_fees_sol),
-            &format!("{:.2}", price),
-            &format!("{:.2}", usd_value),
+            &fees.total_fees_lamports.to_string(),
+            &price.to_string(),
+            &usd_value.to_string(),
             &format!("epoch-{}", fees.epoch),
-            &format!(
-                "{} blocks produced, {} skipped",
-                fees.blocks_produced, fees.skipped_slots
-            ),
+            &if fees.is_complete() {
+                format!(
+                    "{} blocks produced, {} skipped",
+                    fees.blocks_produced, fees.skipped_slots
+                )
+            } else {
+                format!(
+                    "{} blocks produced, {} skipped, {} unavailable (incomplete)",
+                    fees.blocks_produced, fees.skipped_slots, fees.unavailable_blocks
+                )
+            },
+            &data_source(fees.provenance.source, prices, date),
         ])?;
     }
 
@@ -181,13 +328,15 @@ fn generate_income_ledger(
 }
 
 /// Generate expense_ledger.csv
-fn generate_expense_ledger(
-    output_dir: &Path,
-    expenses: &[Expense],
-    vote_costs: &[EpochVoteCost],
-    prices: &PriceCache,
-    config: &Config,
-) -> Result<()> {
+fn generate_expense_ledger(output_dir: &Path, data: &ReportData, period: &ReportPeriod) -> Result<()> {
+    let (prices, config) = (data.prices, data.config);
+    let vote_costs = data
+        .vote_costs
+        .iter()
+        .filter(|c| period.contains_opt(c.date.as_deref()));
+    let incurred = data.incurred_expenses();
+    let expenses = incurred.iter().filter(|e| period.contains_str(&e.date));
+
     let path = output_dir.join(constants::EXPENSE_LEDGER_FILENAME);
     let mut wtr = Writer::from_path(&path)?;
 
@@ -197,6 +346,7 @@ fn generate_expense_ledger(
         "Epoch",
         "Vendor",
         "Category",
+        "Tax_Line",
         "Description",
         "Amount_SOL",
         "Amount_USD",
@@ -204,50 +354,57 @@ fn generate_expense_ledger(
         "SFDP_Coverage",
         "Net_Amount_USD",
         "Invoice_ID",
+        "Tx_Signature",
+        "Data_Source",
     ])?;
 
+    let tax_line = |category: &ExpenseCategory| data.categories.tax_line(category).unwrap_or("").to_string();
+
     // Vote costs per epoch (actual on-chain data)
     for cost in vote_costs {
         let date = cost.date.as_deref().unwrap_or("unknown");
-        let price = get_price(prices, date);
-        let gross_usd = cost.total_fee_sol * price;
-
-        // Calculate SFDP coverage for this epoch's date
-        let parsed_date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
-            .unwrap_or_else(|_| chrono::NaiveDate::parse_from_str(constants::FALLBACK_DATE, "%Y-%m-%d").unwrap());
-        let coverage = config.sfdp_coverage_percent(&parsed_date);
-        let net_usd = gross_usd * (1.0 - coverage);
+        let value = value_vote_cost(cost, prices, config);
 
         wtr.write_record([
             date,
             &cost.epoch.to_string(),
             "Solana Network",
-            "VoteFees",
-            &format!("{} votes ({})", cost.vote_count, cost.source),
-            &format!("{:.6}", cost.total_fee_sol),
-            &format!("{:.2}", gross_usd),
+            ExpenseCategory::VOTE_FEES.as_str(),
+            &tax_line(&ExpenseCategory::VOTE_FEES),
+            &format!("{} votes ({})", cost.vote_count, cost.provenance.source),
+            &cost.total_fee_lamports.to_string(),
+            &value.gross_usd.to_string(),
             "SOL",
-            &format!("{:.0}%", coverage * 100.0),
-            &format!("{:.2}", net_usd),
+            &format!("{}%", value.coverage_bps / 100),
+            &value.net_usd.to_string(),
             "",
+            "",
+            &data_source(cost.provenance.source, prices, date),
         ])?;
     }
 
-    // Off-chain expenses (hosting, contractors, etc.)
+    // Off-chain expenses (hosting, contractors, etc.), with the transfer that
+    // paid any paid in SOL (its lot disposal in capital_gains.csv)
     for expense in expenses {
         let expense_usd = expense.amount_usd;
         wtr.write_record([
             &expense.date,
             "", // No epoch for off-chain expenses
             &expense.vendor,
-            &expense.category.to_string(),
+            expense.category.as_str(),
+            &tax_line(&expense.category),
             &expense.description,
-            "", // No SOL amount
-            &format!("{:.2}", expense_usd),
+            &expense.amount_lamports.map(|l| l.to_string()).unwrap_or_default(),
+            &expense_usd.to_string(),
             &expense.paid_with,
             "", // No SFDP coverage for off-chain expenses
-            &format!("{:.2}", expense_usd),
+            &expense_usd.to_string(),
             expense.invoice_id.as_deref().unwrap_or(""),
+            &expense
+                .tx_signature
+                .as_deref()
+                .map_or(String::new(), |s| s[..16.min(s.len())].to_string()),
+            expense.provenance.source.as_str(),
         ])?;
     }
 
@@ -258,7 +415,10 @@ fn generate_expense_ledger(
 }
 
 /// Generate treasury_ledger.csv (transfers, seeding, withdrawals)
-fn generate_treasury_ledger(output_dir: &Path, categorized: &CategorizedTransfers, prices: &PriceCache) -> Result<()> {
+fn generate_treasury_ledger(output_dir: &Path, data: &ReportData, period: &ReportPeriod) -> Result<()> {
+    let (categorized, prices) = (data.categorized, data.prices);
+    let in_period = |transfer: &&SolTransfer| period.contains_opt(transfer.date.as_deref());
+
     let path = output_dir.join(constants::TREASURY_LEDGER_FILENAME);
     let mut wtr = Writer::from_path(&path)?;
 
@@ -274,13 +434,14 @@ fn generate_treasury_ledger(output_dir: &Path, categorized: &CategorizedTransfer
         "USD_Value",
         "Tx_Signature",
         "Notes",
+        "Data_Source",
     ])?;
 
     // Initial seeding
-    for transfer in &categorized.seeding {
+    for transfer in categorized.seeding.iter().filter(in_period) {
         let date = transfer.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = transfer.amount_sol * price;
+        let usd_value = transfer.amount_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -289,18 +450,19 @@ fn generate_treasury_ledger(output_dir: &Path, categorized: &CategorizedTransfer
             &transfer.from_label,
             &transfer.to.to_string(),
             &transfer.to_label,
-            &format!("{:.6}", transfer.amount_sol),
-            &format!("{:.2}", usd_value),
+            &transfer.amount_lamports.to_string(),
+            &usd_value.to_string(),
             &transfer.signature[..16],
             "Initial validator seeding",
+            &data_source(transfer.provenance.source, prices, date),
         ])?;
     }
 
     // Vote funding (internal transfers)
-    for transfer in &categorized.vote_funding {
+    for transfer in categorized.vote_funding.iter().filter(in_period) {
         let date = transfer.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = transfer.amount_sol * price;
+        let usd_value = transfer.amount_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -309,18 +471,19 @@ fn generate_treasury_ledger(output_dir: &Path, categorized: &CategorizedTransfer
             &transfer.from_label,
             &transfer.to.to_string(),
             &transfer.to_label,
-            &format!("{:.6}", transfer.amount_sol),
-            &format!("{:.2}", usd_value),
+            &transfer.amount_lamports.to_string(),
+            &usd_value.to_string(),
             &transfer.signature[..16],
             "Vote account funding",
+            &data_source(transfer.provenance.source, prices, date),
         ])?;
     }
 
     // Withdrawals
-    for transfer in &categorized.withdrawals {
+    for transfer in categorized.withdrawals.iter().filter(in_period) {
         let date = transfer.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = transfer.amount_sol * price;
+        let usd_value = transfer.amount_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -329,18 +492,48 @@ fn generate_treasury_ledger(output_dir: &Path, categorized: &CategorizedTransfer
             &transfer.from_label,
             &transfer.to.to_string(),
             &transfer.to_label,
-            &format!("{:.6}", transfer.amount_sol),
-            &format!("{:.2}", usd_value),
+            &transfer.amount_lamports.to_string(),
+            &usd_value.to_string(),
             &transfer.signature[..16],
             "Withdrawal to exchange/personal",
+            &data_source(transfer.provenance.source, prices, date),
+        ])?;
+    }
+
+    // SOL payments of expenses
+    for transfer in categorized.expense_payments.iter().filter(in_period) {
+        let date = transfer.date.as_deref().unwrap_or("unknown");
+        let price = get_price(prices, date);
+        let usd_value = transfer.amount_lamports.usd_value(price);
+        let notes = match data
+            .expenses
+            .iter()
+            .find(|e| e.tx_signature.as_deref() == Some(transfer.signature.as_str()))
+        {
+            Some(expense) => format!("Payment of {} ({})", expense.vendor, expense.description),
+            None => "Expense payment".to_string(),
+        };
+
+        wtr.write_record([
+            date,
+            "Expense Payment",
+            &transfer.from.to_string(),
+            &transfer.from_label,
+            &transfer.to.to_string(),
+            &transfer.to_label,
+            &transfer.amount_lamports.to_string(),
+            &usd_value.to_string(),
+            &transfer.signature[..16],
+            &notes,
+            &data_source(transfer.provenance.source, prices, date),
         ])?;
     }
 
     // Other transfers
-    for transfer in &categorized.other {
+    for transfer in categorized.other.iter().filter(in_period) {
         let date = transfer.date.as_deref().unwrap_or("unknown");
         let price = get_price(prices, date);
-        let usd_value = transfer.amount_sol * price;
+        let usd_value = transfer.amount_lamports.usd_value(price);
 
         wtr.write_record([
             date,
@@ -349,10 +542,11 @@ fn generate_treasury_ledger(output_dir: &Path, categorized: &CategorizedTransfer
             &transfer.from_label,
             &transfer.to.to_string(),
             &transfer.to_label,
-            &format!("{:.6}", transfer.amount_sol),
-            &format!("{:.2}", usd_value),
+            &transfer.amount_lamports.to_string(),
+            &usd_value.to_string(),
             &transfer.signature[..16],
             "Uncategorized transfer",
+            &data_source(transfer.provenance.source, prices, date),
         ])?;
     }
 
@@ -362,104 +556,903 @@ fn generate_treasury_ledger(output_dir: &Path, categorized: &CategorizedTransfer
     Ok(())
 }
 
-/// Generate summary.csv (monthly P&L with annual summaries)
-fn generate_summary(output_dir: &Path, data: &ReportData, year_filter: Option<i32>) -> Result<()> {
-    let path = output_dir.join(constants::SUMMARY_FILENAME);
+/// Generate fixed_assets.csv: every asset bought by the end of the period with
+/// its depreciation in the period and book value at the end
+fn generate_fixed_assets(output_dir: &Path, data: &ReportData, period: &ReportPeriod) -> Result<()> {
+    let as_of = period
+        .to
+        .map_or(data.recognized_through, |to| to.min(data.recognized_through));
+    let before = period.from.and_then(|from| from.pred_opt());
+    let fiscal = &period.fiscal;
+
+    let path = output_dir.join(constants::FIXED_ASSETS_FILENAME);
     let mut wtr = Writer::from_path(&path)?;
 
-    // Aggregate by month
-    let mut monthly: HashMap<String, MonthlyData> = HashMap::new();
+    wtr.write_record([
+        "ID",
+        "Purchase_Date",
+        "Vendor",
+        "Description",
+        "Category",
+        "Method",
+        "Life_Years",
+        "Cost_USD",
+        "Salvage_USD",
+        "Period_Depreciation_USD",
+        "Accumulated_Depreciation_USD",
+        "Book_Value_USD",
+        "As_Of",
+    ])?;
+
+    let (mut cost, mut depreciation, mut accumulated) = (Usd::ZERO, Usd::ZERO, Usd::ZERO);
+    let mut listed = 0;
+    for asset in data.assets {
+        if asset.purchase_date().is_ok_and(|date| date > as_of) {
+            continue;
+        }
+        let accumulated_usd = asset.accumulated_depreciation(fiscal, as_of);
+        let earlier_usd = before.map_or(Usd::ZERO, |date| asset.accumulated_depreciation(fiscal, date));
+        wtr.write_record([
+            &asset.id.map(|id| id.to_string()).unwrap_or_default(),
+            &asset.purchase_date,
+            &asset.vendor,
+            &asset.description,
+            asset.category.as_str(),
+            asset.method.as_str(),
+            &asset.life_years.to_string(),
+            &asset.cost_usd.to_string(),
+            &asset.salvage_usd.to_string(),
+            &(accumulated_usd - earlier_usd).to_string(),
+            &accumulated_usd.to_string(),
+            &(asset.cost_usd - accumulated_usd).to_string(),
+            &as_of.to_string(),
+        ])?;
+        cost += asset.cost_usd;
+        depreciation += accumulated_usd - earlier_usd;
+        accumulated += accumulated_usd;
+        listed += 1;
+    }
 
-    // Commission
-    for reward in data.rewards {
-        if let Some(date) = &reward.date {
-            let month = &date[..7];
-            let price = get_price(data.prices, date);
-            let entry = monthly.entry(month.to_string()).or_default();
-            entry.commission_sol += reward.amount_sol;
-            entry.commission_usd += reward.amount_sol * price;
+    if listed > 0 {
+        wtr.write_record([
+            "TOTAL",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            &cost.to_string(),
+            "",
+            &depreciation.to_string(),
+            &accumulated.to_string(),
+            &(cost - accumulated).to_string(),
+            &as_of.to_string(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    println!("  Generated: {}", path.display());
+
+    Ok(())
+}
+
+/// Generate capital_gains.csv (realized gains/losses on withdrawn SOL)
+///
+/// Under specific ID, rows consumed FIFO because the withdrawal's selection
+/// didn't cover them say so, with the selected lots that were skipped.
+fn generate_capital_gains(output_dir: &Path, report: &LotReport, method: LotMethod) -> Result<()> {
+    let path = output_dir.join(constants::CAPITAL_GAINS_FILENAME);
+    let mut wtr = Writer::from_path(&path)?;
+
+    for skip in &report.skipped {
+        eprintln!(
+            "  Warning: lot {} selected for withdrawal {} is {}; using FIFO instead",
+            skip.lot_id,
+            &skip.signature[..16.min(skip.signature.len())],
+            skip.reason
+        );
+    }
+    let skipped_note = |signature: &str| {
+        report
+            .skipped
+            .iter()
+            .filter(|skip| skip.signature == signature)
+            .map(|skip| format!("{} {}", skip.lot_id, skip.reason))
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+
+    // Header
+    wtr.write_record([
+        "Disposal_Date",
+        "Tx_Signature",
+        "Lot_ID",
+        "Acquired_Date",
+        "Amount_SOL",
+        "Cost_Basis_USD",
+        "Proceeds_USD",
+        "Gain_USD",
+        "Term",
+        "Method",
+        "Note",
+    ])?;
+
+    for gain in &report.gains {
+        let known_basis = gain.term != HoldingTerm::Unknown;
+        let row_method = if gain.fallback {
+            format!("{} (FIFO fallback)", method)
+        } else {
+            method.to_string()
+        };
+        let note = if !known_basis {
+            "Basis unknown; excluded from realized totals".to_string()
+        } else if gain.fallback {
+            match skipped_note(&gain.signature) {
+                skipped if skipped.is_empty() => "Not covered by the lot selection".to_string(),
+                skipped => format!("Selected {}", skipped),
+            }
+        } else {
+            String::new()
+        };
+        wtr.write_record([
+            &gain.disposal_date.format("%Y-%m-%d").to_string(),
+            &gain.signature[..16.min(gain.signature.len())],
+            gain.lot_id.as_deref().unwrap_or("UNKNOWN BASIS"),
+            &gain
+                .acquired
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            &gain.amount_lamports.to_string(),
+            &if known_basis {
+                gain.cost_basis_usd.to_string()
+            } else {
+                String::new()
+            },
+            &gain.proceeds_usd.to_string(),
+            &if known_basis {
+                gain.gain_usd().to_string()
+            } else {
+                String::new()
+            },
+            &gain.term.to_string(),
+            &row_method,
+            &note,
+        ])?;
+    }
+
+    wtr.flush()?;
+    println!("  Generated: {}", path.display());
+
+    Ok(())
+}
+
+/// Generate tax_lots.csv (lots with SOL remaining, for specific-ID selection)
+fn generate_tax_lots(output_dir: &Path, report: &LotReport) -> Result<()> {
+    let path = output_dir.join(constants::TAX_LOTS_FILENAME);
+    let mut wtr = Writer::from_path(&path)?;
+
+    // Header
+    wtr.write_record([
+        "Lot_ID",
+        "Acquired_Date",
+        "Source",
+        "Original_SOL",
+        "Remaining_SOL",
+        "Cost_Per_SOL_USD",
+        "Remaining_Basis_USD",
+    ])?;
+
+    for lot in &report.open_lots {
+        wtr.write_record([
+            &lot.id,
+            &lot.acquired.format("%Y-%m-%d").to_string(),
+            lot.source,
+            &lot.original_lamports.to_string(),
+            &lot.remaining_lamports.to_string(),
+            &lot.cost_per_sol().to_string(),
+            &lot.remaining_basis_usd.to_string(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    println!("  Generated: {}", path.display());
+
+    Ok(())
+}
+
+/// Data_Source column: where a row's amount came from, noting when its USD value
+/// uses a fallback price because none was fetched for the date
+fn data_source(source: DataSource, prices: &PriceCache, date: &str) -> String {
+    if price_source(prices, date) == DataSource::Fallback {
+        format!("{} (fallback price)", source)
+    } else {
+        source.to_string()
+    }
+}
+
+/// Commission as a whole percentage of the total (rounded)
+fn commission_percent(commission: Lamports, total: Lamports) -> u128 {
+    if total == Lamports::ZERO {
+        return 0;
+    }
+    (commission.0 as u128 * 100 + total.0 as u128 / 2) / total.0 as u128
+}
+
+/// USD valuation of a vote cost row, rounded per row so ledger and summary agree
+pub struct VoteCostValue {
+    pub gross_usd: Usd,
+    /// SFDP coverage in basis points for the row's date
+    pub coverage_bps: u32,
+    /// SOL the SFDP is expected to reimburse
+    pub sfdp_offset_lamports: Lamports,
+    pub sfdp_offset_usd: Usd,
+    pub net_usd: Usd,
+}
+
+/// Value a vote cost at its date's price, split into SFDP-covered and net portions
+pub fn value_vote_cost(cost: &EpochVoteCost, prices: &PriceCache, config: &Config) -> VoteCostValue {
+    let date = cost.date.as_deref().unwrap_or(constants::FALLBACK_DATE);
+    let gross_usd = cost.total_fee_lamports.usd_value(get_price(prices, date));
+
+    let parsed_date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .unwrap_or_else(|_| chrono::NaiveDate::parse_from_str(constants::FALLBACK_DATE, "%Y-%m-%d").unwrap());
+    let coverage_bps = config.sfdp_coverage_bps(&parsed_date);
+    let sfdp_offset_usd = gross_usd.scale_bps(coverage_bps);
+    let sfdp_offset_lamports =
+        Lamports(((cost.total_fee_lamports.0 as u128 * coverage_bps as u128 + 5_000) / 10_000) as u64);
+
+    VoteCostValue {
+        gross_usd,
+        coverage_bps,
+        sfdp_offset_lamports,
+        sfdp_offset_usd,
+        net_usd: gross_usd - sfdp_offset_usd,
+    }
+}
+
+/// An SFDP reimbursement settled against the coverage receivable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SfdpSettlement {
+    /// Reimbursement value on its date
+    pub received_usd: Usd,
+    /// Receivable it settles, at the value the coverage was expected at
+    pub settled_usd: Usd,
+}
+
+impl SfdpSettlement {
+    /// Difference between the SOL received and the coverage it settles,
+    /// booked as an SFDP true-up (positive when the SOL is worth more)
+    pub fn true_up_usd(&self) -> Usd {
+        self.received_usd - self.settled_usd
+    }
+}
+
+/// Settle SFDP reimbursements (one per transfer, in the same order) against
+/// the SOL coverage expected for vote costs, oldest first
+///
+/// The receivable is kept in SOL: a reimbursement settles the coverage it pays
+/// at that coverage's value, so coverage reimbursed in full leaves nothing
+/// outstanding whatever the SOL price did in between. SOL beyond the expected
+/// coverage settles nothing.
+pub fn settle_sfdp_reimbursements(
+    vote_costs: &[EpochVoteCost],
+    reimbursements: &[SolTransfer],
+    prices: &PriceCache,
+    config: &Config,
+) -> Vec<SfdpSettlement> {
+    let mut expected: Vec<(String, Lamports, Usd)> = vote_costs
+        .iter()
+        .map(|cost| {
+            let value = value_vote_cost(cost, prices, config);
+            let date = cost
+                .date
+                .clone()
+                .unwrap_or_else(|| constants::FALLBACK_DATE.to_string());
+            (date, value.sfdp_offset_lamports, value.sfdp_offset_usd)
+        })
+        .filter(|(_, lamports, _)| *lamports > Lamports::ZERO)
+        .collect();
+    expected.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut expected = expected
+        .into_iter()
+        .map(|(_, lamports, usd)| (lamports, usd))
+        .peekable();
+
+    let date = |transfer: &SolTransfer| {
+        transfer
+            .date
+            .clone()
+            .unwrap_or_else(|| constants::FALLBACK_DATE.to_string())
+    };
+    let mut order: Vec<usize> = (0..reimbursements.len()).collect();
+    order.sort_by_key(|&i| (date(&reimbursements[i]), reimbursements[i].slot));
+
+    let mut settlements = vec![
+        SfdpSettlement {
+            received_usd: Usd::ZERO,
+            settled_usd: Usd::ZERO,
+        };
+        reimbursements.len()
+    ];
+    for index in order {
+        let transfer = &reimbursements[index];
+        let settlement = &mut settlements[index];
+        settlement.received_usd = transfer.amount_lamports.usd_value(get_price(prices, &date(transfer)));
+        let mut remaining = transfer.amount_lamports;
+        while remaining > Lamports::ZERO {
+            let Some((lamports, usd)) = expected.peek_mut() else {
+                break;
+            };
+            let take = (*lamports).min(remaining);
+            let settled = if take == *lamports {
+                *usd
+            } else {
+                usd.mul_div(take.0, lamports.0)
+            };
+            *lamports -= take;
+            *usd -= settled;
+            remaining -= take;
+            settlement.settled_usd += settled;
+            if *lamports == Lamports::ZERO {
+                expected.next();
+            }
+        }
+    }
+    settlements
+}
+
+/// How far a row's USD value can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trust {
+    Observed,
+    Estimated,
+    /// Cached before sources were recorded
+    Unknown,
+}
+
+impl Trust {
+    /// Trust of a row from its amount's source and (for SOL rows) its price's source
+    fn of(sources: &[DataSource]) -> Self {
+        if sources.iter().any(|s| s.is_estimated()) {
+            Trust::Estimated
+        } else if sources.contains(&DataSource::Unknown) {
+            Trust::Unknown
+        } else {
+            Trust::Observed
+        }
+    }
+
+    /// Trust of a SOL amount valued at its date's price
+    fn of_sol(source: DataSource, prices: &PriceCache, date: &str) -> Self {
+        Self::of(&[source, price_source(prices, date)])
+    }
+}
+
+/// Revenue and expense lines broken down in the data-quality footer
+#[derive(Debug, Clone, Copy)]
+enum Line {
+    Commission,
+    LeaderFees,
+    Mev,
+    PriorityFees,
+    VoteFees,
+    OtherExpenses,
+}
+
+impl Line {
+    const ALL: [Line; 6] = [
+        Line::Commission,
+        Line::LeaderFees,
+        Line::Mev,
+        Line::PriorityFees,
+        Line::VoteFees,
+        Line::OtherExpenses,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Line::Commission => "Commission",
+            Line::LeaderFees => "Leader Fees",
+            Line::Mev => "Jito MEV",
+            Line::PriorityFees => "Jito Priority Fees",
+            Line::VoteFees => "Vote Fees (net)",
+            Line::OtherExpenses => "Other Expenses",
+        }
+    }
+}
+
+/// USD value of a line split by trust
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Quality {
+    observed: Usd,
+    estimated: Usd,
+    unknown: Usd,
+}
+
+impl Quality {
+    fn record(&mut self, trust: Trust, usd: Usd) {
+        match trust {
+            Trust::Observed => self.observed += usd,
+            Trust::Estimated => self.estimated += usd,
+            Trust::Unknown => self.unknown += usd,
+        }
+    }
+
+    fn add(&mut self, other: &Quality) {
+        self.observed += other.observed;
+        self.estimated += other.estimated;
+        self.unknown += other.unknown;
+    }
+
+    fn total(&self) -> Usd {
+        self.observed + self.estimated + self.unknown
+    }
+
+    /// Share of the total as a percentage (0 when the line is empty)
+    fn percent(&self, part: Usd) -> f64 {
+        let total = self.total().micros();
+        if total == 0 {
+            0.0
+        } else {
+            part.micros() as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Accrual-basis balances outstanding at the end of a month
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Balances {
+    /// SFDP coverage expected for vote costs but not yet reimbursed
+    sfdp_receivable_usd: Usd,
+    /// Expenses incurred by the end of the month that are still unpaid today.
+    /// Not a month-end balance: the hours log records whether contractor hours
+    /// were paid, not when, so paying them clears every earlier month too.
+    unpaid_expenses_usd: Usd,
+}
+
+/// Per-month totals, summed from the same rounded rows written to the ledgers
+#[derive(Default, Clone)]
+struct MonthlyData {
+    commission_sol: Lamports,
+    commission_usd: Usd,
+    leader_fees_sol: Lamports,
+    leader_fees_usd: Usd,
+    mev_sol: Lamports,
+    mev_usd: Usd,
+    priority_fees_sol: Lamports,
+    priority_fees_usd: Usd,
+    sfdp_sol: Lamports,
+    sfdp_usd: Usd,
+    vote_costs_sol: Lamports,
+    vote_costs_gross_usd: Usd,
+    vote_costs_net_usd: Usd,
+    other_expenses_usd: Usd,
+    /// Off-chain expenses by top-level category
+    category_expenses_usd: BTreeMap<String, Usd>,
+    seeding_sol: Lamports,
+    /// Observed/estimated split of each line, indexed by `Line`
+    quality: [Quality; Line::ALL.len()],
+    /// Month-end receivables and payables (accrual basis only)
+    balances: Option<Balances>,
+}
+
+impl MonthlyData {
+    /// SFDP is an expense offset, not revenue
+    fn total_revenue_usd(&self) -> Usd {
+        self.commission_usd + self.leader_fees_usd + self.mev_usd + self.priority_fees_usd
+    }
+
+    fn total_expenses_usd(&self) -> Usd {
+        self.vote_costs_net_usd + self.other_expenses_usd
+    }
+
+    fn net_profit_usd(&self) -> Usd {
+        self.total_revenue_usd() - self.total_expenses_usd()
+    }
+
+    fn add(&mut self, other: &MonthlyData) {
+        self.commission_sol += other.commission_sol;
+        self.commission_usd += other.commission_usd;
+        self.leader_fees_sol += other.leader_fees_sol;
+        self.leader_fees_usd += other.leader_fees_usd;
+        self.mev_sol += other.mev_sol;
+        self.mev_usd += other.mev_usd;
+        self.priority_fees_sol += other.priority_fees_sol;
+        self.priority_fees_usd += other.priority_fees_usd;
+        self.sfdp_sol += other.sfdp_sol;
+        self.sfdp_usd += other.sfdp_usd;
+        self.vote_costs_sol += other.vote_costs_sol;
+        self.vote_costs_gross_usd += other.vote_costs_gross_usd;
+        self.vote_costs_net_usd += other.vote_costs_net_usd;
+        self.other_expenses_usd += other.other_expenses_usd;
+        for (category, usd) in &other.category_expenses_usd {
+            *self.category_expenses_usd.entry(category.clone()).or_default() += *usd;
+        }
+        self.seeding_sol += other.seeding_sol;
+        for (quality, other) in self.quality.iter_mut().zip(&other.quality) {
+            quality.add(other);
+        }
+        // Balances of different validators add up; for periods see `close_with`
+        self.balances = match (self.balances, other.balances) {
+            (Some(a), Some(b)) => Some(Balances {
+                sfdp_receivable_usd: a.sfdp_receivable_usd + b.sfdp_receivable_usd,
+                unpaid_expenses_usd: a.unpaid_expenses_usd + b.unpaid_expenses_usd,
+            }),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Add a later month of the same period: flows add up, balances are the latest
+    fn close_with(&mut self, month: &MonthlyData) {
+        let balances = month.balances;
+        self.add(month);
+        self.balances = balances;
+    }
+
+    /// Add a revenue row to its line
+    fn record_revenue(&mut self, row: &Recognized) {
+        let (sol, usd) = match row.line {
+            Line::Commission => (&mut self.commission_sol, &mut self.commission_usd),
+            Line::LeaderFees => (&mut self.leader_fees_sol, &mut self.leader_fees_usd),
+            Line::Mev => (&mut self.mev_sol, &mut self.mev_usd),
+            Line::PriorityFees => (&mut self.priority_fees_sol, &mut self.priority_fees_usd),
+            Line::VoteFees | Line::OtherExpenses => unreachable!("not a revenue line"),
+        };
+        *sol += row.lamports;
+        *usd += row.usd;
+        self.quality_mut(row.line).record(row.trust, row.usd);
+    }
+
+    fn quality_mut(&mut self, line: Line) -> &mut Quality {
+        &mut self.quality[line as usize]
+    }
+
+    /// Every line's split combined
+    fn overall_quality(&self) -> Quality {
+        let mut overall = Quality::default();
+        for quality in &self.quality {
+            overall.add(quality);
         }
+        overall
     }
 
-    // SFDP reimbursements
-    for transfer in &data.categorized.sfdp_reimbursements {
-        if let Some(date) = &transfer.date {
-            let month = &date[..7];
-            let price = get_price(data.prices, date);
-            let entry = monthly.entry(month.to_string()).or_default();
-            entry.sfdp_sol += transfer.amount_sol;
-            entry.sfdp_usd += transfer.amount_sol * price;
+    /// Row values under the `write_summary` header, with a column per listed
+    /// top-level expense category
+    fn csv_fields(&self, categories: &[&String]) -> Vec<String> {
+        let mut fields = vec![
+            self.commission_sol.to_string(),
+            self.commission_usd.to_string(),
+            self.leader_fees_sol.to_string(),
+            self.leader_fees_usd.to_string(),
+            self.mev_sol.to_string(),
+            self.mev_usd.to_string(),
+            self.priority_fees_sol.to_string(),
+            self.priority_fees_usd.to_string(),
+            self.total_revenue_usd().to_string(),
+            self.vote_costs_sol.to_string(),
+            self.vote_costs_gross_usd.to_string(),
+            (self.vote_costs_gross_usd - self.vote_costs_net_usd).to_string(),
+            self.vote_costs_net_usd.to_string(),
+            self.other_expenses_usd.to_string(),
+        ];
+        fields.extend(categories.iter().map(|category| {
+            self.category_expenses_usd
+                .get(*category)
+                .copied()
+                .unwrap_or_default()
+                .to_string()
+        }));
+        fields.extend([
+            self.total_expenses_usd().to_string(),
+            self.net_profit_usd().to_string(),
+            self.balances
+                .map(|b| b.sfdp_receivable_usd.to_string())
+                .unwrap_or_default(),
+            self.balances
+                .map(|b| b.unpaid_expenses_usd.to_string())
+                .unwrap_or_default(),
+        ]);
+        fields
+    }
+}
+
+/// A revenue row as the summary recognizes it: its line, date and USD value
+struct Recognized {
+    line: Line,
+    date: String,
+    lamports: Lamports,
+    usd: Usd,
+    trust: Trust,
+}
+
+/// Revenue rows on the dates they were earned (the income ledger rows), oldest first
+fn earned_revenue(data: &ReportData) -> Vec<Recognized> {
+    let mut revenue = Vec::new();
+    let mut push = |line, date: &Option<String>, lamports: Lamports, source| {
+        if let Some(date) = date {
+            revenue.push(Recognized {
+                line,
+                date: date.clone(),
+                lamports,
+                usd: lamports.usd_value(get_price(data.prices, date)),
+                trust: Trust::of_sol(source, data.prices, date),
+            });
         }
+    };
+
+    // Commission
+    for reward in data.rewards {
+        push(
+            Line::Commission,
+            &reward.date,
+            reward.amount_lamports,
+            reward.provenance.source,
+        );
     }
 
     // MEV: Use Jito API claims as source of truth to avoid double-counting.
     // Only use mev_deposits as fallback when mev_claims is empty.
     if data.mev_claims.is_empty() {
-        // Fallback: use transfer detection when no Jito API data
         for transfer in &data.categorized.mev_deposits {
-            if let Some(date) = &transfer.date {
-                let month = &date[..7];
-                let price = get_price(data.prices, date);
-                let entry = monthly.entry(month.to_string()).or_default();
-                entry.mev_sol += transfer.amount_sol;
-                entry.mev_usd += transfer.amount_sol * price;
-            }
+            push(
+                Line::Mev,
+                &transfer.date,
+                transfer.amount_lamports,
+                transfer.provenance.source,
+            );
         }
     } else {
-        // Primary: use Jito API data (per-epoch, accurate)
         for claim in data.mev_claims {
-            if let Some(date) = &claim.date {
-                let month = &date[..7];
-                let price = get_price(data.prices, date);
-                let entry = monthly.entry(month.to_string()).or_default();
-                entry.mev_sol += claim.amount_sol;
-                entry.mev_usd += claim.amount_sol * price;
-            }
+            let source = claim.provenance.source;
+            push(Line::Mev, &claim.date, claim.commission_lamports, source);
+            push(
+                Line::PriorityFees,
+                &claim.date,
+                claim.priority_fee_commission_lamports,
+                source,
+            );
         }
     }
 
     // Leader fees from block production
     for fees in data.leader_fees {
-        if let Some(date) = &fees.date {
-            let month = &date[..7];
-            let price = get_price(data.prices, date);
-            let entry = monthly.entry(month.to_string()).or_default();
-            entry.leader_fees_sol += fees.total_fees_sol;
-            entry.leader_fees_usd += fees.total_fees_sol * price;
+        push(
+            Line::LeaderFees,
+            &fees.date,
+            fees.total_fees_lamports,
+            fees.provenance.source,
+        );
+    }
+
+    revenue.sort_by(|a, b| a.date.cmp(&b.date));
+    revenue
+}
+
+/// Revenue rows on the dates they were withdrawn or spent (cash basis)
+///
+/// Withdrawals, SOL expense payments and on-chain vote fees draw down earned
+/// revenue oldest first; each portion keeps its value when earned, so cash and
+/// accrual revenue agree once everything has been withdrawn or spent. Outflows
+/// beyond earned revenue return (or spend) capital.
+fn withdrawn_revenue(data: &ReportData) -> Vec<Recognized> {
+    let mut earned = earned_revenue(data).into_iter().peekable();
+    let mut current: Option<Recognized> = None;
+
+    // Every outflow of SOL by slot; vote fees are paid from the identity
+    // account's SOL, which holds leader fees, as each epoch's votes land
+    let mut outflows: Vec<(u64, &String, Lamports)> = data
+        .categorized
+        .disposals()
+        .filter_map(|t| Some((t.slot, t.date.as_ref()?, t.amount_lamports)))
+        .collect();
+    outflows.extend(data.vote_costs.iter().filter_map(|cost| {
+        let slot = data.config.epoch_schedule.get_first_slot_in_epoch(cost.epoch);
+        Some((slot, cost.date.as_ref()?, cost.total_fee_lamports))
+    }));
+    outflows.sort_by_key(|&(slot, ..)| slot);
+
+    let mut revenue = Vec::new();
+    for (_, date, amount) in outflows {
+        let mut remaining = amount;
+        while remaining > Lamports::ZERO {
+            if current.as_ref().is_none_or(|row| row.lamports == Lamports::ZERO) {
+                // Only revenue earned by the withdrawal date can be withdrawn
+                current = earned.next_if(|row| row.date <= *date);
+            }
+            let Some(row) = current.as_mut() else { break };
+            let take = row.lamports.min(remaining);
+            let usd = if take == row.lamports {
+                row.usd
+            } else {
+                row.usd.mul_div(take.0, row.lamports.0)
+            };
+            row.lamports -= take;
+            row.usd -= usd;
+            remaining -= take;
+            if take > Lamports::ZERO {
+                revenue.push(Recognized {
+                    line: row.line,
+                    date: date.clone(),
+                    lamports: take,
+                    usd,
+                    trust: row.trust,
+                });
+            }
+        }
+    }
+    revenue
+}
+
+/// Aggregate every ledger row dated within the period by month (YYYY-MM), on
+/// the report's basis
+fn monthly_totals(data: &ReportData, period: &ReportPeriod) -> BTreeMap<String, MonthlyData> {
+    let mut monthly: BTreeMap<String, MonthlyData> = BTreeMap::new();
+    let dated = |date: &Option<String>| date.clone().filter(|d| period.contains_str(d));
+
+    // Revenue: when earned (accrual) or withdrawn or spent (cash)
+    let revenue = match data.basis {
+        Basis::Accrual => earned_revenue(data),
+        Basis::Cash => withdrawn_revenue(data),
+    };
+    for row in revenue.iter().filter(|row| period.contains_str(&row.date)) {
+        monthly
+            .entry(row.date[..7].to_string())
+            .or_default()
+            .record_revenue(row);
+    }
+
+    // SFDP reimbursements: the offset itself when cash basis, otherwise
+    // settling the receivable (with any difference in value a true-up)
+    let reimbursements = &data.categorized.sfdp_reimbursements;
+    let settlements = settle_sfdp_reimbursements(data.vote_costs, reimbursements, data.prices, data.config);
+    for (transfer, settlement) in reimbursements.iter().zip(&settlements) {
+        if let Some(date) = &dated(&transfer.date) {
+            let entry = monthly.entry(date[..7].to_string()).or_default();
+            entry.sfdp_sol += transfer.amount_lamports;
+            entry.sfdp_usd += settlement.received_usd;
+            // Offsets the net vote fee line, so its quality split covers the same amount
+            let offset = match data.basis {
+                Basis::Accrual => settlement.true_up_usd(),
+                Basis::Cash => settlement.received_usd,
+            };
+            entry.vote_costs_net_usd -= offset;
+            entry
+                .quality_mut(Line::VoteFees)
+                .record(Trust::of_sol(transfer.provenance.source, data.prices, date), -offset);
         }
     }
 
-    // Vote costs by month (with SFDP coverage calculation)
+    // Vote costs by month, paid on-chain as they are incurred (offset by the
+    // SFDP coverage expected for the epoch when accrual basis)
     for cost in data.vote_costs {
-        if let Some(date) = &cost.date {
-            let month = &date[..7];
-            let price = get_price(data.prices, date);
-            let gross_usd = cost.total_fee_sol * price;
-
-            // Calculate SFDP coverage for net cost
-            let parsed_date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
-                .unwrap_or_else(|_| chrono::NaiveDate::parse_from_str(constants::FALLBACK_DATE, "%Y-%m-%d").unwrap());
-            let coverage = data.config.sfdp_coverage_percent(&parsed_date);
-            let net_usd = gross_usd * (1.0 - coverage);
-
-            let entry = monthly.entry(month.to_string()).or_default();
-            entry.vote_costs_sol += cost.total_fee_sol;
-            entry.vote_costs_gross_usd += gross_usd;
+        if let Some(date) = &dated(&cost.date) {
+            let value = value_vote_cost(cost, data.prices, data.config);
+            let net_usd = match data.basis {
+                Basis::Accrual => value.net_usd,
+                Basis::Cash => value.gross_usd,
+            };
+            let entry = monthly.entry(date[..7].to_string()).or_default();
+            entry.vote_costs_sol += cost.total_fee_lamports;
+            entry.vote_costs_gross_usd += value.gross_usd;
             entry.vote_costs_net_usd += net_usd;
+            entry
+                .quality_mut(Line::VoteFees)
+                .record(Trust::of_sol(cost.provenance.source, data.prices, date), net_usd);
         }
     }
 
-    // Expenses by month
-    for expense in data.expenses {
-        if let Ok(date) = chrono::NaiveDate::parse_from_str(&expense.date, "%Y-%m-%d") {
-            let month = date.format("%Y-%m").to_string();
-            let entry = monthly.entry(month).or_default();
+    // Expenses by month: when incurred (accrual) or once paid (cash)
+    for expense in &data.incurred_expenses() {
+        if data.basis == Basis::Cash && expense.is_unpaid() {
+            continue;
+        }
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(&expense.date, "%Y-%m-%d")
+            && period.contains(date)
+        {
+            let entry = monthly.entry(date.format("%Y-%m").to_string()).or_default();
             entry.other_expenses_usd += expense.amount_usd;
+            entry
+                .quality_mut(Line::OtherExpenses)
+                .record(Trust::of(&[expense.provenance.source]), expense.amount_usd);
+            *entry
+                .category_expenses_usd
+                .entry(expense.category.top_level().to_string())
+                .or_default() += expense.amount_usd;
+        }
+    }
+
+    // Initial seeding (capital, not P&L)
+    for transfer in &data.categorized.seeding {
+        if let Some(date) = &dated(&transfer.date) {
+            let entry = monthly.entry(date[..7].to_string()).or_default();
+            entry.seeding_sol += transfer.amount_lamports;
+        }
+    }
+
+    if data.basis == Basis::Accrual {
+        for (month, entry) in monthly.iter_mut() {
+            entry.balances = Some(accrued_balances(data, &settlements, month));
+        }
+    }
+
+    monthly
+}
+
+/// Receivables and payables outstanding at the end of a month (YYYY-MM),
+/// counting every row up to then, including those before the report period
+/// (`settlements` are the SFDP reimbursements', see `settle_sfdp_reimbursements`)
+fn accrued_balances(data: &ReportData, settlements: &[SfdpSettlement], month: &str) -> Balances {
+    let by_month_end = |date: &str| date.get(..7).is_some_and(|m| m <= month);
+    let mut balances = Balances::default();
+
+    // SFDP coverage expected for vote costs, less what reimbursements settled
+    for cost in data.vote_costs {
+        if cost.date.as_deref().is_some_and(by_month_end) {
+            balances.sfdp_receivable_usd += value_vote_cost(cost, data.prices, data.config).sfdp_offset_usd;
+        }
+    }
+    for (transfer, settlement) in data.categorized.sfdp_reimbursements.iter().zip(settlements) {
+        if transfer.date.as_deref().is_some_and(by_month_end) {
+            balances.sfdp_receivable_usd -= settlement.settled_usd;
+        }
+    }
+
+    // Expenses incurred by then and still unpaid today (unpaid contractor hours)
+    for expense in data.expenses {
+        if expense.is_unpaid() && by_month_end(&expense.date) {
+            balances.unpaid_expenses_usd += expense.amount_usd;
+        }
+    }
+
+    balances
+}
+
+/// Totals over every month in the period
+fn period_totals(data: &ReportData, period: &ReportPeriod) -> MonthlyData {
+    let mut totals = MonthlyData::default();
+    for month_data in monthly_totals(data, period).values() {
+        totals.close_with(month_data);
+    }
+    totals
+}
+
+/// Generate summary.csv (monthly P&L with quarterly and annual summaries)
+fn generate_summary(output_dir: &Path, data: &ReportData, period: &ReportPeriod) -> Result<()> {
+    let path = output_dir.join(constants::SUMMARY_FILENAME);
+    write_summary(&path, &monthly_totals(data, period), period)
+}
+
+/// Generate consolidated_summary.csv: summary.csv columns summed across validators
+pub fn generate_consolidated_summary(
+    output_dir: &Path,
+    validators: &[ReportData],
+    period: &ReportPeriod,
+) -> Result<()> {
+    let mut monthly: BTreeMap<String, MonthlyData> = BTreeMap::new();
+    for data in validators {
+        for (month, month_data) in monthly_totals(data, period) {
+            monthly.entry(month).or_default().add(&month_data);
         }
     }
 
+    let path = output_dir.join(constants::CONSOLIDATED_SUMMARY_FILENAME);
+    write_summary(&path, &monthly, period)
+}
+
+/// Write monthly P&L rows followed by quarterly and annual totals (by fiscal year)
+fn write_summary(path: &Path, monthly: &BTreeMap<String, MonthlyData>, period: &ReportPeriod) -> Result<()> {
+    let mut wtr = Writer::from_path(path)?;
+
+    // A column per top-level category with expenses in the period
+    let categories: BTreeSet<&String> = monthly
+        .values()
+        .flat_map(|data| data.category_expenses_usd.keys())
+        .collect();
+    let categories: Vec<&String> = categories.into_iter().collect();
+
     // Header
-    wtr.write_record([
+    let mut header: Vec<String> = [
         "Month",
         "Commission_SOL",
         "Commission_USD",
@@ -467,114 +1460,71 @@ fn generate_summary(output_dir: &Path, data: &ReportData, year_filter: Option<i3
         "Leader_Fees_USD",
         "MEV_SOL",
         "MEV_USD",
+        "Jito_Priority_Fees_SOL",
+        "Jito_Priority_Fees_USD",
         "Total_Revenue_USD",
         "Vote_Costs_SOL",
         "Vote_Costs_Gross_USD",
         "SFDP_Offset_USD",
         "Vote_Costs_Net_USD",
         "Other_Expenses_USD",
-        "Total_Expenses_USD",
-        "Net_Profit_USD",
-        "YTD_Profit_USD",
-    ])?;
-
-    let mut months: Vec<_> = monthly.keys().cloned().collect();
-    months.sort();
-
-    // Filter by year if specified
-    let months: Vec<_> = if let Some(year) = year_filter {
-        let year_prefix = format!("{}-", year);
-        months.into_iter().filter(|m| m.starts_with(&year_prefix)).collect()
-    } else {
-        months
-    };
-
-    // Track annual totals for summary rows
-    let mut annual_totals: HashMap<String, MonthlyData> = HashMap::new();
-    let mut ytd = 0.0;
-    let mut current_year: Option<String> = None;
-
-    for month in &months {
-        let year = &month[..4];
-        let data = &monthly[month];
-        // SFDP is expense offset, not revenue
-        let total_revenue = data.commission_usd + data.leader_fees_usd + data.mev_usd;
-        let total_expenses = data.vote_costs_net_usd + data.other_expenses_usd;
-        let net_profit = total_revenue - total_expenses;
-
-        // Reset YTD at year boundary
-        if current_year.as_deref() != Some(year) {
-            current_year = Some(year.to_string());
-            ytd = 0.0;
+    ]
+    .map(String::from)
+    .to_vec();
+    header.extend(categories.iter().map(|category| category_column(category)));
+    header.extend(
+        [
+            "Total_Expenses_USD",
+            "Net_Profit_USD",
+            "SFDP_Receivable_USD",
+            "Unpaid_As_Of_Today_USD",
+            "YTD_Profit_USD",
+        ]
+        .map(String::from),
+    );
+    wtr.write_record(&header)?;
+
+    // Track fiscal year and quarter totals for summary rows
+    let fiscal = &period.fiscal;
+    let mut annual_totals: BTreeMap<i32, (MonthlyData, BTreeMap<u32, MonthlyData>)> = BTreeMap::new();
+    let mut ytd = Usd::ZERO;
+    let mut current_year: Option<i32> = None;
+
+    // BTreeMap keeps months sorted
+    for (month, data) in monthly {
+        let first_day = chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")?;
+        let (year, quarter) = fiscal.quarter_of(first_day);
+
+        // Reset YTD at fiscal year boundary
+        if current_year != Some(year) {
+            current_year = Some(year);
+            ytd = Usd::ZERO;
         }
-        ytd += net_profit;
-
-        // Accumulate annual totals
-        let annual = annual_totals.entry(year.to_string()).or_default();
-        annual.commission_sol += data.commission_sol;
-        annual.commission_usd += data.commission_usd;
-        annual.leader_fees_sol += data.leader_fees_sol;
-        annual.leader_fees_usd += data.leader_fees_usd;
-        annual.mev_sol += data.mev_sol;
-        annual.mev_usd += data.mev_usd;
-        annual.sfdp_sol += data.sfdp_sol;
-        annual.sfdp_usd += data.sfdp_usd;
-        annual.vote_costs_sol += data.vote_costs_sol;
-        annual.vote_costs_gross_usd += data.vote_costs_gross_usd;
-        annual.vote_costs_net_usd += data.vote_costs_net_usd;
-        annual.other_expenses_usd += data.other_expenses_usd;
-
-        let sfdp_offset = data.vote_costs_gross_usd - data.vote_costs_net_usd;
-        wtr.write_record([
-            month,
-            &format!("{:.4}", data.commission_sol),
-            &format!("{:.2}", data.commission_usd),
-            &format!("{:.4}", data.leader_fees_sol),
-            &format!("{:.2}", data.leader_fees_usd),
-            &format!("{:.4}", data.mev_sol),
-            &format!("{:.2}", data.mev_usd),
-            &format!("{:.2}", total_revenue),
-            &format!("{:.4}", data.vote_costs_sol),
-            &format!("{:.2}", data.vote_costs_gross_usd),
-            &format!("{:.2}", sfdp_offset),
-            &format!("{:.2}", data.vote_costs_net_usd),
-            &format!("{:.2}", data.other_expenses_usd),
-            &format!("{:.2}", total_expenses),
-            &format!("{:.2}", net_profit),
-            &format!("{:.2}", ytd),
-        ])?;
-    }
+        ytd += data.net_profit_usd();
 
-    // Write annual summary rows
-    let mut years: Vec<_> = annual_totals.keys().cloned().collect();
-    years.sort();
+        // Accumulate quarterly and annual totals
+        let (annual, quarters) = annual_totals.entry(year).or_default();
+        annual.close_with(data);
+        quarters.entry(quarter).or_default().close_with(data);
 
-    for year in &years {
-        let data = &annual_totals[year];
-        // SFDP is expense offset, not revenue
-        let total_revenue = data.commission_usd + data.leader_fees_usd + data.mev_usd;
-        let total_expenses = data.vote_costs_net_usd + data.other_expenses_usd;
-        let net_profit = total_revenue - total_expenses;
+        let mut record = vec![month.clone()];
+        record.extend(data.csv_fields(&categories));
+        record.push(ytd.to_string());
+        wtr.write_record(&record)?;
+    }
 
-        let sfdp_offset = data.vote_costs_gross_usd - data.vote_costs_net_usd;
-        wtr.write_record([
-            &format!("{} TOTAL", year),
-            &format!("{:.4}", data.commission_sol),
-            &format!("{:.2}", data.commission_usd),
-            &format!("{:.4}", data.leader_fees_sol),
-            &format!("{:.2}", data.leader_fees_usd),
-            &format!("{:.4}", data.mev_sol),
-            &format!("{:.2}", data.mev_usd),
-            &format!("{:.2}", total_revenue),
-            &format!("{:.4}", data.vote_costs_sol),
-            &format!("{:.2}", data.vote_costs_gross_usd),
-            &format!("{:.2}", sfdp_offset),
-            &format!("{:.2}", data.vote_costs_net_usd),
-            &format!("{:.2}", data.other_expenses_usd),
-            &format!("{:.2}", total_expenses),
-            &format!("{:.2}", net_profit),
-            "", // No YTD for annual rows
-        ])?;
+    // Write each year's quarterly rows, then its annual row (no YTD for either)
+    for (year, (annual, quarters)) in &annual_totals {
+        for (quarter, data) in quarters {
+            let mut record = vec![format!("{} TOTAL", fiscal.quarter_label(*year, *quarter))];
+            record.extend(data.csv_fields(&categories));
+            record.push(String::new());
+            wtr.write_record(&record)?;
+        }
+        let mut record = vec![format!("{} TOTAL", fiscal.year_label(*year))];
+        record.extend(annual.csv_fields(&categories));
+        record.push(String::new());
+        wtr.write_record(&record)?;
     }
 
     wtr.flush()?;
@@ -583,238 +1533,405 @@ fn generate_summary(output_dir: &Path, data: &ReportData, year_filter: Option<i3
     Ok(())
 }
 
-#[derive(Default)]
-struct MonthlyData {
-    commission_sol: f64,
-    commission_usd: f64,
-    leader_fees_sol: f64,
-    leader_fees_usd: f64,
-    mev_sol: f64,
-    mev_usd: f64,
-    sfdp_sol: f64,
-    sfdp_usd: f64,
-    vote_costs_sol: f64,
-    vote_costs_gross_usd: f64,
-    vote_costs_net_usd: f64,
-    other_expenses_usd: f64,
+/// summary.csv column of a top-level expense category ("Expenses_Hosting_USD")
+fn category_column(category: &str) -> String {
+    let name: String = category
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("Expenses_{}_USD", name)
 }
 
-/// Normalize -0.0 to 0.0 for cleaner display
-fn normalize_zero(val: f64) -> f64 {
-    if val == 0.0 { 0.0 } else { val }
+/// Print per-validator and combined P&L to console
+pub fn print_consolidated_summary(validators: &[ReportData], period: &ReportPeriod) {
+    println!("\n============================================================");
+    if let Some(label) = &period.label {
+        println!("              CONSOLIDATED SUMMARY ({})", label);
+    } else {
+        println!("                  CONSOLIDATED SUMMARY");
+    }
+    println!("============================================================\n");
+
+    println!(
+        "  {:<16} {:>13} {:>13} {:>13}",
+        "Validator", "Revenue", "Expenses", "Net Profit"
+    );
+    println!("  {}", "-".repeat(58));
+
+    let mut combined = MonthlyData::default();
+    for data in validators {
+        let totals = period_totals(data, period);
+        println!(
+            "  {:<16} ${:>12} ${:>12} ${:>12}",
+            data.config.name,
+            totals.total_revenue_usd(),
+            totals.total_expenses_usd(),
+            totals.net_profit_usd()
+        );
+        combined.add(&totals);
+    }
+
+    println!("  {}", "-".repeat(58));
+    println!(
+        "  {:<16} ${:>12} ${:>12} ${:>12}",
+        "Total",
+        combined.total_revenue_usd(),
+        combined.total_expenses_usd(),
+        combined.net_profit_usd()
+    );
+    println!("============================================================");
 }
 
 /// Print summary to console
-pub fn print_summary(data: &ReportData, year_filter: Option<i32>) {
-    // Helper to check if a date matches the year filter
-    let matches_year = |date: &str| -> bool {
-        if let Some(year) = year_filter {
-            date.starts_with(&format!("{}-", year))
-        } else {
-            true
-        }
-    };
-
+pub fn print_summary(data: &ReportData, period: &ReportPeriod) {
     println!("\n============================================================");
-    if let Some(year) = year_filter {
-        println!("                FINANCIAL SUMMARY ({})", year);
+    if let Some(label) = &period.label {
+        println!("                FINANCIAL SUMMARY ({})", label);
     } else {
         println!("                    FINANCIAL SUMMARY");
     }
     println!("============================================================\n");
 
-    // Calculate totals (filtered by year if specified)
-    let total_commission_sol: f64 = data
-        .rewards
-        .iter()
-        .filter(|r| r.date.as_deref().map(&matches_year).unwrap_or(false))
-        .map(|r| r.amount_sol)
-        .sum();
-    let total_commission_usd: f64 = data
-        .rewards
-        .iter()
-        .filter(|r| r.date.as_deref().map(&matches_year).unwrap_or(false))
-        .map(|r| {
-            let price = get_price(data.prices, r.date.as_deref().unwrap_or(constants::FALLBACK_DATE));
-            r.amount_sol * price
-        })
-        .sum();
-
-    // MEV: Use Jito API claims as source of truth to avoid double-counting.
-    // Only use mev_deposits as fallback when mev_claims is empty.
-    let (total_mev_sol, total_mev_usd) = if data.mev_claims.is_empty() {
-        // Fallback: use transfer detection
-        let mev_sol: f64 = data
-            .categorized
-            .mev_deposits
-            .iter()
-            .filter(|t| t.date.as_deref().map(&matches_year).unwrap_or(false))
-            .map(|t| t.amount_sol)
-            .sum();
-        let mev_usd: f64 = data
-            .categorized
-            .mev_deposits
-            .iter()
-            .filter(|t| t.date.as_deref().map(&matches_year).unwrap_or(false))
-            .map(|t| {
-                let price = get_price(data.prices, t.date.as_deref().unwrap_or(constants::FALLBACK_DATE));
-                t.amount_sol * price
-            })
-            .sum();
-        (mev_sol, mev_usd)
-    } else {
-        // Primary: use Jito API data
-        let mev_sol: f64 = data
-            .mev_claims
-            .iter()
-            .filter(|c| c.date.as_deref().map(&matches_year).unwrap_or(false))
-            .map(|c| c.amount_sol)
-            .sum();
-        let mev_usd: f64 = data
-            .mev_claims
-            .iter()
-            .filter(|c| c.date.as_deref().map(&matches_year).unwrap_or(false))
-            .map(|c| {
-                let price = get_price(data.prices, c.date.as_deref().unwrap_or(constants::FALLBACK_DATE));
-                c.amount_sol * price
-            })
-            .sum();
-        (mev_sol, mev_usd)
-    };
-
-    // Leader fees from block production
-    let total_leader_fees_sol: f64 = data
-        .leader_fees
-        .iter()
-        .filter(|f| f.date.as_deref().map(&matches_year).unwrap_or(false))
-        .map(|f| f.total_fees_sol)
-        .sum();
-    let total_leader_fees_usd: f64 = data
-        .leader_fees
-        .iter()
-        .filter(|f| f.date.as_deref().map(&matches_year).unwrap_or(false))
-        .map(|f| {
-            let price = get_price(data.prices, f.date.as_deref().unwrap_or(constants::FALLBACK_DATE));
-            f.total_fees_sol * price
-        })
-        .sum();
-
-    // Note: SFDP is tracked as expense offset, not calculated separately for revenue
-
-    let total_seeding_sol: f64 = data
-        .categorized
-        .seeding
-        .iter()
-        .filter(|t| t.date.as_deref().map(&matches_year).unwrap_or(false))
-        .map(|t| t.amount_sol)
-        .sum();
-
-    // Vote costs (with SFDP coverage)
-    let total_vote_costs_sol: f64 = data
-        .vote_costs
-        .iter()
-        .filter(|c| c.date.as_deref().map(&matches_year).unwrap_or(false))
-        .map(|c| c.total_fee_sol)
-        .sum();
-    let mut total_vote_costs_gross_usd = 0.0;
-    let mut total_vote_costs_net_usd = 0.0;
-
-    for cost in data.vote_costs {
-        let date = cost.date.as_deref().unwrap_or(constants::FALLBACK_DATE);
-        if !matches_year(date) {
-            continue;
-        }
-        let price = get_price(data.prices, date);
-        let gross_usd = cost.total_fee_sol * price;
-
-        // Calculate SFDP coverage
-        let parsed_date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
-            .unwrap_or_else(|_| chrono::NaiveDate::parse_from_str(constants::FALLBACK_DATE, "%Y-%m-%d").unwrap());
-        let coverage = data.config.sfdp_coverage_percent(&parsed_date);
-        let net_usd = gross_usd * (1.0 - coverage);
-
-        total_vote_costs_gross_usd += gross_usd;
-        total_vote_costs_net_usd += net_usd;
+    // Calculate totals for the period from the same monthly rows as summary.csv
+    let months = monthly_totals(data, period);
+    let mut totals = MonthlyData::default();
+    for month_data in months.values() {
+        totals.close_with(month_data);
     }
 
-    // Other expenses (hosting, contractors, etc.)
-    let total_other_expenses: f64 = data
-        .expenses
-        .iter()
-        .filter(|e| matches_year(&e.date))
-        .map(|e| e.amount_usd)
-        .sum();
-    let hosting_expenses: f64 = data
-        .expenses
-        .iter()
-        .filter(|e| e.category == ExpenseCategory::Hosting && matches_year(&e.date))
-        .map(|e| e.amount_usd)
-        .sum();
-    let contractor_expenses: f64 = data
-        .expenses
-        .iter()
-        .filter(|e| e.category == ExpenseCategory::Contractor && matches_year(&e.date))
-        .map(|e| e.amount_usd)
-        .sum();
-
-    // SFDP is an expense offset, not revenue
-    let total_revenue_usd = total_commission_usd + total_leader_fees_usd + total_mev_usd;
-    let total_expenses_usd = total_vote_costs_net_usd + total_other_expenses;
-    let net_profit = total_revenue_usd - total_expenses_usd;
-
-    // Normalize values to avoid displaying -0.0
-    let total_commission_sol = normalize_zero(total_commission_sol);
-    let total_commission_usd = normalize_zero(total_commission_usd);
-    let total_leader_fees_sol = normalize_zero(total_leader_fees_sol);
-    let total_leader_fees_usd = normalize_zero(total_leader_fees_usd);
-    let total_mev_sol = normalize_zero(total_mev_sol);
-    let total_mev_usd = normalize_zero(total_mev_usd);
-    let total_seeding_sol = normalize_zero(total_seeding_sol);
-
+    println!("Basis: {}\n", data.basis);
     println!("REVENUE:");
     println!(
-        "  Commission:         {:>10.4} SOL  ${:>10.2}",
-        total_commission_sol, total_commission_usd
+        "  Commission:         {:>10.4} SOL  ${:>10}",
+        totals.commission_sol, totals.commission_usd
+    );
+    println!(
+        "  Leader Fees:        {:>10.4} SOL  ${:>10}",
+        totals.leader_fees_sol, totals.leader_fees_usd
     );
     println!(
-        "  Leader Fees:        {:>10.4} SOL  ${:>10.2}",
-        total_leader_fees_sol, total_leader_fees_usd
+        "  Jito MEV:           {:>10.4} SOL  ${:>10}",
+        totals.mev_sol, totals.mev_usd
     );
     println!(
-        "  Jito MEV:           {:>10.4} SOL  ${:>10.2}",
-        total_mev_sol, total_mev_usd
+        "  Jito Priority Fees: {:>10.4} SOL  ${:>10}",
+        totals.priority_fees_sol, totals.priority_fees_usd
     );
     println!("  ─────────────────────────────────────────────");
     println!(
-        "  Total Revenue:      {:>10.4} SOL  ${:>10.2}",
-        total_commission_sol + total_leader_fees_sol + total_mev_sol,
-        total_revenue_usd
+        "  Total Revenue:      {:>10.4} SOL  ${:>10}",
+        totals.commission_sol + totals.leader_fees_sol + totals.mev_sol + totals.priority_fees_sol,
+        totals.total_revenue_usd()
     );
 
     println!("\nEXPENSES:");
     println!(
-        "  Vote Fees (gross):  {:>10.4} SOL  ${:>10.2}",
-        total_vote_costs_sol, total_vote_costs_gross_usd
+        "  Vote Fees (gross):  {:>10.4} SOL  ${:>10}",
+        totals.vote_costs_sol, totals.vote_costs_gross_usd
     );
     println!(
-        "  SFDP Offset:                   -${:>10.2}",
-        total_vote_costs_gross_usd - total_vote_costs_net_usd
+        "  SFDP Offset:                   -${:>10}",
+        totals.vote_costs_gross_usd - totals.vote_costs_net_usd
     );
-    println!("  Vote Fees (net):                ${:>10.2}", total_vote_costs_net_usd);
-    println!("  Hosting:                        ${:>10.2}", hosting_expenses);
-    println!("  Contractor:                     ${:>10.2}", contractor_expenses);
+    println!("  Vote Fees (net):                ${:>10}", totals.vote_costs_net_usd);
+    for (category, usd) in &totals.category_expenses_usd {
+        println!("  {:<30} ${:>10}", format!("{}:", category), usd);
+    }
     println!("  ─────────────────────────────────────────────");
-    println!("  Total Expenses:                 ${:>10.2}", total_expenses_usd);
+    println!("  Total Expenses:                 ${:>10}", totals.total_expenses_usd());
 
     println!("\nPROFIT/LOSS:");
-    println!("  Net Profit:                     ${:>10.2}", net_profit);
+    println!("  Net Profit:                     ${:>10}", totals.net_profit_usd());
+
+    if let Some(balances) = totals.balances {
+        println!("\nRECEIVABLES/PAYABLES (end of period):");
+        println!(
+            "  SFDP Receivable:                ${:>10}",
+            balances.sfdp_receivable_usd
+        );
+        println!(
+            "  Unpaid Expenses (as of today):  ${:>10}",
+            balances.unpaid_expenses_usd
+        );
+    }
+
+    // Realized gains on withdrawn SOL (not part of operating profit)
+    let lot_report = data.lot_report(period);
+    let (short_term, long_term) = lots::realized_totals(&lot_report.gains);
+
+    println!("\nCAPITAL GAINS ({}):", data.lot_method);
+    println!("  Short-term:                     ${:>10}", short_term);
+    println!("  Long-term:                      ${:>10}", long_term);
+    let unknown_proceeds = lots::unknown_basis_proceeds(&lot_report.gains);
+    if unknown_proceeds != Usd::ZERO {
+        println!("  Unknown basis (excluded):       ${:>10} proceeds", unknown_proceeds);
+    }
 
     println!("\nCAPITAL:");
-    println!("  Initial Seeding:    {:>10.4} SOL", total_seeding_sol);
+    println!("  Initial Seeding:    {:>10.4} SOL", totals.seeding_sol);
     println!(
         "  Transfers found:    {}",
         data.categorized.seeding.len() + data.categorized.vote_funding.len()
     );
 
+    print_data_quality(&totals, &months);
+
     println!("============================================================");
 }
+
+/// Print how much of each line's USD value is observed versus estimated, and
+/// which months contain estimates
+fn print_data_quality(totals: &MonthlyData, months: &BTreeMap<String, MonthlyData>) {
+    println!("\nDATA QUALITY (share of USD value):");
+    println!("  {:<20} {:>10} {:>10} {:>10}", "", "Observed", "Estimated", "Unknown");
+    for line in Line::ALL {
+        let quality = &totals.quality[line as usize];
+        if quality.total() == Usd::ZERO {
+            continue;
+        }
+        println!(
+            "  {:<20} {:>9.1}% {:>9.1}% {:>9.1}%",
+            line.label(),
+            quality.percent(quality.observed),
+            quality.percent(quality.estimated),
+            quality.percent(quality.unknown)
+        );
+    }
+
+    let estimated_months: Vec<String> = months
+        .iter()
+        .filter_map(|(month, data)| {
+            let quality = data.overall_quality();
+            (quality.estimated > Usd::ZERO).then(|| format!("{} ({:.1}%)", month, quality.percent(quality.estimated)))
+        })
+        .collect();
+    if estimated_months.is_empty() {
+        println!("  No estimated values");
+    } else {
+        println!("  Months with estimates: {}", estimated_months.join(", "));
+    }
+    if totals.overall_quality().unknown > Usd::ZERO {
+        println!("  Unknown rows were cached before sources were recorded; `cache refresh` refetches them");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addresses::AddressCategory;
+    use crate::journal::{JournalInputs, Units};
+    use crate::prices::Price;
+    use crate::provenance::Provenance;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_cash_basis_recognizes_sol_payments_when_paid() {
+        let config = Config::for_test();
+        let expenses = vec![Expense {
+            id: Some(1),
+            date: "2025-12-29".to_string(),
+            vendor: "Contractor".to_string(),
+            category: ExpenseCategory::CONTRACTOR,
+            description: "December support".to_string(),
+            amount_usd: Usd::from_dollars(500),
+            paid_with: "SOL".to_string(),
+            invoice_id: None,
+            validator: None,
+            tx_signature: Some("pay1".to_string()),
+            amount_lamports: Some(Lamports(4_000_000_000)),
+            paid_date: Some("2026-01-02".to_string()),
+            service_start: None,
+            service_end: None,
+            provenance: Provenance::manual(),
+        }];
+        let data = ReportData {
+            rewards: &[],
+            categorized: &CategorizedTransfers::default(),
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &[],
+            expenses: &expenses,
+            prices: &PriceCache::default(),
+            config: &config,
+            lot_method: LotMethod::default(),
+            lot_selections: &HashMap::new(),
+            basis: Basis::Cash,
+            categories: &Categories::default(),
+            assets: &[],
+            recognized_through: NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(),
+        };
+
+        // Paid in January, so a cash-basis Q4 report doesn't include it
+        let monthly = monthly_totals(&data, &ReportPeriod::default());
+        assert_eq!(monthly.keys().collect::<Vec<_>>(), ["2026-01"]);
+        assert_eq!(monthly["2026-01"].other_expenses_usd, Usd::from_dollars(500));
+        assert_eq!(data.incurred_expenses()[0].date, "2026-01-02");
+
+        // The ledger row carries the SOL sent and the transfer that sent it
+        let dir = std::env::temp_dir().join(format!("validator-accounting-reports-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        generate_expense_ledger(&dir, &data, &ReportPeriod::default()).unwrap();
+        let ledger = std::fs::read_to_string(dir.join(constants::EXPENSE_LEDGER_FILENAME)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(
+            ledger
+                .lines()
+                .nth(1)
+                .unwrap()
+                .contains(",4.000000000,500.00,SOL,,500.00,,pay1,")
+        );
+
+        // Incurred in December
+        let accrual = ReportData {
+            basis: Basis::Accrual,
+            ..data
+        };
+        let monthly = monthly_totals(&accrual, &ReportPeriod::default());
+        assert_eq!(monthly.keys().collect::<Vec<_>>(), ["2025-12"]);
+    }
+
+    #[test]
+    fn test_cash_basis_recognizes_revenue_spent_on_vote_fees() {
+        let config = Config::for_test();
+        let prices = PriceCache::from([(
+            "2025-12-10".to_string(),
+            Price::new(Usd::from_dollars(100), DataSource::CoinGecko),
+        )]);
+        let rewards = vec![EpochReward {
+            epoch: 900,
+            amount_lamports: Lamports(1_000_000_000),
+            commission: 5,
+            effective_slot: 0,
+            date: Some("2025-12-10".to_string()),
+            provenance: Provenance::new(DataSource::Rpc),
+        }];
+        let vote_costs = vec![EpochVoteCost {
+            epoch: 901,
+            vote_count: 80_000,
+            total_fee_lamports: Lamports(400_000_000),
+            date: Some("2025-12-10".to_string()),
+            provenance: Provenance::new(DataSource::Rpc),
+        }];
+        let data = ReportData {
+            rewards: &rewards,
+            categorized: &CategorizedTransfers::default(),
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &vote_costs,
+            expenses: &[],
+            prices: &prices,
+            config: &config,
+            lot_method: LotMethod::default(),
+            lot_selections: &HashMap::new(),
+            basis: Basis::Cash,
+            categories: &Categories::default(),
+            assets: &[],
+            recognized_through: NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+        };
+
+        // Nothing withdrawn, but the SOL that paid for votes was earned revenue
+        let totals = period_totals(&data, &ReportPeriod::default());
+        assert_eq!(totals.commission_sol, Lamports(400_000_000));
+        assert_eq!(totals.commission_usd, Usd::from_dollars(40));
+        assert_eq!(totals.vote_costs_net_usd, Usd::from_dollars(40));
+    }
+
+    #[test]
+    fn test_full_sfdp_reimbursement_clears_the_receivable() {
+        let config = Config::for_test();
+        // SOL is worth more when the reimbursement arrives than when the votes were paid
+        let prices: PriceCache = [("2025-12-10", 10_000), ("2026-01-05", 15_000)]
+            .into_iter()
+            .map(|(date, cents)| {
+                (
+                    date.to_string(),
+                    Price::new(Usd::from_cents(cents), DataSource::CoinGecko),
+                )
+            })
+            .collect();
+        let vote_costs = vec![EpochVoteCost {
+            epoch: 900,
+            vote_count: 400_000,
+            total_fee_lamports: Lamports(2_000_000_000),
+            date: Some("2025-12-10".to_string()),
+            provenance: Provenance::new(DataSource::Rpc),
+        }];
+        let categorized = CategorizedTransfers {
+            sfdp_reimbursements: vec![SolTransfer {
+                signature: "sfdp1".to_string(),
+                slot: 1,
+                timestamp: None,
+                date: Some("2026-01-05".to_string()),
+                from: Pubkey::new_unique(),
+                to: config.identity,
+                amount_lamports: Lamports(2_000_000_000),
+                from_label: "SFDP Vote Reimbursement".to_string(),
+                to_label: "Identity".to_string(),
+                from_category: AddressCategory::SolanaFoundation,
+                to_category: AddressCategory::ValidatorSelf,
+                provenance: Provenance::new(DataSource::Rpc),
+            }],
+            ..Default::default()
+        };
+        let data = ReportData {
+            rewards: &[],
+            categorized: &categorized,
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &vote_costs,
+            expenses: &[],
+            prices: &prices,
+            config: &config,
+            lot_method: LotMethod::default(),
+            lot_selections: &HashMap::new(),
+            basis: Basis::Accrual,
+            categories: &Categories::default(),
+            assets: &[],
+            recognized_through: NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(),
+        };
+
+        let monthly = monthly_totals(&data, &ReportPeriod::default());
+        let receivable = |month: &str| monthly[month].balances.unwrap().sfdp_receivable_usd;
+        assert_eq!(receivable("2025-12"), Usd::from_dollars(200));
+        assert_eq!(receivable("2026-01"), Usd::ZERO);
+        // The extra $100 the SOL is worth on arrival is a true-up, not receivable
+        assert_eq!(monthly["2026-01"].sfdp_usd, Usd::from_dollars(300));
+        assert_eq!(monthly["2026-01"].vote_costs_net_usd, -Usd::from_dollars(100));
+
+        let journal = journal::build_journal(&JournalInputs {
+            rewards: &[],
+            categorized: &categorized,
+            mev_claims: &[],
+            leader_fees: &[],
+            vote_costs: &vote_costs,
+            expenses: &[],
+            assets: &[],
+            recognized_through: data.recognized_through,
+            prices: &prices,
+            config: &config,
+        });
+        let balance = |account: &str| -> i64 {
+            journal
+                .iter()
+                .flat_map(|tx| &tx.postings)
+                .filter(|posting| posting.account == account)
+                .map(|posting| match posting.units {
+                    Units::Usd { cents } => cents,
+                    Units::Sol { .. } => 0,
+                })
+                .sum()
+        };
+        assert_eq!(balance(&config.accounts.sfdp_receivable), 0);
+        assert_eq!(balance(&config.accounts.sfdp_true_up), -10_000);
+
+        // The data-quality split of net vote fees covers the net amount on either basis
+        for basis in [Basis::Accrual, Basis::Cash] {
+            let data = ReportData { basis, ..data };
+            let totals = period_totals(&data, &ReportPeriod::default());
+            assert_eq!(
+                totals.quality[Line::VoteFees as usize].total(),
+                totals.vote_costs_net_usd
+            );
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Data sources for everything the report pipeline fetches over the network
+//!
+//! Each external service sits behind a trait: chain RPC, Jito MEV, CoinGecko
+//! prices, Dune analytics and the Notion hours log. The live implementations
+//! delegate to the fetch functions in the corresponding modules; `fixtures`
+//! wraps them to record responses to disk or replay them without a network.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use futures::future::{BoxFuture, FutureExt};
+use solana_sdk::epoch_schedule::EpochSchedule;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::{Config, NotionConfig};
+use crate::dune::DuneClient;
+use crate::epochs::{self, EpochBoundary};
+use crate::jito::{self, MevClaim};
+use crate::leader_fees::{self, EpochLeaderFees};
+use crate::money::Usd;
+use crate::notion::{self, HoursLogEntry};
+use crate::prices::{self, CoinGeckoApi};
+use crate::reconcile::BalanceSnapshot;
+use crate::transactions::{self, EpochReward, FetchTransfersResult, SolTransfer};
+use crate::vote_costs::EpochVoteCost;
+
+/// Solana RPC: epochs, inflation rewards, leader fees and transfers
+pub trait ChainSource: Send + Sync {
+    fn current_epoch<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<u64>>;
+
+    /// Slots per epoch and warmup of the validator's cluster
+    fn epoch_schedule<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<EpochSchedule>>;
+
+    fn inflation_rewards<'a>(
+        &'a self,
+        config: &'a Config,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> BoxFuture<'a, Result<Vec<EpochReward>>>;
+
+    /// Rewards for the in-progress epoch (usually empty until it completes)
+    fn current_epoch_rewards<'a>(
+        &'a self,
+        config: &'a Config,
+        current_epoch: u64,
+    ) -> BoxFuture<'a, Result<Vec<EpochReward>>>;
+
+    fn leader_fees<'a>(
+        &'a self,
+        config: &'a Config,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> BoxFuture<'a, Result<Vec<EpochLeaderFees>>>;
+
+    fn sol_transfers<'a>(
+        &'a self,
+        config: &'a Config,
+        verbose: bool,
+    ) -> BoxFuture<'a, Result<(Vec<SolTransfer>, Vec<BalanceSnapshot>)>>;
+
+    /// Transfers for one tracked account, newest first, stopping at `stop_at_slot`
+    fn transfers_for_account<'a>(
+        &'a self,
+        config: &'a Config,
+        account: Pubkey,
+        label: &'a str,
+        stop_at_slot: Option<u64>,
+        verbose: bool,
+    ) -> BoxFuture<'a, Result<FetchTransfersResult>>;
+
+    /// Block time of an epoch's first confirmed block
+    fn epoch_boundary<'a>(&'a self, config: &'a Config, epoch: u64) -> BoxFuture<'a, Result<EpochBoundary>>;
+}
+
+/// Jito MEV and priority fee distributions
+pub trait MevSource: Send + Sync {
+    fn mev_claims<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<Vec<MevClaim>>>;
+}
+
+/// Daily SOL/USD prices
+pub trait PriceSource: Send + Sync {
+    /// Daily close prices between two dates, keyed by YYYY-MM-DD
+    fn price_range(&self, from: NaiveDate, to: NaiveDate) -> BoxFuture<'_, Result<Vec<(String, Usd)>>>;
+
+    fn current_price(&self) -> BoxFuture<'_, Result<Usd>>;
+}
+
+/// Dune Analytics queries, used to backfill data the RPC has pruned
+pub trait AnalyticsSource: Send + Sync {
+    fn inflation_rewards<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochReward>>>;
+
+    fn leader_fees<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochLeaderFees>>>;
+
+    fn vote_costs<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochVoteCost>>>;
+
+    fn transfers<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<SolTransfer>>>;
+
+    fn epoch_boundaries<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochBoundary>>>;
+}
+
+/// Contractor hours log
+pub trait HoursSource: Send + Sync {
+    fn hours_log<'a>(&'a self, config: &'a NotionConfig) -> BoxFuture<'a, Result<Vec<HoursLogEntry>>>;
+}
+
+/// Every data source the report pipeline uses
+pub struct Sources {
+    pub chain: Box<dyn ChainSource>,
+    pub mev: Box<dyn MevSource>,
+    pub prices: Box<dyn PriceSource>,
+    /// Dune fallback (only when an API key is configured)
+    pub analytics: Option<Box<dyn AnalyticsSource>>,
+    pub hours: Box<dyn HoursSource>,
+}
+
+impl Sources {
+    /// Sources backed by the real services at the configured endpoints
+    pub fn live(config: &Config) -> Self {
+        Self {
+            chain: Box::new(LiveChain),
+            mev: Box::new(LiveMev),
+            prices: Box::new(LivePrices {
+                api: CoinGeckoApi::new(config),
+            }),
+            analytics: config
+                .dune_api_key
+                .as_deref()
+                .map(|key| Box::new(LiveAnalytics::new(key)) as Box<dyn AnalyticsSource>),
+            hours: Box::new(LiveHours {
+                base_url: config.endpoints.notion.clone(),
+            }),
+        }
+    }
+}
+
+// =============================================================================
+// Live implementations
+// =============================================================================
+
+/// Solana RPC at each validator's configured `rpc_url`
+pub struct LiveChain;
+
+impl ChainSource for LiveChain {
+    fn current_epoch<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<u64>> {
+        async move { transactions::fetch_current_epoch(config) }.boxed()
+    }
+
+    fn epoch_schedule<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<EpochSchedule>> {
+        async move { epochs::fetch_epoch_schedule(config) }.boxed()
+    }
+
+    fn inflation_rewards<'a>(
+        &'a self,
+        config: &'a Config,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> BoxFuture<'a, Result<Vec<EpochReward>>> {
+        transactions::fetch_inflation_rewards(config, start_epoch, end_epoch).boxed()
+    }
+
+    fn current_epoch_rewards<'a>(
+        &'a self,
+        config: &'a Config,
+        current_epoch: u64,
+    ) -> BoxFuture<'a, Result<Vec<EpochReward>>> {
+        transactions::fetch_current_epoch_rewards(config, current_epoch).boxed()
+    }
+
+    fn leader_fees<'a>(
+        &'a self,
+        config: &'a Config,
+        start_epoch: u64,
+        end_epoch: Option<u64>,
+    ) -> BoxFuture<'a, Result<Vec<EpochLeaderFees>>> {
+        leader_fees::fetch_leader_fees(config, start_epoch, end_epoch).boxed()
+    }
+
+    fn sol_transfers<'a>(
+        &'a self,
+        config: &'a Config,
+        verbose: bool,
+    ) -> BoxFuture<'a, Result<(Vec<SolTransfer>, Vec<BalanceSnapshot>)>> {
+        transactions::fetch_sol_transfers(config, verbose).boxed()
+    }
+
+    fn transfers_for_account<'a>(
+        &'a self,
+        config: &'a Config,
+        account: Pubkey,
+        label: &'a str,
+        stop_at_slot: Option<u64>,
+        verbose: bool,
+    ) -> BoxFuture<'a, Result<FetchTransfersResult>> {
+        async move { transactions::fetch_transfers_for_account(config, &account, label, stop_at_slot, verbose).await }
+            .boxed()
+    }
+
+    fn epoch_boundary<'a>(&'a self, config: &'a Config, epoch: u64) -> BoxFuture<'a, Result<EpochBoundary>> {
+        epochs::fetch_epoch_boundary(config, epoch).boxed()
+    }
+}
+
+/// Jito kobe API
+pub struct LiveMev;
+
+impl MevSource for LiveMev {
+    fn mev_claims<'a>(&'a self, config: &'a Config) -> BoxFuture<'a, Result<Vec<MevClaim>>> {
+        jito::fetch_mev_claims(config).boxed()
+    }
+}
+
+/// CoinGecko API
+pub struct LivePrices {
+    api: CoinGeckoApi,
+}
+
+impl PriceSource for LivePrices {
+    fn price_range(&self, from: NaiveDate, to: NaiveDate) -> BoxFuture<'_, Result<Vec<(String, Usd)>>> {
+        prices::fetch_price_range(from, to, &self.api).boxed()
+    }
+
+    fn current_price(&self) -> BoxFuture<'_, Result<Usd>> {
+        prices::fetch_current_price(&self.api).boxed()
+    }
+}
+
+/// Dune Analytics API
+pub struct LiveAnalytics {
+    api_key: String,
+}
+
+impl LiveAnalytics {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+        }
+    }
+
+    fn client(&self, config: &Config) -> DuneClient {
+        DuneClient::new(self.api_key.clone(), config)
+    }
+}
+
+impl AnalyticsSource for LiveAnalytics {
+    fn inflation_rewards<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochReward>>> {
+        async move { self.client(config).fetch_inflation_rewards(since).await }.boxed()
+    }
+
+    fn leader_fees<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochLeaderFees>>> {
+        async move { self.client(config).fetch_leader_fees(since).await }.boxed()
+    }
+
+    fn vote_costs<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochVoteCost>>> {
+        async move { self.client(config).fetch_vote_costs(since).await }.boxed()
+    }
+
+    fn transfers<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<SolTransfer>>> {
+        async move { self.client(config).fetch_transfers(since).await }.boxed()
+    }
+
+    fn epoch_boundaries<'a>(&'a self, config: &'a Config, since: &'a str) -> BoxFuture<'a, Result<Vec<EpochBoundary>>> {
+        async move { self.client(config).fetch_epoch_boundaries(since).await }.boxed()
+    }
+}
+
+/// Notion API
+pub struct LiveHours {
+    base_url: String,
+}
+
+impl HoursSource for LiveHours {
+    fn hours_log<'a>(&'a self, config: &'a NotionConfig) -> BoxFuture<'a, Result<Vec<HoursLogEntry>>> {
+        notion::fetch_hours_log(config, &self.base_url).boxed()
+    }
+}
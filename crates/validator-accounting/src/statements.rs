@@ -0,0 +1,426 @@
+//! Bank and credit card statement import
+//!
+//! Statement CSVs are read through a column mapping (`StatementFormat`): a few
+//! common exports are built in and more can be configured under
+//! `[statement_formats.<name>]`. Each charge is run through a rules file that
+//! maps description patterns to a vendor and category, and is skipped when an
+//! expense with the same date, amount and vendor is already recorded.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use crate::categories::Categories;
+use crate::expenses::{Expense, ExpenseCategory};
+use crate::money::Usd;
+use crate::provenance::{DataSource, Provenance};
+
+/// Column mapping of a statement CSV export
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatementFormat {
+    /// Header of the transaction date column
+    pub date_column: String,
+    /// chrono format of the dates (default MM/DD/YYYY)
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// Header of the merchant / description column
+    pub description_column: String,
+    /// Header of a single signed amount column
+    #[serde(default)]
+    pub amount_column: Option<String>,
+    /// Whether charges are negative in `amount_column` (bank exports) rather
+    /// than positive (most card exports)
+    #[serde(default)]
+    pub charges_negative: bool,
+    /// Header of a separate charges column (instead of `amount_column`)
+    #[serde(default)]
+    pub debit_column: Option<String>,
+    /// Payment method recorded on imported expenses
+    #[serde(default = "default_paid_with")]
+    pub paid_with: String,
+}
+
+fn default_date_format() -> String {
+    "%m/%d/%Y".to_string()
+}
+
+fn default_paid_with() -> String {
+    "Credit Card".to_string()
+}
+
+/// Built-in formats, by name
+const BUILT_IN_FORMATS: &[&str] = &["amex", "capital-one", "chase"];
+
+fn built_in_format(name: &str) -> Option<StatementFormat> {
+    let format = |date: &str, date_format: &str, description: &str| StatementFormat {
+        date_column: date.to_string(),
+        date_format: date_format.to_string(),
+        description_column: description.to_string(),
+        amount_column: None,
+        charges_negative: false,
+        debit_column: None,
+        paid_with: default_paid_with(),
+    };
+    match name {
+        "amex" => Some(StatementFormat {
+            amount_column: Some("Amount".to_string()),
+            ..format("Date", "%m/%d/%Y", "Description")
+        }),
+        "capital-one" => Some(StatementFormat {
+            debit_column: Some("Debit".to_string()),
+            ..format("Transaction Date", "%Y-%m-%d", "Description")
+        }),
+        "chase" => Some(StatementFormat {
+            amount_column: Some("Amount".to_string()),
+            charges_negative: true,
+            ..format("Transaction Date", "%m/%d/%Y", "Description")
+        }),
+        _ => None,
+    }
+}
+
+/// Look up a format by name: configured formats first, then the built-in ones
+pub fn find_format(name: &str, configured: &HashMap<String, StatementFormat>) -> Result<StatementFormat> {
+    if let Some(format) = configured.get(name) {
+        return Ok(format.clone());
+    }
+    built_in_format(name).with_context(|| {
+        let mut names: Vec<&str> = configured.keys().map(String::as_str).collect();
+        names.extend(BUILT_IN_FORMATS);
+        names.sort();
+        format!(
+            "Unknown statement format '{}'. Use one of: {} (or add [statement_formats.{}] to config.toml)",
+            name,
+            names.join(", "),
+            name
+        )
+    })
+}
+
+/// One statement row, with charges positive and payments/refunds negative
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementLine {
+    pub date: NaiveDate,
+    pub description: String,
+    pub amount: Usd,
+}
+
+/// Read a statement CSV file
+pub fn load_statement(path: &Path, format: &StatementFormat) -> Result<Vec<StatementLine>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    parse_statement(file, format).with_context(|| format!("Failed to read statement {}", path.display()))
+}
+
+/// Parse statement CSV rows through a column mapping
+pub fn parse_statement(reader: impl Read, format: &StatementFormat) -> Result<Vec<StatementLine>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = rdr.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name))
+            .with_context(|| {
+                format!(
+                    "No '{}' column (columns: {})",
+                    name,
+                    headers.iter().collect::<Vec<_>>().join(", ")
+                )
+            })
+    };
+    let date_col = column(&format.date_column)?;
+    let description_col = column(&format.description_column)?;
+    let (amount_col, sign) = match (&format.debit_column, &format.amount_column) {
+        (Some(debit), _) => (column(debit)?, 1),
+        (None, Some(amount)) => (column(amount)?, if format.charges_negative { -1 } else { 1 }),
+        (None, None) => anyhow::bail!("Statement format needs an amount_column or debit_column"),
+    };
+
+    let mut lines = Vec::new();
+    for (index, record) in rdr.records().enumerate() {
+        let record = record?;
+        let field = |col: usize| record.get(col).unwrap_or("");
+        // Blank rows and debit-only formats' credit rows have no amount
+        if field(amount_col).is_empty() {
+            continue;
+        }
+        let row = index + 2; // 1-based, after the header
+        let date = NaiveDate::parse_from_str(field(date_col), &format.date_format)
+            .with_context(|| format!("Row {}: invalid date '{}'", row, field(date_col)))?;
+        let amount = parse_amount(field(amount_col)).with_context(|| format!("Row {}: invalid amount", row))?;
+        lines.push(StatementLine {
+            date,
+            description: field(description_col).to_string(),
+            amount: if sign < 0 { -amount } else { amount },
+        });
+    }
+    Ok(lines)
+}
+
+/// Parse a statement amount, including accounting-style negatives ("(45.00)")
+fn parse_amount(s: &str) -> Result<Usd> {
+    match s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        Some(inner) => Ok(-inner.parse::<Usd>()?),
+        None => s.parse(),
+    }
+}
+
+/// Vendor and category for statement descriptions matching a pattern
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    /// Case-insensitive text to find in the description; `*` matches anything
+    pub pattern: String,
+    /// Vendor to record (default: the statement description)
+    #[serde(default)]
+    pub vendor: Option<String>,
+    /// Category to record (default: Other)
+    #[serde(default)]
+    pub category: Option<ExpenseCategory>,
+    /// Description to record (default: the statement description)
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Skip matching charges (personal spending, card payments, etc.)
+    #[serde(default)]
+    pub ignore: bool,
+}
+
+impl Rule {
+    fn matches(&self, description: &str) -> bool {
+        let description = description.to_lowercase();
+        let mut rest = description.as_str();
+        for part in self.pattern.to_lowercase().split('*').filter(|p| !p.is_empty()) {
+            match rest.find(part) {
+                Some(at) => rest = &rest[at + part.len()..],
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Categorization rules, tried in order (first match wins)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Rules {
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<Rule>,
+}
+
+impl Rules {
+    /// Load a rules file (`[[rule]]` tables; see statement_rules.toml.example)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read rules file {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse rules file {}", path.display()))
+    }
+
+    /// Check every rule's category is known, normalizing how it's written
+    pub fn resolve_categories(&mut self, categories: &Categories) -> Result<()> {
+        for rule in &mut self.rules {
+            if let Some(category) = &rule.category {
+                let resolved = categories
+                    .resolve(category.as_str())
+                    .with_context(|| format!("In the rule for '{}'", rule.pattern))?;
+                rule.category = Some(resolved);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn find(&self, description: &str) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.matches(description))
+    }
+}
+
+/// What importing a statement line would do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineStatus {
+    /// Inserted as a new expense
+    New,
+    /// Already recorded (same date, amount and vendor)
+    Duplicate,
+    /// Matched an `ignore` rule
+    Ignored,
+    /// Payment, refund or other credit
+    Credit,
+}
+
+impl LineStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineStatus::New => "new",
+            LineStatus::Duplicate => "duplicate",
+            LineStatus::Ignored => "ignored",
+            LineStatus::Credit => "credit",
+        }
+    }
+}
+
+/// A statement line with the expense it maps to
+#[derive(Debug, Clone)]
+pub struct PlannedLine {
+    pub line: StatementLine,
+    pub expense: Expense,
+    pub status: LineStatus,
+    /// Whether a rule matched (unmatched charges are imported as Other)
+    pub matched: bool,
+}
+
+/// Map statement lines to expenses and decide which ones to insert.
+///
+/// A charge is a duplicate when an existing expense has the same date, amount
+/// and vendor (case-insensitive); each existing expense absorbs at most one
+/// charge, so two identical charges on one day with one recorded import once.
+pub fn plan_import(
+    lines: &[StatementLine],
+    rules: &Rules,
+    existing: &[Expense],
+    paid_with: &str,
+    validator: Option<&str>,
+) -> Vec<PlannedLine> {
+    let key = |date: &str, amount: Usd, vendor: &str| (date.to_string(), amount, vendor.to_lowercase());
+    let mut recorded: HashMap<_, usize> = HashMap::new();
+    for expense in existing {
+        *recorded
+            .entry(key(&expense.date, expense.amount_usd, &expense.vendor))
+            .or_default() += 1;
+    }
+
+    lines
+        .iter()
+        .map(|line| {
+            let rule = rules.find(&line.description);
+            let expense = Expense {
+                id: None,
+                date: line.date.format("%Y-%m-%d").to_string(),
+                vendor: rule
+                    .and_then(|r| r.vendor.clone())
+                    .unwrap_or_else(|| line.description.clone()),
+                category: rule.and_then(|r| r.category.clone()).unwrap_or(ExpenseCategory::OTHER),
+                description: rule
+                    .and_then(|r| r.description.clone())
+                    .unwrap_or_else(|| line.description.clone()),
+                amount_usd: line.amount,
+                paid_with: paid_with.to_string(),
+                invoice_id: None,
+                validator: validator.map(str::to_string),
+                tx_signature: None,
+                amount_lamports: None,
+                paid_date: None,
+                service_start: None,
+                service_end: None,
+                provenance: Provenance::new(DataSource::Import),
+            };
+
+            let status = if line.amount <= Usd::ZERO {
+                LineStatus::Credit
+            } else if rule.is_some_and(|r| r.ignore) {
+                LineStatus::Ignored
+            } else {
+                match recorded.get_mut(&key(&expense.date, expense.amount_usd, &expense.vendor)) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        LineStatus::Duplicate
+                    }
+                    _ => LineStatus::New,
+                }
+            };
+
+            PlannedLine {
+                line: line.clone(),
+                expense,
+                status,
+                matched: rule.is_some(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHASE: &str = "\
+Transaction Date,Post Date,Description,Category,Type,Amount,Memo
+01/03/2026,01/04/2026,LATITUDE.SH* SERVER,Bills & Utilities,Sale,-850.00,
+01/03/2026,01/04/2026,LATITUDE.SH* SERVER,Bills & Utilities,Sale,-850.00,
+01/09/2026,01/10/2026,GITHUB INC,Shopping,Sale,-4.00,
+01/12/2026,01/12/2026,COFFEE SHOP,Food & Drink,Sale,-6.50,
+01/20/2026,01/20/2026,Payment Thank You-Mobile,,Payment,1200.00,
+";
+
+    #[test]
+    fn test_parse_statement_formats() {
+        let chase = find_format("chase", &HashMap::new()).unwrap();
+        let lines = parse_statement(CHASE.as_bytes(), &chase).unwrap();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].date, NaiveDate::from_ymd_opt(2026, 1, 3).unwrap());
+        assert_eq!(lines[0].amount, Usd::from_cents(85_000));
+        assert_eq!(lines[4].amount, Usd::from_cents(-120_000));
+
+        // Debit/credit columns: credit rows have no debit and are skipped
+        let capital_one = find_format("capital-one", &HashMap::new()).unwrap();
+        let csv = "Transaction Date,Posted Date,Card No.,Description,Category,Debit,Credit\n\
+                   2026-01-05,2026-01-06,1234,HETZNER ONLINE,Other,\"1,040.00\",\n\
+                   2026-01-07,2026-01-07,1234,REFUND,Other,,(12.00)\n";
+        let lines = parse_statement(csv.as_bytes(), &capital_one).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].amount, Usd::from_cents(104_000));
+
+        assert!(find_format("mystery-bank", &HashMap::new()).is_err());
+        let missing = StatementFormat {
+            description_column: "Merchant".to_string(),
+            ..chase
+        };
+        assert!(parse_statement(CHASE.as_bytes(), &missing).is_err());
+    }
+
+    #[test]
+    fn test_plan_import_applies_rules_and_skips_duplicates() {
+        let lines = parse_statement(CHASE.as_bytes(), &find_format("chase", &HashMap::new()).unwrap()).unwrap();
+        let rules: Rules = toml::from_str(
+            r#"
+            [[rule]]
+            pattern = "latitude*server"
+            vendor = "Latitude"
+            category = "Hosting"
+            description = "Bare metal server"
+
+            [[rule]]
+            pattern = "coffee"
+            ignore = true
+            "#,
+        )
+        .unwrap();
+        // One of the two identical Latitude charges is already recorded
+        let existing = plan_import(&lines[..1], &rules, &[], "Credit Card", None)
+            .into_iter()
+            .map(|planned| planned.expense)
+            .collect::<Vec<_>>();
+
+        let planned = plan_import(&lines, &rules, &existing, "Credit Card", Some("main"));
+        let statuses: Vec<LineStatus> = planned.iter().map(|p| p.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                LineStatus::Duplicate,
+                LineStatus::New,
+                LineStatus::New,
+                LineStatus::Ignored,
+                LineStatus::Credit,
+            ]
+        );
+        assert_eq!(planned[1].expense.vendor, "Latitude");
+        assert_eq!(planned[1].expense.category, ExpenseCategory::HOSTING);
+        assert_eq!(planned[1].expense.description, "Bare metal server");
+        assert_eq!(planned[1].expense.validator.as_deref(), Some("main"));
+        // Unmatched charges keep the statement description as vendor
+        assert!(!planned[2].matched);
+        assert_eq!(planned[2].expense.vendor, "GITHUB INC");
+        assert_eq!(planned[2].expense.category, ExpenseCategory::OTHER);
+    }
+}
@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use chrono::DateTime;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::RpcTransactionConfig;
@@ -12,6 +12,7 @@ use solana_sdk::signature::Signature;
 use solana_transaction_status::{
     EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiMessage, UiTransactionEncoding,
 };
+use std::collections::HashSet;
 use std::str::FromStr;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -19,6 +20,10 @@ use tokio::time::sleep;
 use crate::addresses::{self, AddressCategory};
 use crate::config::Config;
 use crate::constants;
+use crate::epochs;
+use crate::money::Lamports;
+use crate::provenance::{DataSource, Provenance};
+use crate::reconcile::{BalanceSnapshot, SnapshotSource};
 
 /// Extract account keys from transaction (works for both legacy and versioned)
 fn extract_account_keys(tx: &EncodedTransaction, _debug: bool) -> Option<Vec<Pubkey>> {
@@ -59,31 +64,35 @@ fn extract_account_keys(tx: &EncodedTransaction, _debug: bool) -> Option<Vec<Pub
 }
 
 /// Inflation reward for a single epoch
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EpochReward {
     pub epoch: u64,
-    pub amount_lamports: u64,
-    pub amount_sol: f64,
+    pub amount_lamports: Lamports,
     pub commission: u8,
     pub effective_slot: u64,
     pub date: Option<String>,
+    #[serde(flatten)]
+    pub provenance: Provenance,
 }
 
 /// SOL transfer parsed from transaction
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolTransfer {
     pub signature: String,
     pub slot: u64,
     pub timestamp: Option<i64>,
     pub date: Option<String>,
+    #[serde(with = "addresses::pubkey_string")]
     pub from: Pubkey,
+    #[serde(with = "addresses::pubkey_string")]
     pub to: Pubkey,
-    pub amount_lamports: u64,
-    pub amount_sol: f64,
+    pub amount_lamports: Lamports,
     pub from_label: String,
     pub to_label: String,
     pub from_category: AddressCategory,
     pub to_category: AddressCategory,
+    #[serde(flatten)]
+    pub provenance: Provenance,
 }
 
 /// Categorized transfers
@@ -99,10 +108,38 @@ pub struct CategorizedTransfers {
     pub vote_funding: Vec<SolTransfer>,
     /// Withdrawals to exchanges or personal
     pub withdrawals: Vec<SolTransfer>,
+    /// Outgoing transfers linked to the SOL-paid expenses they paid
+    pub expense_payments: Vec<SolTransfer>,
     /// Other/uncategorized
     pub other: Vec<SolTransfer>,
 }
 
+impl CategorizedTransfers {
+    /// Move the transfers that paid expenses (by signature) out of withdrawals
+    /// and uncategorized transfers into `expense_payments`
+    pub fn separate_expense_payments(&mut self, signatures: &HashSet<&str>) {
+        for list in [&mut self.withdrawals, &mut self.other] {
+            let (payments, rest) = std::mem::take(list)
+                .into_iter()
+                .partition(|t: &SolTransfer| signatures.contains(t.signature.as_str()));
+            *list = rest;
+            self.expense_payments.extend(payments);
+        }
+        self.expense_payments.sort_by_key(|t| t.slot);
+    }
+
+    /// Withdrawals and expense payments: every transfer that disposes of SOL
+    pub fn disposals(&self) -> impl Iterator<Item = &SolTransfer> {
+        self.withdrawals.iter().chain(&self.expense_payments)
+    }
+}
+
+/// Get the cluster's current epoch
+pub fn fetch_current_epoch(config: &Config) -> Result<u64> {
+    let client = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+    Ok(client.get_epoch_info()?.epoch)
+}
+
 /// Fetch inflation rewards for a range of epochs
 pub async fn fetch_inflation_rewards(
     config: &Config,
@@ -139,16 +176,16 @@ async fn fetch_inflation_rewards_internal(
         match client.get_inflation_reward(&[config.vote_account], Some(epoch)) {
             Ok(result) => {
                 if let Some(Some(reward)) = result.first() {
-                    let amount_sol = reward.amount as f64 / 1e9;
+                    let amount = Lamports(reward.amount);
                     rewards.push(EpochReward {
                         epoch,
-                        amount_lamports: reward.amount,
-                        amount_sol,
+                        amount_lamports: amount,
                         commission: reward.commission.unwrap_or(config.commission_percent),
                         effective_slot: reward.effective_slot,
                         date: Some(epoch_to_date(epoch)),
+                        provenance: Provenance::new(DataSource::Rpc),
                     });
-                    println!("    Epoch {}: {:.6} SOL", epoch, amount_sol);
+                    println!("    Epoch {}: {} SOL", epoch, amount);
                 } else if suppress_errors {
                     // Expected for current epoch - rewards not yet distributed
                     println!("    Epoch {} (current): rewards pending epoch completion", epoch);
@@ -166,24 +203,25 @@ async fn fetch_inflation_rewards_internal(
     Ok(rewards)
 }
 
-/// Fetch all SOL transfers involving our accounts
+/// Fetch all SOL transfers involving our accounts, plus the balance snapshots seen along the way
 /// Note: Limited to last 200 transactions per account to avoid RPC timeouts
-pub async fn fetch_sol_transfers(config: &Config, verbose: bool) -> Result<Vec<SolTransfer>> {
+pub async fn fetch_sol_transfers(config: &Config, verbose: bool) -> Result<(Vec<SolTransfer>, Vec<BalanceSnapshot>)> {
     let client = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
 
     let mut all_transfers = Vec::new();
+    let mut all_balances = Vec::new();
 
-    // SFDP reimbursement address (Solana Foundation vote cost reimbursements)
-    let sfdp_address = Pubkey::from_str(constants::SFDP_REIMBURSEMENT).expect("Invalid SFDP address");
-
-    // Fetch for withdraw authority, personal wallet, and SFDP address
+    // Fetch for withdraw authority, personal wallet, and SFDP address (mainnet only)
     // Skip identity (dominated by vote txs) and vote account
     // Personal wallet shows seeding; SFDP shows reimbursements to our accounts
-    for (label, account) in [
+    let mut accounts = vec![
         ("withdraw authority", config.withdraw_authority),
         ("personal wallet", config.personal_wallet),
-        ("SFDP reimbursement", sfdp_address),
-    ] {
+    ];
+    if let Some(sfdp_address) = config.cluster.sfdp_reimbursement() {
+        accounts.push(("SFDP reimbursement", sfdp_address));
+    }
+    for (label, account) in accounts {
         println!(
             "    Fetching transactions for {} ({})...",
             label,
@@ -265,6 +303,7 @@ pub async fn fetch_sol_transfers(config: &Config, verbose: bool) -> Result<Vec<S
                 for retry in 0..3 {
                     match client.get_transaction_with_config(&sig, tx_config) {
                         Ok(tx) => {
+                            all_balances.extend(parse_balance_snapshots(&tx, config));
                             match parse_sol_transfers_debug(&tx, &sig_info.signature, config, verbose && processed < 5)
                             {
                                 Some(transfers) => {
@@ -320,17 +359,20 @@ pub async fn fetch_sol_transfers(config: &Config, verbose: bool) -> Result<Vec<S
     });
 
     // Sort by timestamp (oldest first)
-    all_transfers.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    all_transfers.sort_by_key(|t| t.timestamp);
 
-    Ok(all_transfers)
+    Ok((all_transfers, all_balances))
 }
 
 /// Result of fetching transfers for an account
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FetchTransfersResult {
     /// The SOL transfers found
     pub transfers: Vec<SolTransfer>,
     /// The highest slot we saw (for progress tracking even if no transfers found)
     pub highest_slot_seen: Option<u64>,
+    /// Post-transaction balances of our accounts (for reconciliation)
+    pub balances: Vec<BalanceSnapshot>,
 }
 
 /// Fetch transfers for a single account, stopping at a specific slot
@@ -440,11 +482,13 @@ pub async fn fetch_transfers_for_account(
         return Ok(FetchTransfersResult {
             transfers: Vec::new(),
             highest_slot_seen,
+            balances: Vec::new(),
         });
     }
 
     // Parse each transaction for SOL transfers
     let mut transfers = Vec::new();
+    let mut balances = Vec::new();
     let mut processed = 0;
     let mut transfers_found = 0;
     let mut decode_failures = 0;
@@ -462,6 +506,7 @@ pub async fn fetch_transfers_for_account(
             for retry in 0..3 {
                 match client.get_transaction_with_config(&sig, tx_config) {
                     Ok(tx) => {
+                        balances.extend(parse_balance_snapshots(&tx, config));
                         match parse_sol_transfers_debug(&tx, &sig_info.signature, config, verbose && processed < 5) {
                             Some(t) => {
                                 transfers_found += t.len();
@@ -507,21 +552,23 @@ pub async fn fetch_transfers_for_account(
     Ok(FetchTransfersResult {
         transfers,
         highest_slot_seen,
+        balances,
     })
 }
 
 /// Get the accounts we fetch transactions for (for caching purposes)
 /// Note: We don't include vote_account/identity because they don't have SOL transfers
 /// (they're all vote transactions). Transfers involving them will be captured when
-/// we query the other accounts' histories.
+/// we query the other accounts' histories. The SFDP address is only tracked on mainnet.
 pub fn get_tracked_accounts(config: &Config) -> Vec<(&'static str, Pubkey)> {
-    let sfdp_address = Pubkey::from_str(constants::SFDP_REIMBURSEMENT).expect("Invalid SFDP address");
-
-    vec![
+    let mut accounts = vec![
         ("withdraw_authority", config.withdraw_authority),
         ("personal_wallet", config.personal_wallet),
-        ("sfdp_reimbursement", sfdp_address),
-    ]
+    ];
+    if let Some(sfdp_address) = config.cluster.sfdp_reimbursement() {
+        accounts.push(("sfdp_reimbursement", sfdp_address));
+    }
+    accounts
 }
 
 /// Parse SOL transfers from a transaction with optional debug output
@@ -617,12 +664,12 @@ fn parse_sol_transfers_debug(
                     date: date.clone(),
                     from: *from,
                     to: *to,
-                    amount_lamports: amount,
-                    amount_sol: amount as f64 / 1e9,
+                    amount_lamports: Lamports(amount),
                     from_label: from_label.name.clone(),
                     to_label: to_label.name.clone(),
                     from_category: from_label.category,
                     to_category: to_label.category,
+                    provenance: Provenance::new(DataSource::Rpc),
                 });
 
                 break; // Found the counterparty
@@ -633,9 +680,33 @@ fn parse_sol_transfers_debug(
     if transfers.is_empty() { None } else { Some(transfers) }
 }
 
-/// Categorize transfers based on sender/receiver
+/// Post-transaction balances of our accounts in a transaction
+fn parse_balance_snapshots(tx: &EncodedConfirmedTransactionWithStatusMeta, config: &Config) -> Vec<BalanceSnapshot> {
+    let Some(meta) = tx.transaction.meta.as_ref() else {
+        return Vec::new();
+    };
+    let Some(account_keys) = extract_account_keys(&tx.transaction.transaction, false) else {
+        return Vec::new();
+    };
+
+    account_keys
+        .iter()
+        .zip(&meta.post_balances)
+        .filter(|(account, _)| config.is_relevant_account(account))
+        .map(|(account, post)| BalanceSnapshot {
+            account: *account,
+            slot: tx.slot,
+            lamports: Lamports(*post),
+            source: SnapshotSource::Transaction,
+        })
+        .collect()
+}
+
+/// Categorize transfers based on sender/receiver. Known address labels are
+/// mainnet accounts, so off mainnet only our own accounts are recognized.
 pub fn categorize_transfers(transfers: &[SolTransfer], config: &Config) -> CategorizedTransfers {
     let mut categorized = CategorizedTransfers::default();
+    let known_addresses = config.cluster.has_known_addresses();
 
     for transfer in transfers {
         // Check if this is incoming to our accounts
@@ -647,10 +718,10 @@ pub fn categorize_transfers(transfers: &[SolTransfer], config: &Config) -> Categ
             if transfer.from == config.personal_wallet {
                 // From personal wallet = seeding
                 categorized.seeding.push(transfer.clone());
-            } else if addresses::is_solana_foundation(&transfer.from) {
+            } else if known_addresses && addresses::is_solana_foundation(&transfer.from) {
                 // From SF = SFDP reimbursement
                 categorized.sfdp_reimbursements.push(transfer.clone());
-            } else if addresses::is_jito(&transfer.from) {
+            } else if known_addresses && addresses::is_jito(&transfer.from) {
                 // From Jito = MEV deposit
                 categorized.mev_deposits.push(transfer.clone());
             } else if config.is_our_account(&transfer.from) {
@@ -661,7 +732,7 @@ pub fn categorize_transfers(transfers: &[SolTransfer], config: &Config) -> Categ
             }
         } else if is_outgoing {
             // Outgoing transfers
-            if addresses::is_exchange(&transfer.to) || transfer.to == config.personal_wallet {
+            if (known_addresses && addresses::is_exchange(&transfer.to)) || transfer.to == config.personal_wallet {
                 categorized.withdrawals.push(transfer.clone());
             } else if config.is_our_account(&transfer.to) {
                 // Internal transfer
@@ -675,18 +746,11 @@ pub fn categorize_transfers(transfers: &[SolTransfer], config: &Config) -> Categ
     categorized
 }
 
-/// Convert epoch number to approximate date
-/// Calibrated: epoch 896 = 2025-12-16
+/// Convert epoch number to approximate mainnet date, extrapolated from the
+/// reference epoch (calibrated: epoch 896 = 2025-12-16). Reports re-date rows
+/// with the cluster's cached epoch boundaries (`epochs::EpochCalendar`).
 pub fn epoch_to_date(epoch: u64) -> String {
-    // Use saturating arithmetic to prevent overflow with extreme epoch values
-    let epoch_i64 = epoch.min(i64::MAX as u64) as i64;
-    let epoch_diff = epoch_i64.saturating_sub(constants::REFERENCE_EPOCH);
-    let duration = epoch_diff.saturating_mul(constants::EPOCH_DURATION_SECONDS);
-    let timestamp = constants::REFERENCE_EPOCH_TIMESTAMP.saturating_add(duration);
-
-    DateTime::from_timestamp(timestamp, 0)
-        .map(|dt| dt.format("%Y-%m-%d").to_string())
-        .unwrap_or_else(|| "unknown".to_string())
+    epochs::EpochCalendar::default().date(epoch)
 }
 
 #[cfg(test)]
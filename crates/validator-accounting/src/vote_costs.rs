@@ -9,22 +9,24 @@
 //! on a declining schedule over 12 months.
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+use crate::money::Lamports;
+use crate::provenance::{DataSource, Provenance};
 use crate::transactions::epoch_to_date;
 
 /// Vote cost data for a single epoch
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EpochVoteCost {
     pub epoch: u64,
     pub vote_count: u64,
-    pub total_fee_lamports: u64,
-    pub total_fee_sol: f64,
-    /// Source of data: "dune" (imported), "estimated" (calculated), "rpc" (queried)
-    pub source: String,
+    pub total_fee_lamports: Lamports,
     pub date: Option<String>,
+    /// Dune query, JSON import or estimate
+    #[serde(flatten)]
+    pub provenance: Provenance,
 }
 
 // =============================================================================
@@ -37,8 +39,8 @@ pub const LAMPORTS_PER_VOTE: u64 = 5000;
 /// Average votes per epoch for a healthy validator
 pub const TYPICAL_VOTES_PER_EPOCH: u64 = 431_000;
 
-/// Average cost per epoch in SOL (for quick estimates)
-pub const TYPICAL_COST_PER_EPOCH_SOL: f64 = 2.155;
+/// Average cost per epoch (for quick estimates)
+pub const TYPICAL_COST_PER_EPOCH: Lamports = Lamports(2_155_000_000);
 
 // =============================================================================
 // Historical Import (from Dune Analytics JSON)
@@ -84,15 +86,12 @@ pub fn import_historical_vote_costs(json_path: &Path) -> Result<Vec<EpochVoteCos
     for epoch in epochs {
         let epoch_str = epoch.to_string();
         if let Some(info) = historical.vote_costs_by_epoch.get(&epoch_str) {
-            let total_fee_lamports = (info.total_fee_sol * 1e9) as u64;
-
             results.push(EpochVoteCost {
                 epoch,
                 vote_count: info.vote_count,
-                total_fee_lamports,
-                total_fee_sol: info.total_fee_sol,
-                source: "dune".to_string(),
+                total_fee_lamports: Lamports::from_sol_f64(info.total_fee_sol),
                 date: Some(epoch_to_date(epoch)),
+                provenance: Provenance::new(DataSource::Import),
             });
         }
     }
@@ -110,16 +109,14 @@ pub fn import_historical_vote_costs(json_path: &Path) -> Result<Vec<EpochVoteCos
 /// Most validators see very consistent vote counts (~431K per epoch).
 pub fn estimate_vote_cost(epoch: u64) -> EpochVoteCost {
     let vote_count = TYPICAL_VOTES_PER_EPOCH;
-    let total_fee_lamports = vote_count * LAMPORTS_PER_VOTE;
-    let total_fee_sol = total_fee_lamports as f64 / 1e9;
+    let total_fee_lamports = Lamports(vote_count * LAMPORTS_PER_VOTE);
 
     EpochVoteCost {
         epoch,
         vote_count,
         total_fee_lamports,
-        total_fee_sol,
-        source: "estimated".to_string(),
         date: Some(epoch_to_date(epoch)),
+        provenance: Provenance::new(DataSource::Estimated),
     }
 }
 
@@ -132,9 +129,9 @@ pub fn estimate_vote_costs(start_epoch: u64, end_epoch: u64) -> Vec<EpochVoteCos
 // Utilities
 // =============================================================================
 
-/// Get total vote costs in SOL
-pub fn total_vote_costs_sol(costs: &[EpochVoteCost]) -> f64 {
-    costs.iter().map(|c| c.total_fee_sol).sum()
+/// Get total vote costs
+pub fn total_vote_costs_sol(costs: &[EpochVoteCost]) -> Lamports {
+    costs.iter().map(|c| c.total_fee_lamports).sum()
 }
 
 #[cfg(test)]
@@ -146,9 +143,12 @@ mod tests {
         let cost = estimate_vote_cost(900);
         assert_eq!(cost.epoch, 900);
         assert_eq!(cost.vote_count, TYPICAL_VOTES_PER_EPOCH);
-        assert_eq!(cost.total_fee_lamports, TYPICAL_VOTES_PER_EPOCH * LAMPORTS_PER_VOTE);
-        assert!((cost.total_fee_sol - TYPICAL_COST_PER_EPOCH_SOL).abs() < 0.01);
-        assert_eq!(cost.source, "estimated");
+        assert_eq!(
+            cost.total_fee_lamports,
+            Lamports(TYPICAL_VOTES_PER_EPOCH * LAMPORTS_PER_VOTE)
+        );
+        assert_eq!(cost.total_fee_lamports, TYPICAL_COST_PER_EPOCH);
+        assert_eq!(cost.provenance.source, DataSource::Estimated);
     }
 
     #[test]